@@ -32,6 +32,36 @@ pub struct ProtocolAppData {
     #[serde(default)]
     pub partner_fee: PartnerFees,
     pub flashloan: Option<Flashloan>,
+    /// Condition that has to be met for the order to be included in
+    /// auctions. It is part of the signed app data so it can't be changed
+    /// without the owner's consent.
+    pub condition: Option<OrderCondition>,
+}
+
+/// Condition that determines when an order gets included in auctions. Orders
+/// without a condition are solvable for as long as they are valid.
+#[serde_as]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OrderCondition {
+    /// Time-weighted average price order. The sell amount is split into
+    /// `parts` equally sized parts of which one more becomes available every
+    /// `part_duration` seconds, starting at `start_time`. Only applies to
+    /// partially fillable sell orders.
+    #[serde(rename_all = "camelCase")]
+    Twap {
+        parts: u32,
+        start_time: u32,
+        part_duration: u32,
+    },
+    /// The order only becomes solvable once the oracle price of the sell
+    /// token denominated in the buy token (in atoms, scaled by 10^18) drops to
+    /// or below the strike price.
+    #[serde(rename_all = "camelCase")]
+    StopLoss {
+        #[serde_as(as = "HexOrDecimalU256")]
+        strike_price: U256,
+    },
 }
 
 /// Contains information to hint at how a solver could make
@@ -432,6 +462,7 @@ impl From<BackendAppData> for ProtocolAppData {
             replaced_order: None,
             partner_fee: PartnerFees::default(),
             flashloan: None,
+            condition: None,
         }
     }
 }
@@ -658,6 +689,48 @@ mod tests {
                 ..Default::default()
             },
         );
+
+        assert_app_data!(
+            r#"
+                {
+                    "metadata": {
+                        "condition": {
+                            "kind": "twap",
+                            "parts": 4,
+                            "startTime": 100,
+                            "partDuration": 60
+                        }
+                    }
+                }
+            "#,
+            ProtocolAppData {
+                condition: Some(OrderCondition::Twap {
+                    parts: 4,
+                    start_time: 100,
+                    part_duration: 60,
+                }),
+                ..Default::default()
+            },
+        );
+
+        assert_app_data!(
+            r#"
+                {
+                    "metadata": {
+                        "condition": {
+                            "kind": "stopLoss",
+                            "strikePrice": "1000000000000000000"
+                        }
+                    }
+                }
+            "#,
+            ProtocolAppData {
+                condition: Some(OrderCondition::StopLoss {
+                    strike_price: U256::exp10(18),
+                }),
+                ..Default::default()
+            },
+        );
     }
 
     #[test]
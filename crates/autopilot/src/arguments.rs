@@ -197,6 +197,11 @@ pub struct Arguments {
     #[clap(long, env, default_value = "0.01")]
     pub fee_policy_max_partner_fee: FeeFactor,
 
+    /// Time window over which an owner's traded volume is accumulated for
+    /// fee policies with `minVolume`/`maxVolume` conditions.
+    #[clap(long, env, default_value = "30d", value_parser = humantime::parse_duration)]
+    pub fee_policy_volume_window: Duration,
+
     /// Arguments for uploading information to S3.
     #[clap(flatten)]
     pub s3: infra::persistence::cli::S3,
@@ -250,6 +255,27 @@ pub struct Arguments {
     /// solver, per auction.
     pub max_solutions_per_solver: NonZeroUsize,
 
+    #[clap(long, env, value_enum)]
+    /// The winner selection mechanism deciding the winners of each auction.
+    /// When unset, the mechanism is picked based on
+    /// `combinatorial_auctions_cutover` and `max_winners_per_auction`.
+    pub arbitrator: Option<Arbitrator>,
+
+    #[clap(long, env, value_enum, use_value_delimiter = true)]
+    /// Winner selection mechanisms that run in shadow mode next to the one
+    /// deciding the winners. Their rankings get stored for comparison but
+    /// don't affect the outcome of the auction.
+    pub shadow_arbitrators: Vec<Arbitrator>,
+
+    #[clap(long, env)]
+    /// Name of the driver which submits combined settlements. If set, winning
+    /// solutions of different solvers that trade disjoint orders and tokens
+    /// get merged into a single settlement transaction to save the
+    /// per-settlement gas overhead. The driver has to accept combined
+    /// settlements for its solver. Winners are not combined if the driver's
+    /// own solver would have to settle a solution separately.
+    pub combined_batch_driver: Option<String>,
+
     /// Archive node URL used to index CoW AMM
     #[clap(long, env)]
     pub archive_node_url: Option<Url>,
@@ -370,6 +396,7 @@ impl std::fmt::Display for Arguments {
             solve_deadline,
             fee_policies,
             fee_policy_max_partner_fee,
+            fee_policy_volume_window,
             order_events_cleanup_interval,
             order_events_cleanup_threshold,
             db_url,
@@ -384,6 +411,9 @@ impl std::fmt::Display for Arguments {
             max_winners_per_auction,
             archive_node_url,
             max_solutions_per_solver,
+            arbitrator,
+            shadow_arbitrators,
+            combined_batch_driver,
             db_based_solver_participation_guard,
         } = self;
 
@@ -425,6 +455,7 @@ impl std::fmt::Display for Arguments {
             f,
             "fee_policy_max_partner_fee: {fee_policy_max_partner_fee:?}"
         )?;
+        writeln!(f, "fee_policy_volume_window: {fee_policy_volume_window:?}")?;
         writeln!(
             f,
             "order_events_cleanup_interval: {order_events_cleanup_interval:?}"
@@ -456,6 +487,9 @@ impl std::fmt::Display for Arguments {
         writeln!(f, "max_winners_per_auction: {max_winners_per_auction:?}")?;
         writeln!(f, "archive_node_url: {archive_node_url:?}")?;
         writeln!(f, "max_solutions_per_solver: {max_solutions_per_solver:?}")?;
+        writeln!(f, "arbitrator: {arbitrator:?}")?;
+        writeln!(f, "shadow_arbitrators: {shadow_arbitrators:?}")?;
+        writeln!(f, "combined_batch_driver: {combined_batch_driver:?}")?;
         writeln!(
             f,
             "db_based_solver_participation_guard: {db_based_solver_participation_guard:?}"
@@ -561,10 +595,43 @@ impl FromStr for Solver {
 ///   price_improvement:0.5:0.06:limit
 ///
 /// - Volume based fee for any order class: volume:0.1:any
+///
+/// The order class can be followed by `key=value` conditions which all need
+/// to hold for the policy to apply, and a `tier` name that gets recorded with
+/// every fee policy created by this rule:
+/// - Discounted volume fee between stablecoins:
+///   volume:0.0001:any:tokens=0xA0b8..;0xdAC1..;0x6B17..:tier=stable
+///
+/// - Volume fee for owners trading less than 1000 ETH in the last 30 days:
+///   volume:0.0002:any:maxVolume=1000000000000000000000:tier=retail
 #[derive(Debug, Clone)]
 pub struct FeePolicy {
     pub fee_policy_kind: FeePolicyKind,
     pub fee_policy_order_class: FeePolicyOrderClass,
+    pub fee_policy_conditions: Vec<FeePolicyCondition>,
+    pub fee_policy_tier: Option<String>,
+}
+
+/// Restricts which orders a fee policy applies to. Native token amounts are
+/// denominated in wei. Lower bounds are inclusive and upper bounds are
+/// exclusive, so adjacent tiers can share a boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeePolicyCondition {
+    /// Both the sell and buy token are in the given set (`tokens=0x..;0x..`).
+    Tokens(Vec<H160>),
+    /// Not both the sell and buy token are in the given set
+    /// (`excludeTokens=0x..;0x..`).
+    ExcludeTokens(Vec<H160>),
+    /// The order's sell amount is worth at least this much (`minNotional`).
+    MinNotional(U256),
+    /// The order's sell amount is worth less than this (`maxNotional`).
+    MaxNotional(U256),
+    /// The owner traded at least this much during the volume window
+    /// (`minVolume`).
+    MinVolume(U256),
+    /// The owner traded less than this during the volume window
+    /// (`maxVolume`).
+    MaxVolume(U256),
 }
 
 #[derive(clap::Parser, Debug, Clone)]
@@ -585,6 +652,15 @@ pub enum FeePolicyKind {
     Volume { factor: FeeFactor },
 }
 
+/// Winner selection mechanisms. See `domain::competition::winner_selection`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Arbitrator {
+    /// A single winner with the highest score.
+    MaxScore,
+    /// Multiple winners with uniform directional clearing prices.
+    Combinatorial,
+}
+
 #[derive(clap::Parser, clap::ValueEnum, Clone, Debug)]
 pub enum FeePolicyOrderClass {
     /// If a fee policy needs to be applied to in-market orders.
@@ -654,9 +730,48 @@ impl FromStr for FeePolicy {
         )
         .map_err(|e| anyhow::anyhow!("invalid fee policy order class: {}", e))?;
 
+        let mut fee_policy_conditions = Vec::new();
+        let mut fee_policy_tier = None;
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .with_context(|| format!("invalid fee policy condition: {part}"))?;
+            let tokens = || {
+                value
+                    .split(';')
+                    .map(|token| token.parse::<H160>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(|| format!("invalid token list: {value}"))
+            };
+            let amount = || {
+                U256::from_dec_str(value).with_context(|| format!("invalid {key} amount: {value}"))
+            };
+            match key {
+                "tokens" => fee_policy_conditions.push(FeePolicyCondition::Tokens(tokens()?)),
+                "excludeTokens" => {
+                    fee_policy_conditions.push(FeePolicyCondition::ExcludeTokens(tokens()?))
+                }
+                "minNotional" => {
+                    fee_policy_conditions.push(FeePolicyCondition::MinNotional(amount()?))
+                }
+                "maxNotional" => {
+                    fee_policy_conditions.push(FeePolicyCondition::MaxNotional(amount()?))
+                }
+                "minVolume" => fee_policy_conditions.push(FeePolicyCondition::MinVolume(amount()?)),
+                "maxVolume" => fee_policy_conditions.push(FeePolicyCondition::MaxVolume(amount()?)),
+                "tier" => {
+                    ensure!(!value.is_empty(), "empty fee policy tier");
+                    fee_policy_tier = Some(value.to_owned());
+                }
+                _ => return Err(anyhow!("unknown fee policy condition: {key}")),
+            }
+        }
+
         Ok(FeePolicy {
             fee_policy_kind,
             fee_policy_order_class,
+            fee_policy_conditions,
+            fee_policy_tier,
         })
     }
 }
@@ -734,6 +849,39 @@ mod test {
         }
     }
 
+    #[test]
+    fn parse_fee_policy_conditions() {
+        let policy = FeePolicy::from_str(
+            "volume:0.0001:any:tokens=0x0101010101010101010101010101010101010101;\
+             0x0202020202020202020202020202020202020202:minNotional=1000:maxVolume=2000:\
+             tier=stable",
+        )
+        .unwrap();
+        assert_eq!(
+            policy.fee_policy_conditions,
+            vec![
+                FeePolicyCondition::Tokens(vec![H160([1; 20]), H160([2; 20])]),
+                FeePolicyCondition::MinNotional(1000.into()),
+                FeePolicyCondition::MaxVolume(2000.into()),
+            ]
+        );
+        assert_eq!(policy.fee_policy_tier.as_deref(), Some("stable"));
+
+        let policy = FeePolicy::from_str("surplus:0.5:0.9:limit").unwrap();
+        assert!(policy.fee_policy_conditions.is_empty());
+        assert!(policy.fee_policy_tier.is_none());
+
+        for policy in [
+            "volume:0.1:any:tokens=0x01",
+            "volume:0.1:any:minVolume=-1",
+            "volume:0.1:any:maxNotional",
+            "volume:0.1:any:tier=",
+            "volume:0.1:any:unknown=1",
+        ] {
+            assert!(FeePolicy::from_str(policy).is_err(), "{policy}");
+        }
+    }
+
     #[test]
     fn parse_driver_submission_account_address() {
         let argument = "name1|http://localhost:8080|0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
@@ -23,18 +23,31 @@ pub async fn store_order_events(
     order_uids: Vec<domain::OrderUid>,
     label: OrderEventLabel,
     timestamp: DateTime<Utc>,
+) {
+    let events = order_uids.into_iter().map(|uid| (uid, None)).collect();
+    store_order_events_with_reasons(ex, events, label, timestamp).await
+}
+
+/// Like [`store_order_events`] but additionally stores why each event
+/// happened.
+pub async fn store_order_events_with_reasons(
+    ex: &mut PgConnection,
+    events: Vec<(domain::OrderUid, Option<serde_json::Value>)>,
+    label: OrderEventLabel,
+    timestamp: DateTime<Utc>,
 ) {
     let start = Instant::now();
-    let count = order_uids.len();
+    let count = events.len();
 
     let insert = async move {
         let mut ex = ex.begin().await?;
 
-        for uid in order_uids {
+        for (uid, reason) in events {
             let event = OrderEvent {
                 order_uid: ByteArray(uid.0),
                 timestamp,
                 label,
+                reason,
             };
 
             order_events::insert_order_event(&mut ex, &event).await?;
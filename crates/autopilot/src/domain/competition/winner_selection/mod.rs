@@ -1,8 +1,11 @@
 use {
-    crate::domain::{
-        Auction,
-        competition::{Participant, Ranked, Score, Unranked},
-        eth,
+    crate::{
+        arguments,
+        domain::{
+            Auction,
+            competition::{Participant, Ranked, Score, Unranked},
+            eth,
+        },
     },
     std::collections::HashMap,
 };
@@ -10,6 +13,46 @@ use {
 pub mod combinatorial;
 pub mod max_score;
 
+/// The winner selection mechanisms that can be configured.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Kind {
+    MaxScore,
+    Combinatorial,
+}
+
+impl Kind {
+    /// Instantiates the winner selection mechanism. `max_winners` is only
+    /// relevant for mechanisms supporting multiple winners.
+    pub fn arbitrator(
+        self,
+        max_winners: usize,
+        weth: eth::WrappedNativeToken,
+    ) -> Box<dyn Arbitrator> {
+        match self {
+            Kind::MaxScore => Box::new(max_score::Config),
+            Kind::Combinatorial => Box::new(combinatorial::Config { max_winners, weth }),
+        }
+    }
+}
+
+impl From<arguments::Arbitrator> for Kind {
+    fn from(value: arguments::Arbitrator) -> Self {
+        match value {
+            arguments::Arbitrator::MaxScore => Self::MaxScore,
+            arguments::Arbitrator::Combinatorial => Self::Combinatorial,
+        }
+    }
+}
+
+/// Outcome of a winner selection mechanism that ran in shadow mode, i.e. it
+/// didn't decide the winners of the auction.
+pub struct ShadowRanking {
+    pub arbitrator: Kind,
+    pub ranking: Ranking,
+    pub reference_scores: HashMap<eth::Address, Score>,
+}
+
 pub struct Ranking {
     /// Solutions that were discarded because they were malformed
     /// in some way or deemed unfair by the selection mechanism.
@@ -16,7 +16,10 @@ use {
     derive_more::Into,
     primitive_types::{H160, U256},
     rust_decimal::Decimal,
-    std::{collections::HashSet, str::FromStr},
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        str::FromStr,
+    },
 };
 
 #[derive(Debug)]
@@ -36,10 +39,88 @@ impl From<arguments::FeePolicyOrderClass> for OrderClass {
     }
 }
 
+#[derive(Debug)]
+enum Condition {
+    Tokens(HashSet<H160>),
+    ExcludeTokens(HashSet<H160>),
+    MinNotional(U256),
+    MaxNotional(U256),
+    MinVolume(U256),
+    MaxVolume(U256),
+}
+
+impl From<arguments::FeePolicyCondition> for Condition {
+    fn from(value: arguments::FeePolicyCondition) -> Self {
+        match value {
+            arguments::FeePolicyCondition::Tokens(tokens) => {
+                Self::Tokens(tokens.into_iter().collect())
+            }
+            arguments::FeePolicyCondition::ExcludeTokens(tokens) => {
+                Self::ExcludeTokens(tokens.into_iter().collect())
+            }
+            arguments::FeePolicyCondition::MinNotional(amount) => Self::MinNotional(amount),
+            arguments::FeePolicyCondition::MaxNotional(amount) => Self::MaxNotional(amount),
+            arguments::FeePolicyCondition::MinVolume(amount) => Self::MinVolume(amount),
+            arguments::FeePolicyCondition::MaxVolume(amount) => Self::MaxVolume(amount),
+        }
+    }
+}
+
+impl Condition {
+    fn holds(&self, order: &boundary::Order, market: &Market) -> bool {
+        let pair_in = |tokens: &HashSet<H160>| {
+            tokens.contains(&order.data.sell_token) && tokens.contains(&order.data.buy_token)
+        };
+        let notional = || market.notional(order);
+        let volume = || market.owner_volume(&order.metadata.owner);
+        match self {
+            Self::Tokens(tokens) => pair_in(tokens),
+            Self::ExcludeTokens(tokens) => !pair_in(tokens),
+            Self::MinNotional(min) => notional().is_some_and(|notional| notional >= *min),
+            Self::MaxNotional(max) => notional().is_some_and(|notional| notional < *max),
+            Self::MinVolume(min) => volume() >= *min,
+            Self::MaxVolume(max) => volume() < *max,
+        }
+    }
+
+    fn depends_on_volume(&self) -> bool {
+        matches!(self, Self::MinVolume(_) | Self::MaxVolume(_))
+    }
+}
+
+/// Market data that conditional fee policies are evaluated against.
+#[derive(Debug)]
+pub struct Market<'a> {
+    /// Native prices of the auction's tokens (wei per 10**18 token atoms).
+    pub prices: &'a BTreeMap<H160, U256>,
+    /// Native token volume each owner traded during the volume window.
+    pub owner_volumes: &'a HashMap<H160, U256>,
+}
+
+impl Market<'_> {
+    /// Value of the order's sell amount in the native token.
+    fn notional(&self, order: &boundary::Order) -> Option<U256> {
+        let price = self.prices.get(&order.data.sell_token)?;
+        order
+            .data
+            .sell_amount
+            .full_mul(*price)
+            .checked_div(U256::exp10(18).into())?
+            .try_into()
+            .ok()
+    }
+
+    fn owner_volume(&self, owner: &H160) -> U256 {
+        self.owner_volumes.get(owner).copied().unwrap_or_default()
+    }
+}
+
 /// Constructs fee policies based on the current configuration.
 pub struct ProtocolFee {
     policy: policy::Policy,
     order_class: OrderClass,
+    conditions: Vec<Condition>,
+    tier: Option<String>,
 }
 
 impl From<arguments::FeePolicy> for ProtocolFee {
@@ -47,6 +128,12 @@ impl From<arguments::FeePolicy> for ProtocolFee {
         Self {
             policy: value.fee_policy_kind.into(),
             order_class: value.fee_policy_order_class.into(),
+            conditions: value
+                .fee_policy_conditions
+                .into_iter()
+                .map(Condition::from)
+                .collect(),
+            tier: value.fee_policy_tier,
         }
     }
 }
@@ -56,12 +143,15 @@ pub type ProtocolFeeExemptAddresses = HashSet<H160>;
 pub struct ProtocolFees {
     fee_policies: Vec<ProtocolFee>,
     max_partner_fee: FeeFactor,
+    /// Number of blocks over which owner volumes are accumulated.
+    volume_window: u64,
 }
 
 impl ProtocolFees {
     pub fn new(
         fee_policies: &[arguments::FeePolicy],
         fee_policy_max_partner_fee: FeeFactor,
+        volume_window: u64,
     ) -> Self {
         Self {
             fee_policies: fee_policies
@@ -70,9 +160,20 @@ impl ProtocolFees {
                 .map(ProtocolFee::from)
                 .collect(),
             max_partner_fee: fee_policy_max_partner_fee,
+            volume_window,
         }
     }
 
+    /// Returns the first block of the owner volume window ending at `block`,
+    /// or `None` if no configured fee policy depends on owner volumes.
+    pub fn volume_window_start(&self, block: u64) -> Option<u64> {
+        self.fee_policies
+            .iter()
+            .flat_map(|fee_policy| &fee_policy.conditions)
+            .any(Condition::depends_on_volume)
+            .then(|| block.saturating_sub(self.volume_window))
+    }
+
     /// Returns the capped aggregated partner fee
     fn get_partner_fee(
         order: &boundary::Order,
@@ -145,7 +246,7 @@ impl ProtocolFees {
                         // Create policy and update accumulator
                         let factor =
                             fee_factor_from_capped(fee_decimal, max_partner_fee, &mut accumulated);
-                        Policy::Volume { factor }
+                        Policy::Volume { factor, tier: None }
                     }
                     app_data::FeePolicy::Surplus {
                         bps,
@@ -163,6 +264,7 @@ impl ProtocolFees {
                         Policy::Surplus {
                             factor,
                             max_volume_factor,
+                            tier: None,
                         }
                     }
                     app_data::FeePolicy::PriceImprovement {
@@ -187,6 +289,7 @@ impl ProtocolFees {
                                 fee: quote.fee.into(),
                                 solver: quote.solver.into(),
                             },
+                            tier: None,
                         }
                     }
                 }
@@ -201,6 +304,7 @@ impl ProtocolFees {
         order: boundary::Order,
         quote: Option<domain::Quote>,
         surplus_capturing_jit_order_owners: &[eth::Address],
+        market: &Market,
     ) -> domain::Order {
         // In case there is no quote, we assume 0 buy amount so that the order ends up
         // being considered out of market price.
@@ -219,7 +323,7 @@ impl ProtocolFees {
             return boundary::order::to_domain(order, partner_fee, quote);
         }
 
-        self.apply_policies(order, reference_quote, partner_fee)
+        self.apply_policies(order, reference_quote, partner_fee, market)
     }
 
     fn apply_policies(
@@ -227,12 +331,13 @@ impl ProtocolFees {
         order: boundary::Order,
         quote: domain::Quote,
         partner_fees: Vec<Policy>,
+        market: &Market,
     ) -> domain::Order {
         let protocol_fees = self
             .fee_policies
             .iter()
-            .filter_map(|fee_policy| Self::protocol_fee_into_policy(&order, &quote, fee_policy))
-            .flat_map(|policy| Self::variant_fee_apply(&order, &quote, policy))
+            .filter(|fee_policy| Self::protocol_fee_applies(&order, &quote, fee_policy, market))
+            .flat_map(|fee_policy| Self::variant_fee_apply(&order, &quote, fee_policy))
             .chain(partner_fees)
             .collect::<Vec<_>>();
         boundary::order::to_domain(order, protocol_fees, Some(quote))
@@ -241,32 +346,39 @@ impl ProtocolFees {
     fn variant_fee_apply(
         order: &boundary::Order,
         quote: &domain::Quote,
-        policy: &policy::Policy,
+        protocol_fee: &ProtocolFee,
     ) -> Option<Policy> {
-        match policy {
-            policy::Policy::Surplus(variant) => variant.apply(order),
-            policy::Policy::PriceImprovement(variant) => variant.apply(order, quote),
-            policy::Policy::Volume(variant) => variant.apply(order),
+        let tier = protocol_fee.tier.clone();
+        match &protocol_fee.policy {
+            policy::Policy::Surplus(variant) => variant.apply(order, tier),
+            policy::Policy::PriceImprovement(variant) => variant.apply(order, quote, tier),
+            policy::Policy::Volume(variant) => variant.apply(order, tier),
         }
     }
 
-    fn protocol_fee_into_policy<'a>(
+    fn protocol_fee_applies(
         order: &boundary::Order,
         quote: &domain::Quote,
-        protocol_fee: &'a ProtocolFee,
-    ) -> Option<&'a policy::Policy> {
+        protocol_fee: &ProtocolFee,
+        market: &Market,
+    ) -> bool {
         let outside_market_price =
             boundary::is_order_outside_market_price(&order.into(), &quote.into(), order.data.kind);
-        match (outside_market_price, &protocol_fee.order_class) {
-            (_, OrderClass::Any) => Some(&protocol_fee.policy),
-            (true, OrderClass::Limit) => Some(&protocol_fee.policy),
-            (false, OrderClass::Market) => Some(&protocol_fee.policy),
-            _ => None,
-        }
+        let class_matches = match (outside_market_price, &protocol_fee.order_class) {
+            (_, OrderClass::Any) => true,
+            (true, OrderClass::Limit) => true,
+            (false, OrderClass::Market) => true,
+            _ => false,
+        };
+        class_matches
+            && protocol_fee
+                .conditions
+                .iter()
+                .all(|condition| condition.holds(order, market))
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Policy {
     /// If the order receives more than limit price, take the protocol fee as a
     /// percentage of the difference. The fee is taken in `sell` token for
@@ -282,6 +394,8 @@ pub enum Policy {
         factor: FeeFactor,
         /// Cap protocol fee with a percentage of the order's volume.
         max_volume_factor: FeeFactor,
+        /// Tier of the configured fee rule that created this policy.
+        tier: Option<String>,
     },
     /// A price improvement corresponds to a situation where the order is
     /// executed at a better price than the top quote. The protocol fee in such
@@ -290,6 +404,7 @@ pub enum Policy {
         factor: FeeFactor,
         max_volume_factor: FeeFactor,
         quote: Quote,
+        tier: Option<String>,
     },
     /// How much of the order's volume should be taken as a protocol fee.
     /// The fee is taken in `sell` token for `sell` orders and in `buy`
@@ -298,9 +413,22 @@ pub enum Policy {
         /// Percentage of the order's volume should be taken as a protocol
         /// fee.
         factor: FeeFactor,
+        tier: Option<String>,
     },
 }
 
+impl Policy {
+    /// Tier of the configured fee rule that created this policy. Unset for
+    /// partner fees and untiered rules.
+    pub fn tier(&self) -> Option<&str> {
+        match self {
+            Self::Surplus { tier, .. }
+            | Self::PriceImprovement { tier, .. }
+            | Self::Volume { tier, .. } => tier.as_deref(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Into)]
 pub struct FeeFactor(f64);
 
@@ -349,7 +477,65 @@ impl Quote {
 
 #[cfg(test)]
 mod test {
-    use {super::*, model::order::OrderMetadata};
+    use {
+        super::*,
+        model::order::{OrderClass as ModelOrderClass, OrderData, OrderMetadata},
+    };
+
+    #[test]
+    fn test_conditional_fee_policies() {
+        let stable =
+            "0x0101010101010101010101010101010101010101;0x0202020202020202020202020202020202020202";
+        let policies = [
+            format!("volume:0.0001:any:tokens={stable}:tier=stable"),
+            format!("volume:0.001:any:excludeTokens={stable}:maxVolume=1000:tier=retail"),
+            format!("volume:0.0005:any:excludeTokens={stable}:minVolume=1000:tier=pro"),
+            "surplus:0.5:0.01:any:minNotional=2000".to_owned(),
+        ]
+        .map(|policy| policy.parse().unwrap());
+        let fees = ProtocolFees::new(&policies, FeeFactor(0.01), 0);
+        assert_eq!(fees.volume_window_start(100), Some(100));
+
+        let prices = BTreeMap::from([
+            (H160([1; 20]), U256::exp10(18)),
+            (H160([3; 20]), U256::exp10(18) * 2),
+        ]);
+        let owner_volumes = HashMap::from([(H160([0xaa; 20]), U256::from(1000))]);
+        let market = Market {
+            prices: &prices,
+            owner_volumes: &owner_volumes,
+        };
+        let tiers = |sell_token: u8, buy_token: u8, owner: u8, sell_amount: u64| {
+            let order = boundary::Order {
+                data: OrderData {
+                    sell_token: H160([sell_token; 20]),
+                    buy_token: H160([buy_token; 20]),
+                    sell_amount: sell_amount.into(),
+                    buy_amount: 1.into(),
+                    ..Default::default()
+                },
+                metadata: OrderMetadata {
+                    owner: H160([owner; 20]),
+                    class: ModelOrderClass::Limit,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            fees.apply(order, None, &[], &market)
+                .protocol_fees
+                .iter()
+                .map(|policy| policy.tier().map(str::to_owned))
+                .collect::<Vec<_>>()
+        };
+        let tier = |tier: &str| Some(tier.to_owned());
+
+        assert_eq!(tiers(1, 2, 0xbb, 100), vec![tier("stable")]);
+        assert_eq!(tiers(1, 3, 0xbb, 100), vec![tier("retail")]);
+        assert_eq!(tiers(1, 3, 0xaa, 100), vec![tier("pro")]);
+        assert_eq!(tiers(3, 1, 0xaa, 1000), vec![tier("pro"), None]);
+        // Orders without a native price never satisfy notional conditions.
+        assert_eq!(tiers(4, 1, 0xbb, 1_000_000), vec![tier("retail")]);
+    }
 
     #[test]
     fn test_get_partner_fee_valid_multiple_fees_not_capped() {
@@ -393,9 +579,11 @@ mod test {
             vec![
                 Policy::Volume {
                     factor: FeeFactor(0.05),
+                    tier: None,
                 },
                 Policy::Volume {
                     factor: FeeFactor(0.2),
+                    tier: None,
                 }
             ]
         );
@@ -467,6 +655,7 @@ mod test {
             result,
             vec![Policy::Volume {
                 factor: FeeFactor(0.0),
+                tier: None,
             }]
         );
     }
@@ -512,9 +701,11 @@ mod test {
             vec![
                 Policy::Volume {
                     factor: FeeFactor(0.0),
+                    tier: None,
                 },
                 Policy::Volume {
                     factor: FeeFactor(0.0),
+                    tier: None,
                 }
             ]
         );
@@ -556,6 +747,7 @@ mod test {
             result,
             vec![Policy::Volume {
                 factor: FeeFactor(0.3),
+                tier: None,
             }]
         );
     }
@@ -604,9 +796,11 @@ mod test {
             vec![
                 Policy::Volume {
                     factor: FeeFactor(0.1),
+                    tier: None,
                 },
                 Policy::Volume {
                     factor: FeeFactor(0.18181818181818182),
+                    tier: None,
                 }
             ]
         );
@@ -661,12 +855,15 @@ mod test {
             vec![
                 Policy::Volume {
                     factor: FeeFactor(0.1),
+                    tier: None,
                 },
                 Policy::Volume {
                     factor: FeeFactor(0.18181818181818182),
+                    tier: None,
                 },
                 Policy::Volume {
                     factor: FeeFactor(0.0),
+                    tier: None,
                 }
             ]
         );
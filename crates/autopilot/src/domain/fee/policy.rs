@@ -50,7 +50,11 @@ impl From<arguments::FeePolicyKind> for Policy {
 }
 
 impl Surplus {
-    pub fn apply(&self, order: &boundary::Order) -> Option<domain::fee::Policy> {
+    pub fn apply(
+        &self,
+        order: &boundary::Order,
+        tier: Option<String>,
+    ) -> Option<domain::fee::Policy> {
         match order.metadata.class {
             boundary::OrderClass::Market => None,
             boundary::OrderClass::Liquidity => None,
@@ -58,6 +62,7 @@ impl Surplus {
                 let policy = domain::fee::Policy::Surplus {
                     factor: self.factor,
                     max_volume_factor: self.max_volume_factor,
+                    tier,
                 };
                 Some(policy)
             }
@@ -70,6 +75,7 @@ impl PriceImprovement {
         &self,
         order: &boundary::Order,
         quote: &domain::Quote,
+        tier: Option<String>,
     ) -> Option<domain::fee::Policy> {
         match order.metadata.class {
             boundary::OrderClass::Market => None,
@@ -78,18 +84,24 @@ impl PriceImprovement {
                 factor: self.factor,
                 max_volume_factor: self.max_volume_factor,
                 quote: Quote::from_domain(quote),
+                tier,
             }),
         }
     }
 }
 
 impl Volume {
-    pub fn apply(&self, order: &boundary::Order) -> Option<domain::fee::Policy> {
+    pub fn apply(
+        &self,
+        order: &boundary::Order,
+        tier: Option<String>,
+    ) -> Option<domain::fee::Policy> {
         match order.metadata.class {
             boundary::OrderClass::Market => None,
             boundary::OrderClass::Liquidity => None,
             boundary::OrderClass::Limit => Some(domain::fee::Policy::Volume {
                 factor: self.factor,
+                tier,
             }),
         }
     }
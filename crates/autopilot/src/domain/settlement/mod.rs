@@ -49,7 +49,9 @@ pub struct Settlement {
     block: eth::BlockNo,
     /// The solver (is different from `tx.from` for smart contract solvers)
     solver: eth::Address,
-    /// The corresponding solver's winning solution UID.
+    /// The corresponding solver's winning solution UID. For settlements
+    /// combining the solutions of multiple solvers, the UID of the best ranked
+    /// member solution.
     solution_uid: i64,
     /// The associated auction.
     auction: Auction,
@@ -153,13 +155,16 @@ impl Settlement {
         persistence: &infra::Persistence,
         chain: &Chain,
     ) -> Result<Self, Error> {
-        let (auction, solver_winning_solutions) = tokio::try_join!(
+        let (auction, solver_winning_solutions, combined_solutions) = tokio::try_join!(
             persistence
                 .get_auction(settled.auction_id)
                 .map_err(Error::from),
             persistence
                 .get_solver_winning_solutions(settled.auction_id, settled.solver)
                 .map_err(Error::from),
+            persistence
+                .get_combined_winning_solutions(settled.auction_id, settled.solver)
+                .map_err(Error::from),
         )?;
 
         if settled.block > auction.block + max_settlement_age(chain) {
@@ -177,6 +182,7 @@ impl Settlement {
         // environment.
         let Some(solution_uid) =
             find_winning_solution_uid(&solver_winning_solutions, &settled.trades)
+                .or_else(|| find_combined_solution_uid(&combined_solutions, &settled.trades))
         else {
             Metrics::get()
                 .inconsistent_settlements
@@ -262,6 +268,23 @@ fn find_winning_solution_uid(
     })
 }
 
+/// Finds the solution UID a combined settlement gets recorded with if the
+/// settled trades are exactly the trades of all solutions merged into it. The
+/// best ranked member solution represents the combined settlement.
+fn find_combined_solution_uid(
+    combined_solutions: &[Solution],
+    settled_trades: &[EncodedTrade],
+) -> Option<i64> {
+    let settled_keys: HashSet<_> = settled_trades.iter().map(trade_to_key).collect();
+    let combined_keys: HashSet<_> = combined_solutions
+        .iter()
+        .flat_map(|solution| solution.orders.iter().map(order_to_key))
+        .collect();
+
+    let solution = combined_solutions.first()?;
+    (settled_keys == combined_keys).then_some(solution.uid)
+}
+
 /// How old (in terms of blocks) a settlement should be, to be considered as a
 /// settlement from another environment.
 ///
@@ -346,7 +369,12 @@ mod tests {
             self,
             auction,
             eth,
-            settlement::{OrderMatchKey, trade_to_key},
+            settlement::{
+                OrderMatchKey,
+                find_combined_solution_uid,
+                find_winning_solution_uid,
+                trade_to_key,
+            },
         },
         ethcontract::BlockId,
         hex_literal::hex,
@@ -883,6 +911,7 @@ mod tests {
                 vec![domain::fee::Policy::Surplus {
                     factor: 0.5f64.try_into().unwrap(),
                     max_volume_factor: 0.01.try_into().unwrap(),
+                    tier: None,
                 }],
             )]),
         };
@@ -1065,11 +1094,8 @@ mod tests {
     }
 
     // https://etherscan.io/tx/0x0ee0a609c54cb006d024a4d009db8751730c064b26524379793144c07c3575b3
-    // A special case where the user order and a liquidity order trade the common
-    // token, where liquidity order is supposed to be executed at its limit price
-    // and without fees.
-    #[tokio::test]
-    async fn settlement_with_liquidity_order_and_user_order() {
+    // Settles a user order and a liquidity order trading a common token.
+    async fn liquidity_order_and_user_order_transaction() -> super::transaction::Transaction {
         let calldata = hex!(
             "
         13d79a0b
@@ -1199,7 +1225,7 @@ mod tests {
         let settlement_contract = eth::Address(eth::H160::from_slice(&hex!(
             "9008d19f58aabd9ed0d60971565aa8510560ab41"
         )));
-        let transaction = super::transaction::Transaction::try_new(
+        super::transaction::Transaction::try_new(
             &domain::eth::Transaction {
                 trace_calls: domain::eth::CallFrame {
                     to: Some(settlement_contract),
@@ -1213,7 +1239,15 @@ mod tests {
             &MockAuthenticator,
         )
         .await
-        .unwrap();
+        .unwrap()
+    }
+
+    // A special case where the user order and a liquidity order trade the common
+    // token, where liquidity order is supposed to be executed at its limit price
+    // and without fees.
+    #[tokio::test]
+    async fn settlement_with_liquidity_order_and_user_order() {
+        let transaction = liquidity_order_and_user_order_transaction().await;
 
         let prices: auction::Prices = From::from([
             (
@@ -1246,6 +1280,7 @@ mod tests {
                 vec![domain::fee::Policy::Surplus {
                     factor: 0.5f64.try_into().unwrap(),
                     max_volume_factor: 0.01.try_into().unwrap(),
+                    tier: None,
                 }],
             )]),
         };
@@ -1265,6 +1300,57 @@ mod tests {
         );
     }
 
+    // The two trades of the transaction stem from the solutions of different
+    // solvers which got merged into a single settlement.
+    #[tokio::test]
+    async fn combined_settlement() {
+        let transaction = liquidity_order_and_user_order_transaction().await;
+
+        let order = |trade: &super::transaction::EncodedTrade| {
+            let key = trade_to_key(trade);
+            let executed = number::conversions::u256_to_big_decimal(&key.executed);
+            database::solver_competition_v2::Order {
+                uid: database::byte_array::ByteArray(trade.uid.0),
+                sell_token: database::byte_array::ByteArray(trade.sell.token.0.0),
+                buy_token: database::byte_array::ByteArray(trade.buy.token.0.0),
+                executed_sell: executed.clone(),
+                executed_buy: executed,
+                side: match trade.side {
+                    auction::order::Side::Sell => database::orders::OrderKind::Sell,
+                    auction::order::Side::Buy => database::orders::OrderKind::Buy,
+                },
+                ..Default::default()
+            }
+        };
+        let solution = |uid, trade| database::solver_competition_v2::Solution {
+            uid,
+            is_winner: true,
+            orders: vec![order(trade)],
+            ..Default::default()
+        };
+        let members = [
+            solution(1, &transaction.trades[0]),
+            solution(3, &transaction.trades[1]),
+        ];
+
+        // None of the solutions settles all trades on its own...
+        assert_eq!(
+            find_winning_solution_uid(&members, &transaction.trades),
+            None
+        );
+        // ...but all of them combined do.
+        assert_eq!(
+            find_combined_solution_uid(&members, &transaction.trades),
+            Some(1)
+        );
+        // A combined settlement has to settle all of its member solutions.
+        assert_eq!(
+            find_combined_solution_uid(&members[..1], &transaction.trades),
+            None
+        );
+        assert_eq!(find_combined_solution_uid(&[], &transaction.trades), None);
+    }
+
     // https://gnosisscan.io/tx/0xf4556c35d421623c63571d1006fd1888932c1b78a6e0f3b9b9590bb9781b02af
     // A special case to reproduce an issue where we don't match a settled trade due
     // to a mismatch in executed token amounts. The smart contract, for
@@ -222,7 +222,7 @@ impl Trade {
         for (i, policy) in policies.iter().enumerate().rev() {
             let fee = current_trade.protocol_fee(policy)?;
             fees.push(ExecutedProtocolFee {
-                policy: *policy,
+                policy: policy.clone(),
                 fee: eth::Asset {
                     token: self.surplus_token(),
                     amount: eth::TokenAmount(fee.0),
@@ -316,6 +316,7 @@ impl Trade {
             fee::Policy::Surplus {
                 factor,
                 max_volume_factor,
+                ..
             } => {
                 let surplus = self.surplus_over_limit_price()?;
                 std::cmp::min(
@@ -327,6 +328,7 @@ impl Trade {
                 factor,
                 max_volume_factor,
                 quote,
+                ..
             } => {
                 let price_improvement = self.price_improvement(quote)?;
                 std::cmp::min(
@@ -334,7 +336,7 @@ impl Trade {
                     self.volume_fee((*max_volume_factor).into())?,
                 )
             }
-            fee::Policy::Volume { factor } => self.volume_fee((*factor).into())?,
+            fee::Policy::Volume { factor, .. } => self.volume_fee((*factor).into())?,
         };
         Ok(fee)
     }
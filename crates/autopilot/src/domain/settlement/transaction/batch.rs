@@ -0,0 +1,187 @@
+//! Combining the `settle` calls of multiple winning solutions of the same
+//! auction into a single settlement transaction.
+//
+// The settlement contract executes all trades with the clearing prices passed
+// in the same call and runs each interaction stage in order. Solutions that
+// trade disjoint sets of tokens therefore don't influence each other's clearing
+// prices and can be appended to one another: tokens and prices get
+// concatenated, the token indices of the appended trades get offset and the
+// interactions of each stage get executed one solution after the other.
+
+use {
+    super::{Error, META_DATA_LEN, tokenized},
+    crate::domain::{auction, eth},
+    std::collections::HashSet,
+};
+
+/// The decoded `settle` call of one or more solutions of the same auction.
+pub struct Batch {
+    auction_id: auction::Id,
+    tokenized: tokenized::Tokenized,
+}
+
+impl Batch {
+    /// Decodes the calldata of a solution as revealed by its driver. Only
+    /// plain `settle` calls are supported, i.e. solutions which need to be
+    /// wrapped into a flashloan can't be combined.
+    pub fn try_new(calldata: &eth::Calldata) -> Result<Self, Error> {
+        let (data, metadata) = calldata.0.split_at(
+            calldata
+                .0
+                .len()
+                .checked_sub(META_DATA_LEN)
+                .ok_or(Error::MissingAuctionId)?,
+        );
+        let auction_id = metadata
+            .try_into()
+            .map(auction::Id::from_be_bytes)
+            .map_err(|_| Error::MissingAuctionId)?;
+        let tokenized = tokenized::Tokenized::try_new(&crate::util::Bytes(data.to_vec()))?;
+        Ok(Self {
+            auction_id,
+            tokenized,
+        })
+    }
+
+    pub fn auction_id(&self) -> auction::Id {
+        self.auction_id
+    }
+
+    /// All tokens the batch provides clearing prices for.
+    pub fn tokens(&self) -> HashSet<eth::TokenAddress> {
+        self.tokenized
+            .tokens
+            .iter()
+            .map(|token| eth::TokenAddress(*token))
+            .collect()
+    }
+
+    /// Appends the trades and interactions of `other` to this batch.
+    pub fn append(&mut self, other: Self) -> Result<(), error::Append> {
+        if self.auction_id != other.auction_id {
+            return Err(error::Append::DifferentAuction);
+        }
+        if !self.tokens().is_disjoint(&other.tokens()) {
+            return Err(error::Append::OverlappingTokens);
+        }
+
+        let offset = eth::U256::from(self.tokenized.tokens.len());
+        let tokenized::Tokenized {
+            tokens,
+            clearing_prices,
+            trades,
+            interactions,
+        } = other.tokenized;
+        self.tokenized.tokens.extend(tokens);
+        self.tokenized.clearing_prices.extend(clearing_prices);
+        self.tokenized
+            .trades
+            .extend(trades.into_iter().map(|mut trade| {
+                trade.0 += offset;
+                trade.1 += offset;
+                trade
+            }));
+        for (stage, interactions) in self.tokenized.interactions.iter_mut().zip(interactions) {
+            stage.extend(interactions);
+        }
+        Ok(())
+    }
+
+    /// Encodes the batch into `settle` calldata with the auction id appended.
+    pub fn encode(self) -> eth::Calldata {
+        let mut calldata = self.tokenized.encode();
+        calldata.extend(self.auction_id.to_be_bytes());
+        calldata.into()
+    }
+}
+
+pub mod error {
+    #[derive(Debug, thiserror::Error)]
+    pub enum Append {
+        #[error("solutions belong to different auctions")]
+        DifferentAuction,
+        #[error("solutions provide clearing prices for the same tokens")]
+        OverlappingTokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::Bytes};
+
+    fn batch(auction_id: auction::Id, tokens: &[u8], interactions: usize) -> Batch {
+        let address = |byte| eth::H160([byte; 20]);
+        Batch {
+            auction_id,
+            tokenized: tokenized::Tokenized {
+                tokens: tokens.iter().copied().map(address).collect(),
+                clearing_prices: tokens
+                    .iter()
+                    .map(|byte| eth::U256::from(*byte).into())
+                    .collect(),
+                trades: vec![(
+                    0.into(),
+                    1.into(),
+                    address(0xaa),
+                    100.into(),
+                    90.into(),
+                    u32::MAX,
+                    Bytes([0; 32]),
+                    0.into(),
+                    0.into(),
+                    100.into(),
+                    Bytes(vec![0; 65]),
+                )],
+                interactions: std::array::from_fn(|_| {
+                    (0..interactions)
+                        .map(|_| (address(0xbb), 0.into(), Bytes(vec![1, 2, 3])))
+                        .collect()
+                }),
+            },
+        }
+    }
+
+    #[test]
+    fn appends_disjoint_batches() {
+        let mut combined = batch(1, &[1, 2], 1);
+        combined.append(batch(1, &[3, 4, 3], 2)).unwrap();
+
+        let decoded = Batch::try_new(&combined.encode()).unwrap();
+        assert_eq!(decoded.auction_id(), 1);
+        assert_eq!(
+            decoded.tokenized.tokens,
+            [1, 2, 3, 4, 3].map(|byte| eth::H160([byte; 20]))
+        );
+        assert_eq!(
+            decoded.tokenized.clearing_prices,
+            [1, 2, 3, 4, 3].map(|price| eth::U256::from(price).into())
+        );
+        let indices = decoded
+            .tokenized
+            .trades
+            .iter()
+            .map(|trade| (trade.0.as_usize(), trade.1.as_usize()))
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [(0, 1), (2, 3)]);
+        assert!(
+            decoded
+                .tokenized
+                .interactions
+                .iter()
+                .all(|stage| stage.len() == 3)
+        );
+    }
+
+    #[test]
+    fn rejects_overlapping_batches() {
+        let mut combined = batch(1, &[1, 2], 0);
+        assert!(matches!(
+            combined.append(batch(1, &[2, 3], 0)),
+            Err(error::Append::OverlappingTokens)
+        ));
+        assert!(matches!(
+            combined.append(batch(2, &[3, 4], 0)),
+            Err(error::Append::DifferentAuction)
+        ));
+    }
+}
@@ -7,8 +7,15 @@ use {
     std::{collections::HashSet, sync::LazyLock},
 };
 
+mod batch;
 mod tokenized;
 
+pub use batch::Batch;
+
+/// Number of bytes that may be appended to the calldata to store an auction
+/// id.
+const META_DATA_LEN: usize = 8;
+
 /// The following trait allows to implement custom solver authentication logic
 /// for transactions.
 #[async_trait::async_trait]
@@ -84,10 +91,6 @@ impl Transaction {
         let block = BlockId::Number(transaction.block.0.into());
         let solver = find_solver_address(authenticator, callers, block).await?;
 
-        /// Number of bytes that may be appended to the calldata to store an
-        /// auction id.
-        const META_DATA_LEN: usize = 8;
-
         let (data, metadata) = calldata.0.split_at(
             calldata
                 .0
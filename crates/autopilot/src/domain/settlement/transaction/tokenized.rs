@@ -39,6 +39,27 @@ impl Tokenized {
             interactions,
         })
     }
+
+    /// Encodes the `settle` function call.
+    pub fn encode(self) -> Vec<u8> {
+        let function = contracts::GPv2Settlement::raw_contract()
+            .interface
+            .abi
+            .function("settle")
+            .unwrap();
+        let solution: Solution = (
+            self.tokens,
+            self.clearing_prices.into_iter().map(Into::into).collect(),
+            self.trades,
+            self.interactions,
+        );
+        let web3::ethabi::Token::Tuple(tokens) = solution.into_token() else {
+            unreachable!("solution is a tuple")
+        };
+        function
+            .encode_input(&tokens)
+            .expect("tokens match the function signature")
+    }
 }
 
 type Token = Address;
@@ -13,6 +13,7 @@ pub fn from_domain(
         domain::fee::Policy::Surplus {
             factor,
             max_volume_factor,
+            tier,
         } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
@@ -22,8 +23,9 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tier,
         },
-        domain::fee::Policy::Volume { factor } => FeePolicy {
+        domain::fee::Policy::Volume { factor, tier } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
             kind: FeePolicyKind::Volume,
@@ -32,11 +34,13 @@ pub fn from_domain(
             volume_factor: Some(factor.into()),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tier,
         },
         domain::fee::Policy::PriceImprovement {
             factor,
             max_volume_factor,
             quote: _,
+            tier,
         } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
@@ -46,6 +50,7 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: Some(factor.into()),
             price_improvement_max_volume_factor: Some(max_volume_factor.into()),
+            tier,
         },
     }
 }
@@ -64,12 +69,14 @@ pub fn try_into_domain(
                 .surplus_max_volume_factor
                 .context("missing surplus_max_volume_factor")?
                 .try_into()?,
+            tier: policy.tier,
         },
         FeePolicyKind::Volume => domain::fee::Policy::Volume {
             factor: policy
                 .volume_factor
                 .context("missing volume_factor")?
                 .try_into()?,
+            tier: policy.tier,
         },
         FeePolicyKind::PriceImprovement => domain::fee::Policy::PriceImprovement {
             factor: policy
@@ -89,6 +96,7 @@ pub fn try_into_domain(
                     solver: quote.solver.into(),
                 }
             },
+            tier: policy.tier,
         },
     };
     Ok(policy)
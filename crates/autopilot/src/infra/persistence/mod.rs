@@ -1,7 +1,10 @@
 use {
     crate::{
         boundary,
-        database::{Postgres, order_events::store_order_events},
+        database::{
+            Postgres,
+            order_events::{store_order_events, store_order_events_with_reasons},
+        },
         domain::{self, eth},
         infra::persistence::dto::AuctionId,
     },
@@ -19,7 +22,7 @@ use {
             SigningScheme as DbSigningScheme,
         },
         settlement_observations::Observation,
-        solver_competition_v2::{Order, Solution},
+        solver_competition_v2::{Order, ShadowReferenceScore, ShadowSolution, Solution},
     },
     domain::auction::order::{
         BuyTokenDestination as DomainBuyTokenDestination,
@@ -27,6 +30,7 @@ use {
         SigningScheme as DomainSigningScheme,
     },
     futures::{StreamExt, TryStreamExt},
+    model::order::OrderFilterReason,
     number::conversions::{big_decimal_to_u256, u256_to_big_decimal, u256_to_big_uint},
     primitive_types::H256,
     shared::db_order_conversions::full_order_into_model_order,
@@ -192,6 +196,125 @@ impl Persistence {
         Ok(ex.commit().await?)
     }
 
+    /// Records which winning solutions got merged into the combined settlement
+    /// submitted by `submitter`. `solutions` have to be passed in the same
+    /// order as to [`Self::save_solutions`] so the members refer to the right
+    /// solution uids.
+    pub async fn save_combined_settlement(
+        &self,
+        auction_id: domain::auction::Id,
+        submitter: eth::Address,
+        solutions: impl Iterator<Item = &domain::competition::Participant>,
+        members: &[&domain::competition::Participant],
+    ) -> Result<(), DatabaseError> {
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["save_combined_settlement"])
+            .start_timer();
+
+        let uids = solutions
+            .enumerate()
+            .map(|(uid, participant)| {
+                let key = (participant.solution().solver(), participant.solution().id());
+                Ok((key, i64::try_from(uid).context("uid overflow")?))
+            })
+            .collect::<Result<HashMap<_, _>, DatabaseError>>()?;
+        let solution_uids = members
+            .iter()
+            .map(|member| {
+                let key = (member.solution().solver(), member.solution().id());
+                uids.get(&key)
+                    .copied()
+                    .context("combined solution is not part of the competition")
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        database::solver_competition_v2::save_combined_settlement(
+            &mut ex,
+            auction_id,
+            ByteArray(submitter.0.0),
+            &solution_uids,
+        )
+        .await
+        .context("solver_competition_v2::save_combined_settlement")?;
+        Ok(())
+    }
+
+    /// Saves how the winner selection mechanisms running in shadow mode ranked
+    /// the solutions. `solutions` have to be passed in the same order as to
+    /// [`Self::save_solutions`] so shadow rankings refer to the same solution
+    /// uids.
+    pub async fn save_shadow_rankings(
+        &self,
+        auction_id: domain::auction::Id,
+        solutions: impl Iterator<Item = &domain::competition::Participant>,
+        shadow_rankings: &[domain::competition::winner_selection::ShadowRanking],
+    ) -> Result<(), DatabaseError> {
+        if shadow_rankings.is_empty() {
+            return Ok(());
+        }
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["save_shadow_rankings"])
+            .start_timer();
+
+        let uids = solutions
+            .enumerate()
+            .map(|(uid, participant)| {
+                let key = (participant.solution().solver(), participant.solution().id());
+                Ok((key, i64::try_from(uid).context("uid overflow")?))
+            })
+            .collect::<Result<HashMap<_, _>, DatabaseError>>()?;
+
+        let mut solutions = Vec::new();
+        let mut reference_scores = Vec::new();
+        for shadow in shadow_rankings {
+            let arbitrator = shadow.arbitrator.to_string();
+            for (index, participant) in shadow.ranking.all().enumerate() {
+                let solution = participant.solution();
+                let Some(&solution_uid) = uids.get(&(solution.solver(), solution.id())) else {
+                    tracing::warn!(
+                        %arbitrator,
+                        solver = ?solution.solver(),
+                        id = solution.id(),
+                        "shadow ranking contains unknown solution"
+                    );
+                    continue;
+                };
+                let score = solution
+                    .computed_score()
+                    .copied()
+                    .unwrap_or_else(|| solution.score());
+                solutions.push(ShadowSolution {
+                    arbitrator: arbitrator.clone(),
+                    solution_uid,
+                    ranking: i64::try_from(index + 1).context("ranking overflow")?,
+                    is_winner: participant.is_winner(),
+                    filtered_out: participant.filtered_out(),
+                    score: u256_to_big_decimal(&score.get().0),
+                });
+            }
+            reference_scores.extend(shadow.reference_scores.iter().map(|(solver, score)| {
+                ShadowReferenceScore {
+                    arbitrator: arbitrator.clone(),
+                    solver: ByteArray(solver.0.0),
+                    reference_score: u256_to_big_decimal(&score.get().0),
+                }
+            }));
+        }
+
+        let mut ex = self.postgres.pool.begin().await?;
+        database::solver_competition_v2::save_shadow_rankings(
+            &mut ex,
+            auction_id,
+            &solutions,
+            &reference_scores,
+        )
+        .await?;
+        Ok(ex.commit().await?)
+    }
+
     /// Saves the surplus capturing jit order owners to the DB
     pub async fn save_surplus_capturing_jit_order_owners(
         &self,
@@ -231,6 +354,30 @@ impl Persistence {
         );
     }
 
+    /// Like [`Self::store_order_events`] but additionally stores why the
+    /// orders got filtered.
+    pub fn store_order_events_with_reasons(
+        &self,
+        events: impl IntoIterator<Item = (domain::OrderUid, OrderFilterReason)>,
+        label: boundary::OrderEventLabel,
+    ) {
+        let db = self.postgres.clone();
+        let events = events
+            .into_iter()
+            .map(|(uid, reason)| {
+                let reason = serde_json::to_value(reason).expect("reasons are serializable");
+                (uid, Some(reason))
+            })
+            .collect();
+        tokio::spawn(
+            async move {
+                let mut tx = db.pool.acquire().await.expect("failed to acquire tx");
+                store_order_events_with_reasons(&mut tx, events, label, Utc::now()).await;
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+
     /// Saves the given fee policies to the DB as a single batch.
     pub async fn store_fee_policies(
         &self,
@@ -252,6 +399,38 @@ impl Persistence {
         ex.commit().await.context("commit")
     }
 
+    /// Returns the native token volume each of the given owners traded since
+    /// `from_block`. Owners without trades are omitted.
+    pub async fn owner_volumes(
+        &self,
+        owners: impl IntoIterator<Item = eth::Address>,
+        from_block: u64,
+    ) -> anyhow::Result<HashMap<eth::Address, eth::U256>> {
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["owner_volumes"])
+            .start_timer();
+
+        let owners = owners
+            .into_iter()
+            .map(|owner| ByteArray(owner.0.0))
+            .collect::<Vec<_>>();
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        database::trades::owner_volumes(
+            &mut ex,
+            &owners,
+            i64::try_from(from_block).context("block number overflow")?,
+        )
+        .await
+        .context("trades::owner_volumes")?
+        .into_iter()
+        .map(|row| {
+            let volume = big_decimal_to_u256(&row.volume).context("volume is not a valid U256")?;
+            Ok((eth::H160(row.owner.0).into(), volume))
+        })
+        .collect()
+    }
+
     /// For a given auction and solver, tries to find the settlement
     /// transaction.
     pub async fn find_settlement_transaction(
@@ -440,6 +619,52 @@ impl Persistence {
         })
     }
 
+    /// Reconstructs the auction with the given id as it was sent to the
+    /// solvers so it can be replayed.
+    ///
+    /// Orders are loaded in their current state, so the executed amounts of
+    /// partially fillable orders may differ from the original auction.
+    pub async fn reconstruct_auction(
+        &self,
+        auction_id: domain::auction::Id,
+    ) -> anyhow::Result<domain::Auction> {
+        let auction = self.get_auction(auction_id).await?;
+
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["reconstruct_auction"])
+            .start_timer();
+        let mut ex = self.postgres.pool.acquire().await?;
+        let mut orders = Vec::with_capacity(auction.orders.len());
+        for (uid, protocol_fees) in auction.orders {
+            let (order, quote) =
+                database::orders::single_full_order_with_quote(&mut ex, &ByteArray(uid.0))
+                    .await?
+                    .with_context(|| format!("order {uid} of auction not found"))?
+                    .into_order_and_quote();
+            let quote = quote
+                .map(dto::quote::into_domain)
+                .transpose()
+                .with_context(|| format!("invalid quote for order {uid}"))?;
+            orders.push(boundary::order::to_domain(
+                full_order_into_model_order(order)?,
+                protocol_fees,
+                quote,
+            ));
+        }
+
+        Ok(domain::Auction {
+            id: auction_id,
+            block: auction.block.0,
+            orders,
+            prices: auction.prices,
+            surplus_capturing_jit_order_owners: auction
+                .surplus_capturing_jit_order_owners
+                .into_iter()
+                .collect(),
+        })
+    }
+
     /// Computes solvable orders based on the latest observed block number,
     /// order creation timestamp, and minimum validity period.
     pub async fn solvable_orders_after(
@@ -939,6 +1164,30 @@ impl Persistence {
             .context("solver_competition::fetch_solver_winning_solutions")?,
         )
     }
+
+    /// Fetches the winning solutions which got merged into the combined
+    /// settlement submitted by `submitter`.
+    pub async fn get_combined_winning_solutions(
+        &self,
+        auction_id: domain::auction::Id,
+        submitter: eth::Address,
+    ) -> Result<Vec<Solution>, DatabaseError> {
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["fetch_combined_winning_solutions"])
+            .start_timer();
+
+        Ok(
+            database::solver_competition_v2::fetch_combined_winning_solutions(
+                &mut ex,
+                auction_id,
+                ByteArray(submitter.0.0),
+            )
+            .await
+            .context("solver_competition::fetch_combined_winning_solutions")?,
+        )
+    }
 }
 
 #[derive(prometheus_metric_storage::MetricStorage)]
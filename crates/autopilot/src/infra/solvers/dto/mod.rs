@@ -4,4 +4,5 @@
 pub mod notify;
 pub mod reveal;
 pub mod settle;
+pub mod settle_combined;
 pub mod solve;
@@ -0,0 +1,20 @@
+use {
+    serde::Serialize,
+    serde_with::{serde_as, skip_serializing_none},
+};
+
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// Auction ID in which the combined solutions won.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub auction_id: i64,
+    /// `settle` calldata combining multiple winning solutions, including the
+    /// appended auction ID.
+    #[serde(with = "bytes_hex")]
+    pub calldata: Vec<u8>,
+    /// The last block number in which the settlement TX can be included
+    pub submission_deadline_latest_block: u64,
+}
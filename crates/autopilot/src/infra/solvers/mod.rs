@@ -1,5 +1,5 @@
 use {
-    self::dto::{reveal, settle, solve},
+    self::dto::{reveal, settle, settle_combined, solve},
     crate::{arguments::Account, domain::eth, infra::solvers::dto::notify, util},
     anyhow::{Context, Result, anyhow},
     chrono::{DateTime, Utc},
@@ -91,7 +91,29 @@ impl Driver {
         request: &settle::Request,
         timeout: std::time::Duration,
     ) -> Result<()> {
-        let url = util::join(&self.url, "settle");
+        self.submit("settle", request, request.auction_id, timeout)
+            .await
+    }
+
+    /// Asks the driver to submit a settlement combining the winning solutions
+    /// of multiple solvers.
+    pub async fn settle_combined(
+        &self,
+        request: &settle_combined::Request,
+        timeout: std::time::Duration,
+    ) -> Result<()> {
+        self.submit("settle-combined", request, request.auction_id, timeout)
+            .await
+    }
+
+    async fn submit(
+        &self,
+        path: &str,
+        request: &impl serde::Serialize,
+        auction_id: i64,
+        timeout: std::time::Duration,
+    ) -> Result<()> {
+        let url = util::join(&self.url, path);
         tracing::trace!(
             path=&url.path(),
             body=%serde_json::to_string_pretty(request).unwrap(),
@@ -103,7 +125,7 @@ impl Driver {
             .post(url)
             .json(request)
             .timeout(timeout)
-            .header("X-REQUEST-ID", request.auction_id.to_string())
+            .header("X-REQUEST-ID", auction_id.to_string())
             .headers(tracing_headers())
             .send()
             .await
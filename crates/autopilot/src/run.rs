@@ -140,6 +140,12 @@ pub async fn start(args: impl Iterator<Item = String>) {
     if args.drivers.is_empty() {
         panic!("colocation is enabled but no drivers are configured");
     }
+    if let Some(name) = &args.combined_batch_driver {
+        assert!(
+            args.drivers.iter().any(|driver| &driver.name == name),
+            "combined batch driver {name} is not configured"
+        );
+    }
 
     if args.shadow.is_some() {
         shadow_mode(args).await;
@@ -464,6 +470,7 @@ pub async fn run(args: Arguments) {
         balance_fetcher.clone(),
         args.price_estimation.quote_verification,
         args.price_estimation.quote_timeout,
+        bad_token_detector.clone(),
     ));
 
     let solvable_orders_cache = SolvableOrdersCache::new(
@@ -481,7 +488,16 @@ pub async fn run(args: Arguments) {
         args.limit_order_price_factor
             .try_into()
             .expect("limit order price factor can't be converted to BigDecimal"),
-        domain::ProtocolFees::new(&args.fee_policies, args.fee_policy_max_partner_fee),
+        domain::ProtocolFees::new(
+            &args.fee_policies,
+            args.fee_policy_max_partner_fee,
+            chain.blocks_in(
+                args.fee_policy_volume_window
+                    .as_millis()
+                    .try_into()
+                    .expect("volume window fits into u64"),
+            ) as u64,
+        ),
         cow_amm_registry.clone(),
         args.run_loop_native_price_timeout,
         eth.contracts().settlement().address(),
@@ -588,6 +604,13 @@ pub async fn run(args: Arguments) {
         combinatorial_auctions_cutover: args.combinatorial_auctions_cutover,
         max_winners_per_auction: args.max_winners_per_auction,
         max_solutions_per_solver: args.max_solutions_per_solver,
+        arbitrator: args.arbitrator.map(Into::into),
+        shadow_arbitrators: args
+            .shadow_arbitrators
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        combined_batch_driver: args.combined_batch_driver,
     };
 
     let drivers_futures = args
@@ -7,18 +7,20 @@ use {
             auction::Id,
             competition::{
                 self,
+                Participant,
+                Ranked,
                 Solution,
                 SolutionError,
                 SolverParticipationGuard,
                 Unranked,
-                winner_selection::{self, Ranking},
+                winner_selection::{self, Ranking, ShadowRanking},
             },
             eth::{self, TxId},
-            settlement::{ExecutionEnded, ExecutionStarted},
+            settlement::{ExecutionEnded, ExecutionStarted, transaction::Batch},
         },
         infra::{
             self,
-            solvers::dto::{settle, solve},
+            solvers::dto::{reveal, settle, settle_combined, solve},
         },
         maintenance::Maintenance,
         run::Liveness,
@@ -28,7 +30,7 @@ use {
     anyhow::{Context, Result},
     database::order_events::OrderEventLabel,
     ethrpc::block_stream::BlockInfo,
-    futures::{FutureExt, TryFutureExt},
+    futures::{FutureExt, TryFutureExt, future::BoxFuture},
     itertools::Itertools,
     model::solver_competition::{
         CompetitionAuction,
@@ -62,9 +64,27 @@ pub struct Config {
     pub combinatorial_auctions_cutover: Option<chrono::DateTime<chrono::Utc>>,
     pub max_winners_per_auction: NonZeroUsize,
     pub max_solutions_per_solver: NonZeroUsize,
+    /// Overrides the winner selection mechanism that would otherwise be picked
+    /// based on `combinatorial_auctions_cutover`.
+    pub arbitrator: Option<winner_selection::Kind>,
+    /// Winner selection mechanisms whose rankings only get recorded.
+    pub shadow_arbitrators: Vec<winner_selection::Kind>,
+    /// Name of the driver submitting settlements which combine the winning
+    /// solutions of multiple solvers. Combining is disabled if unset.
+    pub combined_batch_driver: Option<String>,
 }
 
 impl Config {
+    /// The winner selection mechanism deciding the winners of an auction
+    /// starting now.
+    fn arbitrator(&self) -> winner_selection::Kind {
+        match self.arbitrator {
+            Some(arbitrator) => arbitrator,
+            None if self.single_winner() => winner_selection::Kind::MaxScore,
+            None => winner_selection::Kind::Combinatorial,
+        }
+    }
+
     fn single_winner(&self) -> bool {
         // Always single winner if max_winners is 1
         if self.max_winners_per_auction.get() == 1 {
@@ -268,17 +288,31 @@ impl RunLoop {
         // Build the winner selection implementation.
         // We only compute this once to ensure consistency throughout the entire
         // auction.
-        let is_single_winner_selection = self.config.single_winner();
-        tracing::info!(auction_id = ?auction.id, ?is_single_winner_selection, "winner selection implementation");
-        let winner_selection: Box<dyn winner_selection::Arbitrator> = if is_single_winner_selection
-        {
-            Box::new(winner_selection::max_score::Config)
-        } else {
-            Box::new(winner_selection::combinatorial::Config {
-                max_winners: self.config.max_winners_per_auction.get(),
-                weth: self.eth.contracts().wrapped_native_token(),
+        let arbitrator = self.config.arbitrator();
+        let is_single_winner_selection = arbitrator == winner_selection::Kind::MaxScore;
+        tracing::info!(auction_id = ?auction.id, %arbitrator, "winner selection implementation");
+        let max_winners = self.config.max_winners_per_auction.get();
+        let weth = self.eth.contracts().wrapped_native_token();
+        let winner_selection = arbitrator.arbitrator(max_winners, weth);
+
+        // Shadow mechanisms run on the same solutions but their rankings only
+        // get stored to be compared with the actual one.
+        let shadow_rankings = self
+            .config
+            .shadow_arbitrators
+            .iter()
+            .filter(|shadow| **shadow != arbitrator)
+            .map(|&shadow| {
+                let shadow_selection = shadow.arbitrator(max_winners, weth);
+                let ranking = shadow_selection.arbitrate(solutions.clone(), &auction);
+                let reference_scores = shadow_selection.compute_reference_scores(&ranking);
+                ShadowRanking {
+                    arbitrator: shadow,
+                    ranking,
+                    reference_scores,
+                }
             })
-        };
+            .collect::<Vec<_>>();
 
         let ranking = winner_selection.arbitrate(solutions, &auction);
 
@@ -301,6 +335,7 @@ impl RunLoop {
                 block_deadline,
                 winner_selection,
                 is_single_winner_selection,
+                &shadow_rankings,
             )
             .await
         {
@@ -325,7 +360,46 @@ impl RunLoop {
             OrderEventLabel::Considered,
         );
 
-        for winner in ranking.winners() {
+        let (combined, separate_winners) = self
+            .combine_winners(auction.id, ranking.winners().collect())
+            .await;
+        let winners = match combined {
+            Some(combined) => {
+                // The settlement observer needs to know the members of the
+                // combined settlement to attribute it to their solutions.
+                match self
+                    .persistence
+                    .save_combined_settlement(
+                        auction.id,
+                        combined.driver.submission_address,
+                        ranking.all(),
+                        &combined.winners,
+                    )
+                    .await
+                {
+                    Ok(()) => {
+                        self.start_combined_settlement_execution(
+                            auction.id,
+                            single_run_start,
+                            combined,
+                            block_deadline,
+                        )
+                        .await;
+                        separate_winners
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            ?err,
+                            "failed to store combined settlement, settling winners separately"
+                        );
+                        ranking.winners().collect()
+                    }
+                }
+            }
+            None => separate_winners,
+        };
+
+        for winner in winners {
             let (driver, solution) = (winner.driver(), winner.solution());
             tracing::info!(driver = %driver.name, solution = %solution.id(), "winner");
 
@@ -397,6 +471,7 @@ impl RunLoop {
         tokio::spawn(settle_fut);
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn post_processing(
         &self,
         auction: &domain::Auction,
@@ -405,6 +480,7 @@ impl RunLoop {
         block_deadline: u64,
         winner_selection: Box<dyn winner_selection::Arbitrator>,
         is_single_winner_selection: bool,
+        shadow_rankings: &[ShadowRanking],
     ) -> Result<()> {
         let start = Instant::now();
         let reference_scores = winner_selection.compute_reference_scores(ranking);
@@ -528,6 +604,9 @@ impl RunLoop {
             self.persistence
                 .save_solutions(auction.id, ranking.all())
                 .map_err(|e| e.0.context("failed to save solutions")),
+            self.persistence
+                .save_shadow_rankings(auction.id, ranking.all(), shadow_rankings)
+                .map_err(|e| e.0.context("failed to save shadow rankings")),
         ) {
             Ok(_) => {
                 // Notify the solver participation guard that the proposed solutions have been
@@ -693,6 +772,244 @@ impl RunLoop {
         Ok(response.into_domain())
     }
 
+    /// Merges the winning solutions of different solvers trading disjoint
+    /// orders and tokens into a single settlement to be submitted by the
+    /// configured combined batch driver. Winners are considered in the order
+    /// of their ranking.
+    ///
+    /// Returns the combined settlement (if at least two solutions could be
+    /// merged) and the winners which have to settle on their own.
+    async fn combine_winners<'a>(
+        &self,
+        auction_id: Id,
+        winners: Vec<&'a Participant<Ranked>>,
+    ) -> (Option<CombinedBatch<'a>>, Vec<&'a Participant<Ranked>>) {
+        let Some(driver) = self.config.combined_batch_driver.as_ref().and_then(|name| {
+            self.drivers
+                .iter()
+                .find(|driver| &driver.name == name)
+                .cloned()
+        }) else {
+            return (None, winners);
+        };
+        if winners.len() < 2 {
+            return (None, winners);
+        }
+
+        let batches = futures::future::join_all(winners.iter().map(|winner| async move {
+            let request = reveal::Request {
+                solution_id: winner.solution().id(),
+                auction_id,
+            };
+            let response = winner.driver().reveal(request).await.context("reveal")?;
+            // Drivers submit their settlements with internalized interactions.
+            // Since the combined solutions trade disjoint tokens they can't
+            // compete for the same buffers.
+            let calldata: eth::Calldata = response.calldata.internalized.into();
+            anyhow::Ok(Batch::try_new(&calldata)?)
+        }))
+        .await;
+
+        let mut combined: Option<Batch> = None;
+        let mut orders = HashSet::new();
+        let (mut included, mut excluded) = (Vec::new(), Vec::new());
+        for (winner, batch) in winners.iter().copied().zip(batches) {
+            let batch = match batch {
+                Ok(batch) if batch.auction_id() == auction_id => batch,
+                Ok(_) => {
+                    tracing::warn!(driver = %winner.driver().name, "revealed calldata for wrong auction");
+                    excluded.push(winner);
+                    continue;
+                }
+                Err(err) => {
+                    tracing::debug!(?err, driver = %winner.driver().name, "solution can't be combined");
+                    excluded.push(winner);
+                    continue;
+                }
+            };
+            if winner
+                .solution()
+                .order_ids()
+                .any(|order| orders.contains(order))
+            {
+                excluded.push(winner);
+                continue;
+            }
+            let appended = match combined.as_mut() {
+                Some(combined) => combined.append(batch),
+                None => {
+                    combined = Some(batch);
+                    Ok(())
+                }
+            };
+            match appended {
+                Ok(()) => {
+                    orders.extend(winner.solution().order_ids().copied());
+                    included.push(winner);
+                }
+                Err(err) => {
+                    tracing::debug!(?err, driver = %winner.driver().name, "solution can't be combined");
+                    excluded.push(winner);
+                }
+            }
+        }
+
+        // The combined settlement gets attributed to the solver of the
+        // designated driver, so it can't settle a solution of its own in the
+        // same auction.
+        if excluded
+            .iter()
+            .any(|winner| winner.solution().solver() == driver.submission_address)
+        {
+            tracing::debug!(driver = %driver.name, "submitter settles separately, not combining");
+            return (None, winners);
+        }
+
+        match combined {
+            Some(combined) if included.len() > 1 => {
+                tracing::info!(
+                    driver = %driver.name,
+                    solvers = ?included.iter().map(|winner| &winner.driver().name).collect::<Vec<_>>(),
+                    "combined winning solutions"
+                );
+                Metrics::get()
+                    .combined_batch_size
+                    .observe(included.len() as f64);
+                let batch = CombinedBatch {
+                    driver,
+                    calldata: combined.encode(),
+                    winners: included,
+                };
+                (Some(batch), excluded)
+            }
+            _ => (None, winners),
+        }
+    }
+
+    /// Starts the execution of a combined settlement in a background task. The
+    /// function is async only to get access to the locks.
+    async fn start_combined_settlement_execution(
+        self: &Arc<Self>,
+        auction_id: Id,
+        single_run_start: Instant,
+        batch: CombinedBatch<'_>,
+        block_deadline: u64,
+    ) {
+        let solved_order_uids: HashSet<_> = batch
+            .winners
+            .iter()
+            .flat_map(|winner| winner.solution().order_ids().copied())
+            .collect();
+        self.in_flight_orders
+            .lock()
+            .await
+            .extend(solved_order_uids.clone());
+
+        let solvers: Vec<_> = batch
+            .winners
+            .iter()
+            .map(|winner| winner.solution().solver())
+            .collect();
+        let self_ = self.clone();
+        let (driver, calldata) = (batch.driver, batch.calldata);
+
+        let settle_fut = async move {
+            tracing::info!(driver = %driver.name, ?solvers, "settling combined batch");
+            let submission_start = Instant::now();
+
+            match self_
+                .settle_combined(
+                    &driver,
+                    calldata,
+                    solved_order_uids.clone(),
+                    &solvers,
+                    auction_id,
+                    block_deadline,
+                )
+                .await
+            {
+                Ok(tx_hash) => {
+                    Metrics::settle_ok(
+                        &driver,
+                        solved_order_uids.len(),
+                        submission_start.elapsed(),
+                    );
+                    tracing::debug!(?tx_hash, driver = %driver.name, ?solvers, "combined batch settled");
+                }
+                Err(err) => {
+                    Metrics::settle_err(&driver, submission_start.elapsed(), &err);
+                    tracing::warn!(?err, driver = %driver.name, "combined settlement failed");
+                }
+            }
+            Metrics::single_run_completed(single_run_start.elapsed());
+        }
+        .instrument(tracing::Span::current());
+
+        tokio::spawn(settle_fut);
+    }
+
+    /// Executes the combined settlement. Returns Ok when the corresponding
+    /// transaction has been mined.
+    async fn settle_combined(
+        &self,
+        driver: &infra::Driver,
+        calldata: eth::Calldata,
+        solved_order_uids: HashSet<OrderUid>,
+        solvers: &[eth::Address],
+        auction_id: i64,
+        submission_deadline_latest_block: u64,
+    ) -> Result<TxId, SettleError> {
+        let settle = async move {
+            let current_block = self.eth.current_block().borrow().number;
+            anyhow::ensure!(
+                current_block < submission_deadline_latest_block,
+                "submission deadline was missed"
+            );
+
+            let request = settle_combined::Request {
+                auction_id,
+                calldata: calldata.0,
+                submission_deadline_latest_block,
+            };
+
+            for solver in solvers {
+                self.store_execution_started(
+                    auction_id,
+                    *solver,
+                    current_block,
+                    submission_deadline_latest_block,
+                );
+            }
+            driver
+                .settle_combined(&request, self.config.max_settlement_transaction_wait)
+                .await
+        }
+        .boxed();
+
+        // The combined settlement gets submitted by the solver of the
+        // designated driver.
+        let result = self
+            .await_settlement(
+                settle,
+                auction_id,
+                driver.submission_address,
+                submission_deadline_latest_block,
+            )
+            .await;
+
+        for solver in solvers {
+            self.store_execution_ended(*solver, auction_id, &result);
+        }
+
+        // Clean up the in-flight orders regardless the result.
+        self.in_flight_orders
+            .lock()
+            .await
+            .retain(|order| !solved_order_uids.contains(order));
+
+        result
+    }
+
     /// Execute the solver's solution. Returns Ok when the corresponding
     /// transaction has been mined.
     async fn settle(
@@ -729,13 +1046,35 @@ impl RunLoop {
         }
         .boxed();
 
+        let result = self
+            .await_settlement(settle, auction_id, solver, submission_deadline_latest_block)
+            .await;
+
+        self.store_execution_ended(solver, auction_id, &result);
+
+        // Clean up the in-flight orders regardless the result.
+        self.in_flight_orders
+            .lock()
+            .await
+            .retain(|order| !solved_order_uids.contains(order));
+
+        result
+    }
+
+    /// Waits for either the settlement transaction of `solver` to be mined or
+    /// the driver to fail submitting it.
+    async fn await_settlement(
+        &self,
+        settle: BoxFuture<'_, Result<()>>,
+        auction_id: i64,
+        solver: eth::Address,
+        submission_deadline_latest_block: u64,
+    ) -> Result<TxId, SettleError> {
         let wait_for_settlement_transaction = self
             .wait_for_settlement_transaction(auction_id, solver, submission_deadline_latest_block)
             .boxed();
 
-        // Wait for either the settlement transaction to be mined or the driver returned
-        // a result.
-        let result = match futures::future::select(wait_for_settlement_transaction, settle).await {
+        match futures::future::select(wait_for_settlement_transaction, settle).await {
             futures::future::Either::Left((res, _)) => res,
             futures::future::Either::Right((driver_result, wait_for_settlement_transaction)) => {
                 match driver_result {
@@ -743,17 +1082,7 @@ impl RunLoop {
                     Err(err) => Err(SettleError::Other(err)),
                 }
             }
-        };
-
-        self.store_execution_ended(solver, auction_id, &result);
-
-        // Clean up the in-flight orders regardless the result.
-        self.in_flight_orders
-            .lock()
-            .await
-            .retain(|order| !solved_order_uids.contains(order));
-
-        result
+        }
     }
 
     /// Stores settlement execution started event in the DB in a background task
@@ -884,6 +1213,15 @@ impl RunLoop {
     }
 }
 
+/// Winning solutions of multiple solvers merged into a single settlement.
+struct CombinedBatch<'a> {
+    /// The driver submitting the combined settlement.
+    driver: Arc<infra::Driver>,
+    calldata: eth::Calldata,
+    /// The winners whose solutions are part of the settlement.
+    winners: Vec<&'a Participant<Ranked>>,
+}
+
 #[derive(Debug, thiserror::Error)]
 enum SolveError {
     #[error("the solver timed out")]
@@ -914,6 +1252,11 @@ struct Metrics {
     #[metric(buckets(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10))]
     auction_winners: prometheus::Histogram,
 
+    /// Tracks the number of winning solutions merged into a combined
+    /// settlement.
+    #[metric(buckets(2, 3, 4, 5, 6, 7, 8, 9, 10))]
+    combined_batch_size: prometheus::Histogram,
+
     /// Tracks the duration of successful driver `/solve` requests.
     #[metric(
         labels("driver", "result"),
@@ -11,12 +11,12 @@ use {
     indexmap::IndexSet,
     itertools::{Either, Itertools},
     model::{
-        order::{Order, OrderClass, OrderUid},
+        order::{Order, OrderClass, OrderCondition, OrderFilterReason, OrderUid},
         signature::Signature,
         time::now_in_epoch_seconds,
     },
     number::conversions::u256_to_big_decimal,
-    primitive_types::{H160, H256, U256},
+    primitive_types::{H160, H256, U256, U512},
     prometheus::{Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec},
     shared::{
         account_balances::{BalanceFetching, Query},
@@ -100,6 +100,7 @@ pub struct SolvableOrdersCache {
     cow_amm_registry: cow_amm::Registry,
     native_price_timeout: Duration,
     settlement_contract: H160,
+    owner_volumes: Mutex<Option<OwnerVolumes>>,
 }
 
 type Balances = HashMap<Query, U256>;
@@ -109,6 +110,20 @@ struct Inner {
     solvable_orders: boundary::SolvableOrders,
 }
 
+/// How long cached owner volumes are reused before the whole volume window
+/// gets queried again. Volumes accumulated over weeks barely change between
+/// auctions, so they don't need to be recomputed on every update.
+const OWNER_VOLUMES_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Owner volumes of the window starting at `from_block`. Owners without any
+/// trades in the window are stored with a zero volume so they don't get
+/// queried again.
+struct OwnerVolumes {
+    from_block: u64,
+    computed_at: Instant,
+    volumes: HashMap<H160, U256>,
+}
+
 impl SolvableOrdersCache {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -142,6 +157,7 @@ impl SolvableOrdersCache {
             cow_amm_registry,
             native_price_timeout,
             settlement_contract,
+            owner_volumes: Mutex::new(None),
         })
     }
 
@@ -171,25 +187,26 @@ impl SolvableOrdersCache {
             .collect::<Vec<_>>();
 
         let mut counter = OrderFilterCounter::new(self.metrics, &orders);
-        let mut invalid_order_uids = HashSet::new();
+        let mut invalid_order_events = HashMap::new();
         let mut filtered_order_events = Vec::new();
 
         let (balances, orders, cow_amms) = {
             let queries = orders.iter().map(Query::from_order).collect::<Vec<_>>();
             tokio::join!(
                 self.fetch_balances(queries),
-                self.filter_invalid_orders(orders, &mut counter, &mut invalid_order_uids,),
+                self.filter_invalid_orders(orders, &mut counter, &mut invalid_order_events),
                 self.timed_future("cow_amm_registry", self.cow_amm_registry.amms()),
             )
         };
 
-        let orders = orders_with_balance(orders, &balances, self.settlement_contract);
-        let removed = counter.checkpoint("insufficient_balance", &orders);
-        invalid_order_uids.extend(removed);
+        let (orders, missing_balances) =
+            orders_with_balance(orders, &balances, self.settlement_contract);
+        counter.checkpoint("insufficient_balance", &orders);
+        invalid_order_events.extend(missing_balances);
 
         let orders = filter_dust_orders(orders, &balances);
         let removed = counter.checkpoint("dust_order", &orders);
-        filtered_order_events.extend(removed);
+        filtered_order_events.extend(with_reason(removed, OrderFilterReason::DustOrder));
 
         let cow_amm_tokens = cow_amms
             .iter()
@@ -227,25 +244,45 @@ impl SolvableOrdersCache {
         }
 
         let removed = counter.checkpoint("missing_price", &orders);
-        filtered_order_events.extend(removed);
+        filtered_order_events.extend(with_reason(removed, OrderFilterReason::MissingNativePrice));
 
         let orders = filter_mispriced_limit_orders(orders, &prices, &self.limit_order_price_factor);
         let removed = counter.checkpoint("out_of_market", &orders);
-        filtered_order_events.extend(removed);
+        filtered_order_events.extend(with_reason(removed, OrderFilterReason::OutOfMarket));
+
+        // Conditional orders waiting for their condition are expected and not
+        // worth an order event on every auction.
+        let now = now_in_epoch_seconds();
+        let orders = filter_unmet_conditions(orders, &prices, now);
+        counter.checkpoint("condition_not_met", &orders);
 
         let removed = counter.record(&orders);
-        filtered_order_events.extend(removed);
+        filtered_order_events.extend(with_reason(removed, OrderFilterReason::Other));
+
+        let owner_volumes = match self.protocol_fees.volume_window_start(block) {
+            Some(from_block) => {
+                let owners = orders
+                    .iter()
+                    .map(|order| order.metadata.owner)
+                    .collect::<HashSet<_>>();
+                self.timed_future("owner_volumes", self.owner_volumes(owners, from_block))
+                    .await?
+            }
+            None => HashMap::new(),
+        };
 
         // spawning a background task since `order_events` table insert operation takes
         // a while and the result is ignored.
-        self.persistence.store_order_events(
-            invalid_order_uids.iter().map(|id| domain::OrderUid(id.0)),
+        self.persistence.store_order_events_with_reasons(
+            invalid_order_events
+                .into_iter()
+                .map(|(id, reason)| (domain::OrderUid(id.0), reason)),
             OrderEventLabel::Invalid,
         );
-        self.persistence.store_order_events(
+        self.persistence.store_order_events_with_reasons(
             filtered_order_events
-                .iter()
-                .map(|id| domain::OrderUid(id.0)),
+                .into_iter()
+                .map(|(id, reason)| (domain::OrderUid(id.0), reason)),
             OrderEventLabel::Filtered,
         );
 
@@ -268,6 +305,10 @@ impl SolvableOrdersCache {
             .cloned()
             .map(eth::Address::from)
             .collect::<Vec<_>>();
+        let market = domain::fee::Market {
+            prices: &prices,
+            owner_volumes: &owner_volumes,
+        };
         let auction = domain::RawAuctionData {
             block,
             orders: orders
@@ -277,8 +318,20 @@ impl SolvableOrdersCache {
                         .quotes
                         .get(&order.metadata.uid.into())
                         .cloned();
-                    self.protocol_fees
-                        .apply(order, quote, &surplus_capturing_jit_order_owners)
+                    let unreleased = unreleased_twap_amount(&order, now);
+                    let mut order = self.protocol_fees.apply(
+                        order,
+                        quote,
+                        &surplus_capturing_jit_order_owners,
+                        &market,
+                    );
+                    // Only the parts of a TWAP order that are already due may be
+                    // traded, so the rest is reported as executed to solvers.
+                    if let Some(unreleased) = unreleased {
+                        order.executed =
+                            domain::auction::order::TargetAmount(order.executed.0 + unreleased);
+                    }
+                    order
                 })
                 .collect(),
             prices: prices
@@ -368,7 +421,7 @@ impl SolvableOrdersCache {
         &self,
         mut orders: Vec<Order>,
         counter: &mut OrderFilterCounter,
-        invalid_order_uids: &mut HashSet<OrderUid>,
+        invalid_order_events: &mut HashMap<OrderUid, OrderFilterReason>,
     ) -> Vec<Order> {
         let (banned_user_orders, invalid_signature_orders, unsupported_token_orders) = tokio::join!(
             self.timed_future(
@@ -388,11 +441,21 @@ impl SolvableOrdersCache {
         counter.checkpoint_by_invalid_orders("banned_user", &banned_user_orders);
         counter.checkpoint_by_invalid_orders("invalid_signature", &invalid_signature_orders);
         counter.checkpoint_by_invalid_orders("unsupported_token", &unsupported_token_orders);
-        invalid_order_uids.extend(banned_user_orders);
-        invalid_order_uids.extend(invalid_signature_orders);
-        invalid_order_uids.extend(unsupported_token_orders);
+        // Orders that are invalid for multiple reasons report the last one.
+        invalid_order_events.extend(with_reason(
+            unsupported_token_orders,
+            OrderFilterReason::UnsupportedToken,
+        ));
+        invalid_order_events.extend(with_reason(
+            invalid_signature_orders,
+            OrderFilterReason::InvalidSignature,
+        ));
+        invalid_order_events.extend(with_reason(
+            banned_user_orders,
+            OrderFilterReason::BannedUser,
+        ));
 
-        orders.retain(|order| !invalid_order_uids.contains(&order.metadata.uid));
+        orders.retain(|order| !invalid_order_events.contains_key(&order.metadata.uid));
         orders
     }
 
@@ -403,6 +466,46 @@ impl SolvableOrdersCache {
             .inc();
     }
 
+    /// Returns the volumes of the given owners in the window starting at
+    /// `from_block`. Volumes are cached and only owners which were not seen
+    /// since the last full refresh get queried.
+    async fn owner_volumes(
+        &self,
+        owners: HashSet<H160>,
+        from_block: u64,
+    ) -> Result<HashMap<H160, U256>> {
+        let mut cache = self.owner_volumes.lock().await;
+        let cache = match cache.as_mut() {
+            Some(cached) if cached.computed_at.elapsed() < OWNER_VOLUMES_MAX_AGE => cached,
+            _ => cache.insert(OwnerVolumes {
+                from_block,
+                computed_at: Instant::now(),
+                volumes: HashMap::new(),
+            }),
+        };
+
+        let missing = owners
+            .iter()
+            .filter(|owner| !cache.volumes.contains_key(owner))
+            .map(|owner| eth::Address(*owner))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let fetched = self
+                .persistence
+                .owner_volumes(missing.iter().copied(), cache.from_block)
+                .await?;
+            for owner in missing {
+                let volume = fetched.get(&owner).copied().unwrap_or_default();
+                cache.volumes.insert(owner.0, volume);
+            }
+        }
+
+        Ok(owners
+            .into_iter()
+            .filter_map(|owner| Some((owner, *cache.volumes.get(&owner)?)))
+            .collect())
+    }
+
     /// Runs the future and collects runtime metrics.
     async fn timed_future<T>(&self, label: &str, fut: impl Future<Output = T>) -> T {
         let _timer = self
@@ -508,15 +611,23 @@ async fn find_invalid_signature_orders(
     invalid_orders
 }
 
+fn with_reason(
+    order_uids: Vec<OrderUid>,
+    reason: OrderFilterReason,
+) -> impl Iterator<Item = (OrderUid, OrderFilterReason)> {
+    order_uids.into_iter().map(move |uid| (uid, reason))
+}
+
 /// Removes orders that can't possibly be settled because there isn't enough
-/// balance.
+/// balance. Also returns the balances that were missing for the removed orders.
 fn orders_with_balance(
     mut orders: Vec<Order>,
     balances: &Balances,
     settlement_contract: H160,
-) -> Vec<Order> {
+) -> (Vec<Order>, Vec<(OrderUid, OrderFilterReason)>) {
     // Prefer newer orders over older ones.
     orders.sort_by_key(|order| std::cmp::Reverse(order.metadata.creation_date));
+    let mut missing_balances = Vec::new();
     orders.retain(|order| {
         if order.data.receiver.as_ref() == Some(&settlement_contract) {
             // TODO: replace with proper detection logic
@@ -527,22 +638,26 @@ fn orders_with_balance(
             return true;
         }
 
-        let balance = match balances.get(&Query::from_order(order)) {
-            None => return false,
-            Some(balance) => *balance,
+        let balance = balances.get(&Query::from_order(order)).copied();
+        let needed_balance = order.data.sell_amount.checked_add(order.data.fee_amount);
+        let has_balance = match balance {
+            None => false,
+            Some(balance) if order.data.partially_fillable && balance >= 1.into() => true,
+            Some(balance) => needed_balance.is_some_and(|needed| balance >= needed),
         };
-
-        if order.data.partially_fillable && balance >= 1.into() {
-            return true;
+        if !has_balance {
+            let required = match order.data.partially_fillable {
+                true => 1.into(),
+                false => needed_balance.unwrap_or(U256::MAX),
+            };
+            missing_balances.push((
+                order.metadata.uid,
+                OrderFilterReason::InsufficientBalance { balance, required },
+            ));
         }
-
-        let needed_balance = match order.data.sell_amount.checked_add(order.data.fee_amount) {
-            None => return false,
-            Some(balance) => balance,
-        };
-        balance >= needed_balance
+        has_balance
     });
-    orders
+    (orders, missing_balances)
 }
 
 /// Filters out dust orders i.e. partially fillable orders that, when scaled
@@ -676,7 +791,7 @@ async fn find_unsupported_tokens(
                 let bad_token = bad_token.clone();
                 async move {
                     match bad_token.detect(token).await {
-                        Ok(quality) => (!quality.is_good()).then_some(token),
+                        Ok(quality) => (!quality.is_supported()).then_some(token),
                         Err(err) => {
                             tracing::warn!(
                                 ?token,
@@ -747,6 +862,60 @@ fn filter_mispriced_limit_orders(
     orders
 }
 
+/// Filter out conditional orders whose condition is not met yet. TWAP orders
+/// are kept once a part is available that hasn't been traded yet and stop-loss
+/// orders once the native price of the sell token dropped to the strike price.
+fn filter_unmet_conditions(
+    mut orders: Vec<Order>,
+    prices: &BTreeMap<H160, U256>,
+    now: u32,
+) -> Vec<Order> {
+    orders.retain(|order| match order.metadata.condition {
+        None => true,
+        Some(OrderCondition::Twap { .. }) => {
+            unreleased_twap_amount(order, now).is_some_and(|unreleased| {
+                order.data.sell_amount - unreleased
+                    > order.metadata.executed_sell_amount_before_fees
+            })
+        }
+        Some(OrderCondition::StopLoss { strike_price }) => {
+            let (Some(sell_price), Some(buy_price)) = (
+                prices.get(&order.data.sell_token),
+                prices.get(&order.data.buy_token),
+            ) else {
+                return false;
+            };
+            // The strike price is the amount of buy tokens per sell token
+            // scaled by 1e18.
+            sell_price.full_mul(U256::exp10(18)) <= strike_price.full_mul(*buy_price)
+        }
+    });
+    orders
+}
+
+/// Returns the part of a TWAP order's sell amount that isn't available for
+/// trading yet. Returns `None` for orders without a TWAP condition or whose
+/// first part isn't due yet.
+fn unreleased_twap_amount(order: &Order, now: u32) -> Option<U256> {
+    let Some(OrderCondition::Twap {
+        parts,
+        start_time,
+        part_duration,
+    }) = order.metadata.condition
+    else {
+        return None;
+    };
+    if now < start_time || parts == 0 || part_duration == 0 {
+        return None;
+    }
+    let released_parts = ((now - start_time) / part_duration)
+        .saturating_add(1)
+        .min(parts);
+    let released = order.data.sell_amount.full_mul(released_parts.into()) / U512::from(parts);
+    let released = U256::try_from(released).expect("released amount <= sell amount");
+    Some(order.data.sell_amount - released)
+}
+
 /// Order filtering state for recording filtered orders over the course of
 /// building an auction.
 struct OrderFilterCounter {
@@ -1372,6 +1541,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filters_unmet_conditions() {
+        let sell_token = H160([1; 20]);
+        let buy_token = H160([2; 20]);
+
+        // 1 sell token is worth 2 buy tokens.
+        let prices = btreemap! {
+            sell_token => U256::exp10(18) * 2,
+            buy_token => U256::exp10(18),
+        };
+
+        let order = |condition, executed: u32| Order {
+            data: OrderData {
+                sell_token,
+                sell_amount: 100.into(),
+                buy_token,
+                buy_amount: 100.into(),
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                condition: Some(condition),
+                executed_sell_amount_before_fees: executed.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let twap = |executed| {
+            order(
+                OrderCondition::Twap {
+                    parts: 4,
+                    start_time: 1000,
+                    part_duration: 100,
+                },
+                executed,
+            )
+        };
+        let stop_loss = |strike: u64| {
+            order(
+                OrderCondition::StopLoss {
+                    strike_price: U256::from(strike) * U256::exp10(18),
+                },
+                0,
+            )
+        };
+
+        let valid_orders = vec![
+            Order::default(),
+            // Second part is due but only the first one was traded.
+            twap(25),
+            // Sell token is worth less than the strike price.
+            stop_loss(3),
+            stop_loss(2),
+        ];
+        let invalid_orders = vec![
+            // Both due parts were already traded.
+            twap(50),
+            stop_loss(1),
+        ];
+
+        let orders = [valid_orders.clone(), invalid_orders].concat();
+        assert_eq!(filter_unmet_conditions(orders, &prices, 1150), valid_orders,);
+
+        // TWAP orders are not solvable before their start time.
+        assert!(filter_unmet_conditions(vec![twap(0)], &prices, 999).is_empty());
+        // Stop-loss orders without prices are not solvable.
+        assert!(filter_unmet_conditions(vec![stop_loss(3)], &BTreeMap::new(), 0).is_empty());
+    }
+
+    #[test]
+    fn computes_unreleased_twap_amount() {
+        let order = Order {
+            data: OrderData {
+                sell_amount: 100.into(),
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                condition: Some(OrderCondition::Twap {
+                    parts: 3,
+                    start_time: 1000,
+                    part_duration: 100,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(unreleased_twap_amount(&order, 999), None);
+        assert_eq!(unreleased_twap_amount(&order, 1000), Some(67.into()));
+        assert_eq!(unreleased_twap_amount(&order, 1199), Some(34.into()));
+        assert_eq!(unreleased_twap_amount(&order, 1200), Some(0.into()));
+        assert_eq!(unreleased_twap_amount(&order, u32::MAX), Some(0.into()));
+        assert_eq!(unreleased_twap_amount(&Order::default(), 1000), None);
+    }
+
     #[test]
     fn orders_with_balance_() {
         let settlement_contract = H160([1; 20]);
@@ -1444,12 +1708,32 @@ mod tests {
         .collect();
         let expected = &[0, 2, 4];
 
-        let filtered = orders_with_balance(orders.clone(), &balances, settlement_contract);
+        let (filtered, missing_balances) =
+            orders_with_balance(orders.clone(), &balances, settlement_contract);
         assert_eq!(filtered.len(), expected.len());
         for index in expected {
             let found = filtered.iter().any(|o| o.data == orders[*index].data);
             assert!(found, "{}", index);
         }
+        assert_eq!(
+            missing_balances,
+            vec![
+                (
+                    orders[1].metadata.uid,
+                    OrderFilterReason::InsufficientBalance {
+                        balance: Some(1.into()),
+                        required: 2.into(),
+                    }
+                ),
+                (
+                    orders[3].metadata.uid,
+                    OrderFilterReason::InsufficientBalance {
+                        balance: Some(0.into()),
+                        required: 1.into(),
+                    }
+                ),
+            ]
+        );
     }
 
     #[test]
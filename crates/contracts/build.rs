@@ -1050,9 +1050,28 @@ fn main() {
                 },
             )
     });
+    generate_contract("BalancerV3StablePool");
+    // Balancer V3 addresses can be obtained from:
+    // <https://docs.balancer.fi/developer-reference/contracts/deployment-addresses/mainnet.html>
+    generate_contract_with_config("BalancerV3Vault", |builder| {
+        builder
+            .add_network_str(MAINNET, "0xbA1333333333a1BA1108E8412f11850A5C319bA9")
+            .add_network_str(GNOSIS, "0xbA1333333333a1BA1108E8412f11850A5C319bA9")
+    });
+    generate_contract("BalancerV3WeightedPool");
     generate_contract_with_config("BaoswapRouter", |builder| {
         builder.add_network_str(GNOSIS, "0x6093AeBAC87d62b1A5a4cEec91204e35020E38bE")
     });
+    // Curve addresses can be obtained from:
+    // <https://docs.curve.fi/references/deployed-contracts/>
+    generate_contract_with_config("CurveStableSwapNgFactory", |builder| {
+        builder.add_network_str(MAINNET, "0x6A8cbed756804B16E05E741eDaBd5cB544AE21bf")
+    });
+    generate_contract("CurveStableSwapNgPool");
+    generate_contract_with_config("CurveTwocryptoFactory", |builder| {
+        builder.add_network_str(MAINNET, "0x98EE851a00abeE0d95D08cF4CA2BdCE32aeaAF7F")
+    });
+    generate_contract("CurveTwocryptoPool");
     generate_contract("ERC20");
     generate_contract("ERC20Mintable");
     generate_contract("ERC3156FlashLoanSolverWrapper");
@@ -1339,6 +1358,20 @@ fn main() {
             .add_network_str(POLYGON, "0x61fFE014bA17989E743c5F6cB21bF9697530B21e")
         // Not listed on Gnosis and Sepolia chains
     });
+    generate_contract_with_config("UniswapV4PoolManager", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder.add_network(
+            MAINNET,
+            Network {
+                address: addr("0x000000000004444c5dc75cB358380D2e3dE08A90"),
+                deployment_information: Some(DeploymentInformation::BlockNumber(21688329)),
+            },
+        )
+    });
+    generate_contract_with_config("UniswapV4UniversalRouter", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder.add_network_str(MAINNET, "0x66a9893cC07D91D95644AEDD05D03f95e1dBA8Af")
+    });
     generate_contract_with_config("WETH9", |builder| {
         // Note: the WETH address must be consistent with the one used by the ETH-flow
         // contract
@@ -1429,6 +1462,8 @@ fn main() {
             .add_network_str(OPTIMISM, "0x40C57923924B5c5c5455c48D93317139ADDaC8fb")
             .add_network_str(POLYGON, "0x40C57923924B5c5c5455c48D93317139ADDaC8fb")
     });
+    // Chainlink price feed aggregator proxies, configured per token
+    generate_contract("ChainlinkAggregator");
 
     generate_contract("CowAmm");
     generate_contract_with_config("CowAmmConstantProductFactory", |builder| {
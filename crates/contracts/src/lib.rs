@@ -71,6 +71,9 @@ include_contracts! {
     BalancerV2WeightedPoolFactoryV3;
     BalancerV2WeightedPoolFactoryV4;
     BalancerV3BatchRouter;
+    BalancerV3StablePool;
+    BalancerV3Vault;
+    BalancerV3WeightedPool;
     BaoswapRouter;
     CowAmm;
     CowAmmConstantProductFactory;
@@ -79,6 +82,10 @@ include_contracts! {
     CoWSwapEthFlow;
     CoWSwapOnchainOrders;
     CowProtocolToken;
+    CurveStableSwapNgFactory;
+    CurveStableSwapNgPool;
+    CurveTwocryptoFactory;
+    CurveTwocryptoPool;
     ERC1271SignatureValidator;
     ERC20;
     ERC20Mintable;
@@ -102,6 +109,7 @@ include_contracts! {
     PancakeRouter;
     Permit2;
     ChainalysisOracle;
+    ChainlinkAggregator;
     SushiSwapRouter;
     SwaprRouter;
     TestnetUniswapV2Router02;
@@ -110,6 +118,8 @@ include_contracts! {
     UniswapV3Pool;
     UniswapV3QuoterV2;
     UniswapV3SwapRouter;
+    UniswapV4PoolManager;
+    UniswapV4UniversalRouter;
     WETH9;
 }
 
@@ -0,0 +1,246 @@
+use {
+    crate::{Address, TransactionHash},
+    bigdecimal::BigDecimal,
+    sqlx::{
+        PgConnection,
+        types::chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct TokenQuality {
+    pub supported: bool,
+    pub transfer_fee_bps: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Stores the quality of a token. An existing entry only gets replaced if the
+/// new quality marks the token as unsupported or if the existing entry was
+/// determined before `outdated_before`. This way conflicting updates err on the
+/// conservative side.
+pub async fn upsert_token_quality(
+    ex: &mut PgConnection,
+    token: &Address,
+    quality: &TokenQuality,
+    outdated_before: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO driver_token_qualities (token, supported, transfer_fee_bps, updated_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (token) DO UPDATE
+SET supported = EXCLUDED.supported,
+    transfer_fee_bps = EXCLUDED.transfer_fee_bps,
+    updated_at = EXCLUDED.updated_at
+WHERE NOT EXCLUDED.supported OR driver_token_qualities.updated_at < $5
+;"#;
+    sqlx::query(QUERY)
+        .bind(token)
+        .bind(quality.supported)
+        .bind(quality.transfer_fee_bps)
+        .bind(quality.updated_at)
+        .bind(outdated_before)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Returns the quality of a token if it was determined after `updated_after`.
+pub async fn fetch_token_quality(
+    ex: &mut PgConnection,
+    token: &Address,
+    updated_after: DateTime<Utc>,
+) -> Result<Option<TokenQuality>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT supported, transfer_fee_bps, updated_at
+FROM driver_token_qualities
+WHERE token = $1 AND updated_at > $2
+;"#;
+    sqlx::query_as(QUERY)
+        .bind(token)
+        .bind(updated_after)
+        .fetch_optional(ex)
+        .await
+}
+
+pub async fn delete_token_qualities_before(
+    ex: &mut PgConnection,
+    updated_before: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM driver_token_qualities WHERE updated_at < $1;";
+    sqlx::query(QUERY).bind(updated_before).execute(ex).await?;
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, sqlx::FromRow)]
+pub struct Simulation {
+    pub gas: Option<BigDecimal>,
+    pub access_list: Option<serde_json::Value>,
+}
+
+pub async fn fetch_simulation(
+    ex: &mut PgConnection,
+    block_number: i64,
+    tx_hash: &TransactionHash,
+) -> Result<Option<Simulation>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT gas, access_list
+FROM driver_simulations
+WHERE block_number = $1 AND tx_hash = $2
+;"#;
+    sqlx::query_as(QUERY)
+        .bind(block_number)
+        .bind(tx_hash)
+        .fetch_optional(ex)
+        .await
+}
+
+/// Stores the parts of a simulation result that are set. Parts that were
+/// already stored by an earlier simulation are kept if they are not set.
+pub async fn upsert_simulation(
+    ex: &mut PgConnection,
+    block_number: i64,
+    tx_hash: &TransactionHash,
+    simulation: &Simulation,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO driver_simulations (block_number, tx_hash, gas, access_list)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (block_number, tx_hash) DO UPDATE
+SET gas = COALESCE(EXCLUDED.gas, driver_simulations.gas),
+    access_list = COALESCE(EXCLUDED.access_list, driver_simulations.access_list)
+;"#;
+    sqlx::query(QUERY)
+        .bind(block_number)
+        .bind(tx_hash)
+        .bind(&simulation.gas)
+        .bind(&simulation.access_list)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_simulations_before(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM driver_simulations WHERE block_number < $1;";
+    sqlx::query(QUERY).bind(block_number).execute(ex).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::byte_array::ByteArray,
+        chrono::Duration,
+        serde_json::json,
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_token_quality_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let token = ByteArray([1; 20]);
+        let now = Utc::now();
+        let supported = TokenQuality {
+            supported: true,
+            transfer_fee_bps: Some(100),
+            updated_at: now,
+        };
+        let long_ago = now - Duration::hours(1);
+        assert_eq!(
+            fetch_token_quality(&mut db, &token, long_ago)
+                .await
+                .unwrap(),
+            None
+        );
+
+        upsert_token_quality(&mut db, &token, &supported, long_ago)
+            .await
+            .unwrap();
+        assert_eq!(
+            fetch_token_quality(&mut db, &token, long_ago)
+                .await
+                .unwrap()
+                .unwrap()
+                .transfer_fee_bps,
+            Some(100)
+        );
+        assert_eq!(
+            fetch_token_quality(&mut db, &token, now).await.unwrap(),
+            None
+        );
+
+        // Unsupported verdicts always win.
+        let unsupported = TokenQuality {
+            supported: false,
+            transfer_fee_bps: None,
+            updated_at: now,
+        };
+        upsert_token_quality(&mut db, &token, &unsupported, long_ago)
+            .await
+            .unwrap();
+        // Supported verdicts only replace outdated entries.
+        upsert_token_quality(&mut db, &token, &supported, long_ago)
+            .await
+            .unwrap();
+        assert!(
+            !fetch_token_quality(&mut db, &token, long_ago)
+                .await
+                .unwrap()
+                .unwrap()
+                .supported
+        );
+
+        delete_token_qualities_before(&mut db, now + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            fetch_token_quality(&mut db, &token, long_ago)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_simulation_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let tx = ByteArray([2; 32]);
+        assert_eq!(fetch_simulation(&mut db, 1, &tx).await.unwrap(), None);
+
+        let gas = Simulation {
+            gas: Some(21_000.into()),
+            access_list: None,
+        };
+        upsert_simulation(&mut db, 1, &tx, &gas).await.unwrap();
+        let access_list = Simulation {
+            gas: None,
+            access_list: Some(json!([])),
+        };
+        upsert_simulation(&mut db, 1, &tx, &access_list)
+            .await
+            .unwrap();
+        assert_eq!(
+            fetch_simulation(&mut db, 1, &tx).await.unwrap(),
+            Some(Simulation {
+                gas: Some(21_000.into()),
+                access_list: Some(json!([])),
+            })
+        );
+        // Results are specific to the block they were simulated on.
+        assert_eq!(fetch_simulation(&mut db, 2, &tx).await.unwrap(), None);
+
+        delete_simulations_before(&mut db, 2).await.unwrap();
+        assert_eq!(fetch_simulation(&mut db, 1, &tx).await.unwrap(), None);
+    }
+}
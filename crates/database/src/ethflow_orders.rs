@@ -1,6 +1,10 @@
 use {
     crate::{OrderUid, PgTransaction, TransactionHash},
-    sqlx::{Executor, PgConnection},
+    sqlx::{
+        Executor,
+        PgConnection,
+        types::chrono::{DateTime, Utc},
+    },
 };
 
 #[derive(Clone, Debug, Default, sqlx::FromRow, Eq, PartialEq)]
@@ -39,6 +43,8 @@ pub struct EthOrderData {
     pub uid: OrderUid,
     pub valid_to: i64,
     pub refund_tx: Option<TransactionHash>,
+    /// Whether the user requested the order to be refunded.
+    pub refund_requested: bool,
 }
 
 pub async fn read_order(
@@ -46,8 +52,10 @@ pub async fn read_order(
     id: &OrderUid,
 ) -> Result<Option<EthOrderData>, sqlx::Error> {
     const QUERY: &str = r#"
-        SELECT uid, valid_to, ethflow_refunds.tx_hash as refund_tx FROM ethflow_orders
+        SELECT uid, valid_to, ethflow_refunds.tx_hash as refund_tx,
+        ethflow_refund_requests.order_uid IS NOT NULL as refund_requested FROM ethflow_orders
         LEFT JOIN ethflow_refunds ON ethflow_orders.uid = ethflow_refunds.order_uid
+        LEFT JOIN ethflow_refund_requests ON ethflow_orders.uid = ethflow_refund_requests.order_uid
         WHERE uid = $1
     "#;
     sqlx::query_as(QUERY).bind(id).fetch_optional(ex).await
@@ -142,6 +150,58 @@ AND eo.valid_to - extract(epoch from creation_timestamp)::int > $2
         .await
 }
 
+/// Records that the user requested the order to be refunded. Requesting a
+/// refund multiple times keeps the first request.
+pub async fn insert_refund_request(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+    timestamp: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+        INSERT INTO ethflow_refund_requests (order_uid, timestamp) VALUES ($1, $2)
+        ON CONFLICT (order_uid) DO NOTHING
+    "#;
+    sqlx::query(QUERY)
+        .bind(order_uid)
+        .bind(timestamp)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Expired orders the user requested a refund for which are not yet refunded.
+/// Unlike `refundable_orders` this doesn't take the price deviation of the
+/// order into account. Orders that were valid for less than
+/// `min_validity_duration` seconds are still not refunded since anybody can
+/// request refunds.
+pub async fn requested_refundable_orders(
+    ex: &mut PgConnection,
+    since_valid_to: i64,
+    min_validity_duration: i64,
+) -> Result<Vec<EthOrderPlacement>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT eo.uid, eo.valid_to from ethflow_refund_requests r
+INNER JOIN ethflow_orders eo on eo.uid = r.order_uid
+INNER JOIN orders o on o.uid = eo.uid
+LEFT JOIN trades t on o.uid = t.order_uid
+LEFT JOIN onchain_order_invalidations o_inv on o.uid = o_inv.uid
+LEFT JOIN ethflow_refunds o_ref on o.uid = o_ref.order_uid
+WHERE
+o_ref.tx_hash is null
+AND o_inv.uid is null
+AND o.partially_fillable = false
+AND t.order_uid is null
+AND eo.valid_to < $1
+AND eo.valid_to - extract(epoch from o.creation_timestamp)::int > $2
+ORDER BY r.timestamp
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(since_valid_to)
+        .bind(min_validity_duration)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -385,6 +445,70 @@ mod tests {
         assert_eq!(orders, Vec::new());
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_requested_refundable_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order_uid_1 = ByteArray([1u8; 56]);
+        let eth_order = EthOrderPlacement {
+            uid: order_uid_1,
+            valid_to: 4,
+        };
+        let order = Order {
+            uid: order_uid_1,
+            ..Default::default()
+        };
+        insert_order(&mut db, &order).await.unwrap();
+        insert_or_overwrite_ethflow_order(&mut db, &eth_order)
+            .await
+            .unwrap();
+        // no refund was requested
+        let orders = requested_refundable_orders(&mut db, 5, 1).await.unwrap();
+        assert_eq!(orders, Vec::new());
+        assert!(
+            !read_order(&mut db, &order_uid_1)
+                .await
+                .unwrap()
+                .unwrap()
+                .refund_requested
+        );
+
+        let now = Utc::now();
+        insert_refund_request(&mut db, &order_uid_1, now)
+            .await
+            .unwrap();
+        // requesting a refund again is a no-op
+        insert_refund_request(&mut db, &order_uid_1, now)
+            .await
+            .unwrap();
+        assert!(
+            read_order(&mut db, &order_uid_1)
+                .await
+                .unwrap()
+                .unwrap()
+                .refund_requested
+        );
+        // orders without a quote get refunded regardless of the price deviation
+        let orders = requested_refundable_orders(&mut db, 5, 1).await.unwrap();
+        assert_eq!(orders, vec![eth_order]);
+        // order is not expired yet
+        let orders = requested_refundable_orders(&mut db, 4, 1).await.unwrap();
+        assert_eq!(orders, Vec::new());
+        // order was not valid for long enough
+        let orders = requested_refundable_orders(&mut db, 5, 4).await.unwrap();
+        assert_eq!(orders, Vec::new());
+
+        // order was refunded
+        insert_refund_tx_hash(&mut db, &refund(order_uid_1))
+            .await
+            .unwrap();
+        let orders = requested_refundable_orders(&mut db, 5, 1).await.unwrap();
+        assert_eq!(orders, Vec::new());
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_refundable_orders_performance() {
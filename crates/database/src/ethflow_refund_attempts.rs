@@ -0,0 +1,234 @@
+use {
+    crate::{Address, OrderUid, TransactionHash},
+    bigdecimal::BigDecimal,
+    sqlx::{
+        PgConnection,
+        types::chrono::{DateTime, Utc},
+    },
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "EthflowRefundAttemptStatus")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Status {
+    /// Transaction got mined and refunded the orders.
+    Success,
+    /// Transaction got mined but reverted.
+    Reverted,
+    /// Transaction got submitted but was not known to be mined yet.
+    Pending,
+    /// Transaction could not be submitted or got replaced by another
+    /// transaction.
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct RefundAttempt {
+    pub timestamp: DateTime<Utc>,
+    pub ethflow_contract: Address,
+    pub order_uids: Vec<OrderUid>,
+    pub nonce: i64,
+    pub max_fee_per_gas: BigDecimal,
+    pub max_priority_fee_per_gas: BigDecimal,
+    pub status: Status,
+    pub tx_hash: Option<TransactionHash>,
+    pub gas_used: Option<BigDecimal>,
+    /// Wei paid for the transaction. Only set if it got mined.
+    pub fee: Option<BigDecimal>,
+    pub error: Option<String>,
+}
+
+pub async fn insert(ex: &mut PgConnection, attempt: &RefundAttempt) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO ethflow_refund_attempts (timestamp, ethflow_contract, order_uids, nonce, max_fee_per_gas, max_priority_fee_per_gas, status, tx_hash, gas_used, fee, error)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+;"#;
+    sqlx::query(QUERY)
+        .bind(attempt.timestamp)
+        .bind(attempt.ethflow_contract)
+        .bind(&attempt.order_uids)
+        .bind(attempt.nonce)
+        .bind(&attempt.max_fee_per_gas)
+        .bind(&attempt.max_priority_fee_per_gas)
+        .bind(attempt.status)
+        .bind(attempt.tx_hash)
+        .bind(&attempt.gas_used)
+        .bind(&attempt.fee)
+        .bind(&attempt.error)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Returns the refund attempts since the given timestamp whose outcome is not
+/// known yet.
+pub async fn pending_since(
+    ex: &mut PgConnection,
+    since: DateTime<Utc>,
+) -> Result<Vec<RefundAttempt>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT *
+FROM ethflow_refund_attempts
+WHERE timestamp >= $1 AND status = 'pending'
+;"#;
+    sqlx::query_as(QUERY).bind(since).fetch_all(ex).await
+}
+
+/// Stores the outcome of a pending refund attempt once it is known.
+pub async fn update_pending(
+    ex: &mut PgConnection,
+    tx_hash: &TransactionHash,
+    status: Status,
+    gas_used: Option<&BigDecimal>,
+    fee: Option<&BigDecimal>,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE ethflow_refund_attempts
+SET status = $2, gas_used = $3, fee = $4
+WHERE tx_hash = $1 AND status = 'pending'
+;"#;
+    sqlx::query(QUERY)
+        .bind(tx_hash)
+        .bind(status)
+        .bind(gas_used)
+        .bind(fee)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Sums up the fees of all refund attempts since the given timestamp.
+pub async fn fees_spent_since(
+    ex: &mut PgConnection,
+    since: DateTime<Utc>,
+) -> Result<BigDecimal, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT COALESCE(SUM(fee), 0)
+FROM ethflow_refund_attempts
+WHERE timestamp >= $1
+;"#;
+    sqlx::query_scalar(QUERY).bind(since).fetch_one(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, chrono::Duration, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_refund_attempts_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        // Postgres only stores microseconds so use a timestamp without nanoseconds.
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let hour_ago = now - Duration::hours(1);
+        assert_eq!(fees_spent_since(&mut db, hour_ago).await.unwrap(), 0.into());
+
+        let success = RefundAttempt {
+            timestamp: now,
+            ethflow_contract: ByteArray([1; 20]),
+            order_uids: vec![ByteArray([2; 56]), ByteArray([3; 56])],
+            nonce: 0,
+            max_fee_per_gas: 3.into(),
+            max_priority_fee_per_gas: 1.into(),
+            status: Status::Success,
+            tx_hash: Some(ByteArray([4; 32])),
+            gas_used: Some(100.into()),
+            fee: Some(200.into()),
+            error: None,
+        };
+        insert(&mut db, &success).await.unwrap();
+        let failed = RefundAttempt {
+            nonce: 1,
+            status: Status::Failed,
+            tx_hash: None,
+            gas_used: None,
+            fee: None,
+            error: Some("timeout".to_string()),
+            ..success.clone()
+        };
+        insert(&mut db, &failed).await.unwrap();
+        let old = RefundAttempt {
+            timestamp: now - Duration::days(2),
+            nonce: 2,
+            fee: Some(1000.into()),
+            ..success.clone()
+        };
+        insert(&mut db, &old).await.unwrap();
+
+        assert_eq!(
+            fees_spent_since(&mut db, hour_ago).await.unwrap(),
+            200.into()
+        );
+
+        let stored: Vec<RefundAttempt> = sqlx::query_as(
+            "SELECT * FROM ethflow_refund_attempts WHERE timestamp >= $1 ORDER BY nonce",
+        )
+        .bind(hour_ago)
+        .fetch_all(db.as_mut())
+        .await
+        .unwrap();
+        assert_eq!(stored, vec![success, failed]);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_update_pending_refund_attempts() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let hour_ago = now - Duration::hours(1);
+        let pending = RefundAttempt {
+            timestamp: now,
+            ethflow_contract: ByteArray([1; 20]),
+            order_uids: vec![ByteArray([2; 56])],
+            nonce: 0,
+            max_fee_per_gas: 3.into(),
+            max_priority_fee_per_gas: 1.into(),
+            status: Status::Pending,
+            tx_hash: Some(ByteArray([4; 32])),
+            gas_used: None,
+            fee: None,
+            error: None,
+        };
+        insert(&mut db, &pending).await.unwrap();
+        let replaced = RefundAttempt {
+            tx_hash: Some(ByteArray([5; 32])),
+            ..pending.clone()
+        };
+        insert(&mut db, &replaced).await.unwrap();
+        assert_eq!(pending_since(&mut db, hour_ago).await.unwrap().len(), 2);
+        assert_eq!(fees_spent_since(&mut db, hour_ago).await.unwrap(), 0.into());
+
+        update_pending(
+            &mut db,
+            &ByteArray([4; 32]),
+            Status::Success,
+            Some(&100.into()),
+            Some(&200.into()),
+        )
+        .await
+        .unwrap();
+        update_pending(&mut db, &ByteArray([5; 32]), Status::Failed, None, None)
+            .await
+            .unwrap();
+
+        assert!(pending_since(&mut db, hour_ago).await.unwrap().is_empty());
+        assert_eq!(
+            fees_spent_since(&mut db, hour_ago).await.unwrap(),
+            200.into()
+        );
+        // Only pending attempts get updated.
+        update_pending(&mut db, &ByteArray([4; 32]), Status::Failed, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            fees_spent_since(&mut db, hour_ago).await.unwrap(),
+            200.into()
+        );
+    }
+}
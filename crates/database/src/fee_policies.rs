@@ -16,6 +16,7 @@ pub struct FeePolicy {
     pub volume_factor: Option<f64>,
     pub price_improvement_factor: Option<f64>,
     pub price_improvement_max_volume_factor: Option<f64>,
+    pub tier: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, sqlx::Type)]
@@ -38,7 +39,7 @@ pub async fn insert_batch(
     let mut query_builder = QueryBuilder::new(
         "INSERT INTO fee_policies (auction_id, order_uid, kind, surplus_factor, \
          surplus_max_volume_factor, volume_factor, price_improvement_factor, \
-         price_improvement_max_volume_factor)",
+         price_improvement_max_volume_factor, tier)",
     );
 
     query_builder.push_values(fee_policies, |mut b, fee_policy| {
@@ -49,7 +50,8 @@ pub async fn insert_batch(
             .push_bind(fee_policy.surplus_max_volume_factor)
             .push_bind(fee_policy.volume_factor)
             .push_bind(fee_policy.price_improvement_factor)
-            .push_bind(fee_policy.price_improvement_max_volume_factor);
+            .push_bind(fee_policy.price_improvement_max_volume_factor)
+            .push_bind(fee_policy.tier);
     });
 
     query_builder.build().execute(ex).await.map(|_| ())
@@ -124,6 +126,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tier: None,
         };
         // surplus fee policy with caps
         let fee_policy_2 = FeePolicy {
@@ -135,6 +138,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tier: None,
         };
         // volume based fee policy
         let fee_policy_3 = FeePolicy {
@@ -146,6 +150,7 @@ mod tests {
             volume_factor: Some(0.06),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            tier: Some("stable".to_owned()),
         };
         // price improvement fee policy
         let fee_policy_4 = FeePolicy {
@@ -157,6 +162,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: Some(0.1),
             price_improvement_max_volume_factor: Some(0.99999),
+            tier: None,
         };
 
         let fee_policies = vec![
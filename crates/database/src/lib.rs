@@ -4,13 +4,16 @@ pub mod auction_orders;
 pub mod auction_participants;
 pub mod auction_prices;
 pub mod byte_array;
+pub mod driver_caches;
 pub mod ethflow_orders;
+pub mod ethflow_refund_attempts;
 pub mod events;
 pub mod fee_policies;
 pub mod jit_orders;
 pub mod last_indexed_blocks;
 pub mod onchain_broadcasted_orders;
 pub mod onchain_invalidations;
+pub mod order_conditions;
 pub mod order_events;
 pub mod order_execution;
 pub mod order_history;
@@ -56,7 +59,12 @@ pub const TABLES: &[&str] = &[
     "app_data",
     "auction_orders",
     "auctions",
+    "combined_settlement_solutions",
+    "driver_simulations",
+    "driver_token_qualities",
     "ethflow_orders",
+    "ethflow_refund_attempts",
+    "ethflow_refund_requests",
     "ethflow_refunds",
     "interactions",
     "invalidations",
@@ -64,6 +72,7 @@ pub const TABLES: &[&str] = &[
     "last_indexed_blocks",
     "onchain_order_invalidations",
     "onchain_placed_orders",
+    "order_conditions",
     "presignature_events",
     "proposed_jit_orders",
     "quotes",
@@ -72,6 +81,8 @@ pub const TABLES: &[&str] = &[
     "settlement_observations",
     "settlement_scores",
     "settlements",
+    "shadow_proposed_solutions",
+    "shadow_reference_scores",
     "solver_competitions",
     "surplus_capturing_jit_order_owners",
     "trades",
@@ -0,0 +1,93 @@
+use {crate::OrderUid, sqlx::PgConnection};
+
+/// Stores the condition an order was placed with. The condition of an order
+/// never changes so inserting it again is a no-op.
+pub async fn insert(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+    condition: &serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO order_conditions (order_uid, condition)
+VALUES ($1, $2)
+ON CONFLICT DO NOTHING
+;"#;
+    sqlx::query(QUERY)
+        .bind(order_uid)
+        .bind(condition)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT condition
+FROM order_conditions
+WHERE order_uid = $1
+;"#;
+    sqlx::query_scalar(QUERY)
+        .bind(order_uid)
+        .fetch_optional(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            byte_array::ByteArray,
+            orders::{self, Order},
+        },
+        serde_json::json,
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_conditions() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order {
+            uid: ByteArray([1; 56]),
+            ..Default::default()
+        };
+        orders::insert_order(&mut db, &order).await.unwrap();
+        assert_eq!(fetch(&mut db, &order.uid).await.unwrap(), None);
+        let full_order = orders::single_full_order_with_quote(&mut db, &order.uid)
+            .await
+            .unwrap()
+            .unwrap()
+            .full_order;
+        assert_eq!(full_order.condition, None);
+
+        let condition = json!({
+            "kind": "twap",
+            "parts": 4,
+            "startTime": 1_700_000_000,
+            "partDuration": 3600,
+        });
+        insert(&mut db, &order.uid, &condition).await.unwrap();
+        // The condition of an order can't be changed.
+        insert(&mut db, &order.uid, &json!({"kind": "stopLoss"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            fetch(&mut db, &order.uid).await.unwrap(),
+            Some(condition.clone())
+        );
+
+        let full_order = orders::single_full_order_with_quote(&mut db, &order.uid)
+            .await
+            .unwrap()
+            .unwrap()
+            .full_order;
+        assert_eq!(full_order.condition, Some(condition));
+    }
+}
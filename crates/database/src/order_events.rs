@@ -4,13 +4,18 @@
 use {
     crate::{OrderUid, byte_array::ByteArray},
     chrono::Utc,
-    sqlx::{PgConnection, PgPool, types::chrono::DateTime},
+    sqlx::{PgConnection, PgPool, postgres::PgListener, types::chrono::DateTime},
 };
 
+/// Postgres channel on which every newly inserted order event gets announced.
+/// The payload has the form `<hex encoded order uid>:<label>`.
+pub const NOTIFICATION_CHANNEL: &str = "order_events";
+
 /// Describes what kind of event was registered for an order.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type, strum::Display, strum::EnumString)]
 #[sqlx(type_name = "OrderEventLabel")]
 #[sqlx(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum OrderEventLabel {
     /// Order was added to the orderbook.
     Created,
@@ -34,7 +39,7 @@ pub enum OrderEventLabel {
 
 /// Contains a single event of the life cycle of an order and when it was
 /// registered.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type, sqlx::FromRow)]
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::Type, sqlx::FromRow)]
 pub struct OrderEvent {
     /// Which order this event belongs to
     pub order_uid: OrderUid,
@@ -43,34 +48,41 @@ pub struct OrderEvent {
     pub timestamp: DateTime<Utc>,
     /// What kind of event happened
     pub label: OrderEventLabel,
+    /// Details about why the event happened (e.g. why an order got filtered
+    /// from the auction)
+    pub reason: Option<serde_json::Value>,
 }
 
 /// Inserts a row into the `order_events` table only if the latest event for the
-/// corresponding order UID has a different label than the provided event..
+/// corresponding order UID has a different label or kind of reason than the
+/// provided event. Only the `reason` field of the reasons gets compared, so
+/// changing details (e.g. the balance of an order that keeps getting filtered
+/// for insufficient balance) don't cause a new event every auction.
 pub async fn insert_order_event(
     ex: &mut PgConnection,
     event: &OrderEvent,
 ) -> Result<(), sqlx::Error> {
     const QUERY: &str = r#"
         WITH cte AS (
-            SELECT label
+            SELECT label, reason
             FROM order_events
             WHERE order_uid = $1
             ORDER BY timestamp DESC
             LIMIT 1
         )
-        INSERT INTO order_events (order_uid, timestamp, label)
-        SELECT $1, $2, $3
+        INSERT INTO order_events (order_uid, timestamp, label, reason)
+        SELECT $1, $2, $3, $4
         WHERE NOT EXISTS (
             SELECT 1
             FROM cte
-            WHERE label = $3
+            WHERE label = $3 AND reason->'reason' IS NOT DISTINCT FROM $4->'reason'
         )
     "#;
     sqlx::query(QUERY)
         .bind(event.order_uid)
         .bind(event.timestamp)
         .bind(event.label)
+        .bind(&event.reason)
         .execute(ex)
         .await
         .map(|_| ())
@@ -104,6 +116,23 @@ pub async fn get_latest(
         .await
 }
 
+/// Creates a listener that receives a notification for every order event
+/// inserted from now on. Use [`parse_notification`] to decode the payloads.
+pub async fn listen(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(NOTIFICATION_CHANNEL).await?;
+    Ok(listener)
+}
+
+/// Parses the payload of a notification received on
+/// [`NOTIFICATION_CHANNEL`].
+pub fn parse_notification(payload: &str) -> Option<(OrderUid, OrderEventLabel)> {
+    let (uid, label) = payload.split_once(':')?;
+    let mut order_uid = [0u8; 56];
+    hex::decode_to_slice(uid, &mut order_uid).ok()?;
+    Some((ByteArray(order_uid), label.parse().ok()?))
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -129,24 +158,28 @@ mod tests {
             order_uid: uid_a,
             timestamp: now - chrono::Duration::milliseconds(300),
             label: OrderEventLabel::Created,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_a).await.unwrap();
         let event_b = OrderEvent {
             order_uid: uid_a,
             timestamp: now - chrono::Duration::milliseconds(200),
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_b).await.unwrap();
         let event_c = OrderEvent {
             order_uid: uid_b,
             timestamp: now - chrono::Duration::milliseconds(100),
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_c).await.unwrap();
         let event_d = OrderEvent {
             order_uid: uid_a,
             timestamp: now,
             label: OrderEventLabel::Invalid,
+            reason: None,
         };
         insert_order_event(&mut ex, &event_d).await.unwrap();
 
@@ -172,6 +205,89 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_events_with_reasons() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc::now();
+        let uid = ByteArray([1; 56]);
+        let event = |millis, reason: serde_json::Value| OrderEvent {
+            order_uid: uid,
+            timestamp: now + chrono::Duration::milliseconds(millis),
+            label: OrderEventLabel::Filtered,
+            reason: Some(reason),
+        };
+        let insufficient_balance = |balance: &str| {
+            serde_json::json!({
+                "reason": "insufficientBalance",
+                "balance": balance,
+                "required": "100",
+            })
+        };
+        insert_order_event(&mut db, &event(0, insufficient_balance("1")))
+            .await
+            .unwrap();
+        // Same label and kind of reason are not stored again, even if the
+        // details changed.
+        insert_order_event(&mut db, &event(1, insufficient_balance("2")))
+            .await
+            .unwrap();
+        let out_of_market = serde_json::json!({ "reason": "outOfMarket" });
+        insert_order_event(&mut db, &event(2, out_of_market.clone()))
+            .await
+            .unwrap();
+
+        let events = all_order_events(&mut db).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].reason, Some(insufficient_balance("1")));
+        let latest = get_latest(&mut db, &uid).await.unwrap().unwrap();
+        assert_eq!(latest.reason, Some(out_of_market));
+    }
+
+    #[test]
+    fn parses_notification_payloads() {
+        let uid = ByteArray([0x11; 56]);
+        let payload = format!("{}:traded", hex::encode(uid.0));
+        assert_eq!(
+            parse_notification(&payload),
+            Some((uid, OrderEventLabel::Traded))
+        );
+
+        assert_eq!(parse_notification("1111:traded"), None);
+        assert_eq!(
+            parse_notification(&format!("{}:unknown", hex::encode(uid.0))),
+            None
+        );
+        assert_eq!(parse_notification(&hex::encode(uid.0)), None);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_notifies_inserted_order_events() {
+        let pool = PgPool::connect("postgresql://").await.unwrap();
+        crate::clear_DANGER(&pool).await.unwrap();
+        let mut listener = listen(&pool).await.unwrap();
+
+        let event = OrderEvent {
+            order_uid: ByteArray([1; 56]),
+            timestamp: Utc::now(),
+            label: OrderEventLabel::Ready,
+            reason: None,
+        };
+        let mut ex = pool.acquire().await.unwrap();
+        insert_order_event(&mut ex, &event).await.unwrap();
+
+        let notification = listener.recv().await.unwrap();
+        assert_eq!(notification.channel(), NOTIFICATION_CHANNEL);
+        assert_eq!(
+            parse_notification(notification.payload()),
+            Some((event.order_uid, event.label))
+        );
+    }
+
     async fn all_order_events(ex: &mut PgConnection) -> Vec<OrderEvent> {
         const QUERY: &str = r#"
                 SELECT *
@@ -1,5 +1,6 @@
 use {
-    crate::{Address, jit_orders, orders},
+    crate::{Address, OrderUid, jit_orders, orders},
+    chrono::{DateTime, Utc},
     futures::stream::BoxStream,
     sqlx::PgConnection,
 };
@@ -10,14 +11,10 @@ pub fn user_orders<'a>(
     offset: i64,
     limit: Option<i64>,
 ) -> BoxStream<'a, Result<orders::FullOrder, sqlx::Error>> {
-    // As a future consideration for this query we could move from offset to an
-    // approach called keyset pagination where the offset is identified by "key"
-    // of the previous query. In our case that would be the lowest
-    // creation_timestamp. This way the database can start immediately at the
-    // offset through the index without enumerating the first N elements
+    // For large histories prefer `paginated_user_orders` which uses keyset
+    // pagination so that the database can start immediately at the requested
+    // position through the index without enumerating the first N elements
     // before as is the case with OFFSET.
-    // On the other hand that approach is less flexible so we will consider if we
-    // see that these queries are taking too long in practice.
     #[rustfmt::skip]
     const QUERY: &str = const_format::concatcp!(
 "(SELECT ", orders::SELECT,
@@ -47,6 +44,81 @@ pub fn user_orders<'a>(
         .fetch(ex)
 }
 
+/// Position of an order in a user's order history which is sorted by creation
+/// timestamp descending. The order UID breaks ties between orders created at
+/// the same time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderCursor {
+    pub creation_timestamp: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+/// Additional criteria for paginating through a user's orders. Any `None`
+/// value means that this field is unfiltered.
+#[derive(Clone, Debug, Default)]
+pub struct UserOrderFilter {
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    pub class: Option<orders::OrderClass>,
+    /// Only orders created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only orders created before this time.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only orders that come after this position in the history.
+    pub after: Option<OrderCursor>,
+}
+
+/// Like `user_orders` but uses keyset pagination which keeps pages stable
+/// while new orders get created.
+pub fn paginated_user_orders<'a>(
+    ex: &'a mut PgConnection,
+    owner: &'a Address,
+    filter: &'a UserOrderFilter,
+    limit: i64,
+) -> BoxStream<'a, Result<orders::FullOrder, sqlx::Error>> {
+    const FILTER: &str = r#"
+ AND ($2 IS NULL OR o.sell_token = $2)
+ AND ($3 IS NULL OR o.buy_token = $3)
+ AND ($5 IS NULL OR o.creation_timestamp >= $5)
+ AND ($6 IS NULL OR o.creation_timestamp < $6)
+ AND ($7 IS NULL OR (o.creation_timestamp, o.uid) < ($7, $8))"#;
+    const ORDER: &str = " ORDER BY creation_timestamp DESC, uid DESC LIMIT $9";
+
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"(SELECT ", orders::SELECT,
+" FROM ", orders::FROM,
+" WHERE o.owner = $1", FILTER,
+" AND ($4 IS NULL OR o.class = $4)",
+ORDER, ") ",
+" UNION ",
+" (SELECT ", orders::SELECT,
+" FROM ", orders::FROM,
+" JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
+" WHERE onchain_o.sender = $1", FILTER,
+" AND ($4 IS NULL OR o.class = $4)",
+ORDER, ") ",
+" UNION ",
+" (SELECT ", jit_orders::SELECT,
+" FROM ", jit_orders::FROM,
+" WHERE o.owner = $1 AND NOT EXISTS (SELECT 1 FROM orders ord WHERE o.uid = ord.uid)", FILTER,
+" AND ($4 IS NULL OR $4 = 'liquidity'::OrderClass)",
+ORDER, ") ",
+ORDER,
+    );
+    sqlx::query_as(QUERY)
+        .bind(owner)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.class)
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.after.map(|cursor| cursor.creation_timestamp))
+        .bind(filter.after.map(|cursor| cursor.uid))
+        .bind(limit)
+        .fetch(ex)
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -56,7 +128,6 @@ mod tests {
             events::EventIndex,
             onchain_broadcasted_orders::{OnchainOrderPlacement, insert_onchain_order},
         },
-        chrono::{DateTime, Utc},
         futures::StreamExt,
         sqlx::Connection,
     };
@@ -77,6 +148,102 @@ mod tests {
             .await
     }
 
+    async fn paginated_user_orders(
+        ex: &mut PgConnection,
+        owner: &Address,
+        filter: &UserOrderFilter,
+        limit: i64,
+    ) -> Vec<OrderUid> {
+        super::paginated_user_orders(ex, owner, filter, limit)
+            .map(|o| o.unwrap().uid)
+            .collect::<Vec<_>>()
+            .await
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_paginated_user_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        let now = Utc::now();
+        let order = |i: u8, class: orders::OrderClass| orders::Order {
+            uid: ByteArray([i; 56]),
+            owner,
+            creation_timestamp: now - chrono::Duration::seconds(i.into()),
+            sell_token: ByteArray([i % 2; 20]),
+            class,
+            ..Default::default()
+        };
+        for i in 1..=5 {
+            orders::insert_order(&mut db, &order(i, orders::OrderClass::Limit))
+                .await
+                .unwrap();
+        }
+        orders::insert_order(&mut db, &order(6, orders::OrderClass::Market))
+            .await
+            .unwrap();
+        // Another user's order doesn't show up.
+        orders::insert_order(
+            &mut db,
+            &orders::Order {
+                owner: ByteArray([2; 20]),
+                ..order(7, orders::OrderClass::Limit)
+            },
+        )
+        .await
+        .unwrap();
+
+        let uids = |ids: &[u8]| ids.iter().map(|i| ByteArray([*i; 56])).collect::<Vec<_>>();
+
+        // Walking through the pages returns all orders newest first.
+        let mut filter = UserOrderFilter::default();
+        let page = paginated_user_orders(&mut db, &owner, &filter, 4).await;
+        assert_eq!(page, uids(&[1, 2, 3, 4]));
+
+        // Orders created after the first page was fetched don't shift the next
+        // page.
+        orders::insert_order(
+            &mut db,
+            &orders::Order {
+                creation_timestamp: now + chrono::Duration::seconds(1),
+                ..order(8, orders::OrderClass::Limit)
+            },
+        )
+        .await
+        .unwrap();
+        filter.after = Some(OrderCursor {
+            creation_timestamp: now - chrono::Duration::seconds(4),
+            uid: ByteArray([4; 56]),
+        });
+        let page = paginated_user_orders(&mut db, &owner, &filter, 4).await;
+        assert_eq!(page, uids(&[5, 6]));
+
+        let filter = UserOrderFilter {
+            sell_token: Some(ByteArray([1; 20])),
+            ..Default::default()
+        };
+        let page = paginated_user_orders(&mut db, &owner, &filter, 10).await;
+        assert_eq!(page, uids(&[1, 3, 5]));
+
+        let filter = UserOrderFilter {
+            class: Some(orders::OrderClass::Market),
+            ..Default::default()
+        };
+        let page = paginated_user_orders(&mut db, &owner, &filter, 10).await;
+        assert_eq!(page, uids(&[6]));
+
+        let filter = UserOrderFilter {
+            created_after: Some(now - chrono::Duration::seconds(3)),
+            created_before: Some(now - chrono::Duration::seconds(1)),
+            ..Default::default()
+        };
+        let page = paginated_user_orders(&mut db, &owner, &filter, 10).await;
+        assert_eq!(page, uids(&[2, 3]));
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_user_orders_performance_many_users_with_some_orders() {
@@ -112,6 +112,7 @@ pub async fn insert_orders_and_ignore_conflicts(
                 label: OrderEventLabel::Created,
                 timestamp: order.creation_timestamp,
                 order_uid: order.uid,
+                reason: None,
             },
         )
         .await?;
@@ -492,6 +493,7 @@ pub struct FullOrder {
     pub executed_fee: BigDecimal,
     pub executed_fee_token: Address,
     pub full_app_data: Option<Vec<u8>>,
+    pub condition: Option<serde_json::Value>,
 }
 
 impl FullOrder {
@@ -607,7 +609,8 @@ array(Select (p.target, p.value, p.data) from interactions p where p.order_uid =
 (SELECT onchain_o.placement_error from onchain_placed_orders onchain_o where onchain_o.uid = o.uid limit 1) as onchain_placement_error,
 COALESCE((SELECT SUM(executed_fee) FROM order_execution oe WHERE oe.order_uid = o.uid), 0) as executed_fee,
 COALESCE((SELECT executed_fee_token FROM order_execution oe WHERE oe.order_uid = o.uid LIMIT 1), o.sell_token) as executed_fee_token, -- TODO surplus token
-(SELECT full_app_data FROM app_data ad WHERE o.app_data = ad.contract_app_data LIMIT 1) as full_app_data
+(SELECT full_app_data FROM app_data ad WHERE o.app_data = ad.contract_app_data LIMIT 1) as full_app_data,
+(SELECT c.condition FROM order_conditions c WHERE c.order_uid = o.uid) as condition
 "#;
 
 pub const FROM: &str = "orders o";
@@ -764,6 +767,37 @@ pub async fn count_limit_orders_by_owner(
         .await
 }
 
+/// Open limit orders trading `sell_token` for `buy_token` with the conditions
+/// of OPEN_ORDERS that are not waiting for a pre-signature. Returns at most
+/// `limit` orders with the lowest limit price, i.e. asking for the fewest buy
+/// tokens per sell token.
+pub fn open_limit_orders_for_pair<'a>(
+    ex: &'a mut PgConnection,
+    min_valid_to: i64,
+    sell_token: &'a Address,
+    buy_token: &'a Address,
+    limit: i64,
+) -> BoxStream<'a, Result<FullOrder, sqlx::Error>> {
+    // The limit price can't be indexed, so the pair's open orders get sorted
+    // in memory. The `orders_sell_buy_tokens` index keeps that set small.
+    const QUERY: &str = const_format::concatcp!(
+        OPEN_ORDERS,
+        " AND class = 'limit'",
+        " AND sell_token = $2",
+        " AND buy_token = $3",
+        " AND NOT presignature_pending",
+        " AND sell_amount > 0",
+        " ORDER BY buy_amount / sell_amount",
+        " LIMIT $4",
+    );
+    sqlx::query_as(QUERY)
+        .bind(min_valid_to)
+        .bind(sell_token)
+        .bind(buy_token)
+        .bind(limit)
+        .fetch(ex)
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct OrderWithQuote {
     pub order_buy_amount: BigDecimal,
@@ -1990,6 +2024,113 @@ mod tests {
         assert_eq!(order.executed_fee, fee);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_open_limit_orders_for_pair() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let sell_token = ByteArray([1; 20]);
+        let buy_token = ByteArray([2; 20]);
+        let order = |uid: u8| Order {
+            uid: ByteArray([uid; 56]),
+            sell_token,
+            buy_token,
+            sell_amount: 10.into(),
+            buy_amount: 20.into(),
+            valid_to: 10,
+            class: OrderClass::Limit,
+            ..Default::default()
+        };
+        // Matching limit order.
+        insert_order(&mut db, &order(1)).await.unwrap();
+        // Market order.
+        insert_order(
+            &mut db,
+            &Order {
+                class: OrderClass::Market,
+                ..order(2)
+            },
+        )
+        .await
+        .unwrap();
+        // Opposite direction.
+        insert_order(
+            &mut db,
+            &Order {
+                sell_token: buy_token,
+                buy_token: sell_token,
+                ..order(3)
+            },
+        )
+        .await
+        .unwrap();
+        // Expired.
+        insert_order(
+            &mut db,
+            &Order {
+                valid_to: 1,
+                ..order(4)
+            },
+        )
+        .await
+        .unwrap();
+        // Waiting for a pre-signature.
+        insert_order(
+            &mut db,
+            &Order {
+                signing_scheme: SigningScheme::PreSign,
+                ..order(5)
+            },
+        )
+        .await
+        .unwrap();
+
+        let orders: Vec<_> = open_limit_orders_for_pair(&mut db, 5, &sell_token, &buy_token, 10)
+            .map_ok(|order| order.uid)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(orders, vec![ByteArray([1; 56])]);
+
+        // Orders without a sell amount have no limit price.
+        insert_order(
+            &mut db,
+            &Order {
+                kind: OrderKind::Buy,
+                sell_amount: 0.into(),
+                ..order(7)
+            },
+        )
+        .await
+        .unwrap();
+
+        // The orders with the lowest limit price come first and only up to the
+        // limit get returned.
+        insert_order(
+            &mut db,
+            &Order {
+                buy_amount: 15.into(),
+                ..order(6)
+            },
+        )
+        .await
+        .unwrap();
+        let orders: Vec<_> = open_limit_orders_for_pair(&mut db, 5, &sell_token, &buy_token, 10)
+            .map_ok(|order| order.uid)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(orders, vec![ByteArray([6; 56]), ByteArray([1; 56])]);
+        let orders: Vec<_> = open_limit_orders_for_pair(&mut db, 5, &sell_token, &buy_token, 1)
+            .map_ok(|order| order.uid)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(orders, vec![ByteArray([6; 56])]);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_order_app_data() {
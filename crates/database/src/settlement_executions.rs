@@ -55,6 +55,28 @@ WHERE auction_id = $1 AND solver = $2
     Ok(())
 }
 
+/// Returns the outcome of the solver's settlement execution for the auction.
+/// `None` if the solver was not asked to settle or the execution did not end
+/// yet.
+pub async fn outcome(
+    ex: &mut PgConnection,
+    auction_id: AuctionId,
+    solver: Address,
+) -> Result<Option<String>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT outcome
+FROM settlement_executions
+WHERE auction_id = $1 AND solver = $2
+    ;"#;
+
+    let outcome: Option<Option<String>> = sqlx::query_scalar(QUERY)
+        .bind(auction_id)
+        .bind(solver)
+        .fetch_optional(ex)
+        .await?;
+    Ok(outcome.flatten())
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -176,6 +198,17 @@ mod tests {
         };
         assert!(output.contains(&expected_a));
         assert!(output.contains(&expected_b));
+
+        assert_eq!(
+            outcome(&mut db, auction_id, solver_a).await.unwrap(),
+            expected_a.outcome
+        );
+        assert_eq!(
+            outcome(&mut db, auction_id, ByteArray([3u8; 20]))
+                .await
+                .unwrap(),
+            None
+        );
     }
 
     #[derive(Debug, Clone, Eq, PartialEq, sqlx::FromRow)]
@@ -123,13 +123,17 @@ pub async fn load_by_id(
         return Ok(None);
     };
 
+    // A combined settlement settles all solutions merged into it.
     const FETCH_SETTLEMENTS: &str = r#"
-        SELECT solution_uid, tx_hash
+        SELECT COALESCE(css.solution_uid, s.solution_uid) AS solution_uid, tx_hash
         FROM settlements s
         LEFT OUTER JOIN settlement_observations so ON
              s.block_number = so.block_number
              AND s.log_index = so.log_index
-         WHERE auction_id = $1;
+        LEFT OUTER JOIN combined_settlement_solutions css ON
+             s.auction_id = css.auction_id
+             AND s.solver = css.submitter
+         WHERE s.auction_id = $1;
     "#;
     let settlements: Vec<Settlement> = sqlx::query_as(FETCH_SETTLEMENTS)
         .bind(id)
@@ -197,7 +201,7 @@ pub async fn find_non_settling_solvers(
     const QUERY: &str = r#"
 WITH
     last_auctions AS (
-        SELECT ps.auction_id, ps.solver
+        SELECT ps.auction_id, ps.uid, ps.solver
         FROM (
             SELECT DISTINCT ca.id AS auction_id
             FROM competition_auctions ca
@@ -211,8 +215,10 @@ WITH
     unsuccessful_solvers AS (
         SELECT la.auction_id, la.solver
         FROM last_auctions la
+        LEFT JOIN combined_settlement_solutions css
+        ON la.auction_id = css.auction_id AND la.uid = css.solution_uid
         LEFT JOIN settlements s
-        ON la.auction_id = s.auction_id AND la.solver = s.solver
+        ON la.auction_id = s.auction_id AND COALESCE(css.submitter, la.solver) = s.solver
         WHERE s.auction_id IS NULL
     ),
     solver_appearance_count AS (
@@ -250,7 +256,7 @@ pub async fn find_low_settling_solvers(
     const QUERY: &str = r#"
 WITH
     last_auctions AS (
-        SELECT ps.auction_id, ps.solver
+        SELECT ps.auction_id, ps.uid, ps.solver
         FROM (
             SELECT DISTINCT ca.id AS auction_id
             FROM competition_auctions ca
@@ -266,8 +272,10 @@ WITH
                COUNT(DISTINCT la.auction_id) AS total_wins,
                COUNT(DISTINCT s.auction_id) AS total_settlements
         FROM last_auctions la
+        LEFT JOIN combined_settlement_solutions css
+        ON la.auction_id = css.auction_id AND la.uid = css.solution_uid
         LEFT JOIN settlements s
-        ON la.auction_id = s.auction_id AND la.solver = s.solver
+        ON la.auction_id = s.auction_id AND COALESCE(css.submitter, la.solver) = s.solver
         GROUP BY la.solver
     )
 SELECT solver
@@ -420,6 +428,140 @@ async fn save_jit_orders(
     Ok(())
 }
 
+/// How a winner selection mechanism running in shadow mode ranked a proposed
+/// solution. The solution is identified by its `uid` in `proposed_solutions`.
+#[derive(Clone, Debug, PartialEq, Default, sqlx::FromRow)]
+pub struct ShadowSolution {
+    pub arbitrator: String,
+    pub solution_uid: i64,
+    pub ranking: i64,
+    pub is_winner: bool,
+    pub filtered_out: bool,
+    pub score: BigDecimal,
+}
+
+/// Reference score computed by a winner selection mechanism running in shadow
+/// mode.
+#[derive(Clone, Debug, PartialEq, Default, sqlx::FromRow)]
+pub struct ShadowReferenceScore {
+    pub arbitrator: String,
+    pub solver: Address,
+    pub reference_score: BigDecimal,
+}
+
+pub async fn save_shadow_rankings(
+    ex: &mut PgTransaction<'_>,
+    auction_id: AuctionId,
+    solutions: &[ShadowSolution],
+    reference_scores: &[ShadowReferenceScore],
+) -> Result<(), sqlx::Error> {
+    if !solutions.is_empty() {
+        let mut builder = QueryBuilder::new(
+            r#"INSERT INTO shadow_proposed_solutions
+            (auction_id, arbitrator, solution_uid, ranking, is_winner, filtered_out, score)"#,
+        );
+        builder.push_values(solutions, |mut b, solution| {
+            b.push_bind(auction_id)
+                .push_bind(&solution.arbitrator)
+                .push_bind(solution.solution_uid)
+                .push_bind(solution.ranking)
+                .push_bind(solution.is_winner)
+                .push_bind(solution.filtered_out)
+                .push_bind(&solution.score);
+        });
+        builder.push(" ON CONFLICT (auction_id, arbitrator, solution_uid) DO NOTHING;");
+        builder.build().execute(ex.deref_mut()).await?;
+    }
+
+    if !reference_scores.is_empty() {
+        let mut builder = QueryBuilder::new(
+            r#"INSERT INTO shadow_reference_scores
+            (auction_id, arbitrator, solver, reference_score)"#,
+        );
+        builder.push_values(reference_scores, |mut b, score| {
+            b.push_bind(auction_id)
+                .push_bind(&score.arbitrator)
+                .push_bind(score.solver)
+                .push_bind(&score.reference_score);
+        });
+        builder.push(" ON CONFLICT (auction_id, arbitrator, solver) DO NOTHING;");
+        builder.build().execute(ex.deref_mut()).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn fetch_shadow_solutions(
+    ex: &mut PgConnection,
+    auction_id: AuctionId,
+) -> Result<Vec<ShadowSolution>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT arbitrator, solution_uid, ranking, is_winner, filtered_out, score
+        FROM shadow_proposed_solutions
+        WHERE auction_id = $1
+        ORDER BY arbitrator, ranking
+    "#;
+    sqlx::query_as(QUERY).bind(auction_id).fetch_all(ex).await
+}
+
+pub async fn fetch_shadow_reference_scores(
+    ex: &mut PgConnection,
+    auction_id: AuctionId,
+) -> Result<Vec<ShadowReferenceScore>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT arbitrator, solver, reference_score
+        FROM shadow_reference_scores
+        WHERE auction_id = $1
+        ORDER BY arbitrator, solver
+    "#;
+    sqlx::query_as(QUERY).bind(auction_id).fetch_all(ex).await
+}
+
+/// Records that the winning solutions with the given uids got merged into a
+/// single settlement submitted by `submitter`.
+pub async fn save_combined_settlement(
+    ex: &mut PgConnection,
+    auction_id: AuctionId,
+    submitter: Address,
+    solution_uids: &[i64],
+) -> Result<(), sqlx::Error> {
+    if solution_uids.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = QueryBuilder::new(
+        r#"INSERT INTO combined_settlement_solutions (auction_id, solution_uid, submitter)"#,
+    );
+    builder.push_values(solution_uids, |mut b, solution_uid| {
+        b.push_bind(auction_id)
+            .push_bind(solution_uid)
+            .push_bind(submitter);
+    });
+    builder.push(" ON CONFLICT (auction_id, solution_uid) DO NOTHING;");
+    builder.build().execute(ex).await?;
+    Ok(())
+}
+
+/// Fetches the winning solutions which got merged into the combined
+/// settlement of `submitter`.
+pub async fn fetch_combined_winning_solutions(
+    ex: &mut PgConnection,
+    auction_id: AuctionId,
+    submitter: Address,
+) -> Result<Vec<Solution>, sqlx::Error> {
+    let query_str = format!(
+        r#"{BASE_SOLUTIONS_QUERY}
+    JOIN combined_settlement_solutions css
+        ON ps.auction_id = css.auction_id AND ps.uid = css.solution_uid
+    WHERE ps.auction_id = $1 AND css.submitter = $2 AND ps.is_winner = TRUE"#
+    );
+    let query = sqlx::query_as::<_, SolutionRow>(&query_str)
+        .bind(auction_id)
+        .bind(submitter);
+
+    map_rows_to_solutions(query.fetch_all(ex).await?)
+}
+
 #[derive(sqlx::FromRow)]
 struct SolutionRow {
     uid: i64,
@@ -630,6 +772,145 @@ mod tests {
         assert!(fetched_solutions[2].orders.len() == 3);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_shadow_rankings_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let solutions = vec![
+            ShadowSolution {
+                arbitrator: "combinatorial".to_string(),
+                solution_uid: 1,
+                ranking: 1,
+                is_winner: true,
+                filtered_out: false,
+                score: 100.into(),
+            },
+            ShadowSolution {
+                arbitrator: "combinatorial".to_string(),
+                solution_uid: 0,
+                ranking: 2,
+                is_winner: false,
+                filtered_out: true,
+                score: 200.into(),
+            },
+            ShadowSolution {
+                arbitrator: "max_score".to_string(),
+                solution_uid: 0,
+                ranking: 1,
+                is_winner: true,
+                filtered_out: false,
+                score: 200.into(),
+            },
+        ];
+        let reference_scores = vec![ShadowReferenceScore {
+            arbitrator: "combinatorial".to_string(),
+            solver: ByteArray([1; 20]),
+            reference_score: 50.into(),
+        }];
+        save_shadow_rankings(&mut db, 1, &solutions, &reference_scores)
+            .await
+            .unwrap();
+
+        assert_eq!(fetch_shadow_solutions(&mut db, 1).await.unwrap(), solutions);
+        assert_eq!(
+            fetch_shadow_reference_scores(&mut db, 1).await.unwrap(),
+            reference_scores
+        );
+        assert!(fetch_shadow_solutions(&mut db, 2).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_combined_settlement_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let auction = auction::Auction {
+            id: 1,
+            block: 1,
+            deadline: 10,
+            order_uids: Default::default(),
+            price_tokens: Default::default(),
+            price_values: Default::default(),
+            surplus_capturing_jit_order_owners: Default::default(),
+        };
+        auction::save(&mut db, auction).await.unwrap();
+
+        let solution = |uid: i64, solver: u8| Solution {
+            uid,
+            id: uid.into(),
+            solver: ByteArray([solver; 20]),
+            is_winner: true,
+            orders: vec![Order {
+                uid: ByteArray([solver; 56]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let solutions = vec![solution(0, 1), solution(1, 2), solution(2, 3)];
+        save(&mut db, 1, &solutions).await.unwrap();
+
+        // The solutions of solvers 1 and 2 get settled by solver 4.
+        let submitter = ByteArray([4; 20]);
+        save_combined_settlement(&mut db, 1, submitter, &[0, 1])
+            .await
+            .unwrap();
+
+        let combined = fetch_combined_winning_solutions(&mut db, 1, submitter)
+            .await
+            .unwrap();
+        assert_eq!(
+            combined
+                .iter()
+                .map(|solution| solution.uid)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(
+            fetch_combined_winning_solutions(&mut db, 1, ByteArray([1; 20]))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let event = EventIndex {
+            block_number: 5,
+            log_index: 0,
+        };
+        let settlement = Settlement {
+            solver: submitter,
+            transaction_hash: ByteArray([9; 32]),
+        };
+        events::insert_settlement(&mut db, &event, &settlement)
+            .await
+            .unwrap();
+        settlements::update_settlement_auction(&mut db, 5, 0, 1)
+            .await
+            .unwrap();
+        settlements::update_settlement_solver(&mut db, 5, 0, submitter, 0)
+            .await
+            .unwrap();
+
+        // The combined settlement settles both member solutions.
+        let competition = load_by_id(&mut db, 1).await.unwrap().unwrap();
+        let mut settled = competition
+            .settlements
+            .iter()
+            .map(|settlement| (settlement.solution_uid, settlement.tx_hash))
+            .collect::<Vec<_>>();
+        settled.sort_by_key(|(uid, _)| *uid);
+        assert_eq!(settled, [(0, ByteArray([9; 32])), (1, ByteArray([9; 32]))]);
+
+        // Only the solver whose solution was not part of the combined
+        // settlement failed to settle.
+        let result = find_non_settling_solvers(&mut db, 1, 10).await.unwrap();
+        assert_eq!(result, vec![ByteArray([3; 20])]);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_non_settling_solvers_roundtrip() {
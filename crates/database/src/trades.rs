@@ -20,12 +20,7 @@ pub struct TradesQueryRow {
     pub auction_id: Option<AuctionId>,
 }
 
-pub fn trades<'a>(
-    ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
-) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
-    const COMMON_QUERY: &str = r#"
+const COMMON_QUERY: &str = r#"
 SELECT
     t.block_number,
     t.log_index,
@@ -47,6 +42,11 @@ LEFT OUTER JOIN LATERAL (
     LIMIT 1
 ) AS settlement ON true"#;
 
+pub fn trades<'a>(
+    ex: &'a mut PgConnection,
+    owner_filter: Option<&'a Address>,
+    order_uid_filter: Option<&'a OrderUid>,
+) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const QUERY: &str = const_format::concatcp!(
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
@@ -72,6 +72,80 @@ LEFT OUTER JOIN LATERAL (
         .fetch(ex)
 }
 
+/// Position of a trade in the trade history which is sorted by block number
+/// and log index descending.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TradeCursor {
+    pub block_number: i64,
+    pub log_index: i64,
+}
+
+/// Criteria for paginating through trades. Any `None` value means that this
+/// field is unfiltered.
+#[derive(Clone, Debug, Default)]
+pub struct TradeFilter {
+    pub owner: Option<Address>,
+    pub order_uid: Option<OrderUid>,
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    /// Only trades in this block or later.
+    pub from_block: Option<i64>,
+    /// Only trades in this block or earlier.
+    pub to_block: Option<i64>,
+    /// Only trades that come after this position in the history.
+    pub after: Option<TradeCursor>,
+}
+
+/// Like `trades` but supports more filters and uses keyset pagination which
+/// keeps pages stable while new trades get indexed.
+pub fn paginated_trades<'a>(
+    ex: &'a mut PgConnection,
+    filter: &'a TradeFilter,
+    limit: i64,
+) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
+    const FILTER: &str = r#"
+ AND ($2 IS NULL OR o.uid = $2)
+ AND ($3 IS NULL OR o.sell_token = $3)
+ AND ($4 IS NULL OR o.buy_token = $4)
+ AND ($5 IS NULL OR t.block_number >= $5)
+ AND ($6 IS NULL OR t.block_number <= $6)
+ AND ($7 IS NULL OR (t.block_number, t.log_index) < ($7, $8))"#;
+    const ORDER: &str = " ORDER BY block_number DESC, log_index DESC LIMIT $9";
+
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+        "(", COMMON_QUERY,
+        " JOIN orders o ON o.uid = t.order_uid",
+        " WHERE ($1 IS NULL OR o.owner = $1)", FILTER,
+        ORDER, ")",
+        " UNION ",
+        "(", COMMON_QUERY,
+        " JOIN orders o ON o.uid = t.order_uid",
+        " JOIN onchain_placed_orders onchain_o",
+        " ON onchain_o.uid = t.order_uid",
+        " WHERE onchain_o.sender = $1", FILTER,
+        ORDER, ")",
+        " UNION ",
+        "(", COMMON_QUERY,
+        " JOIN jit_orders o ON o.uid = t.order_uid",
+        " WHERE ($1 IS NULL OR o.owner = $1)", FILTER,
+        ORDER, ")",
+        ORDER,
+    );
+
+    sqlx::query_as(QUERY)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(filter.after.map(|cursor| cursor.block_number))
+        .bind(filter.after.map(|cursor| cursor.log_index))
+        .bind(limit)
+        .fetch(ex)
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct TradeEvent {
     pub block_number: i64,
@@ -131,6 +205,45 @@ FROM (
     Ok(block_number)
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::FromRow)]
+pub struct OwnerVolume {
+    pub owner: Address,
+    /// Traded volume in wei.
+    pub volume: BigDecimal,
+}
+
+/// Sums up the native token value of all trades of the given owners settled
+/// since `from_block`. Trades are valued at the sell token's native price of
+/// the auction that settled them, so trades of settlements without a known
+/// auction are not counted.
+pub async fn owner_volumes(
+    ex: &mut PgConnection,
+    owners: &[Address],
+    from_block: i64,
+) -> Result<Vec<OwnerVolume>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT o.owner, FLOOR(SUM(t.sell_amount * ap.price / 1e18)) AS volume
+FROM trades t
+JOIN orders o ON o.uid = t.order_uid
+JOIN LATERAL (
+    SELECT auction_id FROM settlements s
+    WHERE s.block_number = t.block_number
+    AND   s.log_index > t.log_index
+    ORDER BY s.log_index ASC
+    LIMIT 1
+) AS settlement ON true
+JOIN auction_prices ap
+    ON ap.auction_id = settlement.auction_id AND ap.token = o.sell_token
+WHERE t.block_number >= $1 AND o.owner = ANY($2)
+GROUP BY o.owner
+"#;
+    sqlx::query_as(QUERY)
+        .bind(from_block)
+        .bind(owners)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -217,6 +330,99 @@ mod tests {
         assert_eq!(filtered, expected);
     }
 
+    async fn paginated(
+        db: &mut PgConnection,
+        filter: TradeFilter,
+        limit: i64,
+    ) -> Vec<TradesQueryRow> {
+        paginated_trades(db, &filter, limit)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_paginated_trades() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(2, 4).await;
+        let mut expected = Vec::new();
+        for (i, order_uid) in order_ids.iter().enumerate() {
+            let owner = owners[i % 2];
+            let event_index = EventIndex {
+                block_number: i64::try_from(i).unwrap(),
+                log_index: 0,
+            };
+            let order = Order {
+                uid: *order_uid,
+                owner,
+                sell_token: ByteArray([i as u8 % 2; 20]),
+                ..Default::default()
+            };
+            crate::orders::insert_order(&mut db, &order).await.unwrap();
+            let trade = add_trade(&mut db, owner, *order_uid, event_index, None, None).await;
+            expected.push(TradesQueryRow {
+                sell_token: order.sell_token,
+                ..trade
+            });
+        }
+        // Newest trades come first.
+        expected.reverse();
+
+        let page = paginated(&mut db, TradeFilter::default(), 3).await;
+        assert_eq!(page, expected[..3]);
+        let page = paginated(
+            &mut db,
+            TradeFilter {
+                after: Some(TradeCursor {
+                    block_number: 1,
+                    log_index: 0,
+                }),
+                ..Default::default()
+            },
+            3,
+        )
+        .await;
+        assert_eq!(page, expected[3..]);
+
+        let page = paginated(
+            &mut db,
+            TradeFilter {
+                owner: Some(owners[1]),
+                ..Default::default()
+            },
+            10,
+        )
+        .await;
+        assert_eq!(page, [expected[0].clone(), expected[2].clone()]);
+
+        let page = paginated(
+            &mut db,
+            TradeFilter {
+                sell_token: Some(ByteArray([0; 20])),
+                ..Default::default()
+            },
+            10,
+        )
+        .await;
+        assert_eq!(page, [expected[1].clone(), expected[3].clone()]);
+
+        let page = paginated(
+            &mut db,
+            TradeFilter {
+                from_block: Some(1),
+                to_block: Some(2),
+                ..Default::default()
+            },
+            10,
+        )
+        .await;
+        assert_eq!(page, expected[1..3]);
+    }
+
     // Testing trades without corresponding settlement events
     #[tokio::test]
     #[ignore]
@@ -631,4 +837,100 @@ mod tests {
             Some(123)
         );
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_owner_volumes() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(3, 4).await;
+        let token = ByteArray([9; 20]);
+        // 0.5 wei per token atom
+        crate::auction_prices::insert(
+            &mut db,
+            &[crate::auction_prices::AuctionPrice {
+                auction_id: 1,
+                token,
+                price: BigDecimal::from(500_000_000_000_000_000_u64),
+            }],
+        )
+        .await
+        .unwrap();
+
+        // (owner, block, sell amount, settled in known auction)
+        let trades = [
+            (0, 10, 1_000, true),
+            (0, 20, 3_001, true),
+            (1, 30, 4_000, true),
+            (1, 31, 100_000, false),
+        ];
+        for (i, (owner, block_number, sell_amount, known_auction)) in trades.into_iter().enumerate()
+        {
+            crate::orders::insert_order(
+                &mut db,
+                &Order {
+                    uid: order_ids[i],
+                    owner: owners[owner],
+                    sell_token: token,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            crate::events::append(
+                &mut db,
+                &[(
+                    EventIndex {
+                        block_number,
+                        log_index: 0,
+                    },
+                    Event::Trade(Trade {
+                        order_uid: order_ids[i],
+                        sell_amount_including_fee: sell_amount.into(),
+                        ..Default::default()
+                    }),
+                )],
+            )
+            .await
+            .unwrap();
+            add_settlement(
+                &mut db,
+                EventIndex {
+                    block_number,
+                    log_index: 1,
+                },
+                Default::default(),
+                Default::default(),
+                if known_auction { 1 } else { 2 },
+            )
+            .await;
+        }
+
+        let mut volumes = owner_volumes(&mut db, &owners, 0).await.unwrap();
+        volumes.sort_by_key(|volume| volume.owner.0);
+        assert_eq!(
+            volumes,
+            vec![
+                OwnerVolume {
+                    owner: owners[0],
+                    volume: 2_000.into(),
+                },
+                OwnerVolume {
+                    owner: owners[1],
+                    volume: 2_000.into(),
+                },
+            ]
+        );
+
+        let volumes = owner_volumes(&mut db, &owners[..1], 11).await.unwrap();
+        assert_eq!(
+            volumes,
+            vec![OwnerVolume {
+                owner: owners[0],
+                volume: 1_500.into(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,157 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{eth, liquidity},
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    ethrpc::block_stream::{BlockRetrieving, CurrentBlockWatcher},
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::balancer_v3::{BalancerV3FactoryKind, BalancerV3PoolFetcher, PoolKind},
+    },
+    solver::{
+        interactions::allowances::Allowances,
+        liquidity::{
+            BalancerV3PoolOrder,
+            balancer_v3::{BalancerV3Liquidity, SettlementHandler},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub mod stable;
+pub mod weighted;
+
+/// The contracts involved in swapping on a Balancer V3 pool.
+pub struct Pool {
+    pub batch_router: eth::ContractAddress,
+    pub permit2: eth::ContractAddress,
+    pub address: eth::ContractAddress,
+}
+
+pub fn to_domain(id: liquidity::Id, pool: BalancerV3PoolOrder) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<SettlementHandler>()
+        .expect("downcast balancer v3 settlement handler");
+    let contracts = Pool {
+        batch_router: handler.batch_router().into(),
+        permit2: handler.permit2().into(),
+        address: handler.pool().into(),
+    };
+
+    let pool = pool.pool;
+    match &pool.kind {
+        PoolKind::Weighted(weights) => weighted::to_domain(id, contracts, &pool, weights),
+        PoolKind::Stable(amplification_parameter) => {
+            stable::to_domain(id, contracts, &pool, *amplification_parameter)
+        }
+    }
+}
+
+/// Encodes the `Permit2` approval of the `BatchRouter` followed by the router
+/// call executing the swap. The router sends the output tokens to the caller,
+/// so the `receiver` needs to be the settlement contract executing the
+/// interactions.
+pub fn to_interactions(
+    pool: &Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> Vec<eth::Interaction> {
+    let handler = SettlementHandler::new(
+        pool.address.0,
+        pool.batch_router.0,
+        pool.permit2.0,
+        Allowances::empty(receiver.0),
+    );
+
+    let (permit, swap) = handler.swap(
+        TokenAmount::new(input.0.token.into(), input.0.amount),
+        TokenAmount::new(output.0.token.into(), output.0.amount),
+    );
+    [permit.encode(), swap.encode()]
+        .into_iter()
+        .map(|encoded| eth::Interaction {
+            target: eth::Address(encoded.0),
+            value: eth::Ether(encoded.1),
+            call_data: crate::util::Bytes(encoded.2.0),
+        })
+        .collect()
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_stream: CurrentBlockWatcher,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::BalancerV3,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("balancerV3".into()));
+    let reinit_interval = config.reinit_interval;
+    let config = Arc::new(config.clone());
+    let init = move || {
+        let eth = eth.clone();
+        let block_stream = block_stream.clone();
+        let block_retriever = block_retriever.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, &block_stream, block_retriever.clone(), &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "balancer-v3",
+        init,
+        TEN_MINUTES,
+        reinit_interval,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_stream: &CurrentBlockWatcher,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::BalancerV3,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let factories = config
+        .weighted
+        .iter()
+        .map(|factory| (BalancerV3FactoryKind::Weighted, factory.0))
+        .chain(
+            config
+                .stable
+                .iter()
+                .map(|factory| (BalancerV3FactoryKind::Stable, factory.0)),
+        )
+        .collect();
+
+    let pool_fetcher = Arc::new(
+        BalancerV3PoolFetcher::new(
+            web3.clone(),
+            config.vault.0,
+            config.deployment_block,
+            factories,
+            block_retriever,
+            boundary::liquidity::cache_config(),
+            block_stream.clone(),
+        )
+        .await
+        .context("failed to initialise Balancer V3 liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(BalancerV3Liquidity::new(
+        web3,
+        pool_fetcher,
+        eth.contracts().settlement().clone(),
+        config.batch_router.0,
+        config.permit2.0,
+    ))
+}
@@ -0,0 +1,52 @@
+use {
+    super::Pool,
+    crate::{
+        boundary::Result,
+        domain::{
+            eth,
+            liquidity::{self, balancer},
+        },
+    },
+    shared::sources::{
+        balancer_v2::pool_fetching::AmplificationParameter,
+        balancer_v3::{self, swap::SWAP_GAS_COST},
+    },
+};
+
+pub fn to_domain(
+    id: liquidity::Id,
+    contracts: Pool,
+    pool: &balancer_v3::Pool,
+    amplification_parameter: AmplificationParameter,
+) -> Result<liquidity::Liquidity> {
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(SWAP_GAS_COST.into()),
+        kind: liquidity::Kind::BalancerV3Stable(balancer::v3::stable::Pool {
+            batch_router: contracts.batch_router,
+            permit2: contracts.permit2,
+            address: contracts.address,
+            reserves: balancer::v2::stable::Reserves::try_new(
+                pool.tokens
+                    .iter()
+                    .map(|state| {
+                        Ok(balancer::v2::stable::Reserve {
+                            asset: eth::Asset {
+                                token: state.token.into(),
+                                amount: state.balance.into(),
+                            },
+                            scale: balancer::v2::ScalingFactor::from_raw(
+                                state.scaling_factor.as_uint256(),
+                            )?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            )?,
+            amplification_parameter: balancer::v2::stable::AmplificationParameter::new(
+                amplification_parameter.factor(),
+                amplification_parameter.precision(),
+            )?,
+            fee: balancer::v2::Fee::from_raw(pool.swap_fee.as_uint256()),
+        }),
+    })
+}
@@ -0,0 +1,55 @@
+use {
+    super::Pool,
+    crate::{
+        boundary::Result,
+        domain::{
+            eth,
+            liquidity::{self, balancer},
+        },
+    },
+    shared::sources::{
+        balancer_v2::swap::fixed_point::Bfp,
+        balancer_v3::{self, swap::SWAP_GAS_COST},
+    },
+};
+
+pub fn to_domain(
+    id: liquidity::Id,
+    contracts: Pool,
+    pool: &balancer_v3::Pool,
+    weights: &[Bfp],
+) -> Result<liquidity::Liquidity> {
+    anyhow::ensure!(
+        weights.len() == pool.tokens.len(),
+        "Balancer V3 weighted pools should have a weight for each token",
+    );
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(SWAP_GAS_COST.into()),
+        kind: liquidity::Kind::BalancerV3Weighted(balancer::v3::weighted::Pool {
+            batch_router: contracts.batch_router,
+            permit2: contracts.permit2,
+            address: contracts.address,
+            reserves: balancer::v2::weighted::Reserves::try_new(
+                pool.tokens
+                    .iter()
+                    .zip(weights)
+                    .map(|(state, weight)| {
+                        Ok(balancer::v2::weighted::Reserve {
+                            asset: eth::Asset {
+                                token: state.token.into(),
+                                amount: state.balance.into(),
+                            },
+                            weight: balancer::v2::weighted::Weight::from_raw(weight.as_uint256()),
+                            scale: balancer::v2::ScalingFactor::from_raw(
+                                state.scaling_factor.as_uint256(),
+                            )?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            )?,
+            fee: balancer::v2::Fee::from_raw(pool.swap_fee.as_uint256()),
+        }),
+    })
+}
@@ -0,0 +1,178 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{
+                self,
+                curve::{CryptoSwap, Parameters, Pool, StableSwap},
+            },
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    ethrpc::block_stream::{BlockRetrieving, CurrentBlockWatcher},
+    shared::{
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::curve::{
+            CurveFactoryKind,
+            CurvePoolFetcher,
+            PoolKind,
+            swap::{CRYPTO_SWAP_GAS_COST, STABLE_SWAP_GAS_COST},
+        },
+    },
+    solver::{
+        interactions::{CurveExchange, CurveExchangeInteraction},
+        liquidity::{CurvePoolOrder, curve::CurveLiquidity},
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub fn to_domain(id: liquidity::Id, pool: CurvePoolOrder) -> Result<liquidity::Liquidity> {
+    let pool = pool.pool;
+    anyhow::ensure!(
+        pool.tokens.len() >= 2 && pool.tokens.len() == pool.balances.len(),
+        "Curve pools should have a balance for each of at least 2 tokens",
+    );
+
+    let (gas, parameters) = match pool.kind {
+        PoolKind::StableSwap(state) => (
+            STABLE_SWAP_GAS_COST,
+            Parameters::StableSwap(StableSwap {
+                amplification: state.amplification,
+                fee: state.fee,
+                offpeg_fee_multiplier: state.offpeg_fee_multiplier,
+                rates: state.rates,
+            }),
+        ),
+        PoolKind::CryptoSwap(state) => (
+            CRYPTO_SWAP_GAS_COST,
+            Parameters::CryptoSwap(CryptoSwap {
+                a: state.a,
+                gamma: state.gamma,
+                d: state.d,
+                price_scale: state.price_scale,
+                precisions: state.precisions,
+                mid_fee: state.mid_fee,
+                out_fee: state.out_fee,
+                fee_gamma: state.fee_gamma,
+            }),
+        ),
+    };
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(gas.into()),
+        kind: liquidity::Kind::Curve(Pool {
+            address: pool.address.into(),
+            tokens: pool.tokens.into_iter().map(Into::into).collect(),
+            balances: pool.balances,
+            parameters,
+        }),
+    })
+}
+
+/// Encodes a Curve `exchange` call. Curve pools always pull the input tokens
+/// from and send the output tokens to the caller, so the `receiver` needs to
+/// be the settlement contract executing the interaction.
+pub fn to_interaction(
+    pool: &liquidity::curve::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    _receiver: &eth::Address,
+) -> Result<eth::Interaction> {
+    let index = |token: eth::TokenAddress| {
+        pool.tokens
+            .iter()
+            .position(|t| *t == token)
+            .context("token not traded by Curve pool")
+    };
+
+    let interaction = CurveExchangeInteraction {
+        pool: pool.address.0,
+        exchange: match pool.parameters {
+            Parameters::StableSwap(_) => CurveExchange::StableSwap,
+            Parameters::CryptoSwap(_) => CurveExchange::CryptoSwap,
+        },
+        i: index(input.0.token)?,
+        j: index(output.0.token)?,
+        dx: input.0.amount.into(),
+        min_dy: output.0.amount.into(),
+    };
+
+    let encoded = interaction.encode();
+    Ok(eth::Interaction {
+        target: eth::Address(encoded.0),
+        value: eth::Ether(encoded.1),
+        call_data: crate::util::Bytes(encoded.2.0),
+    })
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_stream: CurrentBlockWatcher,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::Curve,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("curve".into()));
+    let reinit_interval = config.reinit_interval;
+    let config = Arc::new(config.clone());
+    let init = move || {
+        let eth = eth.clone();
+        let block_stream = block_stream.clone();
+        let block_retriever = block_retriever.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, &block_stream, block_retriever.clone(), &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "curve",
+        init,
+        TEN_MINUTES,
+        reinit_interval,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_stream: &CurrentBlockWatcher,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::Curve,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let factories = config
+        .stable_swap_ng
+        .iter()
+        .map(|factory| (CurveFactoryKind::StableSwapNg, factory.0))
+        .chain(
+            config
+                .twocrypto_ng
+                .iter()
+                .map(|factory| (CurveFactoryKind::TwocryptoNg, factory.0)),
+        )
+        .collect();
+
+    let pool_fetcher = Arc::new(
+        CurvePoolFetcher::new(
+            web3.clone(),
+            factories,
+            block_retriever,
+            boundary::liquidity::cache_config(),
+            block_stream.clone(),
+        )
+        .await
+        .context("failed to initialise Curve liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(CurveLiquidity::new(
+        web3,
+        pool_fetcher,
+        eth.contracts().settlement().clone(),
+    ))
+}
@@ -27,6 +27,7 @@ use {
 };
 
 pub mod balancer;
+pub mod curve;
 pub mod swapr;
 pub mod uniswap;
 pub mod zeroex;
@@ -92,12 +93,34 @@ impl Fetcher {
             })
             .collect();
 
+        let bal_v3: Vec<_> = config
+            .balancer_v3
+            .iter()
+            .map(|config| {
+                balancer::v3::collector(eth, block_stream.clone(), block_retriever.clone(), config)
+            })
+            .collect();
+
         let uni_v3: Vec<_> = config
             .uniswap_v3
             .iter()
             .map(|config| uniswap::v3::collector(eth, block_retriever.clone(), config))
             .collect();
 
+        let uni_v4: Vec<_> = config
+            .uniswap_v4
+            .iter()
+            .map(|config| uniswap::v4::collector(eth, block_retriever.clone(), config))
+            .collect();
+
+        let curve: Vec<_> = config
+            .curve
+            .iter()
+            .map(|config| {
+                curve::collector(eth, block_stream.clone(), block_retriever.clone(), config)
+            })
+            .collect();
+
         let zeroex: Vec<_> = future::try_join_all(
             config
                 .zeroex
@@ -121,7 +144,7 @@ impl Fetcher {
         Ok(Self {
             blocks: block_stream.clone(),
             inner: LiquidityCollector {
-                liquidity_sources: [uni_v2, swapr, bal_v2, uni_v3, zeroex]
+                liquidity_sources: [uni_v2, swapr, bal_v2, bal_v3, uni_v3, uni_v4, curve, zeroex]
                     .into_iter()
                     .flatten()
                     .collect(),
@@ -172,6 +195,9 @@ impl Fetcher {
                     Liquidity::BalancerStable(pool) => balancer::v2::stable::to_domain(id, pool),
                     Liquidity::LimitOrder(pool) => zeroex::to_domain(id, pool),
                     Liquidity::Concentrated(pool) => uniswap::v3::to_domain(id, pool),
+                    Liquidity::Curve(pool) => curve::to_domain(id, pool),
+                    Liquidity::UniswapV4(pool) => uniswap::v4::to_domain(id, pool),
+                    Liquidity::BalancerV3(pool) => balancer::v3::to_domain(id, pool),
                 }
                 // Ignore "bad" liquidity - this allows the driver to continue
                 // solving with the other good stuff.
@@ -19,7 +19,10 @@ use {
         },
     },
     solver::{
-        interactions::allowances::{AllowanceManaging, Allowances, Approval, ApprovalRequest},
+        interactions::{
+            UniswapInteraction,
+            allowances::{AllowanceManaging, Allowances, Approval, ApprovalRequest},
+        },
         liquidity::{
             ConstantProductOrder,
             uniswap_v2::{self, UniswapLikeLiquidity},
@@ -93,6 +96,40 @@ pub fn to_interaction(
     output: &liquidity::ExactOutput,
     receiver: &eth::Address,
 ) -> eth::Interaction {
+    let (target, value, call_data) =
+        uniswap_interaction(pool, input, output, receiver).encode_swap();
+
+    eth::Interaction {
+        target: target.into(),
+        value: value.into(),
+        call_data: call_data.0.into(),
+    }
+}
+
+/// Like [`to_interaction`] but swaps exactly the input amount for at least the
+/// output amount, which works for tokens that take a fee on transfer.
+pub fn to_fee_on_transfer_interaction(
+    pool: &liquidity::uniswap::v2::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> eth::Interaction {
+    let (target, value, call_data) =
+        uniswap_interaction(pool, input, output, receiver).encode_swap_supporting_fee_on_transfer();
+
+    eth::Interaction {
+        target: target.into(),
+        value: value.into(),
+        call_data: call_data.0.into(),
+    }
+}
+
+fn uniswap_interaction(
+    pool: &liquidity::uniswap::v2::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> UniswapInteraction {
     let handler = uniswap_v2::Inner::new(
         IUniswapLikeRouter::at(&ethrpc::dummy::web3(), pool.router.into()),
         GPv2Settlement::at(&ethrpc::dummy::web3(), receiver.0),
@@ -103,14 +140,7 @@ pub fn to_interaction(
         TokenAmount::new(input.0.token.into(), input.0.amount),
         TokenAmount::new(output.0.token.into(), output.0.amount),
     );
-
-    let (target, value, call_data) = interaction.encode_swap();
-
-    eth::Interaction {
-        target: target.into(),
-        value: value.into(),
-        call_data: call_data.0.into(),
-    }
+    interaction
 }
 
 pub async fn collector(
@@ -0,0 +1,163 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{
+                self,
+                uniswap::{
+                    v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+                    v4::{Id, Pool},
+                },
+            },
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    ethrpc::block_stream::BlockRetrieving,
+    num::rational::Ratio,
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::uniswap_v4::{self, PoolKey, UniswapV4PoolFetcher},
+    },
+    solver::{
+        liquidity::{
+            UniswapV4PoolOrder,
+            uniswap_v4::{SettlementHandler, UniswapV4Liquidity},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+/// Rough gas estimate of transferring the input tokens to the router and
+/// swapping them on a single pool.
+const GAS_PER_SWAP: u64 = 160_000;
+
+pub fn to_domain(id: liquidity::Id, pool: UniswapV4PoolOrder) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<SettlementHandler>()
+        .expect("downcast uniswap v4 settlement handler");
+    let pool = pool.pool;
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(GAS_PER_SWAP.into()),
+        kind: liquidity::Kind::UniswapV4(Pool {
+            router: handler.router().into(),
+            pool_manager: handler.pool_manager().into(),
+            id: Id(pool.id),
+            tokens: liquidity::TokenPair::try_new(
+                pool.key.currency0.into(),
+                pool.key.currency1.into(),
+            )?,
+            tick_spacing: pool.key.tick_spacing,
+            sqrt_price: SqrtPrice(pool.state.sqrt_price),
+            liquidity: Liquidity(pool.state.liquidity),
+            tick: Tick(pool.state.tick),
+            liquidity_net: pool
+                .state
+                .liquidity_net
+                .into_iter()
+                .map(|(tick, net)| (Tick(tick), LiquidityNet(net)))
+                .collect(),
+            fee: Fee(Ratio::new(pool.key.fee, 1_000_000)),
+        }),
+    })
+}
+
+/// Encodes the ERC20 transfer of the input tokens to the router followed by
+/// the router call executing the swap.
+pub fn to_interactions(
+    pool: &liquidity::uniswap::v4::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> Result<Vec<eth::Interaction>> {
+    let (currency0, currency1) = pool.tokens.get();
+    let handler = SettlementHandler::new(
+        pool.router.0,
+        pool.pool_manager.0,
+        receiver.0,
+        &uniswap_v4::Pool {
+            id: pool.id.0,
+            key: PoolKey {
+                currency0: currency0.into(),
+                currency1: currency1.into(),
+                fee: *pool.fee.0.numer(),
+                tick_spacing: pool.tick_spacing,
+                // only pools without hooks are supported
+                hooks: eth::H160::zero(),
+            },
+            ..Default::default()
+        },
+    );
+
+    let (transfer, swap) = handler.swap(
+        TokenAmount::new(input.0.token.into(), input.0.amount),
+        TokenAmount::new(output.0.token.into(), output.0.amount),
+    )?;
+    Ok([transfer.encode(), swap.encode()]
+        .into_iter()
+        .map(|encoded| eth::Interaction {
+            target: eth::Address(encoded.0),
+            value: eth::Ether(encoded.1),
+            call_data: crate::util::Bytes(encoded.2.0),
+        })
+        .collect())
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("uniswapV4".into()));
+    let config = Arc::new(config.clone());
+    let reinit_interval = config.reinit_interval;
+    let init = move || {
+        let eth = eth.clone();
+        let block_retriever = block_retriever.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, block_retriever.clone(), &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "uniswap-v4",
+        init,
+        TEN_MINUTES,
+        reinit_interval,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let pool_fetcher = Arc::new(
+        UniswapV4PoolFetcher::new(
+            boundary::web3(eth),
+            config.pool_manager.0,
+            config.deployment_block,
+            block_retriever,
+        )
+        .await
+        .context("failed to initialise Uniswap V4 liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(UniswapV4Liquidity::new(
+        pool_fetcher,
+        config.router.0,
+        config.pool_manager.0,
+        eth.contracts().settlement().address(),
+    ))
+}
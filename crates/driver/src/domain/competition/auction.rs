@@ -21,7 +21,10 @@ use {
     itertools::Itertools,
     model::{order::OrderKind, signature::Signature},
     prometheus::HistogramTimer,
-    shared::signature_validator::{Contracts, SignatureValidating},
+    shared::{
+        bad_token::TransferFee,
+        signature_validator::{Contracts, SignatureValidating},
+    },
     std::{
         collections::{HashMap, HashSet},
         sync::{Arc, Mutex},
@@ -95,6 +98,13 @@ impl Auction {
         &self.orders
     }
 
+    /// Records the fee a token of the auction takes on transfers.
+    pub(crate) fn set_transfer_fee(&mut self, token: eth::TokenAddress, fee: TransferFee) {
+        if let Some(token) = self.tokens.0.get_mut(&token) {
+            token.transfer_fee = Some(fee);
+        }
+    }
+
     /// The tokens used in the auction.
     pub fn tokens(&self) -> &Tokens {
         &self.tokens
@@ -616,6 +626,7 @@ impl Tokens {
             price: None,
             available_balance: Default::default(),
             trusted: false,
+            transfer_fee: None,
         })
     }
 
@@ -634,6 +645,8 @@ pub struct Token {
     pub available_balance: eth::U256,
     /// Is this token well-known and trusted by the protocol?
     pub trusted: bool,
+    /// The fee the token takes on transfers, if any.
+    pub transfer_fee: Option<TransferFee>,
 }
 
 /// The price of a token in wei. This represents how much wei is needed to buy
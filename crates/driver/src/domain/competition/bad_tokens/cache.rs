@@ -7,8 +7,8 @@ use {
     },
 };
 
-/// Cache keeping track of whether or not a token is considered supported or
-/// not. Internally reference counted for cheap clones and easy sharing.
+/// Cache keeping track of the detected quality of tokens. Internally reference
+/// counted for cheap clones and easy sharing.
 /// Stores a map instead of a set to not recompute the quality of good tokens
 /// over and over.
 /// Evicts cached value after a configurable period of time.
@@ -24,8 +24,8 @@ struct Inner {
 struct CacheEntry {
     /// when the decision on the token quality was made
     last_updated: Instant,
-    /// whether the token is supported or not
-    is_supported: bool,
+    /// the detected quality of the token
+    quality: Quality,
 }
 
 impl Cache {
@@ -38,22 +38,24 @@ impl Cache {
         }))
     }
 
-    /// Updates whether or not a token should be considered supported.
-    pub fn update_quality(&self, token: eth::TokenAddress, is_supported: bool, now: Instant) {
+    /// Updates the quality of a token.
+    pub fn update_quality(&self, token: eth::TokenAddress, quality: Quality, now: Instant) {
         self.0
             .cache
             .entry(token)
             .and_modify(|token| {
-                if !is_supported || now.duration_since(token.last_updated) > self.0.max_age {
+                if quality == Quality::Unsupported
+                    || now.duration_since(token.last_updated) > self.0.max_age
+                {
                     // Only update the value if the cached value is outdated by now or
                     // if the new value is "Unsupported". This means on conflicting updates
                     // we err on the conservative side and assume a token is unsupported.
-                    token.is_supported = is_supported;
+                    token.quality = quality;
                 }
                 token.last_updated = now;
             })
             .or_insert_with(|| CacheEntry {
-                is_supported,
+                quality,
                 last_updated: now,
             });
     }
@@ -72,10 +74,10 @@ impl Cache {
             return Quality::Unknown;
         };
         let still_valid = now.duration_since(token.last_updated) > self.0.max_age;
-        match (still_valid, token.is_supported) {
-            (false, _) => Quality::Unknown,
-            (true, true) => Quality::Supported,
-            (true, false) => Quality::Unsupported,
+        if still_valid {
+            token.quality
+        } else {
+            Quality::Unknown
         }
     }
 }
@@ -2,6 +2,7 @@ use {
     crate::domain::{competition::Auction, eth},
     futures::future::join_all,
     itertools::{Either, Itertools},
+    shared::bad_token::TransferFee,
     std::{collections::HashMap, fmt, time::Instant},
 };
 
@@ -14,9 +15,12 @@ pub enum Quality {
     /// Solver is likely to produce working solutions when computing
     /// routes for this token.
     Supported,
+    /// Solvers can compute routes for this token, but transfers of it arrive
+    /// reduced by the measured fee which needs to be accounted for when
+    /// encoding the settlement.
+    FeeOnTransfer(TransferFee),
     /// Solver will likely produce failing solutions when computing
     /// routes for this token. This can have many reasons:
-    /// * fees on transfer
     /// * token enforces max transfer amount
     /// * trader is deny listed
     /// * bugs in the solidity compiler make it incompatible with the settlement
@@ -61,32 +65,39 @@ impl Detector {
         self
     }
 
-    /// Removes all unsupported orders from the auction.
+    /// Removes all unsupported orders from the auction and records the
+    /// transfer fees of the remaining orders' tokens.
     pub async fn filter_unsupported_orders_in_auction(&self, mut auction: Auction) -> Auction {
         let now = Instant::now();
 
-        let token_quality_checks = auction.orders.into_iter().map(|order| async move {
+        let orders = std::mem::take(&mut auction.orders);
+        let token_quality_checks = orders.into_iter().map(|order| async move {
             let sell = self.get_token_quality(order.sell.token, now);
             let buy = self.get_token_quality(order.buy.token, now);
             match (sell, buy) {
                 // both tokens supported => keep order
-                (Quality::Supported, Quality::Supported) => Either::Left(order),
+                (
+                    Quality::Supported | Quality::FeeOnTransfer(_),
+                    Quality::Supported | Quality::FeeOnTransfer(_),
+                ) => Either::Left((order, [sell, buy])),
                 // at least 1 token unsupported => drop order
                 (Quality::Unsupported, _) | (_, Quality::Unsupported) => Either::Right(order.uid),
                 // sell token quality is unknown => keep order if token is supported
                 (Quality::Unknown, _) => {
                     let Some(detector) = &self.simulation_detector else {
                         // we can't determine quality => assume order is good
-                        return Either::Left(order);
+                        return Either::Left((order, [sell, buy]));
                     };
                     match detector.determine_sell_token_quality(&order, now).await {
-                        Quality::Supported => Either::Left(order),
+                        sell @ (Quality::Supported | Quality::FeeOnTransfer(_)) => {
+                            Either::Left((order, [sell, buy]))
+                        }
                         _ => Either::Right(order.uid),
                     }
                 }
                 // buy token quality is unknown => keep order (because we can't
                 // determine quality and assume it's good)
-                (_, Quality::Unknown) => Either::Left(order),
+                (_, Quality::Unknown) => Either::Left((order, [sell, buy])),
             }
         });
         let (supported_orders, removed_uids): (Vec<_>, Vec<_>) = join_all(token_quality_checks)
@@ -94,7 +105,20 @@ impl Detector {
             .into_iter()
             .partition_map(std::convert::identity);
 
-        auction.orders = supported_orders;
+        for (order, qualities) in &supported_orders {
+            for (token, quality) in [order.sell.token, order.buy.token]
+                .into_iter()
+                .zip(qualities)
+            {
+                if let Quality::FeeOnTransfer(fee) = quality {
+                    auction.set_transfer_fee(token, *fee);
+                }
+            }
+        }
+        auction.orders = supported_orders
+            .into_iter()
+            .map(|(order, _)| order)
+            .collect();
         if !removed_uids.is_empty() {
             tracing::debug!(orders = ?removed_uids, "ignored orders with unsupported tokens");
         }
@@ -126,11 +150,11 @@ impl Detector {
             Some(quality) => return *quality,
         }
 
-        if let Some(Quality::Unsupported) = self
+        let simulated = self
             .simulation_detector
             .as_ref()
-            .map(|d| d.get_quality(&token, now))
-        {
+            .map(|d| d.get_quality(&token, now));
+        if let Some(Quality::Unsupported) = simulated {
             return Quality::Unsupported;
         }
 
@@ -140,7 +164,10 @@ impl Detector {
             return Quality::Unsupported;
         }
 
-        Quality::Unknown
+        match simulated {
+            Some(quality @ Quality::FeeOnTransfer(_)) => quality,
+            _ => Quality::Unknown,
+        }
     }
 }
 
@@ -8,7 +8,7 @@ use {
             },
             eth,
         },
-        infra::{self, observe::metrics},
+        infra::{self, SharedCache, observe::metrics},
     },
     futures::FutureExt,
     model::interaction::InteractionData,
@@ -33,16 +33,23 @@ struct Inner {
     cache: Cache,
     detector: TraceCallDetectorRaw,
     sharing: BoxRequestSharing<order::Uid, Quality>,
+    /// Token qualities determined by other drivers.
+    shared_cache: Option<SharedCache>,
 }
 
 impl Detector {
-    pub fn new(max_age: Duration, eth: &infra::Ethereum) -> Self {
+    pub fn new(
+        max_age: Duration,
+        eth: &infra::Ethereum,
+        shared_cache: Option<SharedCache>,
+    ) -> Self {
         let detector =
             TraceCallDetectorRaw::new(eth.web3().clone(), eth.contracts().settlement().address());
         Self(Arc::new(Inner {
             cache: Cache::new(max_age),
             detector,
             sharing: BoxRequestSharing::labelled("bad_tokens".into()),
+            shared_cache,
         }))
     }
 
@@ -83,11 +90,19 @@ impl Detector {
                 };
 
                 async move {
+                    if let Some(shared_cache) = &inner.shared_cache {
+                        let quality = shared_cache.token_quality(sell_token).await;
+                        if quality != Quality::Unknown {
+                            inner.cache.update_quality(sell_token, quality, now);
+                            return quality;
+                        }
+                    }
+
                     let result = inner
                         .detector
                         .test_transfer(trader, sell_token.0 .0, sell_amount, &pre_interactions)
                         .await;
-                    match result {
+                    let quality = match result {
                         Err(err) => {
                             tracing::debug!(?err, token=?sell_token.0, "failed to determine token quality");
                             Quality::Unknown
@@ -95,19 +110,30 @@ impl Detector {
                         Ok(TokenQuality::Good) => {
                             inner
                                 .cache
-                                .update_quality(sell_token, true, now);
+                                .update_quality(sell_token, Quality::Supported, now);
                             Quality::Supported
                         }
+                        Ok(TokenQuality::FeeOnTransfer { fee }) => {
+                            tracing::debug!(?fee, token=?sell_token.0, "cache token as taking a fee on transfer");
+                            inner
+                                .cache
+                                .update_quality(sell_token, Quality::FeeOnTransfer(fee), now);
+                            Quality::FeeOnTransfer(fee)
+                        }
                         Ok(TokenQuality::Bad { reason }) => {
                             tracing::debug!(reason, token=?sell_token.0, "cache token as unsupported");
                             // All solvers share the same cache for the simulation detector, so there is no need to specify the solver name here.
                             metrics::get().bad_tokens_detected.with_label_values(&["any", "simulation"]).inc();
                             inner
                                 .cache
-                                .update_quality(sell_token, false, now);
+                                .update_quality(sell_token, Quality::Unsupported, now);
                             Quality::Unsupported
                         }
+                    };
+                    if let Some(shared_cache) = &inner.shared_cache {
+                        shared_cache.store_token_quality(sell_token, quality);
                     }
+                    quality
                 }
                 .boxed()
             })
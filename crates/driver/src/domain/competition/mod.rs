@@ -2,6 +2,7 @@ use {
     self::solution::settlement,
     super::{
         Mempools,
+        mempools,
         time::{self, Remaining},
     },
     crate::{
@@ -345,12 +346,47 @@ impl Competition {
         auction_id: auction::Id,
         solution_id: u64,
         submission_deadline: BlockNo,
+    ) -> Result<Settled, Error> {
+        self.enqueue_settle_request(
+            auction_id,
+            SettleKind::Solution(solution_id),
+            submission_deadline,
+        )
+        .await
+    }
+
+    /// Execute `settle` calldata which the autopilot put together by combining
+    /// the winning solutions of multiple solvers. Only available for solvers
+    /// that accept combined settlements.
+    pub async fn settle_combined(
+        &self,
+        auction_id: auction::Id,
+        calldata: Bytes<Vec<u8>>,
+        submission_deadline: BlockNo,
+    ) -> Result<Settled, Error> {
+        if !self.solver.accepts_combined_settlements() {
+            return Err(Error::CombinedSettlementsDisabled);
+        }
+        self.enqueue_settle_request(
+            auction_id,
+            SettleKind::Combined(calldata),
+            submission_deadline,
+        )
+        .await
+    }
+
+    /// Settlements are submitted one after the other to avoid nonce conflicts.
+    async fn enqueue_settle_request(
+        &self,
+        auction_id: auction::Id,
+        kind: SettleKind,
+        submission_deadline: BlockNo,
     ) -> Result<Settled, Error> {
         let (response_sender, response_receiver) = oneshot::channel();
 
         let request = SettleRequest {
             auction_id,
-            solution_id,
+            kind,
             submission_deadline,
             response_sender,
             tracing_span: tracing::Span::current(),
@@ -383,7 +419,7 @@ impl Competition {
         while let Some(request) = settle_receiver.recv().await {
             let SettleRequest {
                 auction_id,
-                solution_id,
+                kind,
                 submission_deadline,
                 mut response_sender,
                 tracing_span,
@@ -400,11 +436,8 @@ impl Competition {
                 }
 
                 observe::settling();
-                let settle_fut = Box::pin(self.process_settle_request(
-                    auction_id,
-                    solution_id,
-                    submission_deadline,
-                ));
+                let settle_fut =
+                    Box::pin(self.process_settle_request(auction_id, kind, submission_deadline));
                 let closed_fut = Box::pin(response_sender.closed());
                 let result = match futures::future::select(closed_fut, settle_fut).await {
                     // Cancel the settlement task if the sender is closed (client likely
@@ -431,6 +464,24 @@ impl Competition {
     }
 
     async fn process_settle_request(
+        &self,
+        auction_id: auction::Id,
+        kind: SettleKind,
+        submission_deadline: BlockNo,
+    ) -> Result<Settled, Error> {
+        match kind {
+            SettleKind::Solution(solution_id) => {
+                self.settle_solution(auction_id, solution_id, submission_deadline)
+                    .await
+            }
+            SettleKind::Combined(calldata) => {
+                self.settle_calldata(auction_id, calldata, submission_deadline)
+                    .await
+            }
+        }
+    }
+
+    async fn settle_solution(
         &self,
         auction_id: auction::Id,
         solution_id: u64,
@@ -462,7 +513,11 @@ impl Competition {
 
         let executed = self
             .mempools
-            .execute(&self.solver, &settlement, submission_deadline)
+            .execute(
+                &self.solver,
+                mempools::Submission::Settlement(&settlement),
+                submission_deadline,
+            )
             .await;
         notify::executed(
             &self.solver,
@@ -487,6 +542,60 @@ impl Competition {
         }
     }
 
+    async fn settle_calldata(
+        &self,
+        auction_id: auction::Id,
+        calldata: Bytes<Vec<u8>>,
+        submission_deadline: BlockNo,
+    ) -> Result<Settled, Error> {
+        if !calldata.0.ends_with(&auction_id.to_be_bytes()) {
+            tracing::warn!("combined settlement calldata is missing the auction id");
+            return Err(Error::InvalidCombinedSettlement);
+        }
+        let tx = eth::Tx {
+            from: self.solver.address(),
+            to: self.eth.contracts().settlement().address().into(),
+            value: 0.into(),
+            input: calldata,
+            access_list: Default::default(),
+        };
+
+        // The combined solutions were already simulated individually by their
+        // drivers but the combination still needs to be checked. Since the
+        // calldata is the only thing known about them this can't reuse the
+        // partial access lists of the individual settlements.
+        let time_limit = submission_time_limit(&self.eth, submission_deadline);
+        let simulated = async {
+            let (access_list, gas) = self.simulator.simulate(&tx).await?;
+            let tx = tx.set_access_list(access_list);
+            let price = self.eth.gas_price(time_limit).await?;
+            let gas = settlement::Gas::new(gas, self.eth.block_gas_limit(), price)?;
+            Ok::<_, solution::Error>((tx, gas))
+        };
+        let (tx, gas) = simulated.await.map_err(|err| {
+            tracing::warn!(?err, "combined settlement failed to simulate");
+            Error::InvalidCombinedSettlement
+        })?;
+
+        let executed = self
+            .mempools
+            .execute(
+                &self.solver,
+                mempools::Submission::Combined { tx: &tx, gas },
+                submission_deadline,
+            )
+            .await;
+
+        match executed {
+            Err(_) => Err(Error::SubmissionError),
+            Ok(tx_hash) => Ok(Settled {
+                internalized_calldata: tx.input.clone(),
+                uninternalized_calldata: tx.input,
+                tx_hash,
+            }),
+        }
+    }
+
     /// The ID of the auction being competed on.
     pub fn auction_id(&self, solution_id: u64) -> Option<auction::Id> {
         self.settlements
@@ -591,12 +700,20 @@ fn submission_time_limit(eth: &Ethereum, submission_deadline: BlockNo) -> Option
 }
 struct SettleRequest {
     auction_id: auction::Id,
-    solution_id: u64,
+    kind: SettleKind,
     submission_deadline: BlockNo,
     response_sender: oneshot::Sender<Result<Settled, Error>>,
     tracing_span: tracing::Span,
 }
 
+/// What a [`SettleRequest`] brings onchain.
+enum SettleKind {
+    /// A solution computed as part of this competition.
+    Solution(u64),
+    /// `settle` calldata combining the winning solutions of multiple solvers.
+    Combined(Bytes<Vec<u8>>),
+}
+
 /// Solution information sent to the protocol by the driver before the solution
 /// ranking happens.
 #[derive(Debug)]
@@ -666,4 +783,8 @@ pub enum Error {
     SubmissionError,
     #[error("too many pending settlements for the same solver")]
     TooManyPendingSettlements,
+    #[error("the solver does not accept combined settlements")]
+    CombinedSettlementsDisabled,
+    #[error("the combined settlement is invalid")]
+    InvalidCombinedSettlement,
 }
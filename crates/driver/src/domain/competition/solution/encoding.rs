@@ -15,6 +15,7 @@ use {
     allowance::Allowance,
     ethcontract::H160,
     itertools::Itertools,
+    shared::bad_token::TransferFee,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -285,16 +286,24 @@ pub fn tx(
             continue;
         }
 
-        interactions.push(match interaction {
-            competition::solution::Interaction::Custom(interaction) => eth::Interaction {
-                value: interaction.value,
-                target: interaction.target.into(),
-                call_data: interaction.call_data.clone(),
-            },
+        match interaction {
+            competition::solution::Interaction::Custom(interaction) => {
+                interactions.push(eth::Interaction {
+                    value: interaction.value,
+                    target: interaction.target.into(),
+                    call_data: interaction.call_data.clone(),
+                })
+            }
             competition::solution::Interaction::Liquidity(liquidity) => {
-                liquidity_interaction(liquidity, &slippage, contracts.settlement())?
+                interactions.extend(transfer_fee_liquidity_interactions(
+                    liquidity,
+                    auction.tokens().get(liquidity.input.token).transfer_fee,
+                    auction.tokens().get(liquidity.output.token).transfer_fee,
+                    &slippage,
+                    contracts.settlement(),
+                )?)
             }
-        })
+        }
     }
 
     // Encode WETH unwrap
@@ -378,11 +387,14 @@ fn ensure_flashloan_is_paid_for(
     Ok(())
 }
 
-pub fn liquidity_interaction(
+/// Encodes the interactions executing a liquidity swap. Most liquidity can be
+/// swapped with a single interaction but some (e.g. Uniswap V4 pools) need
+/// additional setup interactions.
+pub fn liquidity_interactions(
     liquidity: &Liquidity,
     slippage: &slippage::Parameters,
     settlement: &contracts::GPv2Settlement,
-) -> Result<eth::Interaction, Error> {
+) -> Result<Vec<eth::Interaction>, Error> {
     let (input, output) = slippage.apply_to(&slippage::Interaction {
         input: liquidity.input,
         output: liquidity.output,
@@ -391,26 +403,92 @@ pub fn liquidity_interaction(
     match liquidity.liquidity.kind.clone() {
         liquidity::Kind::UniswapV2(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
         liquidity::Kind::UniswapV3(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::UniswapV4(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
         liquidity::Kind::BalancerV2Stable(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
         liquidity::Kind::BalancerV2Weighted(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::BalancerV3Stable(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
-        liquidity::Kind::Swapr(pool) => pool
+        liquidity::Kind::BalancerV3Weighted(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
-        liquidity::Kind::ZeroEx(limit_order) => limit_order.to_interaction(&input).ok(),
+        liquidity::Kind::Curve(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::Swapr(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::ZeroEx(limit_order) => limit_order
+            .to_interaction(&input)
+            .ok()
+            .map(|interaction| vec![interaction]),
     }
     .ok_or(Error::InvalidInteractionExecution(Box::new(
         liquidity.clone(),
     )))
 }
 
+/// Encodes the interactions executing a liquidity swap taking into account
+/// that tokens with a fee on transfer arrive reduced at their recipient.
+fn transfer_fee_liquidity_interactions(
+    liquidity: &Liquidity,
+    input_fee: Option<TransferFee>,
+    output_fee: Option<TransferFee>,
+    slippage: &slippage::Parameters,
+    settlement: &contracts::GPv2Settlement,
+) -> Result<Vec<eth::Interaction>, Error> {
+    if input_fee.is_none() && output_fee.is_none() {
+        return liquidity_interactions(liquidity, slippage, settlement);
+    }
+
+    // The settlement contract needs to send enough tokens for the pool to
+    // still receive the input amount the solver computed.
+    let mut liquidity = liquidity.clone();
+    if let Some(fee) = input_fee {
+        liquidity.input.amount = fee.required(liquidity.input.amount.0).into();
+    }
+
+    match &liquidity.liquidity.kind {
+        // The Uniswap V2 router transfers exactly the input amount it computed
+        // from the reserves for exact output swaps, so the pool would receive
+        // too little. Instead, swap the whole maximum input and let the router
+        // check the output the settlement contract actually receives. Output
+        // bought with the slippage allowance stays in the settlement buffers.
+        liquidity::Kind::UniswapV2(pool) => {
+            let (input, output) = slippage.apply_to(&slippage::Interaction {
+                input: liquidity.input,
+                output: liquidity.output,
+            })?;
+            let output = liquidity::ExactOutput(eth::Asset {
+                token: output.0.token,
+                amount: output_fee
+                    .map_or(output.0.amount.0, |fee| fee.received(output.0.amount.0))
+                    .into(),
+            });
+            pool.swap_supporting_fee_on_transfer(&input, &output, &settlement.address().into())
+                .map(|interaction| vec![interaction])
+                .map_err(|_| Error::InvalidInteractionExecution(Box::new(liquidity.clone())))
+        }
+        _ => liquidity_interactions(&liquidity, slippage, settlement),
+    }
+}
+
 pub fn approve(allowance: &Allowance) -> eth::Interaction {
     let mut amount = [0u8; 32];
     let selector = hex_literal::hex!("095ea7b3");
@@ -603,4 +681,69 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn uniswap_v2_fee_on_transfer_swap() {
+        let sell_token = eth::H160::from_low_u64_be(1);
+        let buy_token = eth::H160::from_low_u64_be(2);
+        let router = eth::H160::from_low_u64_be(3);
+        let settlement = eth::H160::from_low_u64_be(4);
+        let asset = |token: eth::H160, amount: u64| eth::Asset {
+            token: token.into(),
+            amount: eth::U256::from(amount).into(),
+        };
+        let liquidity = Liquidity {
+            liquidity: liquidity::Liquidity {
+                id: liquidity::Id(0),
+                gas: eth::Gas(0.into()),
+                kind: liquidity::Kind::UniswapV2(liquidity::uniswap::v2::Pool {
+                    address: eth::H160::from_low_u64_be(5).into(),
+                    router: router.into(),
+                    reserves: liquidity::uniswap::v2::Reserves::try_new(
+                        asset(sell_token, 1_000_000),
+                        asset(buy_token, 1_000_000),
+                    )
+                    .unwrap(),
+                }),
+            },
+            input: asset(sell_token, 9_900),
+            output: asset(buy_token, 10_000),
+            internalize: false,
+        };
+        let slippage = slippage::Parameters {
+            relative: num::BigRational::new(1.into(), 10.into()),
+            max: None,
+            min: None,
+            prices: Default::default(),
+        };
+        let settlement = contracts::GPv2Settlement::at(&ethrpc::dummy::web3(), settlement);
+        let fee = TransferFee::from_bps(100);
+
+        let interactions =
+            transfer_fee_liquidity_interactions(&liquidity, fee, fee, &slippage, &settlement)
+                .unwrap();
+
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0].target, router.into());
+        let call = interactions[0].call_data.0.as_slice();
+        // swapExactTokensForTokensSupportingFeeOnTransferTokens
+        assert_eq!(call[0..4], hex!("5c11d795"));
+        // The pool needs to receive 9900 after the 1% fee and the whole amount
+        // including the 10% slippage gets swapped.
+        assert_eq!(eth::U256::from_big_endian(&call[4..36]), 11_000.into());
+        // The settlement contract needs to receive at least the output amount
+        // minus the 1% fee.
+        assert_eq!(eth::U256::from_big_endian(&call[36..68]), 9_900.into());
+        assert_eq!(
+            call[100..132],
+            *eth::H256::from(settlement.address()).as_bytes()
+        );
+
+        // Tokens without transfer fees still get swapped for exact output.
+        let interactions =
+            transfer_fee_liquidity_interactions(&liquidity, None, None, &slippage, &settlement)
+                .unwrap();
+        // swapTokensForExactTokens
+        assert_eq!(interactions[0].call_data.0[0..4], hex!("8803dbee"));
+    }
 }
@@ -39,8 +39,17 @@ impl Interaction {
                 let address = match &interaction.liquidity.kind {
                     liquidity::Kind::UniswapV2(pool) => pool.router.into(),
                     liquidity::Kind::UniswapV3(pool) => pool.router.into(),
+                    // The input tokens are transferred to the Uniswap V4 router
+                    // instead, so no approvals are needed.
+                    liquidity::Kind::UniswapV4(_) => return vec![],
                     liquidity::Kind::BalancerV2Stable(pool) => pool.vault.into(),
                     liquidity::Kind::BalancerV2Weighted(pool) => pool.vault.into(),
+                    // The `BatchRouter` pulls the input tokens through
+                    // `Permit2`, the router itself gets allowed on `Permit2`
+                    // as part of the swap interactions.
+                    liquidity::Kind::BalancerV3Stable(pool) => pool.permit2.into(),
+                    liquidity::Kind::BalancerV3Weighted(pool) => pool.permit2.into(),
+                    liquidity::Kind::Curve(pool) => pool.address.into(),
                     liquidity::Kind::Swapr(pool) => pool.base.router.into(),
                     liquidity::Kind::ZeroEx(pool) => pool.zeroex.address().into(),
                 };
@@ -212,15 +212,11 @@ impl Settlement {
         let tx = tx.set_access_list(partial_access_list.to_owned());
 
         // Simulate the full access list, passing the partial access
-        // list into the simulation.
-        let access_list = simulator.access_list(&tx).await?;
-        let tx = tx.set_access_list(access_list.clone());
+        // list into the simulation, and the gas used with it.
+        let simulated = simulator.simulate(&tx).await;
 
-        // Simulate the settlement using the full access list and get the gas used.
-        let gas = simulator.gas(&tx).await;
-
-        observe::simulated(eth, &tx, &gas);
-        Ok((access_list, gas?))
+        observe::simulated(eth, &tx, &simulated);
+        Ok(simulated?)
     }
 
     /// The calldata for this settlement.
@@ -62,7 +62,11 @@ impl Reserves {
     }
 
     /// Returns `true` if the reserves correspond to the specified tokens.
-    fn has_tokens(&self, a: &eth::TokenAddress, b: &eth::TokenAddress) -> bool {
+    pub(in crate::domain::liquidity::balancer) fn has_tokens(
+        &self,
+        a: &eth::TokenAddress,
+        b: &eth::TokenAddress,
+    ) -> bool {
         self.tokens().contains(a) && self.tokens().contains(b)
     }
 
@@ -0,0 +1,10 @@
+//! Balancer V3 pools.
+//!
+//! All V3 pools are registered with a singleton `Vault` and identified by
+//! their address. The pool math is the same as for Balancer V2, so the V2 fee,
+//! scaling factor and reserve types are reused. Note that V3 scaling factors
+//! include the token rates, which is how the wrapped tokens of boosted pools
+//! are priced.
+
+pub mod stable;
+pub mod weighted;
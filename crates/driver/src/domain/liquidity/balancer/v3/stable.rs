@@ -0,0 +1,56 @@
+use crate::{
+    boundary,
+    domain::{
+        eth,
+        liquidity::{
+            self,
+            balancer::v2::{
+                Fee,
+                stable::{AmplificationParameter, Reserves},
+            },
+        },
+    },
+};
+
+/// Liquidity data tied to a Balancer V3 stable pool.
+///
+/// The pool math is the same as for Balancer V2 stable pools.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    /// The `BatchRouter` used for executing swaps.
+    pub batch_router: eth::ContractAddress,
+    /// The `Permit2` contract the `BatchRouter` pulls the input tokens
+    /// through.
+    pub permit2: eth::ContractAddress,
+    pub address: eth::ContractAddress,
+    pub reserves: Reserves,
+    pub amplification_parameter: AmplificationParameter,
+    pub fee: Fee,
+}
+
+impl Pool {
+    /// Encodes a pool swap as interactions. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens do not belong to the pool.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<Vec<eth::Interaction>, liquidity::InvalidSwap> {
+        if !self.reserves.has_tokens(&input.0.token, &output.0.token) {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::balancer::v3::to_interactions(
+            &boundary::liquidity::balancer::v3::Pool {
+                batch_router: self.batch_router,
+                permit2: self.permit2,
+                address: self.address,
+            },
+            input,
+            output,
+            receiver,
+        ))
+    }
+}
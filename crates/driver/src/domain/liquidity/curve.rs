@@ -0,0 +1,82 @@
+use crate::{
+    boundary,
+    domain::{
+        eth,
+        liquidity::{self, InvalidSwap},
+    },
+};
+
+/// A Curve StableSwap or CryptoSwap pool.
+///
+/// Unlike most other AMMs, the coins of a Curve pool are indexed and swaps
+/// are executed by calling `exchange` on the pool directly.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub address: eth::ContractAddress,
+    /// The coins of the pool in the order the pool indexes them.
+    pub tokens: Vec<eth::TokenAddress>,
+    /// The balances of the coins in the same order as `tokens`.
+    pub balances: Vec<eth::U256>,
+    pub parameters: Parameters,
+}
+
+/// The invariant specific parameters of a pool.
+#[derive(Clone, Debug)]
+pub enum Parameters {
+    StableSwap(StableSwap),
+    CryptoSwap(CryptoSwap),
+}
+
+/// Parameters of a StableSwap-NG pool. All fees are in units of 1e10.
+#[derive(Clone, Debug)]
+pub struct StableSwap {
+    /// The amplification coefficient multiplied by `A_PRECISION` (100).
+    pub amplification: eth::U256,
+    pub fee: eth::U256,
+    pub offpeg_fee_multiplier: eth::U256,
+    /// Rates (with 18 decimals) by which the balances are scaled to 18
+    /// decimals.
+    pub rates: Vec<eth::U256>,
+}
+
+/// Parameters of a Twocrypto-NG pool. All fees are in units of 1e10.
+#[derive(Clone, Debug)]
+pub struct CryptoSwap {
+    /// The amplification coefficient multiplied by `N_COINS**N_COINS` and
+    /// `A_MULTIPLIER` (10000).
+    pub a: eth::U256,
+    pub gamma: eth::U256,
+    pub d: eth::U256,
+    /// The price of the second coin in units of the first one (with 18
+    /// decimals).
+    pub price_scale: eth::U256,
+    pub precisions: [eth::U256; 2],
+    pub mid_fee: eth::U256,
+    pub out_fee: eth::U256,
+    pub fee_gamma: eth::U256,
+}
+
+impl Pool {
+    /// Encodes a pool swap as an interaction. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens are not both traded by the pool.
+    ///
+    /// Curve pools only support selling exact amounts, so the whole `input`
+    /// gets sold and `output` is the minimum amount to receive.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, InvalidSwap> {
+        if input.0.token == output.0.token
+            || !self.tokens.contains(&input.0.token)
+            || !self.tokens.contains(&output.0.token)
+        {
+            return Err(InvalidSwap);
+        }
+
+        boundary::liquidity::curve::to_interaction(self, input, output, receiver)
+            .map_err(|_| InvalidSwap)
+    }
+}
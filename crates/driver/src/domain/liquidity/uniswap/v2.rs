@@ -40,6 +40,28 @@ impl Pool {
             self, input, output, receiver,
         ))
     }
+
+    /// Encodes a swap of exactly the input amount for at least the output
+    /// amount as an interaction. Unlike [`Self::swap`] this works for tokens
+    /// that take a fee on transfer, since the router computes the output from
+    /// the tokens the pool actually received. Returns `Err` if the input and
+    /// output tokens don't correspond to the pool's token pair.
+    pub fn swap_supporting_fee_on_transfer(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, liquidity::InvalidSwap> {
+        if !self.reserves.has_tokens(&input.0.token, &output.0.token) {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(
+            boundary::liquidity::uniswap::v2::to_fee_on_transfer_interaction(
+                self, input, output, receiver,
+            ),
+        )
+    }
 }
 
 /// The reserves of a Uniswap V2 pool. These reserves are ordered by token
@@ -0,0 +1,64 @@
+use {
+    super::v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+    crate::{
+        boundary,
+        domain::{
+            eth,
+            liquidity::{self, InvalidSwap},
+        },
+    },
+    derive_more::Debug,
+    std::collections::BTreeMap,
+};
+
+/// A Uniswap V4 concentrated liquidity pool without hooks.
+///
+/// All V4 pools are managed by a single `PoolManager` contract and identified
+/// by their ID. The concentrated liquidity math is the same as for Uniswap V3
+/// pools.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    /// The `UniversalRouter` used for executing swaps.
+    pub router: eth::ContractAddress,
+    pub pool_manager: eth::ContractAddress,
+    pub id: Id,
+    pub tokens: liquidity::TokenPair,
+    pub tick_spacing: i32,
+    pub sqrt_price: SqrtPrice,
+    pub liquidity: Liquidity,
+    pub tick: Tick,
+    #[debug(ignore)]
+    pub liquidity_net: BTreeMap<Tick, LiquidityNet>,
+    pub fee: Fee,
+}
+
+/// The ID of a Uniswap V4 pool, i.e. the hash of its pool key.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Id(pub eth::H256);
+
+impl Pool {
+    /// Encodes a pool swap as interactions. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens don't correspond to the pool's token pair.
+    ///
+    /// Swaps are executed through the `UniversalRouter` which only supports
+    /// selling exact amounts, so the whole `input` gets sold and `output` is
+    /// the minimum amount to receive. The router pays with its own balance,
+    /// so the input tokens get transferred to it in a separate interaction.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<Vec<eth::Interaction>, InvalidSwap> {
+        let tokens_match = (input.0.token == self.tokens.0 && output.0.token == self.tokens.1)
+            || (input.0.token == self.tokens.1 && output.0.token == self.tokens.0);
+
+        if !tokens_match {
+            return Err(InvalidSwap);
+        }
+
+        boundary::liquidity::uniswap::v4::to_interactions(self, input, output, receiver)
+            .map_err(|_| InvalidSwap)
+    }
+}
@@ -46,24 +46,24 @@ impl Mempools {
     pub async fn execute(
         &self,
         solver: &Solver,
-        settlement: &Settlement,
+        submission: Submission<'_>,
         submission_deadline: BlockNo,
     ) -> Result<eth::TxId, Error> {
-        let (submission, _remaining_futures) =
+        let (success, _remaining_futures) =
             select_ok(self.mempools.iter().cloned().map(|mempool| {
                 async move {
                     let result = self
-                        .submit(&mempool, solver, settlement, submission_deadline)
+                        .submit(&mempool, solver, submission, submission_deadline)
                         .instrument(tracing::info_span!("mempool", kind = mempool.to_string()))
                         .await;
-                    observe::mempool_executed(&mempool, settlement, &result);
+                    observe::mempool_executed(&mempool, &submission, &result);
                     result
                 }
                 .boxed()
             }))
             .await?;
 
-        Ok(submission.tx_hash)
+        Ok(success.tx_hash)
     }
 
     /// Defines if the mempools are configured in a way that guarantees that
@@ -88,19 +88,19 @@ impl Mempools {
         &self,
         mempool: &infra::mempool::Mempool,
         solver: &Solver,
-        settlement: &Settlement,
+        submission: Submission<'_>,
         submission_deadline: BlockNo,
     ) -> Result<SubmissionSuccess, Error> {
         // Don't submit risky transactions if revert protection is
         // enabled and the settlement may revert in this mempool.
-        if settlement.may_revert()
+        if submission.may_revert()
             && matches!(self.revert_protection(), RevertProtection::Enabled)
             && mempool.may_revert()
         {
             return Err(Error::Disabled);
         }
 
-        let tx = settlement.transaction(settlement::Internalization::Enable);
+        let (tx, gas) = (submission.tx(), submission.gas());
 
         // Instantiate block stream and skip the current block before we submit the
         // settlement. This way we only run iterations in blocks that can potentially
@@ -130,7 +130,7 @@ impl Mempools {
             }
         }
 
-        let hash = mempool.submit(tx.clone(), settlement.gas, solver).await?;
+        let hash = mempool.submit(tx.clone(), gas, solver).await?;
         let submitted_at_block = self.ethereum.current_block().borrow().number;
         tracing::debug!(?hash, current_block = ?submitted_at_block, "submitted tx to the mempool");
 
@@ -164,17 +164,26 @@ impl Mempools {
 
                         // Check if the current block reached the submission deadline block number
                         if block.number >= submission_deadline {
-                            let cancellation_tx_hash = self
-                                .cancel(mempool, settlement.gas.price, solver, blocks_elapsed)
-                                .await
-                                .context("cancellation tx due to deadline failed")?;
-                            tracing::info!(
-                                settle_tx_hash = ?hash,
-                                deadline = submission_deadline,
-                                current_block = block.number,
-                                ?cancellation_tx_hash,
-                                "tx not confirmed in time, cancelling",
-                            );
+                            if mempool.submits_bundles() {
+                                tracing::info!(
+                                    settle_tx_hash = ?hash,
+                                    deadline = submission_deadline,
+                                    current_block = block.number,
+                                    "bundle not included in time",
+                                );
+                            } else {
+                                let cancellation_tx_hash = self
+                                    .cancel(mempool, gas.price, solver, blocks_elapsed)
+                                    .await
+                                    .context("cancellation tx due to deadline failed")?;
+                                tracing::info!(
+                                    settle_tx_hash = ?hash,
+                                    deadline = submission_deadline,
+                                    current_block = block.number,
+                                    ?cancellation_tx_hash,
+                                    "tx not confirmed in time, cancelling",
+                                );
+                            }
                             return Err(Error::Expired {
                                 tx_id: hash.clone(),
                                 submitted_at_block,
@@ -184,16 +193,24 @@ impl Mempools {
                         // Check if transaction still simulates
                         if let Err(err) = self.ethereum.estimate_gas(tx).await {
                             if err.is_revert() {
-                                let cancellation_tx_hash = self
-                                    .cancel(mempool, settlement.gas.price, solver, blocks_elapsed)
-                                    .await
-                                    .context("cancellation tx due to revert failed")?;
-                                tracing::info!(
-                                    settle_tx_hash = ?hash,
-                                    ?cancellation_tx_hash,
-                                    ?err,
-                                    "tx started failing in mempool, cancelling"
-                                );
+                                if mempool.submits_bundles() {
+                                    tracing::info!(
+                                        settle_tx_hash = ?hash,
+                                        ?err,
+                                        "bundle started failing, dropping it"
+                                    );
+                                } else {
+                                    let cancellation_tx_hash = self
+                                        .cancel(mempool, gas.price, solver, blocks_elapsed)
+                                        .await
+                                        .context("cancellation tx due to revert failed")?;
+                                    tracing::info!(
+                                        settle_tx_hash = ?hash,
+                                        ?cancellation_tx_hash,
+                                        ?err,
+                                        "tx started failing in mempool, cancelling"
+                                    );
+                                }
                                 return Err(Error::SimulationRevert {
                                     submitted_at_block,
                                     reverted_at_block: block.number,
@@ -202,6 +219,21 @@ impl Mempools {
                                 tracing::warn!(?hash, ?err, "couldn't re-simulate tx");
                             }
                         }
+                        // Bundles only target the next block so they have to
+                        // be sent again until they get included.
+                        if mempool.submits_bundles() {
+                            match mempool.submit(tx.clone(), gas, solver).await {
+                                Ok(resubmitted) if resubmitted.0 != hash.0 => tracing::warn!(
+                                    ?hash,
+                                    ?resubmitted,
+                                    "resubmitted bundle has a different tx hash"
+                                ),
+                                Ok(_) => (),
+                                Err(err) => {
+                                    tracing::warn!(?hash, ?err, "failed to resubmit bundle")
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -267,6 +299,45 @@ impl Mempools {
     }
 }
 
+/// A settlement transaction to be published to the mempools.
+#[derive(Debug, Clone, Copy)]
+pub enum Submission<'a> {
+    /// The settlement of a solution computed by this driver.
+    Settlement(&'a Settlement),
+    /// A settlement combining the winning solutions of multiple solvers.
+    Combined {
+        tx: &'a eth::Tx,
+        gas: settlement::Gas,
+    },
+}
+
+impl Submission<'_> {
+    fn tx(&self) -> &eth::Tx {
+        match self {
+            Self::Settlement(settlement) => {
+                settlement.transaction(settlement::Internalization::Enable)
+            }
+            Self::Combined { tx, .. } => tx,
+        }
+    }
+
+    fn gas(&self) -> settlement::Gas {
+        match self {
+            Self::Settlement(settlement) => settlement.gas,
+            Self::Combined { gas, .. } => *gas,
+        }
+    }
+
+    fn may_revert(&self) -> bool {
+        match self {
+            Self::Settlement(settlement) => settlement.may_revert(),
+            // The driver doesn't know the interactions of the combined
+            // solutions, so it has to assume the worst.
+            Self::Combined { .. } => true,
+        }
+    }
+}
+
 pub struct SubmissionSuccess {
     pub tx_hash: eth::TxId,
     /// At which block we started to submit the transaction.
@@ -162,6 +162,7 @@ impl Order {
                     price: None,
                     available_balance: sell_token_metadata.map(|m| m.balance.0).unwrap_or_default(),
                     trusted: false,
+                    transfer_fee: None,
                 },
                 auction::Token {
                     decimals: buy_token_metadata.and_then(|m| m.decimals),
@@ -170,6 +171,7 @@ impl Order {
                     price: None,
                     available_balance: buy_token_metadata.map(|m| m.balance.0).unwrap_or_default(),
                     trusted: false,
+                    transfer_fee: None,
                 },
             ]
             .into_iter(),
@@ -283,13 +285,13 @@ mod encode {
         };
 
         let encoded = match interaction {
-            solution::Interaction::Custom(interaction) => eth::Interaction {
+            solution::Interaction::Custom(interaction) => vec![eth::Interaction {
                 value: interaction.value,
                 target: interaction.target.0.into(),
                 call_data: interaction.call_data.clone(),
-            },
+            }],
             solution::Interaction::Liquidity(liquidity) => {
-                solution::encoding::liquidity_interaction(liquidity, &slippage, settlement)?
+                solution::encoding::liquidity_interactions(liquidity, &slippage, settlement)?
             }
         };
 
@@ -312,7 +314,7 @@ mod encode {
                     solution::encoding::approve(&approval.max().0),
                 ]
             })
-            .chain(std::iter::once(encoded))
+            .chain(encoded)
             .collect())
     }
 }
@@ -21,6 +21,8 @@ enum Kind {
     InvalidAmounts,
     QuoteSameTokens,
     FailedToSubmit,
+    CombinedSettlementsDisabled,
+    InvalidCombinedSettlement,
 }
 
 #[derive(Debug, Serialize)]
@@ -53,6 +55,8 @@ impl From<Kind> for (hyper::StatusCode, axum::Json<Error>) {
             }
             Kind::FailedToSubmit => "Could not submit the solution to the blockchain",
             Kind::TooManyPendingSettlements => "Settlement queue is full",
+            Kind::CombinedSettlementsDisabled => "The solver does not accept combined settlements",
+            Kind::InvalidCombinedSettlement => "The combined settlement failed to simulate",
         };
         (
             hyper::StatusCode::BAD_REQUEST,
@@ -86,6 +90,8 @@ impl From<competition::Error> for (hyper::StatusCode, axum::Json<Error>) {
             competition::Error::Solver(_) => Kind::SolverFailed,
             competition::Error::SubmissionError => Kind::FailedToSubmit,
             competition::Error::TooManyPendingSettlements => Kind::TooManyPendingSettlements,
+            competition::Error::CombinedSettlementsDisabled => Kind::CombinedSettlementsDisabled,
+            competition::Error::InvalidCombinedSettlement => Kind::InvalidCombinedSettlement,
         };
         error.into()
     }
@@ -5,6 +5,7 @@ mod notify;
 mod quote;
 mod reveal;
 mod settle;
+mod settle_combined;
 mod solve;
 
 pub(super) use {
@@ -15,5 +16,6 @@ pub(super) use {
     quote::{OrderError, quote},
     reveal::reveal,
     settle::settle,
+    settle_combined::settle_combined,
     solve::{AuctionError, solve},
 };
@@ -0,0 +1,3 @@
+mod settle_combined_request;
+
+pub use settle_combined_request::SettleCombinedRequest;
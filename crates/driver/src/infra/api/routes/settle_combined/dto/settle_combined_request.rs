@@ -0,0 +1,16 @@
+use {crate::util::serialize, serde::Deserialize, serde_with::serde_as};
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleCombinedRequest {
+    /// Auction ID in which the combined solutions won.
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub auction_id: i64,
+    /// `settle` calldata combining the winning solutions of multiple solvers,
+    /// including the appended auction ID.
+    #[serde_as(as = "serialize::Hex")]
+    pub calldata: Vec<u8>,
+    /// The last block number in which the settlement TX can be included
+    pub submission_deadline_latest_block: u64,
+}
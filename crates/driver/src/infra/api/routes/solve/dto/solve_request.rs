@@ -151,6 +151,7 @@ impl SolveRequest {
                     price: token.price.map(Into::into),
                     available_balance: info.map(|i| i.balance).unwrap_or(0.into()).into(),
                     trusted: token.trusted,
+                    transfer_fee: None,
                 }
             }),
             time::Deadline::new(self.deadline, timeouts),
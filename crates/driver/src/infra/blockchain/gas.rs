@@ -69,6 +69,11 @@ impl GasPriceEstimator {
                     additional_tip_percentage,
                     ..
                 } => (max_additional_tip, additional_tip_percentage),
+                mempool::Kind::Bundle {
+                    max_additional_tip,
+                    additional_tip_percentage,
+                    ..
+                } => (max_additional_tip, additional_tip_percentage),
             })
             .next()
             .unwrap_or((eth::U256::zero(), 0.));
@@ -7,6 +7,7 @@ use {
             config::file,
             liquidity,
             mempool,
+            shared_cache,
             simulator,
             solver::{self, BadTokenDetection, SolutionMerging},
         },
@@ -50,9 +51,12 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
     );
     infra::Config {
         solvers: join_all(config.solvers.into_iter().map(|solver_config| async move {
-            let account = match solver_config.account {
-                file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
-                    ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
+            let (account, remote_signer) = match solver_config.account {
+                file::Account::PrivateKey(private_key) => (
+                    ethcontract::Account::Offline(
+                        ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
+                        None,
+                    ),
                     None,
                 ),
                 file::Account::Kms(key_id) => {
@@ -61,9 +65,18 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         ethcontract::transaction::kms::Account::new((&config).into(), &key_id.0)
                             .await
                             .unwrap_or_else(|_| panic!("Unable to load KMS account {key_id:?}"));
-                    ethcontract::Account::Kms(account, None)
+                    (ethcontract::Account::Kms(account, None), None)
                 }
-                file::Account::Address(address) => ethcontract::Account::Local(address, None),
+                file::Account::Address(address) => {
+                    (ethcontract::Account::Local(address, None), None)
+                }
+                file::Account::Remote(signer) => (
+                    ethcontract::Account::Local(signer.address, None),
+                    Some(infra::mempool::signer::Remote::new(
+                        signer.url,
+                        signer.address,
+                    )),
+                ),
             };
             solver::Config {
                 endpoint: solver_config.endpoint,
@@ -78,6 +91,7 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     solver::Liquidity::Fetch
                 },
                 account,
+                remote_signer,
                 timeouts: solver::Timeouts {
                     http_delay: chrono::Duration::from_std(solver_config.timeouts.http_time_buffer)
                         .unwrap(),
@@ -136,6 +150,7 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         .metrics_strategy_token_freeze_time,
                 },
                 settle_queue_size: solver_config.settle_queue_size,
+                accept_combined_settlements: solver_config.accept_combined_settlements,
                 flashloans_enabled: config.flashloans_enabled,
                 fetch_liquidity_at_block: match config.liquidity.fetch_at_block {
                     file::AtBlock::Latest => liquidity::AtBlock::Latest,
@@ -245,6 +260,37 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            uniswap_v4: config
+                .liquidity
+                .uniswap_v4
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::UniswapV4Config::Preset {
+                        preset,
+                        reinit_interval,
+                    } => liquidity::config::UniswapV4 {
+                        reinit_interval,
+                        ..match preset {
+                            file::UniswapV4Preset::UniswapV4 => {
+                                liquidity::config::UniswapV4::uniswap_v4(chain)
+                            }
+                        }
+                        .expect("no Uniswap V4 preset for current network")
+                    },
+                    file::UniswapV4Config::Manual {
+                        pool_manager,
+                        deployment_block,
+                        router,
+                        reinit_interval,
+                    } => liquidity::config::UniswapV4 {
+                        pool_manager: pool_manager.into(),
+                        deployment_block,
+                        router: router.into(),
+                        reinit_interval,
+                    },
+                })
+                .collect(),
             balancer_v2: config
                 .liquidity
                 .balancer_v2
@@ -301,6 +347,66 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            balancer_v3: config
+                .liquidity
+                .balancer_v3
+                .iter()
+                .cloned()
+                .map(|config| liquidity::config::BalancerV3 {
+                    vault: config.vault.into(),
+                    deployment_block: config.deployment_block,
+                    batch_router: config.batch_router.into(),
+                    permit2: blockchain::contracts::deployment_address(
+                        contracts::Permit2::raw_contract(),
+                        chain,
+                    )
+                    .expect("no Permit2 deployment for current network"),
+                    weighted: config
+                        .weighted
+                        .into_iter()
+                        .map(eth::ContractAddress::from)
+                        .collect(),
+                    stable: config
+                        .stable
+                        .into_iter()
+                        .map(eth::ContractAddress::from)
+                        .collect(),
+                    reinit_interval: config.reinit_interval,
+                })
+                .collect(),
+            curve: config
+                .liquidity
+                .curve
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::CurveConfig::Preset {
+                        preset,
+                        reinit_interval,
+                    } => liquidity::config::Curve {
+                        reinit_interval,
+                        ..match preset {
+                            file::CurvePreset::Curve => liquidity::config::Curve::curve(chain),
+                        }
+                        .expect("no Curve preset for current network")
+                    },
+                    file::CurveConfig::Manual {
+                        stable_swap_ng,
+                        twocrypto_ng,
+                        reinit_interval,
+                    } => liquidity::config::Curve {
+                        stable_swap_ng: stable_swap_ng
+                            .into_iter()
+                            .map(eth::ContractAddress::from)
+                            .collect(),
+                        twocrypto_ng: twocrypto_ng
+                            .into_iter()
+                            .map(eth::ContractAddress::from)
+                            .collect(),
+                        reinit_interval,
+                    },
+                })
+                .collect(),
             zeroex: config
                 .liquidity
                 .zeroex
@@ -327,12 +433,12 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         // If there is no private mempool, revert protection is
                         // disabled, otherwise driver would not even try to settle revertable
                         // settlements
-                        let revert_protection = if config
-                            .submission
-                            .mempools
-                            .iter()
-                            .any(|pool| matches!(pool, file::Mempool::MevBlocker { .. }))
-                        {
+                        let revert_protection = if config.submission.mempools.iter().any(|pool| {
+                            matches!(
+                                pool,
+                                file::Mempool::MevBlocker { .. } | file::Mempool::Bundle { .. }
+                            )
+                        }) {
                             mempool::RevertProtection::Enabled
                         } else {
                             mempool::RevertProtection::Disabled
@@ -355,11 +461,23 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         additional_tip_percentage: *additional_tip_percentage,
                         use_soft_cancellations: *use_soft_cancellations,
                     },
+                    file::Mempool::Bundle {
+                        url,
+                        max_additional_tip,
+                        additional_tip_percentage,
+                        signing_key,
+                    } => mempool::Kind::Bundle {
+                        url: url.to_owned(),
+                        max_additional_tip: *max_additional_tip,
+                        additional_tip_percentage: *additional_tip_percentage,
+                        signing_key: signing_key
+                            .map(|key| ethcontract::PrivateKey::from_raw(key.0).unwrap()),
+                    },
                 },
             })
             .collect(),
-        simulator: match (config.tenderly, config.enso) {
-            (Some(config), None) => {
+        simulator: match (config.tenderly, config.enso, config.revm) {
+            (Some(config), None, None) => {
                 Some(simulator::Config::Tenderly(simulator::tenderly::Config {
                     url: config.url,
                     api_key: config.api_key,
@@ -369,12 +487,15 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     save_if_fails: config.save_if_fails,
                 }))
             }
-            (None, Some(config)) => Some(simulator::Config::Enso(simulator::enso::Config {
+            (None, Some(config), None) => Some(simulator::Config::Enso(simulator::enso::Config {
                 url: config.url,
                 network_block_interval: config.network_block_interval,
             })),
-            (None, None) => None,
-            (Some(_), Some(_)) => panic!("Cannot configure both Tenderly and Enso"),
+            (None, None, Some(config)) => Some(simulator::Config::Revm(simulator::revm::Config {
+                network_block_interval: config.network_block_interval,
+            })),
+            (None, None, None) => None,
+            _ => panic!("Cannot configure more than one of Tenderly, Enso and revm"),
         },
         contracts: blockchain::contracts::Addresses {
             settlement: config.contracts.gp_v2_settlement.map(Into::into),
@@ -415,6 +536,9 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
         order_priority_strategies: config.order_priority_strategies,
         archive_node_url: config.archive_node_url,
         simulation_bad_token_max_age: config.simulation_bad_token_max_age,
+        shared_cache: config.shared_cache.map(|cache| shared_cache::Config {
+            db_url: cache.db_url,
+        }),
         app_data_fetching: config.app_data_fetching,
     }
 }
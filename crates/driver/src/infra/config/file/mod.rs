@@ -49,6 +49,9 @@ struct Config {
     /// Use Enso for transaction simulation.
     enso: Option<EnsoConfig>,
 
+    /// Simulate transactions in-process with revm.
+    revm: Option<RevmConfig>,
+
     #[serde(rename = "solver")]
     solvers: Vec<SolverConfig>,
 
@@ -74,6 +77,11 @@ struct Config {
     )]
     simulation_bad_token_max_age: Duration,
 
+    /// Share simulation results and token qualities with other drivers using
+    /// the same cache. Useful when multiple solvers are run behind separate
+    /// drivers.
+    shared_cache: Option<SharedCacheConfig>,
+
     /// Configuration for the app-data fetching.
     #[serde(default, flatten)]
     app_data_fetching: AppDataFetching,
@@ -83,6 +91,14 @@ struct Config {
     flashloans_enabled: bool,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct SharedCacheConfig {
+    /// Postgres database in which the shared entries are stored. Entries of
+    /// past blocks get evicted whenever a new block arrives.
+    db_url: Url,
+}
+
 #[serde_as]
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
@@ -110,7 +126,8 @@ struct SubmissionConfig {
     retry_interval: Duration,
 
     /// The mempools to submit settlement transactions to. Can be the public
-    /// mempool of a node or the private MEVBlocker mempool.
+    /// mempool of a node, the private MEVBlocker mempool or a block builder
+    /// relay accepting bundles.
     #[serde(rename = "mempool", default)]
     mempools: Vec<Mempool>,
 }
@@ -155,6 +172,26 @@ enum Mempool {
         #[serde(default = "default_soft_cancellations_flag")]
         use_soft_cancellations: bool,
     },
+    #[serde(rename_all = "kebab-case")]
+    Bundle {
+        /// The URL of a relay supporting `eth_sendBundle` (e.g. Flashbots).
+        url: Url,
+        /// Maximum additional tip in Gwei that we are willing to give to
+        /// the block builder above regular gas price estimation.
+        #[serde(default = "default_max_additional_tip")]
+        #[serde_as(as = "serialize::U256")]
+        max_additional_tip: eth::U256,
+        /// Additional tip in percentage of max_fee_per_gas we are giving to
+        /// the block builder above regular gas price estimation. Expects a
+        /// floating point value between 0 and 1.
+        #[serde(default = "default_additional_tip_percentage")]
+        additional_tip_percentage: f64,
+        /// Private key identifying us as a searcher to the relay. Bundles get
+        /// signed with it in the `X-Flashbots-Signature` header. Expects a
+        /// 32-byte hex encoded string. Defaults to the private key of the
+        /// solver account.
+        signing_key: Option<eth::H256>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -289,6 +326,11 @@ struct SolverConfig {
     /// before the driver starts dropping new `/solve` requests.
     #[serde(default = "default_settle_queue_size")]
     settle_queue_size: usize,
+
+    /// Whether the solver submits settlements combining the winning solutions
+    /// of multiple solvers on behalf of the autopilot.
+    #[serde(default)]
+    accept_combined_settlements: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -323,6 +365,18 @@ enum Account {
     /// connected node's account management features. This can also be used to
     /// start the driver in a dry-run mode.
     Address(eth::H160),
+    /// A remote signing service implementing the `eth_signTransaction`
+    /// JSON-RPC method (e.g. Web3Signer) is used to sign transactions.
+    Remote(RemoteSigner),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RemoteSigner {
+    /// The URL of the signing service.
+    url: Url,
+    /// The address of the account the signing service holds the key for.
+    address: eth::H160,
 }
 
 #[serde_as]
@@ -442,6 +496,15 @@ struct EnsoConfig {
     network_block_interval: Option<Duration>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RevmConfig {
+    /// How often the network produces a new block. If this is not set the
+    /// system assumes an unpredictable network like proof-of-work.
+    #[serde(default, with = "humantime_serde")]
+    network_block_interval: Option<Duration>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct LiquidityConfig {
@@ -462,10 +525,22 @@ struct LiquidityConfig {
     #[serde(default)]
     uniswap_v3: Vec<UniswapV3Config>,
 
+    /// Liquidity provided by Uniswap V4 pools.
+    #[serde(default)]
+    uniswap_v4: Vec<UniswapV4Config>,
+
     /// Liquidity provided by a Balancer V2 compatible contract.
     #[serde(default)]
     balancer_v2: Vec<BalancerV2Config>,
 
+    /// Liquidity provided by Balancer V3 pools.
+    #[serde(default)]
+    balancer_v3: Vec<BalancerV3Config>,
+
+    /// Liquidity provided by Curve pools.
+    #[serde(default)]
+    curve: Vec<CurveConfig>,
+
     /// Liquidity provided by 0x API.
     #[serde(default)]
     zeroex: Option<ZeroExConfig>,
@@ -587,6 +662,41 @@ mod uniswap_v3 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum UniswapV4Config {
+    #[serde(rename_all = "kebab-case")]
+    Preset {
+        preset: UniswapV4Preset,
+
+        /// How often the liquidity source should be reinitialized.
+        #[serde(with = "humantime_serde", default = "default_reinit_interval")]
+        reinit_interval: Option<Duration>,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the `PoolManager` contract.
+        pool_manager: eth::H160,
+
+        /// The block at which the `PoolManager` contract was deployed.
+        deployment_block: u64,
+
+        /// The address of the `UniversalRouter` contract.
+        router: eth::H160,
+
+        /// How often the liquidity source should be reinitialized.
+        #[serde(with = "humantime_serde", default = "default_reinit_interval")]
+        reinit_interval: Option<Duration>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum UniswapV4Preset {
+    UniswapV4,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
 enum BalancerV2Config {
@@ -655,6 +765,68 @@ enum BalancerV2Preset {
     BalancerV2,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct BalancerV3Config {
+    /// The address of the `Vault` contract.
+    vault: eth::H160,
+
+    /// The block at which the `Vault` contract was deployed.
+    deployment_block: u64,
+
+    /// The address of the `BatchRouter` contract.
+    batch_router: eth::H160,
+
+    /// The weighted pool factory contract addresses.
+    #[serde(default)]
+    weighted: Vec<eth::H160>,
+
+    /// The stable pool factory contract addresses.
+    #[serde(default)]
+    stable: Vec<eth::H160>,
+
+    /// How often the liquidity source should be reinitialized to get access
+    /// to new pools.
+    #[serde(with = "humantime_serde", default = "default_reinit_interval")]
+    reinit_interval: Option<Duration>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum CurveConfig {
+    #[serde(rename_all = "kebab-case")]
+    Preset {
+        preset: CurvePreset,
+
+        /// How often the liquidity source should be reinitialized to get
+        /// access to new pools.
+        #[serde(with = "humantime_serde", default = "default_reinit_interval")]
+        reinit_interval: Option<Duration>,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The StableSwap-NG factory contract addresses.
+        #[serde(default)]
+        stable_swap_ng: Vec<eth::H160>,
+
+        /// The Twocrypto-NG factory contract addresses.
+        #[serde(default)]
+        twocrypto_ng: Vec<eth::H160>,
+
+        /// How often the liquidity source should be reinitialized to get
+        /// access to new pools.
+        #[serde(with = "humantime_serde", default = "default_reinit_interval")]
+        reinit_interval: Option<Duration>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum CurvePreset {
+    Curve,
+}
+
 fn default_reinit_interval() -> Option<Duration> {
     Some(Duration::from_secs(12 * 60 * 60))
 }
@@ -6,6 +6,7 @@ use {
             config::file::{AppDataFetching, GasEstimatorType, OrderPriorityStrategy},
             liquidity,
             mempool,
+            shared_cache,
             simulator,
             solver,
         },
@@ -30,5 +31,6 @@ pub struct Config {
     pub order_priority_strategies: Vec<OrderPriorityStrategy>,
     pub archive_node_url: Option<Url>,
     pub simulation_bad_token_max_age: Duration,
+    pub shared_cache: Option<shared_cache::Config>,
     pub app_data_fetching: AppDataFetching,
 }
@@ -26,10 +26,21 @@ pub struct Config {
     /// for.
     pub uniswap_v3: Vec<UniswapV3>,
 
+    /// The collection of Uniswap V4 compatible exchanges to fetch liquidity
+    /// for.
+    pub uniswap_v4: Vec<UniswapV4>,
+
     /// The collection of Balancer V2 compatible exchanges to fetch liquidity
     /// for.
     pub balancer_v2: Vec<BalancerV2>,
 
+    /// The collection of Balancer V3 compatible exchanges to fetch liquidity
+    /// for.
+    pub balancer_v3: Vec<BalancerV3>,
+
+    /// The collection of Curve compatible exchanges to fetch liquidity for.
+    pub curve: Vec<Curve>,
+
     /// 0x liquidity fetcher.
     pub zeroex: Option<ZeroEx>,
 }
@@ -167,6 +178,37 @@ impl UniswapV3 {
     }
 }
 
+/// Uniswap V4 liquidity fetching options.
+#[derive(Clone, Debug)]
+pub struct UniswapV4 {
+    /// The address of the singleton `PoolManager` contract.
+    pub pool_manager: eth::ContractAddress,
+
+    /// The block at which the `PoolManager` was deployed. Pools are indexed
+    /// from its events starting at this block.
+    pub deployment_block: u64,
+
+    /// The address of the `UniversalRouter` used for executing swaps.
+    pub router: eth::ContractAddress,
+
+    /// How often the liquidity source should be reinitialized.
+    pub reinit_interval: Option<Duration>,
+}
+
+impl UniswapV4 {
+    /// Returns the liquidity configuration for Uniswap V4.
+    #[allow(clippy::self_named_constructors)]
+    pub fn uniswap_v4(chain: Chain) -> Option<Self> {
+        let pool_manager = contracts::UniswapV4PoolManager::raw_contract();
+        Some(Self {
+            pool_manager: deployment_address(pool_manager, chain)?,
+            deployment_block: contracts::deployment_block(pool_manager, chain.id()).ok()?,
+            router: deployment_address(contracts::UniswapV4UniversalRouter::raw_contract(), chain)?,
+            reinit_interval: None,
+        })
+    }
+}
+
 /// Balancer V2 liquidity fetching options.
 #[derive(Clone, Debug)]
 pub struct BalancerV2 {
@@ -245,6 +287,69 @@ impl BalancerV2 {
     }
 }
 
+/// Balancer V3 liquidity fetching options.
+#[derive(Clone, Debug)]
+pub struct BalancerV3 {
+    /// The address of the singleton `Vault` contract.
+    pub vault: eth::ContractAddress,
+
+    /// The block at which the `Vault` was deployed. Pools are indexed from
+    /// its `PoolRegistered` events starting at this block.
+    pub deployment_block: u64,
+
+    /// The address of the `BatchRouter` used for executing swaps.
+    pub batch_router: eth::ContractAddress,
+
+    /// The address of the `Permit2` contract the `BatchRouter` pulls tokens
+    /// through.
+    pub permit2: eth::ContractAddress,
+
+    /// Weighted pool factory addresses.
+    pub weighted: Vec<eth::ContractAddress>,
+
+    /// Stable pool factory addresses.
+    pub stable: Vec<eth::ContractAddress>,
+
+    /// How often the liquidity source should be reinitialized to become aware
+    /// of new pools.
+    pub reinit_interval: Option<Duration>,
+}
+
+/// Curve liquidity fetching options.
+#[derive(Clone, Debug)]
+pub struct Curve {
+    /// StableSwap-NG factory addresses. Only plain pools are indexed, meta
+    /// pools are ignored.
+    pub stable_swap_ng: Vec<eth::ContractAddress>,
+
+    /// Twocrypto-NG factory addresses.
+    pub twocrypto_ng: Vec<eth::ContractAddress>,
+
+    /// How often the liquidity source should be reinitialized to become aware
+    /// of new pools.
+    pub reinit_interval: Option<Duration>,
+}
+
+impl Curve {
+    /// Returns the liquidity configuration for Curve.
+    #[allow(clippy::self_named_constructors)]
+    pub fn curve(chain: Chain) -> Option<Self> {
+        let stable_swap_ng =
+            deployment_address(contracts::CurveStableSwapNgFactory::raw_contract(), chain);
+        let twocrypto_ng =
+            deployment_address(contracts::CurveTwocryptoFactory::raw_contract(), chain);
+        if stable_swap_ng.is_none() && twocrypto_ng.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            stable_swap_ng: stable_swap_ng.into_iter().collect(),
+            twocrypto_ng: twocrypto_ng.into_iter().collect(),
+            reinit_interval: None,
+        })
+    }
+}
+
 /// ZeroEx liquidity fetching options.
 #[derive(Clone, Debug)]
 pub struct ZeroEx {
@@ -0,0 +1,100 @@
+//! Client for block builder relays that accept transactions as bundles via
+//! `eth_sendBundle` (e.g. Flashbots).
+
+use {
+    crate::domain::BlockNo,
+    anyhow::Context,
+    ethcontract::{Account, PrivateKey},
+    serde::Deserialize,
+    serde_json::json,
+    web3::{
+        signing::{self, Key, SecretKeyRef},
+        types::Bytes,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct Relay {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    /// Key identifying us as a searcher to the relay. Falls back to the key of
+    /// the solver account submitting the bundle.
+    signing_key: Option<PrivateKey>,
+}
+
+impl Relay {
+    pub fn new(url: reqwest::Url, signing_key: Option<PrivateKey>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            signing_key,
+        }
+    }
+
+    /// Asks the relay to include the signed transactions atomically and in
+    /// the given order in the specified block. A bundle only targets a single
+    /// block so it needs to be sent again for every block it should be
+    /// considered for.
+    pub async fn send_bundle(
+        &self,
+        txs: &[Bytes],
+        block: BlockNo,
+        solver: &Account,
+    ) -> anyhow::Result<()> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendBundle",
+            "params": [{
+                "txs": txs,
+                "blockNumber": format!("{block:#x}"),
+            }],
+        });
+        let body = serde_json::to_vec(&request)?;
+        let key = match (&self.signing_key, solver) {
+            (Some(key), _) | (None, Account::Offline(key, _)) => key,
+            _ => anyhow::bail!("bundles need a signing key or a solver account with a private key"),
+        };
+        let response: Response = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Flashbots-Signature", signature(key, &body)?)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(err) = response.error {
+            anyhow::bail!("relay rejected bundle: {} (code {})", err.message, err.code);
+        }
+        Ok(())
+    }
+}
+
+/// Relays authenticate searchers with an EIP-191 signature of the hex encoded
+/// Keccak-256 hash of the request body in the form `<address>:<signature>`.
+fn signature(key: &PrivateKey, body: &[u8]) -> anyhow::Result<String> {
+    let message = format!("0x{}", hex::encode(signing::keccak256(body)));
+    let key = SecretKeyRef::new(key);
+    let signature = key
+        .sign(signing::hash_message(message).as_bytes(), None)
+        .context("failed to sign bundle")?;
+    let mut bytes = [0; 65];
+    bytes[..32].copy_from_slice(signature.r.as_bytes());
+    bytes[32..64].copy_from_slice(signature.s.as_bytes());
+    bytes[64] = signature.v as u8;
+    Ok(format!("{:?}:0x{}", key.address(), hex::encode(bytes)))
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    error: Option<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
@@ -4,9 +4,16 @@ use {
         domain::{competition, eth, mempools},
         infra,
     },
-    ethcontract::dyns::DynWeb3,
+    ethcontract::{
+        dyns::DynWeb3,
+        transaction::{Transaction, TransactionBuilder},
+    },
+    web3::types::Bytes,
 };
 
+pub mod bundle;
+pub mod signer;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub min_priority_fee: eth::U256,
@@ -31,6 +38,17 @@ pub enum Kind {
         additional_tip_percentage: f64,
         use_soft_cancellations: bool,
     },
+    /// A block builder relay accepting settlements as bundles. Bundles only
+    /// target a single block so they get resubmitted for every block until
+    /// the submission deadline. Reverting bundles don't get included.
+    Bundle {
+        url: reqwest::Url,
+        max_additional_tip: eth::U256,
+        additional_tip_percentage: f64,
+        /// Key identifying us as a searcher to the relay. Defaults to the key
+        /// of the solver account.
+        signing_key: Option<ethcontract::PrivateKey>,
+    },
 }
 
 impl Kind {
@@ -39,6 +57,7 @@ impl Kind {
         match self {
             Kind::Public { .. } => "PublicMempool",
             Kind::MEVBlocker { .. } => "MEVBlocker",
+            Kind::Bundle { .. } => "Bundle",
         }
     }
 }
@@ -57,6 +76,7 @@ pub enum RevertProtection {
 #[derive(Debug, Clone)]
 pub struct Mempool {
     transport: DynWeb3,
+    relay: Option<bundle::Relay>,
     config: Config,
 }
 
@@ -72,8 +92,20 @@ impl Mempool {
             Kind::Public { .. } => transport,
             // Flashbots Protect RPC fallback doesn't support buffered transport
             Kind::MEVBlocker { url, .. } => unbuffered_web3_client(url),
+            // Bundles get signed with the nonce and chain ID of the node
+            Kind::Bundle { .. } => transport,
         };
-        Self { config, transport }
+        let relay = match &config.kind {
+            Kind::Bundle {
+                url, signing_key, ..
+            } => Some(bundle::Relay::new(url.clone(), *signing_key)),
+            _ => None,
+        };
+        Self {
+            config,
+            transport,
+            relay,
+        }
     }
 
     /// Submits a transaction to the mempool. Returns optimistically as soon as
@@ -84,7 +116,11 @@ impl Mempool {
         gas: competition::solution::settlement::Gas,
         solver: &infra::Solver,
     ) -> Result<eth::TxId, mempools::Error> {
-        ethcontract::transaction::TransactionBuilder::new(self.transport.clone())
+        if let Some(signer) = solver.remote_signer() {
+            let (bytes, hash) = self.sign_remotely(signer, tx, gas).await?;
+            return self.send_raw(bytes, hash, &solver.account()).await;
+        }
+        let builder = TransactionBuilder::new(self.transport.clone())
             .from(solver.account().clone())
             .to(tx.to.into())
             .gas_price(ethcontract::GasPrice::Eip1559 {
@@ -94,7 +130,22 @@ impl Mempool {
             .data(tx.input.into())
             .value(tx.value.0)
             .gas(gas.limit.0)
-            .access_list(web3::types::AccessList::from(tx.access_list))
+            .access_list(web3::types::AccessList::from(tx.access_list));
+        if self.relay.is_some() {
+            // Signing is deterministic so submitting the same transaction
+            // again for a later block results in the same hash as long as the
+            // account's nonce didn't change.
+            let (bytes, hash) = match builder.build().await.map_err(anyhow::Error::from)? {
+                Transaction::Raw { bytes, hash } => (bytes, hash),
+                Transaction::Request(_) => {
+                    return Err(mempools::Error::Other(anyhow::anyhow!(
+                        "bundle submission requires a solver account with a private key"
+                    )));
+                }
+            };
+            return self.send_raw(bytes, hash, &solver.account()).await;
+        }
+        builder
             .resolve(ethcontract::transaction::ResolveCondition::Pending)
             .send()
             .await
@@ -102,6 +153,62 @@ impl Mempool {
             .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)))
     }
 
+    /// Has the transaction signed by the remote signer. Like for locally
+    /// signed transactions the nonce is the number of mined transactions of
+    /// the account, so resubmissions and cancellations replace the pending
+    /// settlement.
+    async fn sign_remotely(
+        &self,
+        signer: &signer::Remote,
+        tx: eth::Tx,
+        gas: competition::solution::settlement::Gas,
+    ) -> Result<(Bytes, eth::H256), mempools::Error> {
+        let eth = self.transport.eth();
+        let (nonce, chain_id) = futures::try_join!(
+            eth.transaction_count(signer.address(), None),
+            eth.chain_id()
+        )
+        .map_err(anyhow::Error::from)?;
+        let tx = signer::Transaction {
+            from: signer.address(),
+            to: tx.to.into(),
+            gas: gas.limit.0,
+            max_fee_per_gas: gas.price.max().into(),
+            max_priority_fee_per_gas: gas.price.tip().into(),
+            value: tx.value.0,
+            data: tx.input.into(),
+            nonce,
+            chain_id,
+            access_list: tx.access_list.into(),
+        };
+        Ok(signer.sign_transaction(&tx).await?)
+    }
+
+    /// Sends a signed transaction to the node or, for bundle mempools, to the
+    /// relay as a bundle targeting the next block.
+    async fn send_raw(
+        &self,
+        bytes: Bytes,
+        hash: eth::H256,
+        solver: &ethcontract::Account,
+    ) -> Result<eth::TxId, mempools::Error> {
+        let eth = self.transport.eth();
+        match &self.relay {
+            Some(relay) => {
+                let block = eth.block_number().await.map_err(anyhow::Error::from)?;
+                relay
+                    .send_bundle(&[bytes], block.as_u64() + 1, solver)
+                    .await?;
+            }
+            None => {
+                eth.send_raw_transaction(bytes)
+                    .await
+                    .map_err(anyhow::Error::from)?;
+            }
+        }
+        Ok(eth::TxId(hash))
+    }
+
     pub fn config(&self) -> &Config {
         &self.config
     }
@@ -109,7 +216,15 @@ impl Mempool {
     pub fn may_revert(&self) -> bool {
         match &self.config.kind {
             Kind::Public { .. } => true,
-            Kind::MEVBlocker { .. } => false,
+            Kind::MEVBlocker { .. } | Kind::Bundle { .. } => false,
         }
     }
+
+    /// Whether transactions are submitted as bundles that only target a
+    /// single block. Those have to be resubmitted for every block until they
+    /// get included. Since they never sit in a mempool they can simply be
+    /// dropped instead of getting cancelled.
+    pub fn submits_bundles(&self) -> bool {
+        self.relay.is_some()
+    }
 }
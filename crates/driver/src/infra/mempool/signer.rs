@@ -0,0 +1,94 @@
+//! Client for remote signing services that sign transactions via the
+//! `eth_signTransaction` JSON-RPC method (e.g. Web3Signer). This allows
+//! submitting settlements without the driver ever knowing the solver's
+//! private key.
+
+use {
+    crate::domain::eth,
+    serde::{Deserialize, Serialize},
+    serde_json::json,
+    web3::types::{AccessList, Bytes},
+};
+
+#[derive(Debug, Clone)]
+pub struct Remote {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    address: eth::H160,
+}
+
+/// An EIP-1559 transaction to be signed. All fields have to be set since the
+/// signer doesn't have access to the chain to fill them in.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub from: eth::H160,
+    pub to: eth::H160,
+    pub gas: eth::U256,
+    pub max_fee_per_gas: eth::U256,
+    pub max_priority_fee_per_gas: eth::U256,
+    pub value: eth::U256,
+    pub data: Bytes,
+    pub nonce: eth::U256,
+    pub chain_id: eth::U256,
+    pub access_list: AccessList,
+}
+
+impl Remote {
+    pub fn new(url: reqwest::Url, address: eth::H160) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            address,
+        }
+    }
+
+    /// The address of the account the signer holds the key for.
+    pub fn address(&self) -> eth::H160 {
+        self.address
+    }
+
+    /// Asks the signer to sign the transaction. Returns the raw signed
+    /// transaction and its hash.
+    pub async fn sign_transaction(&self, tx: &Transaction) -> anyhow::Result<(Bytes, eth::H256)> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_signTransaction",
+            "params": [tx],
+        });
+        let response: Response = self
+            .client
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(err) = response.error {
+            anyhow::bail!(
+                "signer rejected transaction: {} (code {})",
+                err.message,
+                err.code
+            );
+        }
+        let signed = response
+            .result
+            .ok_or_else(|| anyhow::anyhow!("signer returned no signed transaction"))?;
+        let hash = eth::H256(web3::signing::keccak256(&signed.0));
+        Ok((signed, hash))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    result: Option<Bytes>,
+    error: Option<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
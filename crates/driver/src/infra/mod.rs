@@ -7,6 +7,7 @@ pub mod mempool;
 pub mod notify;
 pub mod observe;
 pub mod persistence;
+pub mod shared_cache;
 pub mod simulator;
 pub mod solver;
 pub mod time;
@@ -18,5 +19,6 @@ pub use {
     blockchain::Ethereum,
     config::Config,
     mempool::Mempool,
+    shared_cache::SharedCache,
     simulator::Simulator,
 };
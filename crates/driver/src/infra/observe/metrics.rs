@@ -26,6 +26,10 @@ pub struct Metrics {
     /// How many tokens detected by specific solver and strategy.
     #[metric(labels("solver", "strategy"))]
     pub bad_tokens_detected: prometheus::IntCounterVec,
+    /// Lookups of simulation results and token qualities in the cache shared
+    /// with other drivers.
+    #[metric(labels("kind", "result"))]
+    pub shared_cache_lookups: prometheus::IntCounterVec,
     /// Time spent in the auction preprocessing stage.
     #[metric(
         labels("stage"),
@@ -322,7 +322,7 @@ pub fn solver_response(
 /// Observe the result of mempool transaction execution.
 pub fn mempool_executed(
     mempool: &Mempool,
-    settlement: &Settlement,
+    submission: &mempools::Submission<'_>,
     res: &Result<SubmissionSuccess, mempools::Error>,
 ) {
     match res {
@@ -330,7 +330,7 @@ pub fn mempool_executed(
             tracing::info!(
                 txid = ?submission.tx_hash,
                 %mempool,
-                ?settlement,
+                ?submission,
                 "sending transaction via mempool succeeded",
             );
         }
@@ -344,7 +344,7 @@ pub fn mempool_executed(
             tracing::warn!(
                 ?err,
                 %mempool,
-                ?settlement,
+                ?submission,
                 "sending transaction via mempool failed",
             );
         }
@@ -415,6 +415,8 @@ fn competition_error(err: &competition::Error) -> &'static str {
         competition::Error::Solver(solver::Error::Dto(_)) => "SolverDtoError",
         competition::Error::SubmissionError => "SubmissionError",
         competition::Error::TooManyPendingSettlements => "TooManyPendingSettlements",
+        competition::Error::CombinedSettlementsDisabled => "CombinedSettlementsDisabled",
+        competition::Error::InvalidCombinedSettlement => "InvalidCombinedSettlement",
     }
 }
 
@@ -445,10 +447,20 @@ pub fn order_excluded_from_auction(
 }
 
 /// Observe that a settlement was simulated
-pub fn simulated(eth: &Ethereum, tx: &eth::Tx, gas: &Result<Gas, simulator::Error>) {
+pub fn simulated(
+    eth: &Ethereum,
+    tx: &eth::Tx,
+    simulated: &Result<(eth::AccessList, Gas), simulator::Error>,
+) {
     let block: eth::BlockNo = eth.current_block().borrow().number.into();
-    match gas {
-        Ok(gas) => tracing::debug!(block = ?block, gas = ?gas.0, ?tx, "simulated settlement"),
+    match simulated {
+        Ok((access_list, gas)) => tracing::debug!(
+            block = ?block,
+            gas = ?gas.0,
+            ?tx,
+            ?access_list,
+            "simulated settlement"
+        ),
         Err(err) => tracing::debug!(block = ?block, ?err, "simulated settlement"),
     }
 }
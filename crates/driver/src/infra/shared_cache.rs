@@ -0,0 +1,284 @@
+//! Cache which can be shared by multiple drivers to avoid simulating the same
+//! settlements and token transfers over and over again. Entries are stored in
+//! a Postgres database.
+
+use {
+    crate::{
+        domain::{competition::bad_tokens::Quality, eth},
+        infra::{Ethereum, observe::metrics},
+    },
+    chrono::Utc,
+    database::{byte_array::ByteArray, driver_caches},
+    ethrpc::block_stream::{self, CurrentBlockWatcher},
+    futures::StreamExt,
+    number::conversions::{big_decimal_to_u256, u256_to_big_decimal},
+    shared::bad_token::TransferFee,
+    sqlx::PgPool,
+    std::{
+        sync::{Arc, Weak},
+        time::Duration,
+    },
+    tracing::Instrument,
+    url::Url,
+};
+
+/// Configuration of the shared cache.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Database in which the cached entries are stored.
+    pub db_url: Url,
+}
+
+#[derive(Clone, Debug)]
+pub struct SharedCache(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    pool: PgPool,
+    /// Token qualities older than this get ignored and evicted.
+    token_quality_max_age: Duration,
+}
+
+impl SharedCache {
+    /// Connects to the cache database and evicts outdated entries whenever a
+    /// new block arrives.
+    pub fn new(config: &Config, eth: &Ethereum, token_quality_max_age: Duration) -> Self {
+        let pool = PgPool::connect_lazy(config.db_url.as_str())
+            .expect("failed to create shared cache database pool");
+        let inner = Arc::new(Inner {
+            pool,
+            token_quality_max_age,
+        });
+        tokio::task::spawn(
+            eviction_task(eth.current_block().clone(), Arc::downgrade(&inner))
+                .instrument(tracing::info_span!("shared_cache")),
+        );
+        Self(inner)
+    }
+
+    /// Returns the quality of the token if another driver determined it
+    /// recently.
+    pub async fn token_quality(&self, token: eth::TokenAddress) -> Quality {
+        let result = async {
+            let mut ex = self.0.pool.acquire().await?;
+            driver_caches::fetch_token_quality(
+                &mut ex,
+                &ByteArray(token.0.0.0),
+                Utc::now() - self.0.token_quality_max_age,
+            )
+            .await
+        }
+        .await;
+        let quality = match result {
+            Ok(Some(quality)) => match (quality.supported, quality.transfer_fee_bps) {
+                (false, _) => Quality::Unsupported,
+                (true, None) => Quality::Supported,
+                (true, Some(bps)) => u32::try_from(bps)
+                    .ok()
+                    .and_then(TransferFee::from_bps)
+                    .map_or(Quality::Supported, Quality::FeeOnTransfer),
+            },
+            Ok(None) => Quality::Unknown,
+            Err(err) => {
+                tracing::warn!(?err, ?token, "failed to fetch cached token quality");
+                Quality::Unknown
+            }
+        };
+        record_lookup("token_quality", quality != Quality::Unknown);
+        quality
+    }
+
+    /// Shares the quality of a token with other drivers. Non-blocking.
+    pub fn store_token_quality(&self, token: eth::TokenAddress, quality: Quality) {
+        let (supported, transfer_fee_bps) = match quality {
+            Quality::Supported => (true, None),
+            Quality::FeeOnTransfer(fee) => (true, i32::try_from(fee.bps()).ok()),
+            Quality::Unsupported => (false, None),
+            Quality::Unknown => return,
+        };
+        let inner = self.0.clone();
+        tokio::task::spawn(async move {
+            let now = Utc::now();
+            let quality = driver_caches::TokenQuality {
+                supported,
+                transfer_fee_bps,
+                updated_at: now,
+            };
+            let result = async {
+                let mut ex = inner.pool.acquire().await?;
+                driver_caches::upsert_token_quality(
+                    &mut ex,
+                    &ByteArray(token.0.0.0),
+                    &quality,
+                    now - inner.token_quality_max_age,
+                )
+                .await
+            }
+            .await;
+            if let Err(err) = result {
+                tracing::warn!(?err, ?token, "failed to store token quality");
+            }
+        });
+    }
+
+    /// Returns the gas another driver simulated for the transaction on the
+    /// given block.
+    pub async fn gas(&self, block: eth::BlockNo, tx: &eth::Tx) -> Option<eth::Gas> {
+        let gas = self
+            .simulation(block, tx)
+            .await
+            .and_then(|simulation| big_decimal_to_u256(&simulation.gas?))
+            .map(eth::Gas);
+        record_lookup("gas", gas.is_some());
+        gas
+    }
+
+    /// Returns the access list another driver simulated for the transaction
+    /// on the given block.
+    pub async fn access_list(&self, block: eth::BlockNo, tx: &eth::Tx) -> Option<eth::AccessList> {
+        let access_list = self.simulation(block, tx).await.and_then(|simulation| {
+            serde_json::from_value::<web3::types::AccessList>(simulation.access_list?)
+                .inspect_err(|err| tracing::warn!(?err, "invalid cached access list"))
+                .ok()
+        });
+        record_lookup("access_list", access_list.is_some());
+        access_list.map(Into::into)
+    }
+
+    /// Shares the simulated gas of a transaction with other drivers.
+    /// Non-blocking.
+    pub fn store_gas(&self, block: eth::BlockNo, tx: &eth::Tx, gas: eth::Gas) {
+        self.store_simulation(
+            block,
+            tx,
+            driver_caches::Simulation {
+                gas: Some(u256_to_big_decimal(&gas.0)),
+                access_list: None,
+            },
+        );
+    }
+
+    /// Shares the simulated access list of a transaction with other drivers.
+    /// Non-blocking.
+    pub fn store_access_list(
+        &self,
+        block: eth::BlockNo,
+        tx: &eth::Tx,
+        access_list: &eth::AccessList,
+    ) {
+        let access_list = web3::types::AccessList::from(access_list.clone());
+        self.store_simulation(
+            block,
+            tx,
+            driver_caches::Simulation {
+                gas: None,
+                access_list: Some(
+                    serde_json::to_value(access_list).expect("access lists are serializable"),
+                ),
+            },
+        );
+    }
+
+    async fn simulation(
+        &self,
+        block: eth::BlockNo,
+        tx: &eth::Tx,
+    ) -> Option<driver_caches::Simulation> {
+        let result = async {
+            let mut ex = self.0.pool.acquire().await?;
+            driver_caches::fetch_simulation(&mut ex, block_number(block), &tx_hash(tx)).await
+        }
+        .await;
+        result
+            .inspect_err(|err| tracing::warn!(?err, "failed to fetch cached simulation"))
+            .ok()
+            .flatten()
+    }
+
+    fn store_simulation(
+        &self,
+        block: eth::BlockNo,
+        tx: &eth::Tx,
+        simulation: driver_caches::Simulation,
+    ) {
+        let inner = self.0.clone();
+        let tx_hash = tx_hash(tx);
+        tokio::task::spawn(async move {
+            let result = async {
+                let mut ex = inner.pool.acquire().await?;
+                driver_caches::upsert_simulation(
+                    &mut ex,
+                    block_number(block),
+                    &tx_hash,
+                    &simulation,
+                )
+                .await
+            }
+            .await;
+            if let Err(err) = result {
+                tracing::warn!(?err, "failed to store simulation");
+            }
+        });
+    }
+}
+
+/// Deletes simulations of past blocks and outdated token qualities whenever a
+/// new block arrives until the cache is dropped.
+async fn eviction_task(blocks: CurrentBlockWatcher, inner: Weak<Inner>) {
+    let mut stream = block_stream::into_stream(blocks);
+    while let Some(block) = stream.next().await {
+        let Some(inner) = inner.upgrade() else {
+            // Cache was dropped, stop eviction task.
+            break;
+        };
+        let result = async {
+            let mut ex = inner.pool.acquire().await?;
+            driver_caches::delete_simulations_before(&mut ex, block_number(block.number.into()))
+                .await?;
+            driver_caches::delete_token_qualities_before(
+                &mut ex,
+                Utc::now() - inner.token_quality_max_age,
+            )
+            .await
+        }
+        .await;
+        if let Err(err) = result {
+            tracing::warn!(?err, "failed to evict outdated shared cache entries");
+        }
+    }
+}
+
+fn block_number(block: eth::BlockNo) -> i64 {
+    i64::try_from(block.0).expect("block number fits into i64")
+}
+
+/// Identifies a transaction by everything that influences its simulation.
+fn tx_hash(tx: &eth::Tx) -> database::TransactionHash {
+    let mut value = [0; 32];
+    tx.value.0.to_big_endian(&mut value);
+    let mut bytes = Vec::with_capacity(80 + tx.input.0.len());
+    bytes.extend_from_slice(tx.from.0.as_bytes());
+    bytes.extend_from_slice(tx.to.0.as_bytes());
+    bytes.extend_from_slice(&value);
+    bytes.extend_from_slice(&(tx.input.0.len() as u64).to_be_bytes());
+    bytes.extend_from_slice(&tx.input.0);
+    // The access list is backed by a hash map, so sort it to get the same
+    // hash for the same access list.
+    let mut access_list = web3::types::AccessList::from(tx.access_list.clone());
+    access_list.sort_by_key(|item| item.address);
+    for mut item in access_list {
+        item.storage_keys.sort();
+        bytes.extend_from_slice(item.address.as_bytes());
+        for key in item.storage_keys {
+            bytes.extend_from_slice(key.as_bytes());
+        }
+    }
+    ByteArray(web3::signing::keccak256(&bytes))
+}
+
+fn record_lookup(kind: &str, hit: bool) {
+    metrics::get()
+        .shared_cache_lookups
+        .with_label_values(&[kind, if hit { "hit" } else { "miss" }])
+        .inc();
+}
@@ -1,12 +1,16 @@
 use {
     crate::{
         domain::eth,
-        infra::blockchain::{self, Ethereum},
+        infra::{
+            SharedCache,
+            blockchain::{self, Ethereum},
+        },
     },
     observe::future::Measure,
 };
 
 pub mod enso;
+pub mod revm;
 pub mod tenderly;
 
 /// Ethereum transaction simulator.
@@ -18,6 +22,9 @@ pub struct Simulator {
     /// If this is [`Some`], every gas estimate will return this fixed
     /// gas value.
     disable_gas: Option<eth::Gas>,
+    /// If this is [`Some`], simulation results get shared with other drivers
+    /// using the same cache.
+    shared_cache: Option<SharedCache>,
 }
 
 /// Configuration of the transaction simulator.
@@ -25,6 +32,7 @@ pub struct Simulator {
 pub enum Config {
     Tenderly(tenderly::Config),
     Enso(enso::Config),
+    Revm(revm::Config),
 }
 
 impl Simulator {
@@ -36,6 +44,7 @@ impl Simulator {
             eth,
             disable_access_lists: false,
             disable_gas: None,
+            shared_cache: None,
         }
     }
 
@@ -47,6 +56,7 @@ impl Simulator {
             eth,
             disable_access_lists: false,
             disable_gas: None,
+            shared_cache: None,
         }
     }
 
@@ -63,6 +73,20 @@ impl Simulator {
             eth,
             disable_access_lists: false,
             disable_gas: None,
+            shared_cache: None,
+        }
+    }
+
+    /// Simulate transactions in-process with [revm](https://github.com/bluealloy/revm).
+    /// The required chain state is lazily fetched using the Ethereum RPC API.
+    pub fn revm(config: revm::Config, eth: Ethereum) -> Self {
+        let eth = eth.with_metric_label("revmSimulator".into());
+        Self {
+            inner: Inner::Revm(revm::Revm::new(config, eth.clone())),
+            eth,
+            disable_access_lists: false,
+            disable_gas: None,
+            shared_cache: None,
         }
     }
 
@@ -78,6 +102,12 @@ impl Simulator {
         self.disable_gas = Some(fixed_gas);
     }
 
+    /// Look up simulation results in a cache shared with other drivers before
+    /// simulating and share new results with them.
+    pub fn with_shared_cache(&mut self, cache: SharedCache) {
+        self.shared_cache = Some(cache);
+    }
+
     /// Simulate the access list needed by a transaction. If the transaction
     /// already has an access list, the returned access list will be a
     /// superset of the existing one.
@@ -86,6 +116,13 @@ impl Simulator {
             return Ok(tx.access_list.clone());
         }
         let block = self.eth.current_block().borrow().number.into();
+        let cached = match &self.shared_cache {
+            Some(cache) => cache.access_list(block, tx).await,
+            None => None,
+        };
+        if let Some(access_list) = cached {
+            return Ok(tx.access_list.clone().merge(access_list));
+        }
         let access_list = match &self.inner {
             Inner::Tenderly(tenderly) => {
                 tenderly
@@ -104,17 +141,81 @@ impl Simulator {
                 .create_access_list(tx.clone())
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Revm(revm) => {
+                revm.simulate(tx)
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .access_list
+            }
         };
+        if let Some(cache) = &self.shared_cache {
+            cache.store_access_list(block, tx, &access_list);
+        }
         Ok(tx.access_list.clone().merge(access_list))
     }
 
+    /// Simulate the access list needed by a transaction and the gas it needs
+    /// with that access list. Simulators that can produce both in a single
+    /// simulation only simulate the transaction once.
+    pub async fn simulate(&self, tx: &eth::Tx) -> Result<(eth::AccessList, eth::Gas), Error> {
+        if let Inner::Revm(revm) = &self.inner {
+            if !self.disable_access_lists && self.disable_gas.is_none() {
+                return self.simulate_revm(revm, tx).await;
+            }
+        }
+        let access_list = self.access_list(tx).await?;
+        let gas = self
+            .gas(&tx.clone().set_access_list(access_list.clone()))
+            .await?;
+        Ok((access_list, gas))
+    }
+
+    async fn simulate_revm(
+        &self,
+        revm: &revm::Revm,
+        tx: &eth::Tx,
+    ) -> Result<(eth::AccessList, eth::Gas), Error> {
+        let block = self.eth.current_block().borrow().number.into();
+        if let Some(cache) = &self.shared_cache {
+            if let Some(access_list) = cache.access_list(block, tx).await {
+                let access_list = tx.access_list.clone().merge(access_list);
+                let gas = self
+                    .gas(&tx.clone().set_access_list(access_list.clone()))
+                    .await?;
+                return Ok((access_list, gas));
+            }
+        }
+        let simulation = revm
+            .simulate(tx)
+            .measure("revm_simulate")
+            .await
+            .map_err(with(tx.clone(), block))?;
+        let access_list = tx.access_list.clone().merge(simulation.access_list.clone());
+        if let Some(cache) = &self.shared_cache {
+            cache.store_access_list(block, tx, &simulation.access_list);
+            cache.store_gas(
+                block,
+                &tx.clone().set_access_list(access_list.clone()),
+                simulation.gas,
+            );
+        }
+        Ok((access_list, simulation.gas))
+    }
+
     /// Simulate the gas needed by a transaction.
     pub async fn gas(&self, tx: &eth::Tx) -> Result<eth::Gas, Error> {
         if let Some(gas) = self.disable_gas {
             return Ok(gas);
         }
         let block = self.eth.current_block().borrow().number.into();
-        Ok(match &self.inner {
+        let cached = match &self.shared_cache {
+            Some(cache) => cache.gas(block, tx).await,
+            None => None,
+        };
+        if let Some(gas) = cached {
+            return Ok(gas);
+        }
+        let gas = match &self.inner {
             Inner::Tenderly(tenderly) => {
                 tenderly
                     .simulate(tx, tenderly::GenerateAccessList::No)
@@ -133,7 +234,18 @@ impl Simulator {
                 .measure("enso_simulate_gas")
                 .await
                 .map_err(with(tx.clone(), block))?,
-        })
+            Inner::Revm(revm) => {
+                revm.simulate(tx)
+                    .measure("revm_simulate_gas")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .gas
+            }
+        };
+        if let Some(cache) = &self.shared_cache {
+            cache.store_gas(block, tx, gas);
+        }
+        Ok(gas)
     }
 }
 
@@ -142,6 +254,7 @@ enum Inner {
     Tenderly(tenderly::Tenderly),
     Ethereum,
     Enso(enso::Enso),
+    Revm(revm::Revm),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -152,6 +265,8 @@ pub enum SimulatorError {
     Blockchain(#[from] blockchain::Error),
     #[error("enso error: {0:?}")]
     Enso(#[from] enso::Error),
+    #[error("revm error: {0:?}")]
+    Revm(#[from] revm::Error),
     #[error("the simulated gas {0} exceeded the gas limit {1} provided in the solution")]
     GasExceeded(eth::Gas, eth::Gas),
 }
@@ -194,6 +309,8 @@ where
             }
             SimulatorError::Enso(enso::Error::Http(_)) => None,
             SimulatorError::Enso(enso::Error::Revert(_)) => Some(tx),
+            SimulatorError::Revm(revm::Error::Revert(_)) => Some(tx),
+            SimulatorError::Revm(revm::Error::Rpc(_) | revm::Error::Other(_)) => None,
             SimulatorError::GasExceeded(..) => Some(tx),
         };
         match tx {
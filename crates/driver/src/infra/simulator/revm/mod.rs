@@ -0,0 +1,490 @@
+//! In-process transaction simulation on top of [revm](https://github.com/bluealloy/revm).
+//!
+//! Instead of sending every simulation to a node, transactions get executed
+//! locally against a fork of the latest block. The state of that fork is
+//! fetched lazily over the regular RPC transport and cached until the next
+//! block arrives, so simulating many similar transactions (e.g. competing
+//! solutions for the same auction) only fetches each account and storage slot
+//! once and the simulations can run in parallel.
+
+use {
+    crate::{domain::eth, infra::blockchain::Ethereum},
+    ::revm::{
+        DatabaseRef,
+        Evm,
+        precompile::{PrecompileSpecId, Precompiles},
+        primitives::{
+            AccessListItem,
+            AccountInfo,
+            Address,
+            B256,
+            Bytecode,
+            Bytes,
+            ExecutionResult,
+            SpecId,
+            TxKind,
+            U256,
+        },
+    },
+    chain::Chain,
+    ethcontract::dyns::DynWeb3,
+    ethrpc::block_stream::BlockInfo,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    thiserror::Error,
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// Selector of the `Error(string)` revert reason.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+#[derive(Debug, Clone)]
+pub(super) struct Revm {
+    eth: Ethereum,
+    network_block_interval: Option<Duration>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The time between new blocks in the network. Used to predict the
+    /// timestamp of the block the transaction gets simulated in. If this is
+    /// not set the timestamp of the latest block is used.
+    pub network_block_interval: Option<Duration>,
+}
+
+/// The result of simulating a transaction.
+pub(super) struct Simulation {
+    /// Gas used by the transaction as it was simulated, i.e. without
+    /// `access_list`. Since an access list only makes the accesses it covers
+    /// cheaper, this slightly overestimates the gas needed with it.
+    pub gas: eth::Gas,
+    /// Accounts and storage slots accessed by the transaction, excluding the
+    /// sender, the receiver and precompiles.
+    pub access_list: eth::AccessList,
+}
+
+impl Revm {
+    pub(super) fn new(config: Config, eth: Ethereum) -> Self {
+        Self {
+            eth,
+            network_block_interval: config.network_block_interval,
+            state: Default::default(),
+        }
+    }
+
+    pub(super) async fn simulate(&self, tx: &eth::Tx) -> Result<Simulation, Error> {
+        let block = *self.eth.current_block().borrow();
+        let fork = Fork {
+            web3: self.eth.web3().clone(),
+            block: block.number,
+            state: self.state.clone(),
+            runtime: tokio::runtime::Handle::current(),
+        };
+        fork.reset_if_outdated();
+
+        let timestamp = block.timestamp
+            + self
+                .network_block_interval
+                .map(|interval| interval.as_secs())
+                .unwrap_or_default();
+        let env = Env {
+            chain_id: self.eth.chain().id(),
+            spec: spec_id(self.eth.chain(), timestamp),
+            block,
+            timestamp,
+        };
+        let tx = tx.clone();
+        // The EVM runs synchronously and blocks on fetching missing state, so
+        // it must not run on the async executor.
+        tokio::task::spawn_blocking(move || execute(fork, env, tx))
+            .await
+            .map_err(|err| Error::Other(err.to_string()))?
+    }
+}
+
+/// Block environment the transaction gets simulated in.
+struct Env {
+    chain_id: u64,
+    spec: SpecId,
+    block: BlockInfo,
+    timestamp: u64,
+}
+
+/// Returns the hardfork the chain runs at the given timestamp. Only hardforks
+/// since Shanghai are distinguished since older blocks never get simulated.
+fn spec_id(chain: Chain, timestamp: u64) -> SpecId {
+    // Activation timestamps of Cancun and Prague.
+    let (cancun, prague) = match chain {
+        Chain::Mainnet => (1_710_338_135, Some(1_746_612_311)),
+        Chain::Sepolia => (1_706_655_072, Some(1_741_159_776)),
+        Chain::Goerli => (1_705_473_120, None),
+        Chain::Gnosis => (1_710_181_820, Some(1_746_021_820)),
+        // Rollups and sidechains adopted the Cancun EVM with their own
+        // upgrades and are simulated with it.
+        Chain::ArbitrumOne
+        | Chain::Base
+        | Chain::Optimism
+        | Chain::Polygon
+        | Chain::Bnb
+        | Chain::Avalanche
+        | Chain::Hardhat => (0, None),
+    };
+    match prague {
+        Some(prague) if timestamp >= prague => SpecId::PRAGUE,
+        _ if timestamp >= cancun => SpecId::CANCUN,
+        _ => SpecId::SHANGHAI,
+    }
+}
+
+fn execute<DB>(db: DB, env: Env, tx: eth::Tx) -> Result<Simulation, Error>
+where
+    DB: DatabaseRef<Error = Error>,
+{
+    let (from, to) = (address(tx.from.0), address(tx.to.0));
+    let access_list = web3::types::AccessList::from(tx.access_list)
+        .into_iter()
+        .map(|item| AccessListItem {
+            address: address(item.address),
+            storage_keys: item
+                .storage_keys
+                .into_iter()
+                .map(|key| B256::from(key.0))
+                .collect(),
+        })
+        .collect();
+
+    let mut evm = Evm::builder()
+        .with_ref_db(db)
+        .with_spec_id(env.spec)
+        .modify_cfg_env(|cfg| cfg.chain_id = env.chain_id)
+        .modify_block_env(|block| {
+            // Simulate in the block following the latest one, like `eth_call`
+            // on the pending block would.
+            block.number = U256::from(env.block.number + 1);
+            block.timestamp = U256::from(env.timestamp);
+            block.gas_limit = u256(env.block.gas_limit);
+            // The gas price is irrelevant for simulations, so skip the base
+            // fee and balance checks by not charging for gas at all.
+            block.basefee = U256::ZERO;
+            block.prevrandao = Some(B256::ZERO);
+        })
+        .modify_tx_env(|env_tx| {
+            env_tx.caller = from;
+            env_tx.transact_to = TxKind::Call(to);
+            env_tx.value = u256(tx.value.0);
+            env_tx.data = Bytes::from(tx.input.0);
+            env_tx.gas_limit = env.block.gas_limit.low_u64();
+            env_tx.gas_price = U256::ZERO;
+            env_tx.gas_priority_fee = None;
+            env_tx.nonce = None;
+            env_tx.access_list = access_list;
+        })
+        .build();
+    let result = evm
+        .transact()
+        .map_err(|err| Error::Other(err.to_string()))?;
+
+    let gas = match result.result {
+        ExecutionResult::Success { gas_used, .. } => gas_used.into(),
+        ExecutionResult::Revert { output, .. } => {
+            return Err(Error::Revert(revert_reason(&output)));
+        }
+        ExecutionResult::Halt { reason, .. } => return Err(Error::Revert(format!("{reason:?}"))),
+    };
+
+    // The sender, the receiver, the block's coinbase and created contracts
+    // are always warm.
+    let precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(env.spec));
+    let access_list: web3::types::AccessList = result
+        .state
+        .into_iter()
+        .filter(|(account, state)| {
+            ![from, to, Address::ZERO].contains(account)
+                && !precompiles.contains(account)
+                && !state.is_created()
+        })
+        .map(|(account, state)| web3::types::AccessListItem {
+            address: eth::H160(account.0.0),
+            storage_keys: state
+                .storage
+                .into_keys()
+                .map(|key| eth::H256(key.to_be_bytes()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(Simulation {
+        gas,
+        access_list: access_list.into(),
+    })
+}
+
+/// Decodes the reason of a reverted transaction. Reverts with an
+/// `Error(string)` get decoded into the message, custom errors are returned hex
+/// encoded.
+fn revert_reason(output: &[u8]) -> String {
+    output
+        .strip_prefix(&ERROR_SELECTOR)
+        .and_then(|data| ethabi::decode(&[ethabi::ParamType::String], data).ok())
+        .and_then(|tokens| tokens.into_iter().next()?.into_string())
+        .unwrap_or_else(|| format!("0x{}", hex::encode(output)))
+}
+
+/// State of the chain at a specific block which gets fetched from the node on
+/// demand.
+struct Fork {
+    web3: DynWeb3,
+    block: u64,
+    state: Arc<Mutex<State>>,
+    runtime: tokio::runtime::Handle,
+}
+
+/// Chain state fetched so far. Shared by all simulations on the same block.
+#[derive(Debug, Default)]
+struct State {
+    block: u64,
+    accounts: HashMap<Address, AccountInfo>,
+    storage: HashMap<(Address, U256), U256>,
+    block_hashes: HashMap<u64, B256>,
+}
+
+impl Fork {
+    /// Drops the cached state if it belongs to an older block.
+    fn reset_if_outdated(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.block < self.block {
+            *state = State {
+                block: self.block,
+                ..Default::default()
+            };
+        }
+    }
+
+    fn block_number(&self) -> BlockNumber {
+        BlockNumber::Number(self.block.into())
+    }
+
+    /// Only caches state of the block this fork is based on. A newer block
+    /// might have replaced the cache in the meantime.
+    fn cache(&self, update: impl FnOnce(&mut State)) {
+        let mut state = self.state.lock().unwrap();
+        if state.block == self.block {
+            update(&mut state);
+        }
+    }
+}
+
+impl DatabaseRef for Fork {
+    type Error = Error;
+
+    fn basic_ref(&self, account: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.state.lock().unwrap().accounts.get(&account) {
+            return Ok(Some(info.clone()));
+        }
+
+        let eth = self.web3.eth();
+        let (address, block) = (eth::H160(account.0.0), Some(self.block_number()));
+        let (balance, nonce, code) = self.runtime.block_on(async {
+            futures::try_join!(
+                eth.balance(address, block),
+                eth.transaction_count(address, block),
+                eth.code(address, block),
+            )
+        })?;
+        let code = Bytecode::new_raw(code.0.into());
+        let info = AccountInfo::new(u256(balance), nonce.low_u64(), code.hash_slow(), code);
+
+        self.cache(|state| {
+            state.accounts.insert(account, info.clone());
+        });
+        Ok(Some(info))
+    }
+
+    fn code_by_hash_ref(&self, hash: B256) -> Result<Bytecode, Self::Error> {
+        // The code is always fetched together with the account, so revm never
+        // needs to look it up by its hash.
+        Err(Error::Other(format!("code {hash} is not fetched by hash")))
+    }
+
+    fn storage_ref(&self, account: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.state.lock().unwrap().storage.get(&(account, index)) {
+            return Ok(*value);
+        }
+
+        let value = self.runtime.block_on(self.web3.eth().storage(
+            eth::H160(account.0.0),
+            eth::U256::from_big_endian(&index.to_be_bytes::<32>()),
+            Some(self.block_number()),
+        ))?;
+        let value = U256::from_be_bytes(value.0);
+
+        self.cache(|state| {
+            state.storage.insert((account, index), value);
+        });
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        if let Some(hash) = self.state.lock().unwrap().block_hashes.get(&number) {
+            return Ok(*hash);
+        }
+
+        let block = self.runtime.block_on(
+            self.web3
+                .eth()
+                .block(BlockId::Number(BlockNumber::Number(number.into()))),
+        )?;
+        let hash = block
+            .and_then(|block| block.hash)
+            .map(|hash| B256::from(hash.0))
+            .unwrap_or_default();
+
+        self.cache(|state| {
+            state.block_hashes.insert(number, hash);
+        });
+        Ok(hash)
+    }
+}
+
+fn address(address: eth::H160) -> Address {
+    Address::from(address.0)
+}
+
+fn u256(value: eth::U256) -> U256 {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    U256::from_be_bytes(bytes)
+}
+
+#[derive(Debug, Error)]
+#[error("revm tx simulation error")]
+pub enum Error {
+    Rpc(#[from] web3::Error),
+    Revert(String),
+    Other(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ::revm::db::{CacheDB, EmptyDBTyped},
+        std::time::Instant,
+    };
+
+    fn env() -> Env {
+        Env {
+            chain_id: 1,
+            spec: SpecId::CANCUN,
+            block: BlockInfo {
+                number: 1,
+                hash: Default::default(),
+                parent_hash: Default::default(),
+                timestamp: 1_710_338_135,
+                gas_limit: 30_000_000.into(),
+                gas_price: Default::default(),
+                observed_at: Instant::now(),
+            },
+            timestamp: 1_710_338_147,
+        }
+    }
+
+    fn tx(to: eth::H160) -> eth::Tx {
+        eth::Tx {
+            from: eth::H160([1; 20]).into(),
+            to: to.into(),
+            value: eth::U256::zero().into(),
+            input: Default::default(),
+            access_list: Default::default(),
+        }
+    }
+
+    /// Creates a database with the given contracts.
+    fn db(contracts: &[(eth::H160, &[u8])]) -> CacheDB<EmptyDBTyped<Error>> {
+        let mut db = CacheDB::new(EmptyDBTyped::default());
+        for (contract, code) in contracts {
+            db.insert_account_info(
+                address(*contract),
+                AccountInfo::from_bytecode(Bytecode::new_raw(code.to_vec().into())),
+            );
+        }
+        db
+    }
+
+    #[test]
+    fn simulates_gas_and_access_list() {
+        let (caller, callee) = (eth::H160([2; 20]), eth::H160([3; 20]));
+        // CALL(GAS, callee, 0, 0, 0, 0, 0) POP STOP
+        let caller_code = [
+            &[
+                0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73,
+            ][..],
+            callee.as_bytes(),
+            &[0x5a, 0xf1, 0x50, 0x00],
+        ]
+        .concat();
+        // SLOAD(7) POP STOP
+        let callee_code = [0x60, 0x07, 0x54, 0x50, 0x00];
+
+        let simulation = execute(
+            db(&[(caller, &caller_code), (callee, &callee_code)]),
+            env(),
+            tx(caller),
+        )
+        .unwrap();
+
+        // 21000 intrinsic gas, 2622 for the caller's code including the cold
+        // account access of the call and 2105 for the callee's code including
+        // the cold storage access.
+        assert_eq!(simulation.gas, 25_727.into());
+        let access_list = web3::types::AccessList::from(simulation.access_list);
+        assert_eq!(
+            access_list,
+            vec![web3::types::AccessListItem {
+                address: callee,
+                storage_keys: vec![eth::H256::from_low_u64_be(7)],
+            }]
+        );
+    }
+
+    #[test]
+    fn simulates_reverts() {
+        let contract = eth::H160([2; 20]);
+        // MSTORE(0, 0xdeadbeef) REVERT(28, 4)
+        let code = [
+            0x63, 0xde, 0xad, 0xbe, 0xef, 0x60, 0x00, 0x52, 0x60, 0x04, 0x60, 0x1c, 0xfd,
+        ];
+
+        let result = execute(db(&[(contract, &code)]), env(), tx(contract));
+
+        assert!(matches!(result, Err(Error::Revert(reason)) if reason == "0xdeadbeef"));
+    }
+
+    #[test]
+    fn derives_spec_from_chain() {
+        assert_eq!(spec_id(Chain::Mainnet, 1_710_338_134), SpecId::SHANGHAI);
+        assert_eq!(spec_id(Chain::Mainnet, 1_710_338_135), SpecId::CANCUN);
+        assert_eq!(spec_id(Chain::Mainnet, 1_746_612_311), SpecId::PRAGUE);
+        assert_eq!(spec_id(Chain::ArbitrumOne, 1_800_000_000), SpecId::CANCUN);
+    }
+
+    #[test]
+    fn decodes_revert_reasons() {
+        let output = [
+            &ERROR_SELECTOR[..],
+            &ethabi::encode(&[ethabi::Token::String(
+                "GPv2: limit price not respected".into(),
+            )]),
+        ]
+        .concat();
+        assert_eq!(revert_reason(&output), "GPv2: limit price not respected");
+
+        // Custom errors can't be decoded without the ABI.
+        assert_eq!(revert_reason(&[0xde, 0xad, 0xbe, 0xef]), "0xdeadbeef");
+        assert_eq!(revert_reason(&[]), "0x");
+    }
+}
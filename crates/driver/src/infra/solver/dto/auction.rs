@@ -48,9 +48,13 @@ pub fn new(
         .flat_map(|liquidity| match &liquidity.kind {
             liquidity::Kind::UniswapV2(pool) => pool.reserves.iter().map(|r| r.token).collect(),
             liquidity::Kind::UniswapV3(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
+            liquidity::Kind::UniswapV4(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
             liquidity::Kind::BalancerV2Stable(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::BalancerV2Weighted(pool) => pool.reserves.tokens().collect(),
+            liquidity::Kind::BalancerV3Stable(pool) => pool.reserves.tokens().collect(),
+            liquidity::Kind::BalancerV3Weighted(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::Swapr(pool) => pool.base.reserves.iter().map(|r| r.token).collect(),
+            liquidity::Kind::Curve(pool) => pool.tokens.clone(),
             liquidity::Kind::ZeroEx(limit_order) => {
                 vec![
                     limit_order.order.maker_token.into(),
@@ -206,6 +210,28 @@ pub fn new(
                                 .map(|(key, value)| (key.0, value.0))
                                 .collect(),
                             fee: rational_to_big_decimal(&pool.fee.0),
+                            pool_id: None,
+                        },
+                    )
+                }
+                liquidity::Kind::UniswapV4(pool) => {
+                    solvers_dto::auction::Liquidity::ConcentratedLiquidity(
+                        solvers_dto::auction::ConcentratedLiquidityPool {
+                            id: liquidity.id.0.to_string(),
+                            address: pool.pool_manager.0,
+                            router: pool.router.into(),
+                            gas_estimate: liquidity.gas.0,
+                            tokens: vec![pool.tokens.get().0.into(), pool.tokens.get().1.into()],
+                            sqrt_price: pool.sqrt_price.0,
+                            liquidity: pool.liquidity.0,
+                            tick: pool.tick.0,
+                            liquidity_net: pool
+                                .liquidity_net
+                                .iter()
+                                .map(|(key, value)| (key.0, value.0))
+                                .collect(),
+                            fee: rational_to_big_decimal(&pool.fee.0),
+                            pool_id: Some(pool.id.0),
                         },
                     )
                 }
@@ -268,6 +294,60 @@ pub fn new(
                         },
                     )
                 }
+                liquidity::Kind::BalancerV3Stable(pool) => {
+                    solvers_dto::auction::Liquidity::Stable(solvers_dto::auction::StablePool {
+                        id: liquidity.id.0.to_string(),
+                        address: pool.address.into(),
+                        balancer_pool_id: balancer_v3_pool_id(pool.address),
+                        gas_estimate: liquidity.gas.into(),
+                        tokens: pool
+                            .reserves
+                            .iter()
+                            .map(|r| {
+                                (
+                                    r.asset.token.into(),
+                                    solvers_dto::auction::StableReserve {
+                                        balance: r.asset.amount.into(),
+                                        scaling_factor: scaling_factor_to_decimal(r.scale),
+                                    },
+                                )
+                            })
+                            .collect(),
+                        amplification_parameter: rational_to_big_decimal(&num::BigRational::new(
+                            pool.amplification_parameter.factor().to_big_int(),
+                            pool.amplification_parameter.precision().to_big_int(),
+                        )),
+                        fee: fee_to_decimal(pool.fee),
+                    })
+                }
+                liquidity::Kind::BalancerV3Weighted(pool) => {
+                    solvers_dto::auction::Liquidity::WeightedProduct(
+                        solvers_dto::auction::WeightedProductPool {
+                            id: liquidity.id.0.to_string(),
+                            address: pool.address.into(),
+                            balancer_pool_id: balancer_v3_pool_id(pool.address),
+                            gas_estimate: liquidity.gas.into(),
+                            tokens: pool
+                                .reserves
+                                .iter()
+                                .map(|r| {
+                                    (
+                                        r.asset.token.into(),
+                                        solvers_dto::auction::WeightedProductReserve {
+                                            balance: r.asset.amount.into(),
+                                            scaling_factor: scaling_factor_to_decimal(r.scale),
+                                            weight: weight_to_decimal(r.weight),
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            fee: fee_to_decimal(pool.fee),
+                            // Balancer V3 weighted pools use the same math as
+                            // V2 weighted pools of version 3 and higher.
+                            version: solvers_dto::auction::WeightedProductVersion::V3Plus,
+                        },
+                    )
+                }
                 liquidity::Kind::Swapr(pool) => solvers_dto::auction::Liquidity::ConstantProduct(
                     solvers_dto::auction::ConstantProductPool {
                         id: liquidity.id.0.to_string(),
@@ -290,6 +370,41 @@ pub fn new(
                         fee: bigdecimal::BigDecimal::new(pool.fee.bps().into(), 4),
                     },
                 ),
+                liquidity::Kind::Curve(pool) => {
+                    solvers_dto::auction::Liquidity::Curve(solvers_dto::auction::CurvePool {
+                        id: liquidity.id.0.to_string(),
+                        address: pool.address.into(),
+                        gas_estimate: liquidity.gas.into(),
+                        tokens: pool.tokens.iter().copied().map(Into::into).collect(),
+                        balances: pool.balances.clone(),
+                        parameters: match &pool.parameters {
+                            liquidity::curve::Parameters::StableSwap(parameters) => {
+                                solvers_dto::auction::CurveParameters::StableSwap(
+                                    solvers_dto::auction::CurveStableSwapParameters {
+                                        amplification: parameters.amplification,
+                                        fee: parameters.fee,
+                                        offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                                        rates: parameters.rates.clone(),
+                                    },
+                                )
+                            }
+                            liquidity::curve::Parameters::CryptoSwap(parameters) => {
+                                solvers_dto::auction::CurveParameters::CryptoSwap(
+                                    solvers_dto::auction::CurveCryptoSwapParameters {
+                                        a: parameters.a,
+                                        gamma: parameters.gamma,
+                                        d: parameters.d,
+                                        price_scale: parameters.price_scale,
+                                        precisions: parameters.precisions,
+                                        mid_fee: parameters.mid_fee,
+                                        out_fee: parameters.out_fee,
+                                        fee_gamma: parameters.fee_gamma,
+                                    },
+                                )
+                            }
+                        },
+                    })
+                }
                 liquidity::Kind::ZeroEx(limit_order) => {
                     solvers_dto::auction::Liquidity::LimitOrder(
                         solvers_dto::auction::ForeignLimitOrder {
@@ -378,6 +493,14 @@ fn weight_to_decimal(weight: liquidity::balancer::v2::weighted::Weight) -> bigde
     bigdecimal::BigDecimal::new(weight.as_raw().to_big_int(), 18)
 }
 
+/// Balancer V3 pools are identified by their address, so the pool ID passed
+/// to solvers is the pool address followed by zeros.
+fn balancer_v3_pool_id(address: eth::ContractAddress) -> eth::H256 {
+    let mut id = eth::H256::zero();
+    id.0[..20].copy_from_slice(address.0.as_bytes());
+    id
+}
+
 fn scaling_factor_to_decimal(
     scale: liquidity::balancer::v2::ScalingFactor,
 ) -> bigdecimal::BigDecimal {
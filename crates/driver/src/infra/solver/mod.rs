@@ -109,6 +109,9 @@ pub struct Config {
     pub liquidity: Liquidity,
     /// The private key of this solver, used for settlement submission.
     pub account: ethcontract::Account,
+    /// Signs settlements on behalf of `account` if its key is held by a
+    /// remote signing service.
+    pub remote_signer: Option<infra::mempool::signer::Remote>,
     /// How much time to spend for each step of the solving and competition.
     pub timeouts: Timeouts,
     /// HTTP headers that should be added to every request.
@@ -130,6 +133,9 @@ pub struct Config {
     pub bad_token_detection: BadTokenDetection,
     /// Max size of the pending settlements queue.
     pub settle_queue_size: usize,
+    /// Whether the solver submits settlements combining the winning solutions
+    /// of multiple solvers.
+    pub accept_combined_settlements: bool,
     /// Whether flashloan hints should be sent to the solver.
     pub flashloans_enabled: bool,
     /// Defines at which block the liquidity needs to be fetched on /solve
@@ -195,6 +201,11 @@ impl Solver {
         self.config.account.clone()
     }
 
+    /// The remote service signing settlements for this solver, if any.
+    pub fn remote_signer(&self) -> Option<&infra::mempool::signer::Remote> {
+        self.config.remote_signer.as_ref()
+    }
+
     /// Timeout configuration for this solver.
     pub fn timeouts(&self) -> Timeouts {
         self.config.timeouts
@@ -221,6 +232,10 @@ impl Solver {
         self.config.settle_queue_size
     }
 
+    pub fn accepts_combined_settlements(&self) -> bool {
+        self.config.accept_combined_settlements
+    }
+
     pub fn fetch_liquidity_at_block(&self) -> infra::liquidity::AtBlock {
         self.config.fetch_liquidity_at_block.clone()
     }
@@ -60,6 +60,10 @@ async fn run_with(args: cli::Args, addr_sender: Option<oneshot::Sender<SocketAdd
 
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let eth = ethereum(&config, ethrpc).await;
+    let shared_cache = config
+        .shared_cache
+        .as_ref()
+        .map(|cache| infra::SharedCache::new(cache, &eth, config.simulation_bad_token_max_age));
     let app_data_retriever = match &config.app_data_fetching {
         config::file::AppDataFetching::Enabled {
             orderbook_url,
@@ -70,7 +74,7 @@ async fn run_with(args: cli::Args, addr_sender: Option<oneshot::Sender<SocketAdd
     let serve = Api {
         solvers: solvers(&config, &eth).await,
         liquidity: liquidity(&config, &eth).await,
-        simulator: simulator(&config, &eth),
+        simulator: simulator(&config, &eth, shared_cache.clone()),
         mempools: Mempools::try_new(
             config
                 .mempools
@@ -85,6 +89,7 @@ async fn run_with(args: cli::Args, addr_sender: Option<oneshot::Sender<SocketAdd
         bad_token_detector: bad_tokens::simulation::Detector::new(
             config.simulation_bad_token_max_age,
             &eth,
+            shared_cache,
         ),
         eth,
         addr: args.addr,
@@ -113,7 +118,11 @@ async fn run_with(args: cli::Args, addr_sender: Option<oneshot::Sender<SocketAdd
     };
 }
 
-fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
+fn simulator(
+    config: &infra::Config,
+    eth: &Ethereum,
+    shared_cache: Option<infra::SharedCache>,
+) -> Simulator {
     let mut simulator = match &config.simulator {
         Some(infra::simulator::Config::Tenderly(tenderly)) => Simulator::tenderly(
             simulator::tenderly::Config {
@@ -133,6 +142,12 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
             },
             eth.to_owned(),
         ),
+        Some(infra::simulator::Config::Revm(revm)) => Simulator::revm(
+            simulator::revm::Config {
+                network_block_interval: revm.network_block_interval,
+            },
+            eth.to_owned(),
+        ),
         None => Simulator::ethereum(eth.to_owned()),
     };
     if config.disable_access_list_simulation {
@@ -141,6 +156,9 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
     if let Some(gas) = config.disable_gas_simulation {
         simulator.disable_gas(gas)
     }
+    if let Some(cache) = shared_cache {
+        simulator.with_shared_cache(cache)
+    }
     simulator
 }
 
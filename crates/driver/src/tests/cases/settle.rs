@@ -289,3 +289,117 @@ async fn accepts_new_settle_requests_after_timeout() {
         .err()
         .kind("FailedToSubmit");
 }
+
+/// Checks that settlements submitted as bundles to a relay get included.
+#[tokio::test]
+#[ignore]
+async fn bundle_included() {
+    let test = tests::setup()
+        .name("bundle included")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle { include: true }])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
+/// Checks that bundles which never get included expire at the submission
+/// deadline without sending a cancellation transaction.
+#[tokio::test]
+#[ignore]
+async fn bundle_not_included() {
+    let test = tests::setup()
+        .name("bundle not included")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle { include: false }])
+        .done()
+        .await;
+
+    let solver = tests::setup::test_solver().address();
+    let nonce = test
+        .web3()
+        .eth()
+        .transaction_count(solver, None)
+        .await
+        .unwrap();
+
+    let id = test.solve().await.ok().id();
+    // Nothing gets mined by the relay so blocks have to be produced manually
+    // for the submission deadline to be reached.
+    let mine_blocks = async {
+        loop {
+            test.mine_block().await;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::select! {
+        settle = test.settle(id) => settle.err().kind("FailedToSubmit"),
+        _ = mine_blocks => unreachable!(),
+    }
+
+    // The nonce is still unused since no cancellation was needed.
+    assert_eq!(
+        test.web3()
+            .eth()
+            .transaction_count(solver, None)
+            .await
+            .unwrap(),
+        nonce
+    );
+}
+
+/// Checks that settlements signed by a remote signer get included.
+#[tokio::test]
+#[ignore]
+async fn remote_signer() {
+    let test = tests::setup()
+        .name("remote signer")
+        .solvers(vec![tests::setup::test_solver().remote_signer()])
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
+/// Checks that remotely signed settlements can be submitted as bundles.
+#[tokio::test]
+#[ignore]
+async fn remote_signer_bundle() {
+    let test = tests::setup()
+        .name("remote signer bundle")
+        .solvers(vec![tests::setup::test_solver().remote_signer()])
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle { include: true }])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
@@ -886,6 +886,14 @@ impl Blockchain {
             .await
             .unwrap();
     }
+
+    pub async fn mine_block(&self) {
+        self.web3
+            .transport()
+            .execute("evm_mine", vec![])
+            .await
+            .unwrap();
+    }
 }
 
 async fn primary_address(web3: &DynWeb3) -> ethcontract::H160 {
@@ -5,7 +5,7 @@ use {
         infra::config::file::OrderPriorityStrategy,
         tests::{
             hex_address,
-            setup::{blockchain::Trade, orderbook::Orderbook},
+            setup::{blockchain::Trade, orderbook::Orderbook, relay::Relay, signer::Signer},
         },
     },
     rand::seq::SliceRandom,
@@ -265,6 +265,22 @@ async fn create_config_file(
                 )
                 .unwrap();
             }
+            Mempool::Bundle { include } => {
+                // Without a configured signing key bundles get signed by the
+                // solver account.
+                let searcher = solvers[0].0.private_key.public_address();
+                let relay = Relay::start(searcher, include.then(|| blockchain.web3.clone()));
+                write!(
+                    file,
+                    r#"[[submission.mempool]]
+                    mempool = "bundle"
+                    additional-tip-percentage = 0.0
+                    url = "http://{}"
+                    "#,
+                    relay.addr,
+                )
+                .unwrap();
+            }
         }
     }
 
@@ -307,6 +323,16 @@ async fn create_config_file(
     }
 
     for (solver, addr) in solvers {
+        let account = if solver.remote_signer {
+            let signer = Signer::start(solver.private_key.clone(), blockchain.web3.clone());
+            format!(
+                r#"{{ url = "http://{}", address = "{}" }}"#,
+                signer.addr,
+                hex_address(solver.address()),
+            )
+        } else {
+            format!(r#""0x{}""#, hex::encode(solver.private_key.secret_bytes()))
+        };
         write!(
             file,
             r#"[[solver]]
@@ -314,7 +340,7 @@ async fn create_config_file(
                endpoint = "http://{}"
                absolute-slippage = "{}"
                relative-slippage = "{}"
-               account = "0x{}"
+               account = {}
                solving-share-of-deadline = {}
                http-time-buffer = "{}ms"
                fee-handler = {}
@@ -328,7 +354,7 @@ async fn create_config_file(
                 .map(|abs| abs.0)
                 .unwrap_or_default(),
             solver.slippage.relative,
-            hex::encode(solver.private_key.secret_bytes()),
+            account,
             solver.timeouts.solving_share_of_deadline.get(),
             solver.timeouts.http_delay.num_milliseconds(),
             serde_json::to_string(&solver.fee_handler).unwrap(),
@@ -59,6 +59,8 @@ pub mod blockchain;
 mod driver;
 pub mod fee;
 mod orderbook;
+mod relay;
+mod signer;
 mod solver;
 
 #[derive(Debug, Clone, Copy)]
@@ -357,6 +359,9 @@ pub struct Solver {
     /// Whether or not solver is allowed to combine multiple solutions into a
     /// new one.
     merge_solutions: bool,
+    /// Whether settlements get signed by a remote signer instead of the
+    /// driver itself.
+    remote_signer: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +389,7 @@ pub fn test_solver() -> Solver {
         },
         fee_handler: FeeHandler::default(),
         merge_solutions: false,
+        remote_signer: false,
     }
 }
 
@@ -422,6 +428,11 @@ impl Solver {
         self.merge_solutions = true;
         self
     }
+
+    pub fn remote_signer(mut self) -> Self {
+        self.remote_signer = true;
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -505,6 +516,12 @@ pub enum Mempool {
         /// Uses ethrpc node if None
         url: Option<String>,
     },
+    /// A mocked block builder relay accepting bundles.
+    Bundle {
+        /// Whether the relay gets the bundles included on chain or silently
+        /// drops them.
+        include: bool,
+    },
 }
 
 /// Create a builder for the setup process.
@@ -1212,6 +1229,11 @@ impl Test {
     pub async fn set_auto_mining(&self, enabled: bool) {
         self.blockchain.set_auto_mining(enabled).await
     }
+
+    /// Mines a new block regardless of pending transactions.
+    pub async fn mine_block(&self) {
+        self.blockchain.mine_block().await
+    }
 }
 
 /// A /solve response.
@@ -0,0 +1,84 @@
+use {
+    axum::{Extension, Json, Router, http::HeaderMap, routing::post},
+    ethcontract::{H160, dyns::DynWeb3},
+    serde_json::{Value, json},
+    std::net::SocketAddr,
+    web3::{Transport, signing},
+};
+
+/// A mocked block builder relay that accepts `eth_sendBundle` requests.
+pub struct Relay {
+    pub addr: SocketAddr,
+}
+
+impl Relay {
+    /// Starts the relay server listening on a random port. Bundles have to be
+    /// signed by the searcher. If a node is provided the transactions of
+    /// every bundle get forwarded to it so they get included on chain,
+    /// otherwise bundles are accepted but never included.
+    pub fn start(searcher: H160, node: Option<DynWeb3>) -> Self {
+        let app = Router::new()
+            .route("/", post(Self::send_bundle_handler))
+            .layer(Extension(node))
+            .layer(Extension(searcher));
+        let server =
+            axum::Server::bind(&"0.0.0.0:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+
+        tracing::info!("Relay mock server listening on {}", addr);
+
+        tokio::spawn(server);
+
+        Relay { addr }
+    }
+
+    async fn send_bundle_handler(
+        Extension(node): Extension<Option<DynWeb3>>,
+        Extension(searcher): Extension<H160>,
+        headers: HeaderMap,
+        body: String,
+    ) -> Json<Value> {
+        assert_eq!(signer(&headers, &body), searcher);
+        let request: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(request["method"], "eth_sendBundle");
+        let bundle = &request["params"][0];
+        assert!(bundle["blockNumber"].is_string());
+        if let Some(node) = node {
+            for tx in bundle["txs"].as_array().unwrap() {
+                // The same bundle gets sent for every block until it's
+                // included, so the node rejecting known txs is expected.
+                if let Err(err) = node
+                    .transport()
+                    .execute("eth_sendRawTransaction", vec![tx.clone()])
+                    .await
+                {
+                    tracing::debug!(?err, "failed to forward bundle tx");
+                }
+            }
+        }
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {
+                "bundleHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            },
+        }))
+    }
+}
+
+/// Recovers the signer of the request body from the `X-Flashbots-Signature`
+/// header and checks that it matches the address in the header.
+fn signer(headers: &HeaderMap, body: &str) -> H160 {
+    let header = headers["X-Flashbots-Signature"].to_str().unwrap();
+    let (address, signature) = header.split_once(':').unwrap();
+    let signature = hex::decode(signature.trim_start_matches("0x")).unwrap();
+    let message = format!("0x{}", hex::encode(signing::keccak256(body.as_bytes())));
+    let signer = signing::recover(
+        signing::hash_message(message).as_bytes(),
+        &signature[..64],
+        i32::from(signature[64]) - 27,
+    )
+    .unwrap();
+    assert_eq!(signer, address.parse::<H160>().unwrap());
+    signer
+}
@@ -0,0 +1,84 @@
+use {
+    axum::{Extension, Json, Router, routing::post},
+    ethcontract::{
+        Account,
+        GasPrice,
+        PrivateKey,
+        dyns::DynWeb3,
+        transaction::{Transaction, TransactionBuilder},
+    },
+    serde::de::DeserializeOwned,
+    serde_json::{Value, json},
+    std::net::SocketAddr,
+};
+
+/// A stub remote signer that signs `eth_signTransaction` requests with a
+/// local private key.
+pub struct Signer {
+    pub addr: SocketAddr,
+}
+
+#[derive(Clone)]
+struct State {
+    key: PrivateKey,
+    web3: DynWeb3,
+}
+
+impl Signer {
+    /// Starts the signer server listening on a random port.
+    pub fn start(key: PrivateKey, web3: DynWeb3) -> Self {
+        let app = Router::new()
+            .route("/", post(Self::sign_transaction_handler))
+            .layer(Extension(State { key, web3 }));
+        let server =
+            axum::Server::bind(&"0.0.0.0:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+
+        tracing::info!("Signer mock server listening on {}", addr);
+
+        tokio::spawn(server);
+
+        Signer { addr }
+    }
+
+    async fn sign_transaction_handler(
+        Extension(state): Extension<State>,
+        Json(request): Json<Value>,
+    ) -> Json<Value> {
+        assert_eq!(request["method"], "eth_signTransaction");
+        let tx = &request["params"][0];
+        let from: ethcontract::H160 = field(tx, "from");
+        assert_eq!(from, state.key.public_address());
+        let chain_id: ethcontract::U256 = field(tx, "chainId");
+
+        // All fields are set so building the transaction doesn't need to
+        // query the node.
+        let signed = TransactionBuilder::new(state.web3)
+            .from(Account::Offline(state.key, Some(chain_id.as_u64())))
+            .to(field(tx, "to"))
+            .gas(field(tx, "gas"))
+            .gas_price(GasPrice::Eip1559 {
+                max_fee_per_gas: field(tx, "maxFeePerGas"),
+                max_priority_fee_per_gas: field(tx, "maxPriorityFeePerGas"),
+            })
+            .value(field(tx, "value"))
+            .data(field(tx, "data"))
+            .nonce(field(tx, "nonce"))
+            .access_list(field(tx, "accessList"))
+            .build()
+            .await
+            .unwrap();
+        let Transaction::Raw { bytes, .. } = signed else {
+            panic!("offline account must produce a raw transaction");
+        };
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": bytes,
+        }))
+    }
+}
+
+fn field<T: DeserializeOwned>(tx: &Value, name: &str) -> T {
+    serde_json::from_value(tx[name].clone()).unwrap()
+}
@@ -12,6 +12,7 @@ use {
     serde_json::json,
     shared::{
         addr,
+        bad_token::list_based::ListBasedDetector,
         price_estimation::{
             Estimate,
             Verification,
@@ -141,6 +142,7 @@ async fn test_bypass_verification_for_rfq_quotes(web3: Web3) {
         onchain.contracts().gp_settlement.address(),
         onchain.contracts().weth.address(),
         BigDecimal::zero(),
+        Arc::new(ListBasedDetector::deny_list(Vec::new())),
     )
     .await
     .unwrap();
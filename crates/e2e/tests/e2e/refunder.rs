@@ -6,7 +6,10 @@ use {
     ethrpc::{Web3, block_stream::timestamp_of_current_block_in_seconds},
     model::quote::{OrderQuoteRequest, OrderQuoteSide, QuoteSigningScheme, Validity},
     number::nonzero::U256 as NonZeroU256,
-    refunder::refund_service::RefundService,
+    refunder::{
+        refund_service::{Limits, RefundService},
+        submitter::{self, Submitter},
+    },
     sqlx::PgPool,
 };
 
@@ -121,13 +124,29 @@ async fn refunder_tx(web3: Web3) {
 
     // Create the refund service and execute the refund tx
     let pg_pool = PgPool::connect_lazy("postgresql://").expect("failed to create database");
+    let submitter = Submitter::new(
+        web3.clone(),
+        web3.clone(),
+        pg_pool.clone(),
+        submitter::Config {
+            max_gas_price: 800_000_000_000f64,
+            gas_price_buffer_factor: 1.3,
+            start_priority_fee_tip: 2_000_000_000f64,
+            confirmation_block_timeout: 5,
+        },
+        refunder.account().clone(),
+    );
     let mut refunder = RefundService::new(
         pg_pool,
         web3,
         vec![ethflow_contract.clone(), ethflow_contract_2.clone()],
         validity_duration as i64 / 2,
         10i64,
-        refunder.account().clone(),
+        Limits {
+            max_orders_per_refund_tx: 30,
+            daily_spend_limit: None,
+        },
+        submitter,
     );
 
     assert_ne!(
@@ -514,7 +514,8 @@ async fn store_filtered_solutions(web3: Web3) {
                 "--price-estimation-drivers=test_solver|http://localhost:11088/test_solver"
                     .to_string(),
                 "--max-winners-per-auction=10".to_string(),
-                "--combinatorial-auctions-cutover=1970-03-27T15:04:50.410Z".to_string()
+                "--combinatorial-auctions-cutover=1970-03-27T15:04:50.410Z".to_string(),
+                "--shadow-arbitrators=max-score".to_string(),
             ],
         )
         .await;
@@ -683,4 +684,20 @@ async fn store_filtered_solutions(web3: Web3) {
             && s.is_winner
             && s.solver.0 == good_solver_account.address().0)
     );
+
+    // the max score mechanism running in shadow mode doesn't filter out the bad
+    // solution and ranks it next to the actual solutions
+    let shadow_solutions =
+        database::solver_competition_v2::fetch_shadow_solutions(&mut db, competition.auction_id)
+            .await
+            .unwrap();
+    assert_eq!(shadow_solutions.len(), 2);
+    assert!(shadow_solutions.iter().all(|s| {
+        s.arbitrator == "max_score"
+            && !s.filtered_out
+            && solutions
+                .iter()
+                .any(|solution| solution.uid == s.solution_uid)
+    }));
+    assert_eq!(shadow_solutions.iter().filter(|s| s.is_winner).count(), 1);
 }
@@ -58,4 +58,7 @@ pub struct ExecutedProtocolFee {
     #[serde_as(as = "HexOrDecimalU256")]
     pub amount: U256,
     pub token: H160,
+    /// Name of the protocol's fee tier the policy was created by, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
 }
@@ -1,6 +1,8 @@
 //! Contains the order type as described by the specification with serialization
 //! as described by the openapi documentation.
 
+/// Order conditions are part of the signed app data.
+pub use app_data::OrderCondition;
 use {
     crate::{
         DomainSeparator,
@@ -400,6 +402,39 @@ impl OrderCreation {
     }
 }
 
+/// Why an order did not get included in an auction.
+#[serde_as]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum OrderFilterReason {
+    /// The owner of the order is not allowed to trade.
+    BannedUser,
+    /// The EIP-1271 signature of the order is not valid (anymore).
+    InvalidSignature,
+    /// The order trades a token that is not supported.
+    UnsupportedToken,
+    /// The owner does not have enough sell token balance or allowance to
+    /// trade the order. `balance` is missing if it could not be fetched. Since
+    /// order events only get stored when the kind of reason changes, the
+    /// persisted `balance` is the one the order first got filtered with.
+    #[serde(rename_all = "camelCase")]
+    InsufficientBalance {
+        #[serde_as(as = "Option<HexOrDecimalU256>")]
+        balance: Option<U256>,
+        #[serde_as(as = "HexOrDecimalU256")]
+        required: U256,
+    },
+    /// The order is partially fillable but the owner's balance only allows
+    /// trading a dust amount.
+    DustOrder,
+    /// No native price could be computed for one of the order's tokens.
+    MissingNativePrice,
+    /// The limit price of the order is too far from the market price.
+    OutOfMarket,
+    /// The order got filtered for another reason.
+    Other,
+}
+
 // Note that the order of the variants is important for deserialization.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(untagged)]
@@ -696,6 +731,8 @@ pub struct OrderMetadata {
     /// quote data for reference.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub quote: Option<OrderQuote>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<OrderCondition>,
 }
 
 // uid as 56 bytes: 32 for orderDigest, 20 for ownerAddress and 4 for validTo
@@ -1164,6 +1201,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn order_condition_serialization() {
+        for (condition, json) in [
+            (
+                OrderCondition::Twap {
+                    parts: 4,
+                    start_time: 1_700_000_000,
+                    part_duration: 3600,
+                },
+                json!({
+                    "kind": "twap",
+                    "parts": 4,
+                    "startTime": 1_700_000_000,
+                    "partDuration": 3600,
+                }),
+            ),
+            (
+                OrderCondition::StopLoss {
+                    strike_price: 1_500_000_000_000_000_000u128.into(),
+                },
+                json!({
+                    "kind": "stopLoss",
+                    "strikePrice": "1500000000000000000",
+                }),
+            ),
+        ] {
+            assert_json_matches!(json!(condition), json);
+            assert_eq!(
+                serde_json::from_value::<OrderCondition>(json).unwrap(),
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn order_filter_reason_serialization() {
+        for (reason, json) in [
+            (
+                OrderFilterReason::InsufficientBalance {
+                    balance: Some(1.into()),
+                    required: 100.into(),
+                },
+                json!({
+                    "reason": "insufficientBalance",
+                    "balance": "1",
+                    "required": "100",
+                }),
+            ),
+            (
+                OrderFilterReason::MissingNativePrice,
+                json!({ "reason": "missingNativePrice" }),
+            ),
+        ] {
+            assert_json_matches!(json!(reason), json);
+            assert_eq!(
+                serde_json::from_value::<OrderFilterReason>(json).unwrap(),
+                reason
+            );
+        }
+    }
+
     #[test]
     fn order_creation_app_data() {
         #[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
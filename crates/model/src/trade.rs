@@ -74,7 +74,8 @@ mod tests {
                         "volume": {
                             "factor": 0.9
                         }
-                    }
+                    },
+                    "tier": "stable"
                 },
                 {
                     "amount": "5",
@@ -112,11 +113,13 @@ mod tests {
                         factor: 1.1,
                         max_volume_factor: 2.2,
                     },
+                    tier: None,
                 },
                 ExecutedProtocolFee {
                     amount: U256::from(5u64),
                     token: H160::from_low_u64_be(10),
                     policy: FeePolicy::Volume { factor: 0.9 },
+                    tier: Some("stable".to_owned()),
                 },
                 ExecutedProtocolFee {
                     amount: U256::from(5u64),
@@ -130,6 +133,7 @@ mod tests {
                             fee: U256::from(5u64),
                         },
                     },
+                    tier: None,
                 },
             ],
         };
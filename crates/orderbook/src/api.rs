@@ -1,12 +1,22 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    crate::{
+        app_data,
+        arguments::EndpointRateLimit,
+        database::Postgres,
+        order_events::OrderEventStream,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+    },
     anyhow::Result,
     observe::distributed_tracing::tracing_warp::make_span,
+    rate_limit::token_bucket::{Permit, Throttled, TokenBucketLimiter},
     serde::{Serialize, de::DeserializeOwned},
     shared::price_estimation::{PriceEstimationError, native::NativePriceEstimating},
     std::{
+        collections::HashSet,
         convert::Infallible,
         fmt::Debug,
+        net::SocketAddr,
         sync::Arc,
         time::{Duration, Instant},
     },
@@ -15,8 +25,9 @@ use {
         Rejection,
         Reply,
         filters::BoxedFilter,
+        http::header::RETRY_AFTER,
         hyper::StatusCode,
-        reply::{Json, WithStatus, json, with_status},
+        reply::{Json, WithStatus, json, with_header, with_status},
     },
 };
 
@@ -26,19 +37,27 @@ mod get_app_data;
 mod get_auction;
 mod get_native_price;
 mod get_order_by_uid;
+mod get_order_events;
 mod get_order_status;
+mod get_orderbook_depth;
 mod get_orders_by_tx;
 mod get_solver_competition;
 mod get_solver_competition_v2;
 mod get_token_metadata;
 mod get_total_surplus;
 mod get_trades;
+mod get_trades_v2;
 mod get_user_orders;
+mod get_user_orders_v2;
 mod post_order;
+mod post_orders;
 mod post_quote;
+mod post_quote_stream;
 mod put_app_data;
+mod request_ethflow_refund;
 mod version;
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_all_routes(
     database: Postgres,
     orderbook: Arc<Orderbook>,
@@ -46,6 +65,10 @@ pub fn handle_all_routes(
     app_data: Arc<app_data::Registry>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
     quote_timeout: Duration,
+    order_events: Arc<OrderEventStream>,
+    rate_limits: &[EndpointRateLimit],
+    api_keys: &[String],
+    behind_trusted_proxy: bool,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
@@ -56,6 +79,10 @@ pub fn handle_all_routes(
             "v1/create_order",
             box_filter(post_order::post_order(orderbook.clone())),
         ),
+        (
+            "v1/create_orders",
+            box_filter(post_orders::post_orders(orderbook.clone())),
+        ),
         (
             "v1/get_order",
             box_filter(get_order_by_uid::get_order_by_uid(orderbook.clone())),
@@ -64,10 +91,21 @@ pub fn handle_all_routes(
             "v1/get_order_status",
             box_filter(get_order_status::get_status(orderbook.clone())),
         ),
+        (
+            "v1/get_order_events",
+            box_filter(get_order_events::get_order_events(
+                orderbook.clone(),
+                order_events,
+            )),
+        ),
         (
             "v1/get_trades",
             box_filter(get_trades::get_trades(database.clone())),
         ),
+        (
+            "v2/get_trades",
+            box_filter(get_trades_v2::get_trades(database.clone())),
+        ),
         (
             "v1/cancel_order",
             box_filter(cancel_order::cancel_order(orderbook.clone())),
@@ -76,15 +114,36 @@ pub fn handle_all_routes(
             "v1/cancel_orders",
             box_filter(cancel_orders::filter(orderbook.clone())),
         ),
+        (
+            "v1/request_ethflow_refund",
+            box_filter(request_ethflow_refund::request_ethflow_refund(
+                orderbook.clone(),
+            )),
+        ),
         (
             "v1/get_user_orders",
             box_filter(get_user_orders::get_user_orders(orderbook.clone())),
         ),
+        (
+            "v2/get_user_orders",
+            box_filter(get_user_orders_v2::get_user_orders(orderbook.clone())),
+        ),
+        (
+            "v1/get_orderbook_depth",
+            box_filter(get_orderbook_depth::get_orderbook_depth(orderbook.clone())),
+        ),
         (
             "v1/get_orders_by_tx",
             box_filter(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
         ),
-        ("v1/post_quote", box_filter(post_quote::post_quote(quotes))),
+        (
+            "v1/post_quote",
+            box_filter(post_quote::post_quote(quotes.clone())),
+        ),
+        (
+            "v1/post_quote_stream",
+            box_filter(post_quote_stream::post_quote_stream(quotes)),
+        ),
         (
             "v1/auction",
             box_filter(get_auction::get_auction(orderbook.clone())),
@@ -133,7 +192,124 @@ pub fn handle_all_routes(
         ),
     ];
 
-    finalize_router(routes, "orderbook::api::request_summary")
+    finalize_router(
+        apply_rate_limits(routes, rate_limits, api_keys, behind_trusted_proxy),
+        "orderbook::api::request_summary",
+    )
+}
+
+/// Header identifying API clients for the purpose of rate limiting.
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Wraps the routes with a configured rate limit in a per client token bucket
+/// limiter.
+///
+/// # Panics
+///
+/// This method panics if a rate limit is configured for an unknown endpoint.
+fn apply_rate_limits(
+    routes: Vec<(&'static str, BoxedRoute)>,
+    rate_limits: &[EndpointRateLimit],
+    api_keys: &[String],
+    behind_trusted_proxy: bool,
+) -> Vec<(&'static str, BoxedRoute)> {
+    for rate_limit in rate_limits {
+        assert!(
+            routes.iter().any(|(name, _)| *name == rate_limit.endpoint),
+            "rate limit configured for unknown endpoint {}",
+            rate_limit.endpoint
+        );
+    }
+
+    let api_keys: Arc<HashSet<String>> = Arc::new(api_keys.iter().cloned().collect());
+    routes
+        .into_iter()
+        .map(|(name, route)| {
+            let Some(rate_limit) = rate_limits.iter().find(|limit| limit.endpoint == name) else {
+                return (name, route);
+            };
+            let limiter = Arc::new(TokenBucketLimiter::new(rate_limit.limit, name.to_string()));
+            let client_key = client_key(api_keys.clone(), behind_trusted_proxy);
+            (name, rate_limited(route, limiter, client_key.boxed()))
+        })
+        .collect()
+}
+
+/// Takes a token from the client's bucket before handing the request to the
+/// route.
+///
+/// Since warp tries all routes in order until one of them matches, every
+/// request passes the limiters of the routes before the one handling it. The
+/// token is therefore only consumed if the route actually handles the request
+/// and gets returned to the bucket otherwise.
+fn rate_limited(
+    route: BoxedRoute,
+    limiter: Arc<TokenBucketLimiter>,
+    client_key: BoxedFilter<(String,)>,
+) -> BoxedRoute {
+    client_key
+        .and_then(move |key: String| {
+            let result = limiter
+                .try_acquire(key)
+                .map_err(|throttled| warp::reject::custom(RateLimited(throttled)));
+            async move { result }
+        })
+        .and(route)
+        .map(|permit: Permit, reply: Box<dyn Reply>| {
+            permit.commit();
+            reply
+        })
+        .boxed()
+}
+
+/// Identifies the client of a request by its API key. Requests without a known
+/// API key are identified by their remote address or, behind a trusted proxy,
+/// by the IP address the proxy received them from. Unknown keys and, without a
+/// trusted proxy, the `X-Forwarded-For` header are ignored since clients could
+/// otherwise get a fresh bucket for every request by sending random values.
+fn client_key(
+    api_keys: Arc<HashSet<String>>,
+    behind_trusted_proxy: bool,
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(API_KEY_HEADER)
+        .and(warp::header::optional::<String>("X-Forwarded-For"))
+        .and(warp::addr::remote())
+        .map(
+            move |api_key: Option<String>,
+                  forwarded_for: Option<String>,
+                  remote: Option<SocketAddr>| {
+                if let Some(api_key) = api_key.filter(|key| api_keys.contains(key)) {
+                    return format!("key:{api_key}");
+                }
+                let ip = forwarded_for
+                    .as_deref()
+                    .filter(|_| behind_trusted_proxy)
+                    .and_then(|addresses| addresses.rsplit(',').next())
+                    .map(|ip| ip.trim().to_string())
+                    .or_else(|| remote.map(|addr| addr.ip().to_string()))
+                    .unwrap_or_default();
+                format!("ip:{ip}")
+            },
+        )
+}
+
+#[derive(Debug)]
+struct RateLimited(Throttled);
+
+impl warp::reject::Reject for RateLimited {}
+
+fn too_many_requests(throttled: &Throttled) -> warp::reply::Response {
+    // `Retry-After` only supports whole seconds.
+    let retry_after = (throttled.retry_after.as_secs_f64().ceil() as u64).max(1);
+    with_header(
+        with_status(
+            error("TooManyRequests", "rate limit exceeded"),
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        RETRY_AFTER,
+        retry_after.to_string(),
+    )
+    .into_response()
 }
 
 pub type ApiReply = WithStatus<Json>;
@@ -141,7 +317,13 @@ pub type ApiReply = WithStatus<Json>;
 // We turn Rejection into Reply to workaround warp not setting CORS headers on
 // rejections.
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    let response = err.default_response();
+    let response = match err.find::<RateLimited>() {
+        Some(RateLimited(throttled)) => {
+            throttled.record();
+            too_many_requests(throttled)
+        }
+        None => err.default_response(),
+    };
 
     let metrics = ApiMetrics::instance(observe::metrics::get_storage_registry()).unwrap();
     metrics
@@ -178,6 +360,7 @@ impl ApiMetrics {
         StatusCode::UNAUTHORIZED,
         StatusCode::FORBIDDEN,
         StatusCode::NOT_FOUND,
+        StatusCode::TOO_MANY_REQUESTS,
         StatusCode::INTERNAL_SERVER_ERROR,
         StatusCode::SERVICE_UNAVAILABLE,
     ];
@@ -341,7 +524,13 @@ pub fn finalize_router(
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS", "PUT", "PATCH"])
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec![
+            "Origin",
+            "Content-Type",
+            "X-Auth-Token",
+            "X-AppId",
+            API_KEY_HEADER,
+        ]);
 
     warp::path!("api" / ..)
         .and(instrumented)
@@ -441,4 +630,70 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    async fn rate_limits_clients_per_endpoint() {
+        let reply = || with_status(json(&()), StatusCode::OK);
+        let routes = vec![
+            ("v1/foo", box_filter(warp::path!("v1" / "foo").map(reply))),
+            ("v1/bar", box_filter(warp::path!("v1" / "bar").map(reply))),
+        ];
+        let rate_limits = [EndpointRateLimit {
+            endpoint: "v1/foo".to_string(),
+            limit: "1/1h".parse().unwrap(),
+        }];
+        let api_keys = ["a".to_string(), "b".to_string()];
+        let router = finalize_router(
+            apply_rate_limits(routes, &rate_limits, &api_keys, false),
+            "test",
+        );
+        let request = |path: &str, key: &str| {
+            warp::test::request()
+                .path(path)
+                .header(API_KEY_HEADER, key)
+                .reply(&router)
+        };
+
+        // Requests handled by other routes don't consume tokens.
+        assert_eq!(request("/api/v1/bar", "a").await.status(), StatusCode::OK);
+        assert_eq!(request("/api/v1/foo", "a").await.status(), StatusCode::OK);
+        assert_eq!(request("/api/v1/bar", "a").await.status(), StatusCode::OK);
+
+        let throttled = request("/api/v1/foo", "a").await;
+        assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(throttled.headers()[RETRY_AFTER], "3600");
+
+        assert_eq!(request("/api/v1/foo", "b").await.status(), StatusCode::OK);
+
+        // Unknown keys share the bucket of their IP address.
+        assert_eq!(request("/api/v1/foo", "c").await.status(), StatusCode::OK);
+        assert_eq!(
+            request("/api/v1/foo", "d").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[tokio::test]
+    async fn identifies_clients_by_forwarded_for_only_behind_trusted_proxy() {
+        let key = |behind_trusted_proxy: bool, forwarded_for: &str| {
+            warp::test::request()
+                .remote_addr("1.1.1.1:1234".parse().unwrap())
+                .header("X-Forwarded-For", forwarded_for)
+                .filter(&client_key(Default::default(), behind_trusted_proxy))
+        };
+
+        assert_eq!(key(false, "2.2.2.2").await.unwrap(), "ip:1.1.1.1");
+        assert_eq!(key(false, "3.3.3.3").await.unwrap(), "ip:1.1.1.1");
+        assert_eq!(key(true, "2.2.2.2, 3.3.3.3").await.unwrap(), "ip:3.3.3.3");
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_rate_limits_of_unknown_endpoints() {
+        let rate_limits = [EndpointRateLimit {
+            endpoint: "v1/foo".to_string(),
+            limit: "1/1s".parse().unwrap(),
+        }];
+        apply_rate_limits(vec![], &rate_limits, &[], false);
+    }
 }
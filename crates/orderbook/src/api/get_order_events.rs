@@ -0,0 +1,224 @@
+use {
+    crate::{
+        api::error,
+        dto,
+        order_events::{OrderEvent, OrderEventStream},
+        orderbook::Orderbook,
+    },
+    futures::Stream,
+    model::order::OrderUid,
+    primitive_types::H160,
+    serde::{Deserialize, Serialize},
+    std::{collections::HashSet, convert::Infallible, sync::Arc},
+    tokio::sync::broadcast::{Receiver, error::RecvError},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        hyper::StatusCode,
+        reply::with_status,
+        sse::{self, Event},
+    },
+};
+
+/// Maximum number of order UIDs a single client can subscribe to.
+const MAX_UIDS: usize = 128;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    owner: Option<H160>,
+    /// Comma separated list of order UIDs.
+    uids: Option<String>,
+}
+
+/// Selects which orders a client wants to receive events for.
+#[derive(Debug, Eq, PartialEq)]
+struct Subscription {
+    owner: Option<H160>,
+    uids: HashSet<OrderUid>,
+}
+
+impl Subscription {
+    fn matches(&self, uid: &OrderUid) -> bool {
+        self.uids.contains(uid) || self.owner.is_some_and(|owner| uid.parts().1 == owner)
+    }
+}
+
+impl Query {
+    fn validate(self) -> Result<Subscription, String> {
+        let uids = self
+            .uids
+            .iter()
+            .flat_map(|uids| uids.split(','))
+            .map(|uid| uid.parse().map_err(|_| format!("invalid order UID {uid}")))
+            .collect::<Result<HashSet<_>, _>>()?;
+        if uids.len() > MAX_UIDS {
+            return Err(format!(
+                "at most {MAX_UIDS} order UIDs can be subscribed to"
+            ));
+        }
+        if self.owner.is_none() && uids.is_empty() {
+            return Err("must specify an owner or at least one order UID".to_owned());
+        }
+        Ok(Subscription {
+            owner: self.owner,
+            uids,
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventData {
+    uid: OrderUid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<dto::order::Status>,
+}
+
+fn request() -> impl Filter<Extract = (Result<Subscription, String>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "events")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(Query::validate)
+}
+
+pub fn get_order_events(
+    orderbook: Arc<Orderbook>,
+    events: Arc<OrderEventStream>,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    request().map(move |subscription: Result<Subscription, String>| {
+        let subscription = match subscription {
+            Ok(subscription) => subscription,
+            Err(msg) => {
+                let err = error("InvalidSubscription", msg);
+                return Box::new(with_status(err, StatusCode::BAD_REQUEST)) as Box<dyn Reply>;
+            }
+        };
+        let stream = event_stream(events.subscribe(), subscription, orderbook.clone());
+        Box::new(sse::reply(sse::keep_alive().stream(stream)))
+    })
+}
+
+/// Turns the events of all orders into a stream of server-sent events for the
+/// orders matching the subscription. Each event carries the order's status as
+/// it would be reported by `v1/get_order_status` at the time of the event.
+fn event_stream(
+    receiver: Receiver<OrderEvent>,
+    subscription: Subscription,
+    orderbook: Arc<Orderbook>,
+) -> impl Stream<Item = Result<Event, Infallible>> + Send + 'static {
+    futures::stream::unfold(
+        (receiver, subscription, orderbook),
+        |(mut receiver, subscription, orderbook)| async move {
+            let event = loop {
+                match receiver.recv().await {
+                    Ok(event) if subscription.matches(&event.uid) => break event,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "order event subscriber lagging behind");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            };
+            let status = match orderbook.get_order_status(&event.uid).await {
+                Ok(status) => Some(status),
+                Err(err) => {
+                    tracing::warn!(?err, uid = %event.uid, "failed to fetch order status");
+                    None
+                }
+            };
+            let data = EventData {
+                uid: event.uid,
+                status,
+            };
+            let sse_event = Event::default()
+                .event(event.label.to_string())
+                .json_data(&data)
+                .unwrap_or_else(|err| {
+                    tracing::error!(?err, "failed to serialize order event");
+                    Event::default().event(event.label.to_string())
+                });
+            Some((Ok(sse_event), (receiver, subscription, orderbook)))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::addr, warp::test::request as test_request};
+
+    async fn subscription(path: &str) -> Result<Subscription, String> {
+        test_request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn request_ok() {
+        let owner = addr!("0000000000000000000000000000000000000001");
+        let result = subscription(&format!("/v1/orders/events?owner=0x{owner:x}"))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            Subscription {
+                owner: Some(owner),
+                uids: Default::default(),
+            }
+        );
+
+        let uids = [OrderUid([1; 56]), OrderUid([2; 56])];
+        let result = subscription(&format!("/v1/orders/events?uids={},{}", uids[0], uids[1]))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            Subscription {
+                owner: None,
+                uids: uids.into_iter().collect(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn request_err() {
+        assert!(subscription("/v1/orders/events").await.is_err());
+        assert!(subscription("/v1/orders/events?uids=0x01").await.is_err());
+
+        let uids = (0..=MAX_UIDS as u32)
+            .map(|i| OrderUid::from_integer(i).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(
+            subscription(&format!("/v1/orders/events?uids={uids}"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn subscription_matches_owner_and_uids() {
+        let owner = H160([1; 20]);
+        let own_order = OrderUid::from_parts(Default::default(), owner, 0);
+        let other_order = OrderUid::from_parts(Default::default(), H160([2; 20]), 0);
+
+        let subscription = Subscription {
+            owner: Some(owner),
+            uids: Default::default(),
+        };
+        assert!(subscription.matches(&own_order));
+        assert!(!subscription.matches(&other_order));
+
+        let subscription = Subscription {
+            owner: None,
+            uids: [other_order].into_iter().collect(),
+        };
+        assert!(!subscription.matches(&own_order));
+        assert!(subscription.matches(&other_order));
+    }
+}
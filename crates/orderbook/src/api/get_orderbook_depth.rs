@@ -0,0 +1,54 @@
+use {
+    crate::orderbook::Orderbook,
+    primitive_types::H160,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, hyper::StatusCode, reply},
+};
+
+fn get_orderbook_depth_request() -> impl Filter<Extract = (H160, H160), Error = Rejection> + Clone {
+    warp::path!("v1" / "orderbook" / H160 / H160).and(warp::get())
+}
+
+pub fn get_orderbook_depth(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    get_orderbook_depth_request().and_then(move |sell_token: H160, buy_token: H160| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.get_orderbook_depth(sell_token, buy_token).await;
+            let response = match result {
+                Ok(depth) => reply::with_status(reply::json(&depth), StatusCode::OK),
+                Err(err) => {
+                    tracing::error!(?err, "get_orderbook_depth");
+                    crate::api::internal_error_reply()
+                }
+            };
+            Result::<_, Infallible>::Ok(response)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::addr, warp::test::request};
+
+    #[tokio::test]
+    async fn get_orderbook_depth_request_ok() {
+        let result = request()
+            .path(
+                "/v1/orderbook/0x0000000000000000000000000000000000000001/\
+                 0x0000000000000000000000000000000000000002",
+            )
+            .method("GET")
+            .filter(&get_orderbook_depth_request())
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            (
+                addr!("0000000000000000000000000000000000000001"),
+                addr!("0000000000000000000000000000000000000002"),
+            )
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use {
+    crate::{
+        api::{ApiReply, error},
+        database::{
+            Postgres,
+            trades::{PaginatedTradeFilter, TradeCursor, TradeRetrieving},
+        },
+    },
+    model::{order::OrderUid, trade::Trade},
+    primitive_types::H160,
+    serde::{Deserialize, Serialize},
+    std::convert::Infallible,
+    warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
+};
+
+const DEFAULT_LIMIT: u64 = 10;
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    limit: Option<u64>,
+    cursor: Option<String>,
+    owner: Option<H160>,
+    order_uid: Option<OrderUid>,
+    sell_token: Option<H160>,
+    buy_token: Option<H160>,
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+}
+
+impl Query {
+    fn validate(self) -> Result<(PaginatedTradeFilter, u64), (&'static str, String)> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
+            return Err((
+                "LIMIT_OUT_OF_BOUNDS",
+                format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
+            ));
+        }
+        if self.owner.is_none()
+            && self.order_uid.is_none()
+            && self.sell_token.is_none()
+            && self.buy_token.is_none()
+        {
+            return Err((
+                "InvalidTradeFilter",
+                "Must specify at least one of owner, orderUid, sellToken or buyToken.".to_owned(),
+            ));
+        }
+        let after = self
+            .cursor
+            .map(|cursor| decode_cursor(&cursor).ok_or(cursor))
+            .transpose()
+            .map_err(|cursor| ("InvalidCursor", format!("invalid cursor {cursor}")))?;
+        let filter = PaginatedTradeFilter {
+            owner: self.owner,
+            order_uid: self.order_uid,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            from_block: self.from_block,
+            to_block: self.to_block,
+            after,
+        };
+        Ok((filter, limit))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    trades: Vec<Trade>,
+    /// Pass this to the next request to get the following page. Unset if the
+    /// end of the trade history has been reached.
+    next_cursor: Option<String>,
+}
+
+/// The cursor is the block number followed by the log index (8 bytes big
+/// endian each), hex encoded.
+fn encode_cursor(cursor: &TradeCursor) -> String {
+    let mut bytes = cursor.block_number.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&cursor.log_index.to_be_bytes());
+    hex::encode(bytes)
+}
+
+fn decode_cursor(cursor: &str) -> Option<TradeCursor> {
+    let mut bytes = [0u8; 16];
+    hex::decode_to_slice(cursor, &mut bytes).ok()?;
+    let (block_number, log_index) = bytes.split_at(8);
+    Some(TradeCursor {
+        block_number: u64::from_be_bytes(block_number.try_into().unwrap()),
+        log_index: u64::from_be_bytes(log_index.try_into().unwrap()),
+    })
+}
+
+type Request = Result<(PaginatedTradeFilter, u64), (&'static str, String)>;
+
+fn request() -> impl Filter<Extract = (Request,), Error = Rejection> + Clone {
+    warp::path!("v2" / "trades")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(Query::validate)
+}
+
+pub fn get_trades(db: Postgres) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |request: Request| {
+        let database = db.clone();
+        async move {
+            let (filter, limit) = match request {
+                Ok(request) => request,
+                Err((error_type, description)) => {
+                    return Result::<_, Infallible>::Ok(with_status(
+                        error(error_type, description),
+                        StatusCode::BAD_REQUEST,
+                    ));
+                }
+            };
+            Ok(match database.paginated_trades(&filter, limit).await {
+                Ok((trades, cursor)) => with_status(
+                    warp::reply::json(&Response {
+                        trades,
+                        next_cursor: cursor.as_ref().map(encode_cursor),
+                    }),
+                    StatusCode::OK,
+                ),
+                Err(err) => {
+                    tracing::error!(?err, "get_trades_v2");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::addr, warp::test::request as test_request};
+
+    async fn filter(path: &str) -> Request {
+        test_request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn request_ok() {
+        let owner = addr!("0000000000000000000000000000000000000001");
+        assert_eq!(
+            filter(&format!("/v2/trades?owner=0x{owner:x}"))
+                .await
+                .unwrap(),
+            (
+                PaginatedTradeFilter {
+                    owner: Some(owner),
+                    ..Default::default()
+                },
+                DEFAULT_LIMIT
+            )
+        );
+
+        let cursor = TradeCursor {
+            block_number: 20_000_000,
+            log_index: 42,
+        };
+        let sell_token = addr!("0000000000000000000000000000000000000002");
+        let buy_token = addr!("0000000000000000000000000000000000000003");
+        let path = format!(
+            "/v2/trades?sellToken=0x{sell_token:x}&buyToken=0x{buy_token:x}&fromBlock=1&toBlock=2&\
+             limit=100&cursor={}",
+            encode_cursor(&cursor)
+        );
+        assert_eq!(
+            filter(&path).await.unwrap(),
+            (
+                PaginatedTradeFilter {
+                    sell_token: Some(sell_token),
+                    buy_token: Some(buy_token),
+                    from_block: Some(1),
+                    to_block: Some(2),
+                    after: Some(cursor),
+                    ..Default::default()
+                },
+                100
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn request_err() {
+        assert!(filter("/v2/trades").await.is_err());
+        assert!(filter("/v2/trades?fromBlock=1").await.is_err());
+        let owner = "owner=0x0000000000000000000000000000000000000001";
+        for query in ["limit=0", "limit=1001", "cursor=0102"] {
+            assert!(
+                filter(&format!("/v2/trades?{owner}&{query}"))
+                    .await
+                    .is_err(),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn cursor_roundtrip() {
+        let cursor = TradeCursor {
+            block_number: 1,
+            log_index: u64::MAX,
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+        assert_eq!(decode_cursor("zz"), None);
+    }
+}
@@ -0,0 +1,220 @@
+use {
+    crate::{
+        api::{ApiReply, error},
+        database::orders::{OrderCursor, UserOrderFilter},
+        orderbook::Orderbook,
+    },
+    chrono::{DateTime, Utc},
+    model::order::{Order, OrderStatus, OrderUid},
+    primitive_types::H160,
+    serde::{Deserialize, Serialize},
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
+};
+
+const DEFAULT_LIMIT: u64 = 10;
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    limit: Option<u64>,
+    cursor: Option<String>,
+    status: Option<OrderStatus>,
+    sell_token: Option<H160>,
+    buy_token: Option<H160>,
+    class: Option<String>,
+    /// Unix timestamp in seconds.
+    created_after: Option<i64>,
+    /// Unix timestamp in seconds.
+    created_before: Option<i64>,
+}
+
+impl Query {
+    fn validate(self) -> Result<(UserOrderFilter, u64), (&'static str, String)> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
+            return Err((
+                "LIMIT_OUT_OF_BOUNDS",
+                format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
+            ));
+        }
+        let after = self
+            .cursor
+            .map(|cursor| decode_cursor(&cursor).ok_or(cursor))
+            .transpose()
+            .map_err(|cursor| ("InvalidCursor", format!("invalid cursor {cursor}")))?;
+        let class = self
+            .class
+            .map(|class| class.parse().map_err(|_| class))
+            .transpose()
+            .map_err(|class| ("InvalidOrderClass", format!("invalid order class {class}")))?;
+        let timestamp = |timestamp: Option<i64>| {
+            timestamp
+                .map(|secs| DateTime::<Utc>::from_timestamp(secs, 0).ok_or(secs))
+                .transpose()
+                .map_err(|secs| ("InvalidTimestamp", format!("invalid timestamp {secs}")))
+        };
+        let filter = UserOrderFilter {
+            status: self.status,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            class,
+            created_after: timestamp(self.created_after)?,
+            created_before: timestamp(self.created_before)?,
+            after,
+        };
+        Ok((filter, limit))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    orders: Vec<Order>,
+    /// Pass this to the next request to get the following page. Unset if the
+    /// end of the order history has been reached.
+    next_cursor: Option<String>,
+}
+
+/// The cursor is the creation timestamp in microseconds (8 bytes big endian)
+/// followed by the order UID, hex encoded.
+fn encode_cursor(cursor: &OrderCursor) -> String {
+    let mut bytes = cursor
+        .creation_date
+        .timestamp_micros()
+        .to_be_bytes()
+        .to_vec();
+    bytes.extend_from_slice(&cursor.uid.0);
+    hex::encode(bytes)
+}
+
+fn decode_cursor(cursor: &str) -> Option<OrderCursor> {
+    let mut bytes = [0u8; 8 + 56];
+    hex::decode_to_slice(cursor, &mut bytes).ok()?;
+    let (timestamp, uid) = bytes.split_at(8);
+    Some(OrderCursor {
+        creation_date: DateTime::from_timestamp_micros(i64::from_be_bytes(
+            timestamp.try_into().unwrap(),
+        ))?,
+        uid: OrderUid(uid.try_into().unwrap()),
+    })
+}
+
+type Request = Result<(UserOrderFilter, u64), (&'static str, String)>;
+
+fn request() -> impl Filter<Extract = (H160, Request), Error = Rejection> + Clone {
+    warp::path!("v2" / "account" / H160 / "orders")
+        .and(warp::get())
+        .and(warp::query::<Query>().map(Query::validate))
+}
+
+pub fn get_user_orders(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |owner: H160, request: Request| {
+        let orderbook = orderbook.clone();
+        async move {
+            let (filter, limit) = match request {
+                Ok(request) => request,
+                Err((error_type, description)) => {
+                    return Result::<_, Infallible>::Ok(with_status(
+                        error(error_type, description),
+                        StatusCode::BAD_REQUEST,
+                    ));
+                }
+            };
+            let result = orderbook.get_user_orders_page(&owner, &filter, limit).await;
+            Ok(match result {
+                Ok((orders, cursor)) => with_status(
+                    warp::reply::json(&Response {
+                        orders,
+                        next_cursor: cursor.as_ref().map(encode_cursor),
+                    }),
+                    StatusCode::OK,
+                ),
+                Err(err) => {
+                    tracing::error!(?err, "get_user_orders_v2");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, model::order::OrderClass, shared::addr};
+
+    async fn filter(path: &str) -> Request {
+        warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap()
+            .1
+    }
+
+    #[tokio::test]
+    async fn request_ok() {
+        let path = "/v2/account/0x0000000000000000000000000000000000000001/orders";
+        let result = warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(result.0, addr!("0000000000000000000000000000000000000001"));
+        assert_eq!(
+            result.1.unwrap(),
+            (UserOrderFilter::default(), DEFAULT_LIMIT)
+        );
+
+        let cursor = OrderCursor {
+            creation_date: DateTime::from_timestamp(1_700_000_000, 123_000).unwrap(),
+            uid: OrderUid([2; 56]),
+        };
+        let path = format!(
+            "/v2/account/0x0000000000000000000000000000000000000001/orders?limit=5&cursor={}&\
+             status=open&sellToken=0x0000000000000000000000000000000000000002&\
+             buyToken=0x0000000000000000000000000000000000000003&class=limit&\
+             createdAfter=1700000000&createdBefore=1700000100",
+            encode_cursor(&cursor),
+        );
+        assert_eq!(
+            filter(&path).await.unwrap(),
+            (
+                UserOrderFilter {
+                    status: Some(OrderStatus::Open),
+                    sell_token: Some(addr!("0000000000000000000000000000000000000002")),
+                    buy_token: Some(addr!("0000000000000000000000000000000000000003")),
+                    class: Some(OrderClass::Limit),
+                    created_after: DateTime::from_timestamp(1_700_000_000, 0),
+                    created_before: DateTime::from_timestamp(1_700_000_100, 0),
+                    after: Some(cursor),
+                },
+                5
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn request_err() {
+        let base = "/v2/account/0x0000000000000000000000000000000000000001/orders";
+        for query in ["limit=0", "limit=1001", "cursor=01", "class=unknown"] {
+            assert!(filter(&format!("{base}?{query}")).await.is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn cursor_roundtrip() {
+        let cursor = OrderCursor {
+            creation_date: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            uid: OrderUid([7; 56]),
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+        assert_eq!(decode_cursor("zz"), None);
+    }
+}
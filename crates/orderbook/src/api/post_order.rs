@@ -223,6 +223,10 @@ impl IntoWarpReply for ValidationErrorWrapper {
                 error("TooMuchGas", "Executing order requires too many gas units"),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::InvalidCondition(reason) => with_status(
+                error("InvalidOrderCondition", reason),
+                StatusCode::BAD_REQUEST,
+            ),
 
             ValidationError::Other(err) => {
                 tracing::error!(?err, "ValidationErrorWrapper");
@@ -0,0 +1,171 @@
+use {
+    crate::{
+        api::{
+            ApiReply,
+            IntoWarpReply,
+            MAX_JSON_BODY_PAYLOAD,
+            error,
+            extract_payload_with_max_size,
+            response_body,
+            rich_error,
+        },
+        orderbook::{AddOrdersError, Orderbook},
+    },
+    model::order::{OrderCreation, OrderUid},
+    serde::Serialize,
+    std::{convert::Infallible, sync::Arc},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        hyper::StatusCode,
+        reply::{json, with_status},
+    },
+};
+
+/// Maximum number of orders that can be placed with a single request.
+const MAX_ORDERS_PER_BATCH: usize = 100;
+
+pub fn request() -> impl Filter<Extract = (Vec<OrderCreation>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "batch")
+        .and(warp::post())
+        .and(extract_payload_with_max_size(
+            MAX_JSON_BODY_PAYLOAD * MAX_ORDERS_PER_BATCH as u64,
+        ))
+}
+
+/// Error of a single order of a rejected batch.
+#[derive(Serialize)]
+struct OrderError {
+    index: usize,
+    #[serde(flatten)]
+    error: serde_json::Value,
+}
+
+pub async fn response(result: Result<Vec<OrderUid>, AddOrdersError>) -> ApiReply {
+    let errors = match result {
+        Ok(uids) => return with_status(json(&uids), StatusCode::CREATED),
+        Err(AddOrdersError::InvalidOrders(errors)) => errors,
+        Err(AddOrdersError::Database(err)) => {
+            tracing::error!(?err, "AddOrdersError");
+            return crate::api::internal_error_reply();
+        }
+    };
+
+    // The batch gets rejected with the most severe status code of its orders.
+    let mut status = StatusCode::BAD_REQUEST;
+    let mut order_errors = Vec::with_capacity(errors.len());
+    for (index, err) in errors {
+        let response = err.into_warp_reply().into_response();
+        status = status.max(response.status());
+        let error = serde_json::from_slice(&response_body(response).await).unwrap_or_default();
+        order_errors.push(OrderError { index, error });
+    }
+    with_status(
+        rich_error(
+            "InvalidOrders",
+            "None of the orders were created because some of them are invalid",
+            order_errors,
+        ),
+        status,
+    )
+}
+
+pub fn post_orders(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    request().and_then(move |orders: Vec<OrderCreation>| {
+        let orderbook = orderbook.clone();
+        async move {
+            if orders.is_empty() || orders.len() > MAX_ORDERS_PER_BATCH {
+                return Result::<_, Infallible>::Ok(with_status(
+                    error(
+                        "InvalidBatchSize",
+                        format!("A batch must contain 1 to {MAX_ORDERS_PER_BATCH} orders"),
+                    ),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let result = orderbook
+                .add_orders(orders)
+                .await
+                .map(|orders| {
+                    orders
+                        .into_iter()
+                        .map(|(order_uid, quote_metadata)| {
+                            let quote_id = quote_metadata.as_ref().and_then(|q| q.id);
+                            tracing::debug!(%order_uid, ?quote_id, "order created");
+                            order_uid
+                        })
+                        .collect()
+                })
+                .inspect_err(|err| {
+                    tracing::debug!(?err, "error creating orders");
+                });
+
+            Result::<_, Infallible>::Ok(response(result).await)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::orderbook::AddOrderError,
+        serde_json::json,
+        shared::order_validation::ValidationError,
+        warp::test,
+    };
+
+    #[tokio::test]
+    async fn request_ok() {
+        let filter = request();
+        let orders = vec![OrderCreation::default(), OrderCreation::default()];
+        let result = test::request()
+            .path("/v1/orders/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&orders)
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(result, orders);
+    }
+
+    #[tokio::test]
+    async fn response_contains_errors_of_each_order() {
+        let response = response(Err(AddOrdersError::InvalidOrders(vec![
+            (1, AddOrderError::DuplicatedOrder),
+            (
+                3,
+                AddOrderError::OrderValidation(ValidationError::TooManyLimitOrders),
+            ),
+        ])))
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response_body(response).await).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "errorType": "InvalidOrders",
+                "description": "None of the orders were created because some of them are invalid",
+                "data": [
+                    {
+                        "index": 1,
+                        "errorType": "DuplicatedOrder",
+                        "description": "order already exists",
+                    },
+                    {
+                        "index": 3,
+                        "errorType": "TooManyLimitOrders",
+                        "description": "Too many limit orders",
+                    },
+                ],
+            })
+        );
+    }
+}
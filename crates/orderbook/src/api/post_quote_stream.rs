@@ -0,0 +1,113 @@
+use {
+    super::post_quote::OrderQuoteErrorWrapper,
+    crate::{
+        api::{self, IntoWarpReply, response_body},
+        quoter::{QuoteHandler, QuoteStreamUpdate},
+    },
+    futures::StreamExt,
+    model::quote::{OrderQuoteRequest, OrderQuoteResponse},
+    serde::Serialize,
+    std::{convert::Infallible, sync::Arc},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        sse::{self, Event},
+    },
+};
+
+fn request() -> impl Filter<Extract = (OrderQuoteRequest,), Error = Rejection> + Clone {
+    warp::path!("v1" / "quote" / "stream")
+        .and(warp::post())
+        .and(api::extract_payload())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteEvent {
+    #[serde(flatten)]
+    response: OrderQuoteResponse,
+    best: bool,
+}
+
+/// Converts a quote stream update into a server-sent event:
+/// - `quote` for the quote of a single price source
+/// - `final` for the best quote after all price sources returned
+/// - `error` if no quote could be computed
+async fn event(update: QuoteStreamUpdate) -> Event {
+    let event = match update {
+        QuoteStreamUpdate::Quote { response, best } => Event::default()
+            .event("quote")
+            .json_data(QuoteEvent { response, best }),
+        QuoteStreamUpdate::Final(response) => Event::default().event("final").json_data(response),
+        QuoteStreamUpdate::Error(err) => {
+            tracing::warn!(%err, "post_quote_stream error");
+            let response = OrderQuoteErrorWrapper(err)
+                .into_warp_reply()
+                .into_response();
+            let body = response_body(response).await;
+            Ok(Event::default()
+                .event("error")
+                .data(String::from_utf8_lossy(&body)))
+        }
+    };
+    event.unwrap_or_else(|err| {
+        tracing::error!(?err, "failed to serialize quote event");
+        Event::default().event("error")
+    })
+}
+
+pub fn post_quote_stream(
+    quotes: Arc<QuoteHandler>,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    request().map(move |request: OrderQuoteRequest| {
+        let events = quotes
+            .clone()
+            .calculate_quote_stream(request)
+            .then(event)
+            .map(Result::<_, Infallible>::Ok);
+        Box::new(sse::reply(sse::keep_alive().stream(events))) as Box<dyn Reply>
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::quoter::OrderQuoteError,
+        model::quote::OrderQuoteSide,
+        number::nonzero::U256 as NonZeroU256,
+        shared::order_quoting::CalculateQuoteError,
+        warp::test,
+    };
+
+    #[tokio::test]
+    async fn request_ok() {
+        let quote_request = OrderQuoteRequest {
+            side: OrderQuoteSide::Buy {
+                buy_amount_after_fee: NonZeroU256::try_from(1).unwrap(),
+            },
+            ..Default::default()
+        };
+        let result = test::request()
+            .path("/v1/quote/stream")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&quote_request)
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(result, quote_request);
+    }
+
+    #[tokio::test]
+    async fn errors_are_reported_as_events() {
+        let event = event(QuoteStreamUpdate::Error(OrderQuoteError::CalculateQuote(
+            CalculateQuoteError::QuoteNotVerified,
+        )))
+        .await
+        .to_string();
+        assert!(event.starts_with("event:error\n"));
+        assert!(event.contains("\"errorType\":\"QuoteNotVerified\""));
+    }
+}
@@ -0,0 +1,123 @@
+use {
+    crate::{
+        api::{IntoWarpReply, convert_json_response},
+        orderbook::{Orderbook, RefundRequestError},
+    },
+    anyhow::Result,
+    model::order::OrderUid,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
+};
+
+fn request_ethflow_refund_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone
+{
+    warp::path!("v1" / "orders" / OrderUid / "refund").and(warp::post())
+}
+
+impl IntoWarpReply for RefundRequestError {
+    fn into_warp_reply(self) -> super::ApiReply {
+        match self {
+            Self::OrderNotFound => with_status(
+                super::error("OrderNotFound", "Order not located in database"),
+                StatusCode::NOT_FOUND,
+            ),
+            Self::NotEthflowOrder => with_status(
+                super::error("NotEthflowOrder", "Only ethflow orders can be refunded"),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::PartiallyFillable => with_status(
+                super::error(
+                    "PartiallyFillable",
+                    "Only fill-or-kill orders can be refunded",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::OrderFullyExecuted => with_status(
+                super::error("OrderFullyExecuted", "Order is fully executed"),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::NotExpired => with_status(
+                super::error("NotExpired", "Order is not expired yet"),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::AlreadyRefunded => with_status(
+                super::error("AlreadyRefunded", "Order is already refunded"),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::ValidityTooShort => with_status(
+                super::error(
+                    "ValidityTooShort",
+                    "Order was not valid long enough to get refunded",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            Self::Other(err) => {
+                tracing::error!(?err, "request_ethflow_refund");
+                crate::api::internal_error_reply()
+            }
+        }
+    }
+}
+
+pub fn request_ethflow_refund_response(result: Result<(), RefundRequestError>) -> super::ApiReply {
+    convert_json_response(result.map(|_| "Refund requested"))
+}
+
+pub fn request_ethflow_refund(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    request_ethflow_refund_request().and_then(move |uid| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.request_ethflow_refund(&uid).await;
+            Result::<_, Infallible>::Ok(request_ethflow_refund_response(result))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        warp::{Reply, test::request},
+    };
+
+    #[tokio::test]
+    async fn request_ethflow_refund_request_ok() {
+        let filter = request_ethflow_refund_request();
+        let uid = OrderUid([1; 56]);
+
+        let result = request()
+            .path(&format!("/v1/orders/{uid}/refund"))
+            .method("POST")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(result, uid);
+    }
+
+    #[test]
+    fn request_ethflow_refund_response_ok() {
+        let response = request_ethflow_refund_response(Ok(())).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn request_ethflow_refund_response_err() {
+        let response =
+            request_ethflow_refund_response(Err(RefundRequestError::OrderNotFound)).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response =
+            request_ethflow_refund_response(Err(RefundRequestError::NotExpired)).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = request_ethflow_refund_response(Err(RefundRequestError::AlreadyRefunded))
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = request_ethflow_refund_response(Err(RefundRequestError::ValidityTooShort))
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
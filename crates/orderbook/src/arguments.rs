@@ -1,5 +1,7 @@
 use {
+    anyhow::Context,
     primitive_types::H160,
+    rate_limit::token_bucket::Limit,
     reqwest::Url,
     shared::{
         arguments::{display_option, display_secret_option},
@@ -7,7 +9,7 @@ use {
         http_client,
         price_estimation::{self, NativePriceEstimators},
     },
-    std::{net::SocketAddr, num::NonZeroUsize, time::Duration},
+    std::{net::SocketAddr, num::NonZeroUsize, str::FromStr, time::Duration},
 };
 
 #[derive(clap::Parser)]
@@ -136,6 +138,59 @@ pub struct Arguments {
     /// whether an order is actively being bid on.
     #[clap(long, env, default_value = "5")]
     pub active_order_competition_threshold: u32,
+
+    /// Per client rate limits of API endpoints in the form
+    /// `<endpoint>=<requests>/<period>`, e.g. `v1/post_quote=10/1s`. Clients
+    /// are identified by their `X-API-Key` header if it is one of the
+    /// configured `api_keys` and by their IP address otherwise (see
+    /// `api_behind_trusted_proxy`). Endpoints
+    /// without a configured limit are not rate limited.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_rate_limits: Vec<EndpointRateLimit>,
+
+    /// API keys of known clients which get rate limited separately from other
+    /// clients sharing their IP address.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub api_keys: Vec<String>,
+
+    /// Whether the API is only reachable through a trusted proxy appending
+    /// the client's IP address to the `X-Forwarded-For` header. Otherwise the
+    /// header is ignored for rate limiting since clients could set it to
+    /// arbitrary values.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub api_behind_trusted_proxy: bool,
+
+    /// The minimum validity duration ethflow orders need to get refunded.
+    /// Has to match the refunder's `min_validity_duration` so refund requests
+    /// get rejected for orders the refunder would never refund.
+    #[clap(
+        long,
+        env,
+        default_value = "2m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub ethflow_refund_min_validity_duration: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointRateLimit {
+    /// Name of the endpoint as used in the API metrics.
+    pub endpoint: String,
+    pub limit: Limit,
+}
+
+impl FromStr for EndpointRateLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (endpoint, limit) = s
+            .split_once('=')
+            .context("rate limit must be of the form <endpoint>=<limit>")?;
+        Ok(Self {
+            endpoint: endpoint.trim().to_string(),
+            limit: limit.parse()?,
+        })
+    }
 }
 
 impl std::fmt::Display for Arguments {
@@ -166,6 +221,10 @@ impl std::fmt::Display for Arguments {
             db_url,
             max_gas_per_order,
             active_order_competition_threshold,
+            api_rate_limits,
+            api_keys,
+            api_behind_trusted_proxy,
+            ethflow_refund_min_validity_duration,
         } = self;
 
         write!(f, "{shared}")?;
@@ -215,6 +274,20 @@ impl std::fmt::Display for Arguments {
             f,
             "active_order_competition_threshold: {active_order_competition_threshold}"
         )?;
+        writeln!(
+            f,
+            "api_rate_limits: {:?}",
+            api_rate_limits
+                .iter()
+                .map(|rate_limit| format!("{}={}", rate_limit.endpoint, rate_limit.limit))
+                .collect::<Vec<_>>()
+        )?;
+        writeln!(f, "api_keys: {} SECRET", api_keys.len())?;
+        writeln!(f, "api_behind_trusted_proxy: {api_behind_trusted_proxy}")?;
+        writeln!(
+            f,
+            "ethflow_refund_min_validity_duration: {ethflow_refund_min_validity_duration:?}"
+        )?;
 
         Ok(())
     }
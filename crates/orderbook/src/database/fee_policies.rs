@@ -70,6 +70,7 @@ impl super::Postgres {
                             .context("executed fee amount")?,
                         token: primitive_types::H160(executed_fee.token.0),
                         policy: fee_policy_from(policy.clone(), quotes.get(&key.1), key.1)?,
+                        tier: policy.tier.clone(),
                     };
                     result
                         .entry(key)
@@ -8,7 +8,9 @@ use {
     chrono::{DateTime, Utc},
     database::{
         byte_array::ByteArray,
+        ethflow_orders::EthOrderData,
         order_events::{OrderEvent, OrderEventLabel, insert_order_event},
+        order_history,
         orders::{self, FullOrder, OrderKind as DbOrderKind},
     },
     ethcontract::H256,
@@ -58,6 +60,12 @@ use {
 #[async_trait::async_trait]
 pub trait OrderStoring: Send + Sync {
     async fn insert_order(&self, order: &Order) -> Result<(), InsertionError>;
+    /// Inserts all orders in a single transaction, cancelling the orders they
+    /// replace. Either all of the orders get inserted or none of them.
+    async fn insert_orders(
+        &self,
+        orders: &[(Order, Option<OrderUid>)],
+    ) -> Result<(), BatchInsertionError>;
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()>;
     async fn cancel_order(&self, order_uid: &OrderUid, now: DateTime<Utc>) -> Result<()>;
     async fn replace_order(
@@ -74,8 +82,49 @@ pub trait OrderStoring: Send + Sync {
         offset: u64,
         limit: Option<u64>,
     ) -> Result<Vec<Order>>;
+    /// Up to `limit` orders of a single user matching the filter ordered by
+    /// creation date descending (newest orders first). Also returns the
+    /// position to continue from if there might be more matching orders.
+    async fn paginated_user_orders(
+        &self,
+        owner: &H160,
+        filter: &UserOrderFilter,
+        limit: u64,
+    ) -> Result<(Vec<Order>, Option<OrderCursor>)>;
     async fn latest_order_event(&self, order_uid: &OrderUid) -> Result<Option<OrderEvent>>;
     async fn single_order(&self, uid: &OrderUid) -> Result<Option<Order>>;
+    /// Up to `limit` open limit orders selling `sell_token` for `buy_token`
+    /// with the best limit prices.
+    async fn open_limit_orders_for_pair(
+        &self,
+        sell_token: &H160,
+        buy_token: &H160,
+        limit: u64,
+    ) -> Result<Vec<Order>>;
+}
+
+/// Maximum number of pages of a user's order history scanned for orders
+/// matching a status filter in a single request.
+const MAX_SCANNED_PAGES: usize = 10;
+
+/// Position of an order in a user's order history.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderCursor {
+    pub creation_date: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+/// Any default value means that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserOrderFilter {
+    pub status: Option<OrderStatus>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub class: Option<OrderClass>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only orders that come after this position in the history.
+    pub after: Option<OrderCursor>,
 }
 
 #[derive(Debug)]
@@ -93,6 +142,24 @@ impl From<sqlx::Error> for InsertionError {
     }
 }
 
+/// Error inserting a batch of orders.
+#[derive(Debug)]
+pub struct BatchInsertionError {
+    /// Index of the order that could not be inserted or `None` if the error
+    /// is not related to a specific order.
+    pub index: Option<usize>,
+    pub error: InsertionError,
+}
+
+impl From<sqlx::Error> for BatchInsertionError {
+    fn from(err: sqlx::Error) -> Self {
+        Self {
+            index: None,
+            error: err.into(),
+        }
+    }
+}
+
 /// Applies the needed DB modification to cancel a single order.
 async fn cancel_order(
     ex: &mut PgConnection,
@@ -106,6 +173,7 @@ async fn cancel_order(
             order_uid: uid,
             timestamp: now,
             label: OrderEventLabel::Cancelled,
+            reason: None,
         },
     )
     .await?;
@@ -121,6 +189,7 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             order_uid,
             timestamp: Utc::now(),
             label: OrderEventLabel::Created,
+            reason: None,
         },
     )
     .await?;
@@ -207,6 +276,14 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             .map_err(InsertionError::DbError)?;
     }
 
+    if let Some(condition) = order.metadata.condition.as_ref() {
+        let condition =
+            serde_json::to_value(condition).expect("order conditions are always serializable");
+        database::order_conditions::insert(ex, &order_uid, &condition)
+            .await
+            .map_err(InsertionError::DbError)?;
+    }
+
     Ok(())
 }
 
@@ -229,6 +306,42 @@ impl OrderStoring for Postgres {
         Ok(())
     }
 
+    async fn insert_orders(
+        &self,
+        orders: &[(Order, Option<OrderUid>)],
+    ) -> Result<(), BatchInsertionError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_orders"])
+            .start_timer();
+
+        let mut connection = self.pool.acquire().await?;
+        let mut ex = connection.begin().await?;
+
+        for (index, (order, replaced_order)) in orders.iter().enumerate() {
+            async {
+                if let Some(old_order) = replaced_order {
+                    database::orders::cancel_order(
+                        &mut ex,
+                        &ByteArray(old_order.0),
+                        order.metadata.creation_date,
+                    )
+                    .await?;
+                }
+                insert_order(order, &mut ex).await?;
+                Self::insert_order_app_data(order, &mut ex).await
+            }
+            .await
+            .map_err(|error| BatchInsertionError {
+                index: Some(index),
+                error,
+            })?;
+        }
+
+        ex.commit().await?;
+        Ok(())
+    }
+
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -288,6 +401,33 @@ impl OrderStoring for Postgres {
             .await
     }
 
+    async fn open_limit_orders_for_pair(
+        &self,
+        sell_token: &H160,
+        buy_token: &H160,
+        limit: u64,
+    ) -> Result<Vec<Order>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["open_limit_orders_for_pair"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        orders::open_limit_orders_for_pair(
+            &mut ex,
+            now_in_epoch_seconds().into(),
+            &ByteArray(sell_token.0),
+            &ByteArray(buy_token.0),
+            i64::try_from(limit).unwrap_or(i64::MAX),
+        )
+        .map(|result| match result {
+            Ok(order) => full_order_into_model_order(order),
+            Err(err) => Err(anyhow::Error::from(err)),
+        })
+        .try_collect()
+        .await
+    }
+
     async fn single_order(&self, uid: &OrderUid) -> Result<Option<Order>> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -351,6 +491,79 @@ impl OrderStoring for Postgres {
         .await
     }
 
+    async fn paginated_user_orders(
+        &self,
+        owner: &H160,
+        filter: &UserOrderFilter,
+        limit: u64,
+    ) -> Result<(Vec<Order>, Option<OrderCursor>)> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["paginated_user_orders"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let owner = ByteArray(owner.0);
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let mut db_filter = order_history::UserOrderFilter {
+            sell_token: filter.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: filter.buy_token.map(|token| ByteArray(token.0)),
+            class: filter.class.as_ref().map(order_class_into),
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+            after: filter.after.map(|cursor| order_history::OrderCursor {
+                creation_timestamp: cursor.creation_date,
+                uid: ByteArray(cursor.uid.0),
+            }),
+        };
+
+        // The status of an order is derived from many tables and can't be
+        // filtered on by the query, so we keep fetching pages until enough
+        // orders match or the user's history is exhausted. To bound the work
+        // per request only a few pages get scanned, returning fewer orders and
+        // a cursor pointing after the last scanned order otherwise.
+        let mut orders = Vec::new();
+        for _ in 0..MAX_SCANNED_PAGES {
+            let page: Vec<FullOrder> = order_history::paginated_user_orders(
+                &mut ex,
+                &owner,
+                &db_filter,
+                i64::try_from(limit).unwrap_or(i64::MAX),
+            )
+            .try_collect()
+            .await?;
+            let exhausted = page.is_empty() || page.len() < limit;
+            for order in page {
+                db_filter.after = Some(order_history::OrderCursor {
+                    creation_timestamp: order.creation_timestamp,
+                    uid: order.uid,
+                });
+                let order = full_order_into_model_order(order)?;
+                if filter
+                    .status
+                    .is_none_or(|status| status == order.metadata.status)
+                {
+                    orders.push(order);
+                    if orders.len() == limit {
+                        let cursor = OrderCursor {
+                            creation_date: orders[limit - 1].metadata.creation_date,
+                            uid: orders[limit - 1].metadata.uid,
+                        };
+                        return Ok((orders, Some(cursor)));
+                    }
+                }
+            }
+            if exhausted {
+                return Ok((orders, None));
+            }
+        }
+        let cursor = db_filter.after.map(|cursor| OrderCursor {
+            creation_date: cursor.creation_timestamp,
+            uid: OrderUid(cursor.uid.0),
+        });
+        Ok((orders, cursor))
+    }
+
     async fn latest_order_event(&self, order_uid: &OrderUid) -> Result<Option<OrderEvent>> {
         let mut ex = self.pool.begin().await.context("could not init tx")?;
         let _timer = super::Metrics::get()
@@ -425,6 +638,33 @@ impl Postgres {
         })
     }
 
+    /// Records that the user requested the expired ethflow order to be
+    /// refunded.
+    pub async fn insert_ethflow_refund_request(
+        &self,
+        uid: &OrderUid,
+        timestamp: DateTime<Utc>,
+    ) -> Result<()> {
+        self.execute_instrumented("insert_ethflow_refund_request", async {
+            let mut ex = self.pool.acquire().await?;
+            database::ethflow_orders::insert_refund_request(&mut ex, &ByteArray(uid.0), timestamp)
+                .await
+                .map_err(anyhow::Error::from)
+        })
+        .await
+    }
+
+    /// The refund state of an ethflow order. Returns `None` for other orders.
+    pub async fn ethflow_order(&self, uid: &OrderUid) -> Result<Option<EthOrderData>> {
+        self.execute_instrumented("ethflow_order", async {
+            let mut ex = self.pool.acquire().await?;
+            database::ethflow_orders::read_order(&mut ex, &ByteArray(uid.0))
+                .await
+                .map_err(anyhow::Error::from)
+        })
+        .await
+    }
+
     async fn execute_instrumented<F, T>(&self, label: &str, f: F) -> Result<T>
     where
         F: std::future::Future<Output = Result<T>>,
@@ -577,6 +817,11 @@ fn full_order_with_quote_into_model_order(
         quote: quote
             .map(|q| order_quote_into_model(q, status))
             .transpose()?,
+        condition: order
+            .condition
+            .map(serde_json::from_value)
+            .transpose()
+            .context("invalid order condition")?,
     };
     let data = OrderData {
         sell_token: H160(order.sell_token.0),
@@ -639,7 +884,7 @@ mod tests {
         },
         model::{
             interaction::InteractionData,
-            order::{Order, OrderData, OrderMetadata, OrderStatus, OrderUid},
+            order::{Order, OrderCondition, OrderData, OrderMetadata, OrderStatus, OrderUid},
             signature::{Signature, SigningScheme},
         },
         primitive_types::U256,
@@ -684,6 +929,7 @@ mod tests {
             executed_fee: Default::default(),
             executed_fee_token: ByteArray([1; 20]), // TODO surplus token
             full_app_data: Default::default(),
+            condition: None,
         };
 
         // Open - sell (filled - 0%)
@@ -972,6 +1218,89 @@ mod tests {
         assert_eq!(old_order_cancellation, None);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_orders_is_atomic() {
+        let owner = H160([0x77; 20]);
+
+        let db = Postgres::try_new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+
+        let order = |uid: u8| Order {
+            data: OrderData {
+                valid_to: u32::MAX,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                owner,
+                uid: OrderUid([uid; 56]),
+                creation_date: Utc::now(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        db.insert_order(&order(1)).await.unwrap();
+
+        // The third order already exists so none of the orders get inserted and
+        // the replaced order does not get cancelled.
+        let err = db
+            .insert_orders(&[
+                (order(2), Some(OrderUid([1; 56]))),
+                (order(3), None),
+                (order(1), None),
+            ])
+            .await
+            .unwrap_err();
+        assert_eq!(err.index, Some(2));
+        assert!(matches!(err.error, InsertionError::DuplicatedRecord));
+        let orders = db.user_orders(&owner, 0, None).await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].metadata.status, OrderStatus::Open);
+
+        db.insert_orders(&[(order(2), Some(OrderUid([1; 56]))), (order(3), None)])
+            .await
+            .unwrap();
+        let mut order_statuses = db
+            .user_orders(&owner, 0, None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|order| (order.metadata.uid, order.metadata.status))
+            .collect::<Vec<_>>();
+        order_statuses.sort_by_key(|(uid, _)| uid.0);
+        assert_eq!(
+            order_statuses,
+            vec![
+                (OrderUid([1; 56]), OrderStatus::Cancelled),
+                (OrderUid([2; 56]), OrderStatus::Open),
+                (OrderUid([3; 56]), OrderStatus::Open),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_order_with_condition() {
+        let db = Postgres::try_new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+        let order = Order {
+            metadata: OrderMetadata {
+                uid: OrderUid([1; 56]),
+                condition: Some(OrderCondition::Twap {
+                    parts: 4,
+                    start_time: 1_700_000_000,
+                    part_duration: 3600,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        db.insert_order(&order).await.unwrap();
+
+        let stored = db.single_order(&order.metadata.uid).await.unwrap().unwrap();
+        assert_eq!(stored.metadata.condition, order.metadata.condition);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_presignature_status() {
@@ -1094,6 +1423,53 @@ mod tests {
         assert_eq!(order_status(3).await, OrderStatus::Open);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_paginated_user_orders_scans_limited_pages() {
+        let db = Postgres::try_new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+
+        let uid = |byte: u8| OrderUid([byte; 56]);
+        let order = |byte: u8| Order {
+            data: OrderData {
+                valid_to: u32::MAX,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: uid(byte),
+                creation_date: DateTime::from_timestamp(i64::from(byte), 0).unwrap(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pages = u8::try_from(MAX_SCANNED_PAGES).unwrap();
+        for byte in 1..=pages + 1 {
+            db.insert_order(&order(byte)).await.unwrap();
+        }
+        // Only the oldest order matches the filter.
+        db.cancel_order(&uid(1), Utc::now()).await.unwrap();
+
+        let mut filter = UserOrderFilter {
+            status: Some(OrderStatus::Cancelled),
+            ..Default::default()
+        };
+        let (orders, cursor) = db
+            .paginated_user_orders(&H160::zero(), &filter, 1)
+            .await
+            .unwrap();
+        assert!(orders.is_empty());
+        let cursor = cursor.unwrap();
+        assert_eq!(cursor.uid, uid(2));
+
+        filter.after = Some(cursor);
+        let (orders, _) = db
+            .paginated_user_orders(&H160::zero(), &filter, 1)
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].metadata.uid, uid(1));
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_insert_orders_with_interactions() {
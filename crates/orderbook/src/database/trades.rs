@@ -13,6 +13,14 @@ use {
 #[async_trait::async_trait]
 pub trait TradeRetrieving: Send + Sync {
     async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>>;
+    /// Up to `limit` trades matching the filter ordered by block number and
+    /// log index descending (newest trades first). Also returns the position
+    /// to continue from if there might be more matching trades.
+    async fn paginated_trades(
+        &self,
+        filter: &PaginatedTradeFilter,
+        limit: u64,
+    ) -> Result<(Vec<Trade>, Option<TradeCursor>)>;
 }
 
 /// Any default value means that this field is unfiltered.
@@ -22,6 +30,26 @@ pub struct TradeFilter {
     pub order_uid: Option<OrderUid>,
 }
 
+/// Position of a trade in the trade history.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TradeCursor {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+/// Any default value means that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PaginatedTradeFilter {
+    pub owner: Option<H160>,
+    pub order_uid: Option<OrderUid>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    /// Only trades that come after this position in the history.
+    pub after: Option<TradeCursor>,
+}
+
 #[async_trait::async_trait]
 impl TradeRetrieving for Postgres {
     async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>> {
@@ -41,6 +69,52 @@ impl TradeRetrieving for Postgres {
         .await?;
         timer.stop_and_record();
 
+        self.trades_with_protocol_fees(trades).await
+    }
+
+    async fn paginated_trades(
+        &self,
+        filter: &PaginatedTradeFilter,
+        limit: u64,
+    ) -> Result<(Vec<Trade>, Option<TradeCursor>)> {
+        let timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["paginated_trades"])
+            .start_timer();
+
+        let db_filter = database::trades::TradeFilter {
+            owner: filter.owner.map(|owner| ByteArray(owner.0)),
+            order_uid: filter.order_uid.map(|uid| ByteArray(uid.0)),
+            sell_token: filter.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: filter.buy_token.map(|token| ByteArray(token.0)),
+            from_block: filter.from_block.map(to_i64),
+            to_block: filter.to_block.map(to_i64),
+            after: filter.after.map(|cursor| database::trades::TradeCursor {
+                block_number: to_i64(cursor.block_number),
+                log_index: to_i64(cursor.log_index),
+            }),
+        };
+        let mut ex = self.pool.acquire().await?;
+        let trades = database::trades::paginated_trades(&mut ex, &db_filter, to_i64(limit))
+            .map_err(anyhow::Error::from)
+            .try_collect::<Vec<TradesQueryRow>>()
+            .await?;
+        timer.stop_and_record();
+
+        let trades = self.trades_with_protocol_fees(trades).await?;
+        let cursor = match trades.last() {
+            Some(last) if trades.len() as u64 == limit => Some(TradeCursor {
+                block_number: last.block_number,
+                log_index: last.log_index,
+            }),
+            _ => None,
+        };
+        Ok((trades, cursor))
+    }
+}
+
+impl Postgres {
+    async fn trades_with_protocol_fees(&self, trades: Vec<TradesQueryRow>) -> Result<Vec<Trade>> {
         let auction_order_uids = trades
             .iter()
             .filter_map(|t| t.auction_id.map(|auction_id| (auction_id, t.order_uid)))
@@ -67,6 +141,10 @@ impl TradeRetrieving for Postgres {
     }
 }
 
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
 fn trade_from(
     row: TradesQueryRow,
     executed_protocol_fees: Vec<ExecutedProtocolFee>,
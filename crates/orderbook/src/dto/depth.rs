@@ -0,0 +1,33 @@
+use {
+    number::serialization::HexOrDecimalU256,
+    primitive_types::{H160, U256},
+    serde::Serialize,
+    serde_with::serde_as,
+};
+
+/// Resting limit orders of a token pair aggregated by limit price.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderbookDepth {
+    pub sell_token: H160,
+    pub buy_token: H160,
+    /// Price levels ordered by price ascending, i.e. the levels asking for the
+    /// fewest buy tokens per sell token come first.
+    pub levels: Vec<PriceLevel>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceLevel {
+    /// Limit price in buy token atoms per sell token atom.
+    pub price: f64,
+    /// Remaining sell amount of all orders at this price.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub sell_amount: U256,
+    /// Remaining buy amount of all orders at this price.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub buy_amount: U256,
+    /// Number of orders at this price.
+    pub orders: usize,
+}
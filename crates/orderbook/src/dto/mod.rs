@@ -1,8 +1,10 @@
 pub mod auction;
+pub mod depth;
 pub mod order;
 
 pub use {
     auction::{Auction, AuctionId, AuctionWithId},
+    depth::{OrderbookDepth, PriceLevel},
     order::Order,
 };
 use {
@@ -2,11 +2,18 @@ use {
     app_data::AppDataHash,
     model::{
         interaction::InteractionData,
-        order::{BuyTokenDestination, OrderClass, OrderKind, OrderUid, SellTokenSource},
+        order::{
+            BuyTokenDestination,
+            OrderClass,
+            OrderFilterReason,
+            OrderKind,
+            OrderUid,
+            SellTokenSource,
+        },
         signature::Signature,
     },
     number::serialization::HexOrDecimalU256,
-    primitive_types::{H160, U256},
+    primitive_types::{H160, H256, U256},
     serde::{Deserialize, Serialize},
     serde_with::serde_as,
 };
@@ -102,8 +109,9 @@ pub struct SolutionInclusion {
 pub enum Status {
     /// Order is part of the orderbook but not actively being worked on. This
     /// can for example happen if the necessary balances are missing or if
-    /// the order's signature check fails.
-    Open,
+    /// the order's signature check fails. Contains the reason why the order
+    /// did not get included in the latest auction if it is known.
+    Open(Option<OrderFilterReason>),
     /// Order awaits being put into the current auction.
     Scheduled,
     /// Order is part of the current and solvers are computing solutions for it.
@@ -118,4 +126,9 @@ pub enum Status {
     Traded(Vec<SolutionInclusion>),
     /// The user cancelled the order. It will no longer show up in any auctions.
     Cancelled,
+    /// The user requested the expired ethflow order to be refunded and the
+    /// refunder did not refund it yet.
+    RefundPending,
+    /// The expired ethflow order got refunded in the contained transaction.
+    Refunded(H256),
 }
@@ -0,0 +1,84 @@
+//! Forwards order events inserted into the database to API subscribers so
+//! they get notified about order status changes without having to poll.
+
+use {
+    database::order_events::OrderEventLabel,
+    model::order::OrderUid,
+    sqlx::PgPool,
+    std::time::Duration,
+    tokio::sync::broadcast,
+};
+
+/// How many events can be buffered for a subscriber before it starts missing
+/// events.
+const CAPACITY: usize = 1024;
+
+/// How long to wait before trying to listen for notifications again after the
+/// database connection failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// An event in the life cycle of an order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderEvent {
+    pub uid: OrderUid,
+    pub label: OrderEventLabel,
+}
+
+/// Broadcasts every order event that gets stored in the database.
+pub struct OrderEventStream {
+    sender: broadcast::Sender<OrderEvent>,
+}
+
+impl OrderEventStream {
+    /// Creates a stream that gets fed by notifications published on the
+    /// database's order events channel. The listener runs in a background
+    /// task for as long as the process lives.
+    pub fn spawn(pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        tokio::task::spawn(forward_notifications(pool, sender.clone()));
+        Self { sender }
+    }
+
+    /// Returns a receiver for all order events registered from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEvent> {
+        self.sender.subscribe()
+    }
+}
+
+async fn forward_notifications(pool: PgPool, sender: broadcast::Sender<OrderEvent>) {
+    loop {
+        let mut listener = match database::order_events::listen(&pool).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::warn!(?err, "failed to listen for order event notifications");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        // Lost connections get re-established by the listener itself. Events
+        // that happen while it's disconnected are not delivered.
+        loop {
+            let notification = match listener.recv().await {
+                Ok(notification) => notification,
+                Err(err) => {
+                    tracing::warn!(?err, "failed to receive order event notification");
+                    break;
+                }
+            };
+            let Some((uid, label)) =
+                database::order_events::parse_notification(notification.payload())
+            else {
+                tracing::warn!(
+                    payload = notification.payload(),
+                    "malformed order event notification"
+                );
+                continue;
+            };
+            // Sending only fails if nobody is subscribed which is fine.
+            let _ = sender.send(OrderEvent {
+                uid: OrderUid(uid.0),
+                label,
+            });
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use {
     crate::{
         database::{
-            orders::{InsertionError, OrderStoring},
+            orders::{InsertionError, OrderCursor, OrderStoring, UserOrderFilter},
             trades::{TradeFilter, TradeRetrieving},
         },
         dto,
@@ -10,6 +10,7 @@ use {
     anyhow::{Context, Result},
     app_data::{AppDataHash, Validator},
     bigdecimal::ToPrimitive,
+    cached::{Cached, TimedSizedCache},
     chrono::Utc,
     database::order_events::OrderEventLabel,
     ethcontract::H256,
@@ -28,8 +29,9 @@ use {
         solver_competition::{self, SolverCompetitionAPI},
     },
     observe::metrics::LivenessChecking,
-    primitive_types::H160,
+    primitive_types::{H160, U256},
     shared::{
+        account_balances::{BalanceFetching, Query},
         fee::FeeParameters,
         order_quoting::Quote,
         order_validation::{
@@ -38,8 +40,15 @@ use {
             ValidationError,
             is_order_outside_market_price,
         },
+        remaining_amounts,
+    },
+    std::{
+        borrow::Cow,
+        cmp::Ordering,
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+        time::Duration,
     },
-    std::{borrow::Cow, sync::Arc},
     strum_macros::Display,
     thiserror::Error,
 };
@@ -73,28 +82,7 @@ impl Metrics {
     }
 
     fn on_order_operation(order: &Order, operation: OrderOperation) {
-        let class = if order.metadata.quote.as_ref().is_some_and(|quote| {
-            // Check if the order at the submission time was "in market"
-            !is_order_outside_market_price(
-                &Amounts {
-                    sell: order.data.sell_amount,
-                    buy: order.data.buy_amount,
-                    fee: order.data.fee_amount,
-                },
-                &Amounts {
-                    sell: quote.sell_amount,
-                    buy: quote.buy_amount,
-                    fee: FeeParameters {
-                        // safe to unwrap as these values were converted from f64 previously
-                        gas_amount: quote.gas_amount.to_f64().unwrap(),
-                        gas_price: quote.gas_price.to_f64().unwrap(),
-                        sell_token_price: quote.sell_token_price.to_f64().unwrap(),
-                    }
-                    .fee(),
-                },
-                order.data.kind,
-            )
-        }) {
+        let class = if is_in_market(order) == Some(true) {
             OrderClass::Market
         } else {
             OrderClass::Limit
@@ -119,6 +107,31 @@ impl Metrics {
     }
 }
 
+/// Returns whether the order was "in market" at submission time or `None` if
+/// the order was placed without a quote.
+fn is_in_market(order: &Order) -> Option<bool> {
+    let quote = order.metadata.quote.as_ref()?;
+    Some(!is_order_outside_market_price(
+        &Amounts {
+            sell: order.data.sell_amount,
+            buy: order.data.buy_amount,
+            fee: order.data.fee_amount,
+        },
+        &Amounts {
+            sell: quote.sell_amount,
+            buy: quote.buy_amount,
+            fee: FeeParameters {
+                // safe to unwrap as these values were converted from f64 previously
+                gas_amount: quote.gas_amount.to_f64().unwrap(),
+                gas_price: quote.gas_price.to_f64().unwrap(),
+                sell_token_price: quote.sell_token_price.to_f64().unwrap(),
+            }
+            .fee(),
+        },
+        order.data.kind,
+    ))
+}
+
 #[derive(Debug, Error)]
 pub enum AddOrderError {
     #[error("unable to find an existing order: {0}")]
@@ -171,6 +184,14 @@ impl AddOrderError {
     }
 }
 
+#[derive(Debug, Error)]
+pub enum AddOrdersError {
+    #[error("{} orders of the batch are invalid", .0.len())]
+    InvalidOrders(Vec<(usize, AddOrderError)>),
+    #[error("database error: {0}")]
+    Database(#[from] anyhow::Error),
+}
+
 // This requires a manual implementation because the `#[from]` attribute from
 // `thiserror` implies `#[source]` which requires `ValidationError: Error`,
 // which it currently does not!
@@ -200,6 +221,26 @@ pub enum OrderCancellationError {
     Other(#[from] anyhow::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum RefundRequestError {
+    #[error("order not found")]
+    OrderNotFound,
+    #[error("order is not an ethflow order")]
+    NotEthflowOrder,
+    #[error("order is partially fillable")]
+    PartiallyFillable,
+    #[error("order fully executed")]
+    OrderFullyExecuted,
+    #[error("order not expired")]
+    NotExpired,
+    #[error("order already refunded")]
+    AlreadyRefunded,
+    #[error("order was not valid long enough to get refunded")]
+    ValidityTooShort,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 #[derive(Debug, Error)]
 pub enum OrderReplacementError {
     #[error("invalid signature")]
@@ -234,8 +275,22 @@ pub struct Orderbook {
     order_validator: Arc<dyn OrderValidating>,
     app_data: Arc<crate::app_data::Registry>,
     active_order_competition_threshold: u32,
+    balance_fetcher: Arc<dyn BalanceFetching>,
+    /// Recently computed depths by token pair, so repeated requests don't
+    /// query the database and the balances over and over again.
+    depth_cache: Mutex<TimedSizedCache<(H160, H160), dto::OrderbookDepth>>,
+    /// Ethflow orders with a shorter validity never get refunded by the
+    /// refunder.
+    ethflow_refund_min_validity: Duration,
 }
 
+/// Maximum number of orders aggregated into the depth of a token pair. Only
+/// the orders with the best limit prices get considered beyond that.
+const MAX_DEPTH_ORDERS: u64 = 1000;
+
+/// Number of seconds a computed depth gets served from the cache.
+const DEPTH_CACHE_LIFESPAN: u64 = 5;
+
 impl Orderbook {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -245,6 +300,8 @@ impl Orderbook {
         order_validator: Arc<dyn OrderValidating>,
         app_data: Arc<crate::app_data::Registry>,
         active_order_competition_threshold: u32,
+        balance_fetcher: Arc<dyn BalanceFetching>,
+        ethflow_refund_min_validity: Duration,
     ) -> Self {
         Metrics::initialize();
         Self {
@@ -254,6 +311,13 @@ impl Orderbook {
             order_validator,
             app_data,
             active_order_competition_threshold,
+            balance_fetcher,
+            depth_cache: Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
+                1000,
+                DEPTH_CACHE_LIFESPAN,
+                false,
+            )),
+            ethflow_refund_min_validity,
         }
     }
 
@@ -261,6 +325,123 @@ impl Orderbook {
         &self,
         payload: OrderCreation,
     ) -> Result<(OrderUid, Option<QuoteMetadata>), AddOrderError> {
+        let (order, quote, replaced_order, _) = self.validate_order(payload).await?;
+        let order_uid = order.metadata.uid;
+
+        // Check if it has to replace an existing order
+        if let Some(old_order) = replaced_order {
+            self.replace_order(order, old_order).await?
+        } else {
+            self.database
+                .insert_order(&order)
+                .await
+                .map_err(|err| AddOrderError::from_insertion(err, &order))?;
+            Metrics::on_order_operation(&order, OrderOperation::Created);
+        }
+
+        Ok((order_uid, quote.as_ref().map(QuoteMetadata::from)))
+    }
+
+    /// Adds all orders of the batch or none of them. The orders get validated
+    /// concurrently and are inserted in a single database transaction.
+    pub async fn add_orders(
+        &self,
+        payloads: Vec<OrderCreation>,
+    ) -> Result<Vec<(OrderUid, Option<QuoteMetadata>)>, AddOrdersError> {
+        let results = futures::future::join_all(payloads.into_iter().map(|payload| async move {
+            let (order, quote, replaced_order, is_limit_order) =
+                self.validate_order(payload).await?;
+            if let Some(old_order) = &replaced_order {
+                self.check_replacement(&order, old_order).await?;
+            }
+            Ok((order, quote, replaced_order, is_limit_order))
+        }))
+        .await;
+
+        let mut errors = Vec::new();
+        let mut orders = Vec::with_capacity(results.len());
+        let mut uids = HashSet::new();
+        // Every order was checked against the limit order count of its owner
+        // individually, so we still need to make sure that all the new limit
+        // orders together don't exceed the limit.
+        let mut limit_orders = HashMap::<H160, Vec<usize>>::new();
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok((order, ..)) if !uids.insert(order.metadata.uid) => {
+                    errors.push((index, AddOrderError::DuplicatedOrder));
+                }
+                Ok((order, quote, replaced_order, is_limit_order)) => {
+                    if is_limit_order {
+                        limit_orders
+                            .entry(order.metadata.owner)
+                            .or_default()
+                            .push(index);
+                    }
+                    orders.push((order, quote, replaced_order));
+                }
+                Err(err) => errors.push((index, err)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(AddOrdersError::InvalidOrders(errors));
+        }
+
+        for (owner, indices) in limit_orders {
+            let new_orders = u64::try_from(indices.len()).unwrap_or(u64::MAX);
+            match self
+                .order_validator
+                .check_limit_order_capacity(owner, new_orders)
+                .await
+            {
+                Ok(()) => (),
+                Err(ValidationError::TooManyLimitOrders) => errors.extend(
+                    indices
+                        .into_iter()
+                        .map(|index| (index, ValidationError::TooManyLimitOrders.into())),
+                ),
+                Err(err) => errors.push((indices[0], err.into())),
+            }
+        }
+        if !errors.is_empty() {
+            errors.sort_by_key(|(index, _)| *index);
+            return Err(AddOrdersError::InvalidOrders(errors));
+        }
+
+        let entries = orders
+            .iter()
+            .map(|(order, _, replaced_order)| {
+                let replaced_order = replaced_order.as_ref().map(|order| order.metadata.uid);
+                (order.clone(), replaced_order)
+            })
+            .collect::<Vec<_>>();
+        self.database.insert_orders(&entries).await.map_err(|err| {
+            match (err.index, err.error) {
+                (Some(index), error) => AddOrdersError::InvalidOrders(vec![(
+                    index,
+                    AddOrderError::from_insertion(error, &entries[index].0),
+                )]),
+                (None, error) => AddOrdersError::Database(anyhow::anyhow!("{error:?}")),
+            }
+        })?;
+
+        Ok(orders
+            .into_iter()
+            .map(|(order, quote, replaced_order)| {
+                if let Some(old_order) = &replaced_order {
+                    Metrics::on_order_operation(old_order, OrderOperation::Cancelled);
+                }
+                Metrics::on_order_operation(&order, OrderOperation::Created);
+                (order.metadata.uid, quote.as_ref().map(QuoteMetadata::from))
+            })
+            .collect())
+    }
+
+    /// Validates the order and finds the existing order it replaces, if any.
+    /// Also returns whether the order counts towards the owner's limit orders.
+    async fn validate_order(
+        &self,
+        payload: OrderCreation,
+    ) -> Result<(Order, Option<Quote>, Option<Order>, bool), AddOrderError> {
         let full_app_data_override = match payload.app_data {
             OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
             _ => None,
@@ -270,7 +451,7 @@ impl Orderbook {
             .get_replaced_order(&payload, full_app_data_override.as_deref())
             .await?;
 
-        let (order, quote) = self
+        let (order, quote, is_limit_order) = self
             .order_validator
             .validate_and_construct_order(
                 payload,
@@ -280,20 +461,7 @@ impl Orderbook {
             )
             .await?;
 
-        let order_uid = order.metadata.uid;
-
-        // Check if it has to replace an existing order
-        if let Some(old_order) = replaced_order {
-            self.replace_order(order, old_order).await?
-        } else {
-            self.database
-                .insert_order(&order)
-                .await
-                .map_err(|err| AddOrderError::from_insertion(err, &order))?;
-            Metrics::on_order_operation(&order, OrderOperation::Created);
-        }
-
-        Ok((order_uid, quote.as_ref().map(QuoteMetadata::from)))
+        Ok((order, quote, replaced_order, is_limit_order))
     }
 
     /// Finds an order for cancellation.
@@ -382,6 +550,24 @@ impl Orderbook {
         Ok(())
     }
 
+    /// Requests the refunder to refund the expired ethflow order regardless of
+    /// its price deviation. Refunds always go to the user who placed the
+    /// order, so anybody is allowed to request them.
+    pub async fn request_ethflow_refund(&self, uid: &OrderUid) -> Result<(), RefundRequestError> {
+        let order = self
+            .database
+            .single_order(uid)
+            .await?
+            .ok_or(RefundRequestError::OrderNotFound)?;
+        check_refundable(&order, self.ethflow_refund_min_validity)?;
+
+        self.database
+            .insert_ethflow_refund_request(uid, Utc::now())
+            .await?;
+        tracing::debug!(order_uid =% uid, "ethflow refund requested");
+        Ok(())
+    }
+
     async fn get_replaced_order(
         &self,
         new_order: &OrderCreation,
@@ -414,6 +600,25 @@ impl Orderbook {
         &self,
         validated_new_order: Order,
         old_order: Order,
+    ) -> Result<(), AddOrderError> {
+        self.check_replacement(&validated_new_order, &old_order)
+            .await?;
+
+        self.database
+            .replace_order(&old_order.metadata.uid, &validated_new_order)
+            .await
+            .map_err(|err| AddOrderError::from_insertion(err, &validated_new_order))?;
+        Metrics::on_order_operation(&old_order, OrderOperation::Cancelled);
+        Metrics::on_order_operation(&validated_new_order, OrderOperation::Created);
+
+        Ok(())
+    }
+
+    /// Verifies that the new order is allowed to replace the old order.
+    async fn check_replacement(
+        &self,
+        validated_new_order: &Order,
+        old_order: &Order,
     ) -> Result<(), AddOrderError> {
         // Replacement order signatures need to be validated meaning we cannot
         // accept `PreSign` orders, otherwise anyone can cancel a user order by
@@ -445,13 +650,6 @@ impl Orderbook {
             ));
         }
 
-        self.database
-            .replace_order(&old_order.metadata.uid, &validated_new_order)
-            .await
-            .map_err(|err| AddOrderError::from_insertion(err, &validated_new_order))?;
-        Metrics::on_order_operation(&old_order, OrderOperation::Cancelled);
-        Metrics::on_order_operation(&validated_new_order, OrderOperation::Created);
-
         Ok(())
     }
 
@@ -505,6 +703,73 @@ impl Orderbook {
             .context("get_user_orders error")
     }
 
+    pub async fn get_user_orders_page(
+        &self,
+        owner: &H160,
+        filter: &UserOrderFilter,
+        limit: u64,
+    ) -> Result<(Vec<Order>, Option<OrderCursor>)> {
+        self.database
+            .paginated_user_orders(owner, filter, limit)
+            .await
+            .context("get_user_orders_page error")
+    }
+
+    /// Aggregates the open limit orders selling `sell_token` for `buy_token`
+    /// into price levels. Order amounts are scaled down to what is left to be
+    /// filled and, where the owner's balance could be fetched, to what the
+    /// owner can actually pay for. Results are cached for a few seconds.
+    pub async fn get_orderbook_depth(
+        &self,
+        sell_token: H160,
+        buy_token: H160,
+    ) -> Result<dto::OrderbookDepth> {
+        if let Some(depth) = self
+            .depth_cache
+            .lock()
+            .unwrap()
+            .cache_get(&(sell_token, buy_token))
+        {
+            return Ok(depth.clone());
+        }
+
+        let orders = self
+            .database
+            .open_limit_orders_for_pair(&sell_token, &buy_token, MAX_DEPTH_ORDERS)
+            .await
+            .context("get_orderbook_depth error")?;
+
+        let queries = orders
+            .iter()
+            .map(Query::from_order)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let balances = self.balance_fetcher.get_balances(&queries).await;
+        let balances = queries
+            .into_iter()
+            .zip(balances)
+            .filter_map(|(query, balance)| match balance {
+                Ok(balance) => Some((query, balance)),
+                Err(err) => {
+                    tracing::debug!(?query, ?err, "failed to fetch balance for depth");
+                    None
+                }
+            })
+            .collect::<HashMap<_, _>>();
+
+        let depth = dto::OrderbookDepth {
+            sell_token,
+            buy_token,
+            levels: price_levels(&orders, &balances),
+        };
+        self.depth_cache
+            .lock()
+            .unwrap()
+            .cache_set((sell_token, buy_token), depth.clone());
+        Ok(depth)
+    }
+
     pub async fn get_order_status(
         &self,
         uid: &OrderUid,
@@ -569,8 +834,20 @@ impl Orderbook {
             None => (),
         }
 
-        let latest_event = self.database.latest_order_event(uid).await?;
-        let status = match latest_event.ok_or(OrderStatusError::NotFound)?.label {
+        if let Some(ethflow_order) = self.database.ethflow_order(uid).await? {
+            match (ethflow_order.refund_tx, ethflow_order.refund_requested) {
+                (Some(tx_hash), _) => return Ok(dto::order::Status::Refunded(H256(tx_hash.0))),
+                (None, true) => return Ok(dto::order::Status::RefundPending),
+                (None, false) => (),
+            }
+        }
+
+        let latest_event = self
+            .database
+            .latest_order_event(uid)
+            .await?
+            .ok_or(OrderStatusError::NotFound)?;
+        let status = match latest_event.label {
             OrderEventLabel::Ready => dto::order::Status::Active,
             OrderEventLabel::Created => dto::order::Status::Scheduled,
             OrderEventLabel::Considered => dto::order::Status::Solved(latest_competition.await?),
@@ -578,8 +855,13 @@ impl Orderbook {
             // order executed but not fully indexed and processed
             OrderEventLabel::Traded => dto::order::Status::Traded(latest_competition.await?),
             OrderEventLabel::Cancelled => dto::order::Status::Cancelled,
-            OrderEventLabel::Filtered => dto::order::Status::Open,
-            OrderEventLabel::Invalid => dto::order::Status::Open,
+            OrderEventLabel::Filtered | OrderEventLabel::Invalid => {
+                // Events stored before reasons were recorded don't have one.
+                let reason = latest_event
+                    .reason
+                    .and_then(|reason| serde_json::from_value(reason).ok());
+                dto::order::Status::Open(reason)
+            }
         };
         Ok(status)
     }
@@ -609,6 +891,92 @@ impl LivenessChecking for Orderbook {
     }
 }
 
+/// Groups orders by limit price, summing up their remaining amounts. Orders
+/// without a known balance are assumed to be fully funded.
+fn price_levels(orders: &[Order], balances: &HashMap<Query, U256>) -> Vec<dto::PriceLevel> {
+    let mut remaining = orders
+        .iter()
+        .filter(|order| !order.data.sell_amount.is_zero())
+        .filter_map(|order| {
+            let balance = balances
+                .get(&Query::from_order(order))
+                .copied()
+                .unwrap_or(U256::MAX);
+            let remaining =
+                remaining_amounts::Remaining::from_order_with_balance(&order.into(), balance)
+                    .ok()?;
+            let sell = remaining.remaining(order.data.sell_amount).ok()?;
+            let buy = remaining.remaining(order.data.buy_amount).ok()?;
+            (!sell.is_zero()).then_some((order, sell, buy))
+        })
+        .collect::<Vec<_>>();
+
+    // Compare limit prices exactly so orders with the same price end up in
+    // the same level regardless of their size.
+    let price_cmp = |a: &Order, b: &Order| {
+        a.data
+            .buy_amount
+            .full_mul(b.data.sell_amount)
+            .cmp(&b.data.buy_amount.full_mul(a.data.sell_amount))
+    };
+    remaining.sort_by(|(a, ..), (b, ..)| price_cmp(a, b));
+
+    let mut levels: Vec<(&Order, dto::PriceLevel)> = Vec::new();
+    for (order, sell, buy) in remaining {
+        match levels.last_mut() {
+            Some((first, level)) if price_cmp(first, order) == Ordering::Equal => {
+                level.sell_amount = level.sell_amount.saturating_add(sell);
+                level.buy_amount = level.buy_amount.saturating_add(buy);
+                level.orders += 1;
+            }
+            _ => levels.push((
+                order,
+                dto::PriceLevel {
+                    price: order.data.buy_amount.to_f64_lossy()
+                        / order.data.sell_amount.to_f64_lossy(),
+                    sell_amount: sell,
+                    buy_amount: buy,
+                    orders: 1,
+                },
+            )),
+        }
+    }
+    levels.into_iter().map(|(_, level)| level).collect()
+}
+
+/// Checks whether the refunder would refund the order once it got requested.
+fn check_refundable(order: &Order, min_validity: Duration) -> Result<(), RefundRequestError> {
+    let ethflow_data = order
+        .metadata
+        .ethflow_data
+        .as_ref()
+        .ok_or(RefundRequestError::NotEthflowOrder)?;
+    if ethflow_data.refund_tx_hash.is_some() {
+        return Err(RefundRequestError::AlreadyRefunded);
+    }
+    // The refunder only refunds fill-or-kill orders.
+    if order.data.partially_fillable {
+        return Err(RefundRequestError::PartiallyFillable);
+    }
+    // The refunder skips orders that were valid for a too short time.
+    if ethflow_data.user_valid_to - order.metadata.creation_date.timestamp()
+        <= min_validity.as_secs() as i64
+    {
+        return Err(RefundRequestError::ValidityTooShort);
+    }
+    match order.metadata.status {
+        OrderStatus::Expired => (),
+        OrderStatus::Fulfilled => return Err(RefundRequestError::OrderFullyExecuted),
+        // Ethflow orders get cancelled by invalidating them on-chain which
+        // already refunds the user.
+        OrderStatus::Cancelled => return Err(RefundRequestError::AlreadyRefunded),
+        OrderStatus::Open | OrderStatus::PresignaturePending => {
+            return Err(RefundRequestError::NotExpired);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -617,10 +985,10 @@ mod tests {
         ethcontract::H160,
         mockall::predicate::eq,
         model::{
-            order::{OrderData, OrderMetadata},
+            order::{EthflowData, OrderData, OrderMetadata},
             signature::Signature,
         },
-        shared::order_validation::MockOrderValidating,
+        shared::{account_balances::MockBalanceFetching, order_validation::MockOrderValidating},
     };
 
     #[tokio::test]
@@ -666,6 +1034,7 @@ mod tests {
                         ..Default::default()
                     },
                     Default::default(),
+                    false,
                 ))
             });
 
@@ -684,6 +1053,9 @@ mod tests {
             settlement_contract: H160([0xba; 20]),
             app_data,
             active_order_competition_threshold: Default::default(),
+            balance_fetcher: Arc::new(MockBalanceFetching::new()),
+            depth_cache: Mutex::new(TimedSizedCache::with_size_and_lifespan(1, 1)),
+            ethflow_refund_min_validity: Default::default(),
         };
 
         // Different owner
@@ -763,4 +1135,93 @@ mod tests {
             .unwrap();
         assert_eq!(order_id, new_order_uid,);
     }
+
+    #[test]
+    fn price_levels_aggregate_remaining_amounts() {
+        let order = |owner: u8, sell: u64, buy: u64, executed: u64, partially_fillable| Order {
+            metadata: OrderMetadata {
+                owner: H160([owner; 20]),
+                executed_sell_amount_before_fees: executed.into(),
+                ..Default::default()
+            },
+            data: OrderData {
+                sell_token: H160([1; 20]),
+                buy_token: H160([2; 20]),
+                sell_amount: sell.into(),
+                buy_amount: buy.into(),
+                partially_fillable,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let orders = vec![
+            // Worse price.
+            order(1, 100, 300, 0, false),
+            // Same price as the next order, half filled already.
+            order(2, 100, 200, 50, true),
+            order(3, 10, 20, 0, false),
+            // Owner can only pay for a quarter of the order.
+            order(4, 400, 400, 0, true),
+            // Fully filled.
+            order(5, 100, 100, 100, true),
+            // Fill-or-kill order without enough balance.
+            order(6, 100, 100, 0, false),
+        ];
+        let balances = HashMap::from([
+            (Query::from_order(&orders[3]), U256::from(100)),
+            (Query::from_order(&orders[5]), U256::from(99)),
+        ]);
+
+        assert_eq!(
+            price_levels(&orders, &balances),
+            vec![
+                dto::PriceLevel {
+                    price: 1.,
+                    sell_amount: 100.into(),
+                    buy_amount: 100.into(),
+                    orders: 1,
+                },
+                dto::PriceLevel {
+                    price: 2.,
+                    sell_amount: 60.into(),
+                    buy_amount: 120.into(),
+                    orders: 2,
+                },
+                dto::PriceLevel {
+                    price: 3.,
+                    sell_amount: 100.into(),
+                    buy_amount: 300.into(),
+                    orders: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_refunds_of_orders_with_too_short_validity() {
+        let creation_date = chrono::DateTime::from_timestamp(1_000, 0).unwrap();
+        let order = |user_valid_to| Order {
+            metadata: OrderMetadata {
+                creation_date,
+                status: OrderStatus::Expired,
+                ethflow_data: Some(EthflowData {
+                    user_valid_to,
+                    refund_tx_hash: None,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let min_validity = Duration::from_secs(120);
+
+        assert!(check_refundable(&order(1_121), min_validity).is_ok());
+        assert!(matches!(
+            check_refundable(&order(1_120), min_validity),
+            Err(RefundRequestError::ValidityTooShort)
+        ));
+        assert!(matches!(
+            check_refundable(&order(1_060), min_validity),
+            Err(RefundRequestError::ValidityTooShort)
+        ));
+    }
 }
@@ -1,12 +1,14 @@
 use {
     crate::app_data,
+    anyhow::anyhow,
     chrono::{TimeZone, Utc},
+    futures::{Stream, StreamExt},
     model::{
         order::OrderCreationAppData,
         quote::{OrderQuote, OrderQuoteRequest, OrderQuoteResponse, PriceQuality},
     },
     shared::{
-        order_quoting::{CalculateQuoteError, OrderQuoting, QuoteParameters},
+        order_quoting::{CalculateQuoteError, OrderQuoting, Quote, QuoteParameters},
         order_validation::{
             AppDataValidationError,
             OrderValidating,
@@ -55,6 +57,106 @@ impl QuoteHandler {
     ) -> Result<OrderQuoteResponse, OrderQuoteError> {
         tracing::debug!(?request, "calculating quote");
 
+        let (params, valid_to) = self.quote_parameters(request).await?;
+        let quote = match request.price_quality {
+            PriceQuality::Optimal | PriceQuality::Verified => {
+                let quote = self.optimal_quoter.calculate_quote(params).await?;
+                self.optimal_quoter
+                    .store_quote(quote)
+                    .await
+                    .map_err(CalculateQuoteError::Other)?
+            }
+            PriceQuality::Fast => {
+                let quote = self.fast_quoter.calculate_quote(params).await?;
+                fast_quote(quote)
+            }
+        };
+
+        let response = quote_response(request, valid_to, &quote);
+        tracing::debug!(?response, "finished computing quote");
+        Ok(response)
+    }
+
+    /// Like [`QuoteHandler::calculate_quote`] but reports a quote for every
+    /// price source as soon as it returns. Once all price sources returned,
+    /// the best quote gets stored and reported as the final quote.
+    pub fn calculate_quote_stream(
+        self: Arc<Self>,
+        request: OrderQuoteRequest,
+    ) -> impl Stream<Item = QuoteStreamUpdate> + Send + 'static {
+        async_stream::stream! {
+            tracing::debug!(?request, "streaming quote");
+
+            let (params, valid_to) = match self.quote_parameters(&request).await {
+                Ok(params) => params,
+                Err(err) => {
+                    yield QuoteStreamUpdate::Error(err);
+                    return;
+                }
+            };
+            let quoter = match request.price_quality {
+                PriceQuality::Optimal | PriceQuality::Verified => &self.optimal_quoter,
+                PriceQuality::Fast => &self.fast_quoter,
+            };
+
+            let mut best = None;
+            let mut last_error = None;
+            let mut updates = quoter.calculate_quote_stream(params);
+            while let Some(update) = updates.next().await {
+                let quote = match update.result {
+                    Ok(quote) => quote,
+                    Err(err) => {
+                        tracing::debug!(?err, "price source failed to quote");
+                        last_error = Some(err);
+                        continue;
+                    }
+                };
+                let quote = match request.price_quality {
+                    PriceQuality::Fast => fast_quote(quote),
+                    PriceQuality::Optimal | PriceQuality::Verified => quote,
+                };
+                let response = quote_response(&request, valid_to, &quote);
+                if update.best {
+                    best = Some(quote);
+                }
+                yield QuoteStreamUpdate::Quote {
+                    response,
+                    best: update.best,
+                };
+            }
+
+            let Some(quote) = best else {
+                let err = last_error.unwrap_or_else(|| {
+                    CalculateQuoteError::Other(anyhow!("no price source returned a quote"))
+                });
+                yield QuoteStreamUpdate::Error(err.into());
+                return;
+            };
+            let quote = match request.price_quality {
+                PriceQuality::Optimal | PriceQuality::Verified => {
+                    match quoter.store_quote(quote).await {
+                        Ok(quote) => quote,
+                        Err(err) => {
+                            yield QuoteStreamUpdate::Error(CalculateQuoteError::Other(err).into());
+                            return;
+                        }
+                    }
+                }
+                PriceQuality::Fast => quote,
+            };
+
+            let response = quote_response(&request, valid_to, &quote);
+            tracing::debug!(?response, "finished streaming quote");
+            yield QuoteStreamUpdate::Final(response);
+        }
+    }
+
+    /// Validates the quote request and returns the parameters for computing
+    /// the quote together with the `valid_to` of the quoted order.
+    async fn quote_parameters(
+        &self,
+        request: &OrderQuoteRequest,
+    ) -> Result<(QuoteParameters, u32), OrderQuoteError> {
         let full_app_data_override = match request.app_data {
             OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await.unwrap_or(None),
             _ => None,
@@ -84,58 +186,64 @@ impl QuoteHandler {
             additional_gas: app_data.inner.protocol.hooks.gas_limit(),
             timeout: request.timeout,
         };
+        Ok((params, valid_to))
+    }
+}
 
-        let quote = match request.price_quality {
-            PriceQuality::Optimal | PriceQuality::Verified => {
-                let quote = self.optimal_quoter.calculate_quote(params).await?;
-                self.optimal_quoter
-                    .store_quote(quote)
-                    .await
-                    .map_err(CalculateQuoteError::Other)?
-            }
-            PriceQuality::Fast => {
-                let mut quote = self.fast_quoter.calculate_quote(params).await?;
-                // We maintain an API guarantee that fast quotes always have an expiry of zero,
-                // because they're not very accurate and can be considered to
-                // expire immediately.
-                quote.data.expiration = Utc.timestamp_millis_opt(0).unwrap();
-                quote
-            }
-        };
+/// We maintain an API guarantee that fast quotes always have an expiry of zero,
+/// because they're not very accurate and can be considered to expire
+/// immediately.
+fn fast_quote(mut quote: Quote) -> Quote {
+    quote.data.expiration = Utc.timestamp_millis_opt(0).unwrap();
+    quote
+}
 
-        let response = OrderQuoteResponse {
-            quote: OrderQuote {
-                sell_token: request.sell_token,
-                buy_token: request.buy_token,
-                receiver: request.receiver,
-                sell_amount: quote.sell_amount,
-                buy_amount: quote.buy_amount,
-                valid_to,
-                app_data: match &request.app_data {
-                    OrderCreationAppData::Full { full } => OrderCreationAppData::Both {
-                        full: full.clone(),
-                        expected: request.app_data.hash(),
-                    },
-                    app_data => app_data.clone(),
+fn quote_response(request: &OrderQuoteRequest, valid_to: u32, quote: &Quote) -> OrderQuoteResponse {
+    OrderQuoteResponse {
+        quote: OrderQuote {
+            sell_token: request.sell_token,
+            buy_token: request.buy_token,
+            receiver: request.receiver,
+            sell_amount: quote.sell_amount,
+            buy_amount: quote.buy_amount,
+            valid_to,
+            app_data: match &request.app_data {
+                OrderCreationAppData::Full { full } => OrderCreationAppData::Both {
+                    full: full.clone(),
+                    expected: request.app_data.hash(),
                 },
-                fee_amount: quote.fee_amount,
-                kind: quote.data.kind,
-                partially_fillable: false,
-                sell_token_balance: request.sell_token_balance,
-                buy_token_balance: request.buy_token_balance,
-                signing_scheme: request.signing_scheme.into(),
+                app_data => app_data.clone(),
             },
-            from: request.from,
-            expiration: quote.data.expiration,
-            id: quote.id,
-            verified: quote.data.verified,
-        };
-
-        tracing::debug!(?response, "finished computing quote");
-        Ok(response)
+            fee_amount: quote.fee_amount,
+            kind: quote.data.kind,
+            partially_fillable: false,
+            sell_token_balance: request.sell_token_balance,
+            buy_token_balance: request.buy_token_balance,
+            signing_scheme: request.signing_scheme.into(),
+        },
+        from: request.from,
+        expiration: quote.data.expiration,
+        id: quote.id,
+        verified: quote.data.verified,
     }
 }
 
+/// Progress of a streamed quote request.
+#[derive(Debug)]
+pub enum QuoteStreamUpdate {
+    /// Quote based on the result of a single price source.
+    Quote {
+        response: OrderQuoteResponse,
+        /// Whether this is the best quote so far.
+        best: bool,
+    },
+    /// The best quote after all price sources returned. Unless it is a fast
+    /// quote, it has been stored and carries an ID.
+    Final(OrderQuoteResponse),
+    /// No quote could be computed.
+    Error(OrderQuoteError),
+}
+
 /// Result from handling a quote request.
 #[derive(Debug, Error)]
 pub enum OrderQuoteError {
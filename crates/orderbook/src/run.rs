@@ -1,10 +1,11 @@
 use {
     crate::{
         api,
-        arguments::Arguments,
+        arguments::{Arguments, EndpointRateLimit},
         database::Postgres,
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        order_events::OrderEventStream,
         orderbook::Orderbook,
         quoter::QuoteHandler,
     },
@@ -347,6 +348,7 @@ pub async fn run(args: Arguments) {
             balance_fetcher.clone(),
             verification,
             args.price_estimation.quote_timeout,
+            bad_token_detector.clone(),
         ))
     };
     let optimal_quoter = create_quoter(price_estimator, args.price_estimation.quote_verification);
@@ -369,7 +371,7 @@ pub async fn run(args: Arguments) {
         bad_token_detector.clone(),
         hooks_contract,
         optimal_quoter.clone(),
-        balance_fetcher,
+        balance_fetcher.clone(),
         signature_validator,
         Arc::new(postgres.clone()),
         args.max_limit_orders_per_user,
@@ -400,6 +402,8 @@ pub async fn run(args: Arguments) {
         order_validator.clone(),
         app_data.clone(),
         args.active_order_competition_threshold,
+        balance_fetcher,
+        args.ethflow_refund_min_validity_duration,
     ));
 
     check_database_connection(orderbook.as_ref()).await;
@@ -408,6 +412,8 @@ pub async fn run(args: Arguments) {
             .with_fast_quoter(fast_quoter),
     );
 
+    let order_events = Arc::new(OrderEventStream::spawn(postgres.pool.clone()));
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let serve_api = serve_api(
         postgres,
@@ -420,6 +426,10 @@ pub async fn run(args: Arguments) {
         },
         native_price_estimator,
         args.price_estimation.quote_timeout,
+        order_events,
+        &args.api_rate_limits,
+        &args.api_keys,
+        args.api_behind_trusted_proxy,
     );
 
     let mut metrics_address = args.bind_address;
@@ -486,6 +496,10 @@ fn serve_api(
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
     quote_timeout: Duration,
+    order_events: Arc<OrderEventStream>,
+    rate_limits: &[EndpointRateLimit],
+    api_keys: &[String],
+    behind_trusted_proxy: bool,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -494,6 +508,10 @@ fn serve_api(
         app_data,
         native_price_estimator,
         quote_timeout,
+        order_events,
+        rate_limits,
+        api_keys,
+        behind_trusted_proxy,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
@@ -10,6 +10,8 @@ use {
     thiserror::Error,
 };
 
+pub mod token_bucket;
+
 #[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
 #[metric(subsystem = "rate_limiter")]
 struct Metrics {
@@ -22,6 +24,12 @@ struct Metrics {
     /// Number of successful requests.
     #[metric(labels("endpoint"))]
     successful_requests: prometheus::IntCounterVec,
+    /// Number of inbound requests admitted by a token bucket limiter.
+    #[metric(labels("endpoint"))]
+    requests_admitted: prometheus::IntCounterVec,
+    /// Number of inbound requests rejected by a token bucket limiter.
+    #[metric(labels("endpoint"))]
+    requests_throttled: prometheus::IntCounterVec,
 }
 
 fn metrics() -> &'static Metrics {
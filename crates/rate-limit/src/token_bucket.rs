@@ -0,0 +1,269 @@
+//! Token bucket rate limiting of inbound requests.
+//!
+//! Every client (identified by an arbitrary key like an API key or IP address)
+//! gets its own bucket which holds up to `requests` tokens and refills at a
+//! rate of `requests` tokens per `period`. Each request consumes one token and
+//! gets rejected while the bucket is empty.
+
+use {
+    super::metrics,
+    anyhow::{Context, Result},
+    std::{
+        collections::HashMap,
+        fmt::{self, Display, Formatter},
+        num::NonZeroU32,
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+/// Buckets get garbage collected once there are at least that many.
+const MIN_BUCKETS_BEFORE_CLEANUP: usize = 1024;
+
+/// Maximum number of requests a client can make in a period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limit {
+    pub requests: NonZeroU32,
+    pub period: Duration,
+}
+
+impl Limit {
+    fn refill_rate(&self) -> f64 {
+        f64::from(self.requests.get()) / self.period.as_secs_f64()
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}",
+            self.requests,
+            humantime::format_duration(self.period)
+        )
+    }
+}
+
+impl FromStr for Limit {
+    type Err = anyhow::Error;
+
+    /// Parses limits of the form `<requests>/<period>`, e.g. `10/1s`.
+    fn from_str(limit: &str) -> Result<Self> {
+        let (requests, period) = limit
+            .split_once('/')
+            .context("limit must be of the form <requests>/<period>")?;
+        let requests = requests.trim().parse().context("parsing requests")?;
+        let period = humantime::parse_duration(period.trim()).context("parsing period")?;
+        anyhow::ensure!(!period.is_zero(), "period must not be zero");
+        Ok(Self { requests, period })
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Rate limiter keeping one token bucket per client.
+#[derive(Debug)]
+pub struct TokenBucketLimiter {
+    limit: Limit,
+    name: String,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    next_cleanup: usize,
+}
+
+/// A request got rejected because the client ran out of tokens.
+#[derive(Error, Debug, Clone)]
+#[error("{name} rate limit exceeded, retry after {retry_after:?}")]
+pub struct Throttled {
+    /// How long the client has to wait until the next request gets admitted.
+    pub retry_after: Duration,
+    name: String,
+}
+
+impl Throttled {
+    /// Records that the client actually got told to back off.
+    pub fn record(&self) {
+        metrics()
+            .requests_throttled
+            .with_label_values(&[&self.name])
+            .inc();
+    }
+}
+
+impl TokenBucketLimiter {
+    pub fn new(limit: Limit, name: String) -> Self {
+        let metrics = metrics();
+        metrics
+            .requests_admitted
+            .with_label_values(&[&name])
+            .reset();
+        metrics
+            .requests_throttled
+            .with_label_values(&[&name])
+            .reset();
+        Self {
+            limit,
+            name,
+            buckets: Mutex::new(Buckets {
+                by_key: Default::default(),
+                next_cleanup: MIN_BUCKETS_BEFORE_CLEANUP,
+            }),
+        }
+    }
+
+    /// Takes a token from the client's bucket. The token gets returned to the
+    /// bucket when the permit is dropped without being committed.
+    pub fn try_acquire(self: &Arc<Self>, key: String) -> Result<Permit, Throttled> {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(self: &Arc<Self>, key: String, now: Instant) -> Result<Permit, Throttled> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.by_key.len() >= buckets.next_cleanup {
+            // Full buckets are indistinguishable from new ones so they can be
+            // forgotten.
+            buckets
+                .by_key
+                .retain(|_, bucket| self.refill(bucket, now) < self.capacity());
+            buckets.next_cleanup = (buckets.by_key.len() * 2).max(MIN_BUCKETS_BEFORE_CLEANUP);
+        }
+
+        let bucket = buckets.by_key.entry(key.clone()).or_insert(Bucket {
+            tokens: self.capacity(),
+            updated_at: now,
+        });
+        bucket.tokens = self.refill(bucket, now);
+        bucket.updated_at = now;
+        if bucket.tokens < 1. {
+            let missing = 1. - bucket.tokens;
+            return Err(Throttled {
+                retry_after: self.limit.period.mul_f64(missing / self.capacity()),
+                name: self.name.clone(),
+            });
+        }
+        bucket.tokens -= 1.;
+
+        Ok(Permit {
+            limiter: self.clone(),
+            key: Some(key),
+        })
+    }
+
+    fn release(&self, key: &str) {
+        let capacity = self.capacity();
+        if let Some(bucket) = self.buckets.lock().unwrap().by_key.get_mut(key) {
+            bucket.tokens = (bucket.tokens + 1.).min(capacity);
+        }
+    }
+
+    fn capacity(&self) -> f64 {
+        self.limit.requests.get().into()
+    }
+
+    /// Returns the number of tokens the bucket holds at the given time.
+    fn refill(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        (bucket.tokens + elapsed.as_secs_f64() * self.limit.refill_rate()).min(self.capacity())
+    }
+}
+
+/// A token taken from a client's bucket.
+#[derive(Debug)]
+pub struct Permit {
+    limiter: Arc<TokenBucketLimiter>,
+    key: Option<String>,
+}
+
+impl Permit {
+    /// Consumes the token for good.
+    pub fn commit(mut self) {
+        self.key = None;
+        metrics()
+            .requests_admitted
+            .with_label_values(&[&self.limiter.name])
+            .inc();
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.limiter.release(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit: &str) -> Arc<TokenBucketLimiter> {
+        Arc::new(TokenBucketLimiter::new(
+            limit.parse().unwrap(),
+            "test".into(),
+        ))
+    }
+
+    #[test]
+    fn parses_limits() {
+        let limit: Limit = "10/1m".parse().unwrap();
+        assert_eq!(limit.requests.get(), 10);
+        assert_eq!(limit.period, Duration::from_secs(60));
+        assert_eq!(limit.to_string(), "10/1m");
+
+        assert!("10".parse::<Limit>().is_err());
+        assert!("0/1s".parse::<Limit>().is_err());
+        assert!("10/0s".parse::<Limit>().is_err());
+    }
+
+    #[test]
+    fn throttles_until_bucket_refills() {
+        let limiter = limiter("2/1s");
+        let now = Instant::now();
+
+        limiter.try_acquire_at("a".into(), now).unwrap().commit();
+        limiter.try_acquire_at("a".into(), now).unwrap().commit();
+        let throttled = limiter.try_acquire_at("a".into(), now).unwrap_err();
+        assert_eq!(throttled.retry_after, Duration::from_millis(500));
+
+        // Other clients have their own buckets.
+        limiter.try_acquire_at("b".into(), now).unwrap().commit();
+
+        let later = now + Duration::from_millis(500);
+        limiter.try_acquire_at("a".into(), later).unwrap().commit();
+        assert!(limiter.try_acquire_at("a".into(), later).is_err());
+    }
+
+    #[test]
+    fn returns_uncommitted_tokens() {
+        let limiter = limiter("1/1h");
+        let now = Instant::now();
+
+        drop(limiter.try_acquire_at("a".into(), now).unwrap());
+        limiter.try_acquire_at("a".into(), now).unwrap().commit();
+        assert!(limiter.try_acquire_at("a".into(), now).is_err());
+    }
+
+    #[test]
+    fn forgets_full_buckets() {
+        let limiter = limiter("1/1s");
+        let now = Instant::now();
+        for i in 0..MIN_BUCKETS_BEFORE_CLEANUP {
+            limiter.try_acquire_at(i.to_string(), now).unwrap().commit();
+        }
+
+        let later = now + Duration::from_secs(1);
+        limiter.try_acquire_at("a".into(), later).unwrap().commit();
+        assert_eq!(limiter.buckets.lock().unwrap().by_key.len(), 1);
+    }
+}
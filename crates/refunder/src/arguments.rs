@@ -1,7 +1,12 @@
 use {
     clap::Parser,
-    ethcontract::H160,
-    shared::{arguments::display_option, ethrpc, http_client, logging_args_with_default_filter},
+    ethcontract::{H160, U256},
+    shared::{
+        arguments::{display_option, display_secret_option, wei_from_ether, wei_from_gwei},
+        ethrpc,
+        http_client,
+        logging_args_with_default_filter,
+    },
     std::time::Duration,
     tracing::level_filters::LevelFilter,
     url::Url,
@@ -10,6 +15,11 @@ use {
 logging_args_with_default_filter!(LoggingArguments, "warn,refunder=debug,shared=debug");
 
 #[derive(Parser)]
+#[clap(group(
+    clap::ArgGroup::new("refunder_account")
+    .args(&["refunder_pk", "refunder_kms_key_id", "refunder_address"])
+    .required(true),
+))]
 pub struct Arguments {
     #[clap(flatten)]
     pub http_client: http_client::Arguments,
@@ -61,8 +71,54 @@ pub struct Arguments {
     #[clap(long, env, use_value_delimiter = true)]
     pub ethflow_contracts: Vec<H160>,
 
+    /// Private key of the account submitting the refund transactions.
     #[clap(long, env, hide_env_values = true)]
-    pub refunder_pk: String,
+    pub refunder_pk: Option<String>,
+
+    /// AWS KMS key identifier of the account submitting the refund
+    /// transactions.
+    #[clap(long, env)]
+    pub refunder_kms_key_id: Option<String>,
+
+    /// Address of the account submitting the refund transactions. Signing is
+    /// delegated to the connected node or to the `refunder_signer_url` if set.
+    #[clap(long, env)]
+    pub refunder_address: Option<H160>,
+
+    /// Url of a remote signer (e.g. Web3Signer) that signs and forwards the
+    /// refund transactions of the `refunder_address` via
+    /// `eth_sendTransaction`.
+    #[clap(long, env, requires = "refunder_address")]
+    pub refunder_signer_url: Option<Url>,
+
+    /// Maximum gas price in Gwei the refunder is willing to pay.
+    #[clap(long, env, default_value = "800", value_parser = wei_from_gwei)]
+    pub max_gas_price: f64,
+
+    /// Factor by which the estimated gas price gets increased to get refund
+    /// transactions mined quickly.
+    #[clap(long, env, default_value = "1.3")]
+    pub gas_price_buffer_factor: f64,
+
+    /// Priority fee in Gwei the refunder starts out with before increasing it
+    /// for resubmissions.
+    #[clap(long, env, default_value = "2", value_parser = wei_from_gwei)]
+    pub start_priority_fee_tip: f64,
+
+    /// How many blocks to wait for a refund transaction to get mined before
+    /// resubmitting it with a higher gas price.
+    #[clap(long, env, default_value = "5")]
+    pub confirmation_block_timeout: usize,
+
+    /// Maximum number of orders refunded in a single transaction. Needs to be
+    /// low enough for the transaction to fit into a block.
+    #[clap(long, env, default_value = "30")]
+    pub max_orders_per_refund_tx: usize,
+
+    /// Maximum amount of ETH the refunder may spend on transaction fees
+    /// within 24 hours. Refunds are paused while the limit is exceeded.
+    #[clap(long, env, value_parser = wei_from_ether)]
+    pub daily_spend_limit: Option<U256>,
 
     /// The port at which we serve our metrics
     #[clap(long, env, default_value = "9590")]
@@ -83,6 +139,15 @@ impl std::fmt::Display for Arguments {
             logging,
             db_url,
             refunder_pk,
+            refunder_kms_key_id,
+            refunder_address,
+            refunder_signer_url,
+            max_gas_price,
+            gas_price_buffer_factor,
+            start_priority_fee_tip,
+            confirmation_block_timeout,
+            max_orders_per_refund_tx,
+            daily_spend_limit,
         } = self;
 
         write!(f, "{http_client}")?;
@@ -95,8 +160,23 @@ impl std::fmt::Display for Arguments {
         writeln!(f, "node_url: {node_url}")?;
         display_option(f, "chain_id", chain_id)?;
         writeln!(f, "ethflow_contracts: {ethflow_contracts:?}")?;
-        let _intentionally_ignored = refunder_pk;
-        writeln!(f, "refunder_pk: SECRET")?;
+        display_secret_option(f, "refunder_pk", refunder_pk.as_ref())?;
+        display_option(f, "refunder_kms_key_id", refunder_kms_key_id)?;
+        display_option(
+            f,
+            "refunder_address",
+            &refunder_address.map(|a| format!("{a:?}")),
+        )?;
+        display_option(f, "refunder_signer_url", refunder_signer_url)?;
+        writeln!(f, "max_gas_price: {max_gas_price}")?;
+        writeln!(f, "gas_price_buffer_factor: {gas_price_buffer_factor}")?;
+        writeln!(f, "start_priority_fee_tip: {start_priority_fee_tip}")?;
+        writeln!(
+            f,
+            "confirmation_block_timeout: {confirmation_block_timeout}"
+        )?;
+        writeln!(f, "max_orders_per_refund_tx: {max_orders_per_refund_tx}")?;
+        display_option(f, "daily_spend_limit", daily_spend_limit)?;
         writeln!(f, "metrics_port: {metrics_port}")?;
         Ok(())
     }
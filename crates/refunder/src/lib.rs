@@ -9,13 +9,14 @@ use {
     contracts::CoWSwapEthFlow,
     ethcontract::{Account, PrivateKey},
     observe::metrics::LivenessChecking,
-    refund_service::RefundService,
+    refund_service::{Limits, RefundService},
     shared::http_client::HttpClientFactory,
     sqlx::PgPool,
     std::{
         sync::{Arc, RwLock},
         time::{Duration, Instant},
     },
+    submitter::Submitter,
 };
 
 const LOOP_INTERVAL: Duration = Duration::from_secs(30);
@@ -65,14 +66,33 @@ pub async fn run(args: arguments::Arguments) {
         .iter()
         .map(|contract| CoWSwapEthFlow::at(&web3, *contract))
         .collect();
-    let refunder_account = Account::Offline(args.refunder_pk.parse::<PrivateKey>().unwrap(), None);
+    let submission_web3 = match &args.refunder_signer_url {
+        Some(url) => shared::ethrpc::web3(&args.ethrpc, &http_factory, url, "signer"),
+        None => web3.clone(),
+    };
+    let submitter = Submitter::new(
+        web3.clone(),
+        submission_web3,
+        pg_pool.clone(),
+        submitter::Config {
+            max_gas_price: args.max_gas_price,
+            gas_price_buffer_factor: args.gas_price_buffer_factor,
+            start_priority_fee_tip: args.start_priority_fee_tip,
+            confirmation_block_timeout: args.confirmation_block_timeout,
+        },
+        refunder_account(&args).await,
+    );
     let mut refunder = RefundService::new(
         pg_pool,
         web3,
         ethflow_contracts,
         i64::try_from(args.min_validity_duration.as_secs()).unwrap_or(i64::MAX),
         args.min_price_deviation_bps,
-        refunder_account,
+        Limits {
+            max_orders_per_refund_tx: args.max_orders_per_refund_tx,
+            daily_spend_limit: args.daily_spend_limit,
+        },
+        submitter,
     );
     loop {
         tracing::info!("Staring a new refunding loop");
@@ -90,6 +110,25 @@ pub async fn run(args: arguments::Arguments) {
     }
 }
 
+/// Builds the account submitting the refund txs. Exactly one of the account
+/// arguments is set, which is enforced by the argument parser.
+async fn refunder_account(args: &Arguments) -> Account {
+    if let Some(pk) = &args.refunder_pk {
+        return Account::Offline(pk.parse::<PrivateKey>().unwrap(), None);
+    }
+    if let Some(key_id) = &args.refunder_kms_key_id {
+        let config = ethcontract::aws_config::load_from_env().await;
+        let account = ethcontract::transaction::kms::Account::new((&config).into(), key_id)
+            .await
+            .unwrap_or_else(|_| panic!("Unable to load KMS account {key_id:?}"));
+        return Account::Kms(account, None);
+    }
+    let address = args
+        .refunder_address
+        .expect("one of the refunder account arguments is set");
+    Account::Local(address, None)
+}
+
 struct Liveness {
     last_successful_loop: RwLock<Instant>,
 }
@@ -2,22 +2,30 @@ use {
     super::ethflow_order::{EncodedEthflowOrder, EthflowOrder, order_to_ethflow_data},
     crate::submitter::Submitter,
     anyhow::{Context, Result, anyhow},
+    chrono::{Duration, Utc},
     contracts::CoWSwapEthFlow,
     database::{
         OrderUid,
-        ethflow_orders::{EthOrderPlacement, read_order, refundable_orders},
+        ethflow_orders::{
+            EthOrderPlacement,
+            read_order,
+            refundable_orders,
+            requested_refundable_orders,
+        },
+        ethflow_refund_attempts,
         orders::read_order as read_db_order,
     },
-    ethcontract::{Account, H160, H256},
+    ethcontract::{H160, H256, U256},
     ethrpc::{Web3, block_stream::timestamp_of_current_block_in_seconds},
     futures::{StreamExt, stream},
+    itertools::Itertools,
+    number::conversions::big_decimal_to_u256,
     sqlx::PgPool,
     std::collections::HashMap,
 };
 
 pub const NO_OWNER: H160 = H160([0u8; 20]);
 pub const INVALIDATED_OWNER: H160 = H160([255u8; 20]);
-const MAX_NUMBER_OF_UIDS_PER_REFUND_TX: usize = 30;
 
 type CoWSwapEthFlowAddress = H160;
 
@@ -27,9 +35,20 @@ pub struct RefundService {
     pub ethflow_contracts: Vec<CoWSwapEthFlow>,
     pub min_validity_duration: i64,
     pub min_price_deviation: f64,
+    pub limits: Limits,
     pub submitter: Submitter,
 }
 
+/// Limits on the refunds the service submits.
+#[derive(Clone, Debug)]
+pub struct Limits {
+    /// Maximum number of orders refunded in a single tx, in order to fit into
+    /// the gas limit.
+    pub max_orders_per_refund_tx: usize,
+    /// Maximum amount of wei that may be spent on fees within 24 hours.
+    pub daily_spend_limit: Option<U256>,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum RefundStatus {
     Refunded,
@@ -44,21 +63,17 @@ impl RefundService {
         ethflow_contracts: Vec<CoWSwapEthFlow>,
         min_validity_duration: i64,
         min_price_deviation_bps: i64,
-        account: Account,
+        limits: Limits,
+        submitter: Submitter,
     ) -> Self {
         RefundService {
             db,
-            web3: web3.clone(),
+            web3,
             ethflow_contracts,
             min_validity_duration,
             min_price_deviation: min_price_deviation_bps as f64 / 10000f64,
-            submitter: Submitter {
-                web3: web3.clone(),
-                account,
-                gas_estimator: Box::new(web3),
-                gas_parameters_of_last_tx: None,
-                nonce_of_last_submission: None,
-            },
+            limits,
+            submitter,
         }
     }
 
@@ -77,7 +92,18 @@ impl RefundService {
         let block_time = timestamp_of_current_block_in_seconds(&self.web3).await? as i64;
 
         let mut ex = self.db.acquire().await?;
-        refundable_orders(
+        // Orders users requested a refund for come first so they get refunded
+        // even if there are more refundable orders than fit into a single tx.
+        let requested =
+            requested_refundable_orders(&mut ex, block_time, self.min_validity_duration)
+                .await
+                .map_err(|err| {
+                    anyhow!(
+                        "Error while retrieving the requested ethflow refunds from db: {:?}",
+                        err
+                    )
+                })?;
+        let refundable = refundable_orders(
             &mut ex,
             block_time,
             self.min_validity_duration,
@@ -89,7 +115,12 @@ impl RefundService {
                 "Error while retrieving the refundable ethflow orders from db: {:?}",
                 err
             )
-        })
+        })?;
+        Ok(requested
+            .into_iter()
+            .chain(refundable)
+            .unique_by(|order| order.uid)
+            .collect())
     }
 
     async fn identify_uids_refunding_status_via_web3_calls(
@@ -188,6 +219,31 @@ impl RefundService {
         Ok(order_to_ethflow_data(order, ethflow_order))
     }
 
+    /// Checks whether the fees spent within the last 24 hours exceed the
+    /// configured limit.
+    async fn daily_spend_limit_exceeded(&self) -> Result<bool> {
+        let Some(limit) = self.limits.daily_spend_limit else {
+            return Ok(false);
+        };
+        let since = Utc::now() - Duration::days(1);
+        // Attempts that were still pending when they got recorded might have
+        // been mined since then.
+        self.submitter
+            .reconcile_pending_attempts(since)
+            .await
+            .context("reconcile pending attempts")?;
+        let mut ex = self.db.acquire().await.context("acquire")?;
+        let spent = ethflow_refund_attempts::fees_spent_since(&mut ex, since)
+            .await
+            .context("fees spent")?;
+        let spent = big_decimal_to_u256(&spent).context("invalid spent fees")?;
+        if spent < limit {
+            return Ok(false);
+        }
+        tracing::warn!(%spent, %limit, "daily spend limit exceeded, pausing refunds");
+        Ok(true)
+    }
+
     async fn send_out_refunding_tx(
         &mut self,
         uids_by_contract: HashMap<CoWSwapEthFlowAddress, Vec<OrderUid>>,
@@ -198,9 +254,12 @@ impl RefundService {
 
         // For each ethflow contract, issue a separate tx to refund
         for (contract, mut uids) in uids_by_contract.into_iter() {
-            // only try to refund MAX_NUMBER_OF_UIDS_PER_REFUND_TX uids, in order to fit
-            // into gas limit
-            uids.truncate(MAX_NUMBER_OF_UIDS_PER_REFUND_TX);
+            if self.daily_spend_limit_exceeded().await? {
+                break;
+            }
+            // only try to refund a limited number of uids, in order to fit into gas
+            // limit
+            uids.truncate(self.limits.max_orders_per_refund_tx);
 
             tracing::debug!("Trying to refund the following uids: {:?}", uids);
 
@@ -2,48 +2,71 @@
 // It tries to submit a tx - as EIP1559 - with a small tx tip,
 // but a quite high max_fee_per_gas such that it's likely being mined quickly
 //
-// Then it waits for 5 blocks. If the tx is not mined, it will return an error
-// and it needs to be called again. If the last submission was not successful,
-// this submitter stores the last gas_price in order to submit the new tx with
-// a higher gas price, in order to avoid: ErrReplaceUnderpriced erros
+// Then it waits for a configured number of blocks. If the tx is not mined, it
+// will return an error and it needs to be called again. If the last submission
+// was not successful, this submitter stores the last gas_price in order to
+// submit the new tx with a higher gas price, in order to avoid:
+// ErrReplaceUnderpriced erros
 // In the re-newed attempt for submission the same nonce is used as before.
+//
+// Every submission gets recorded in the database for auditing.
 
 use {
     super::ethflow_order::EncodedEthflowOrder,
     anyhow::{Result, anyhow},
+    chrono::{DateTime, Utc},
     contracts::CoWSwapEthFlow,
-    database::OrderUid,
+    database::{
+        OrderUid,
+        byte_array::ByteArray,
+        ethflow_refund_attempts::{self, RefundAttempt, Status},
+    },
     ethcontract::{
         Account,
         H160,
+        H256,
         U256,
-        transaction::{ResolveCondition, confirm::ConfirmParams},
+        errors::ExecutionError,
+        transaction::{ResolveCondition, TransactionResult, confirm::ConfirmParams},
+        web3::types::TransactionReceipt,
     },
     gas_estimation::{GasPrice1559, GasPriceEstimating},
+    number::conversions::u256_to_big_decimal,
     shared::{
         conversions::into_gas_price,
         ethrpc::Web3,
         submitter_constants::{TX_ALREADY_KNOWN, TX_ALREADY_MINED},
     },
+    sqlx::PgPool,
 };
 
-// Max gas price used for submitting transactions
-const MAX_GAS_PRICE: u64 = 800_000_000_000;
-
-// The gas price buffer determines the gas price buffer used to
-// send out EIP1559 txs.
-// Example: If the prevailing gas is 10Gwei and the buffer factor is 1.20
-// then the gas_price used will be 12.
-const GAS_PRICE_BUFFER_FACTOR: f64 = 1.3;
-// Starting priority fee that the refunder is willing to pay. (=2 Gwei)
-const START_PRIORITY_FEE_TIP: u64 = 2_000_000_000;
-
 // In order to resubmit a new tx with the same nonce, the gas tip and
 // max_fee_per_gas needs to be increased by at least 10 percent.
 const GAS_PRICE_BUMP: f64 = 1.125;
 
+/// Parameters determining how refund transactions get submitted.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Max gas price used for submitting transactions.
+    pub max_gas_price: f64,
+    /// The gas price buffer determines the gas price buffer used to
+    /// send out EIP1559 txs.
+    /// Example: If the prevailing gas is 10Gwei and the buffer factor is 1.20
+    /// then the gas_price used will be 12.
+    pub gas_price_buffer_factor: f64,
+    /// Starting priority fee that the refunder is willing to pay.
+    pub start_priority_fee_tip: f64,
+    /// Number of blocks to wait for a submitted tx to get mined.
+    pub confirmation_block_timeout: usize,
+}
+
 pub struct Submitter {
     pub web3: Web3,
+    /// Used to send out the refund txs. Differs from `web3` if a remote
+    /// signer is used.
+    pub submission_web3: Web3,
+    pub db: PgPool,
+    pub config: Config,
     pub account: Account,
     pub gas_estimator: Box<dyn GasPriceEstimating>,
     pub gas_parameters_of_last_tx: Option<GasPrice1559>,
@@ -51,6 +74,25 @@ pub struct Submitter {
 }
 
 impl Submitter {
+    pub fn new(
+        web3: Web3,
+        submission_web3: Web3,
+        db: PgPool,
+        config: Config,
+        account: Account,
+    ) -> Self {
+        Self {
+            gas_estimator: Box::new(web3.clone()),
+            web3,
+            submission_web3,
+            db,
+            config,
+            account,
+            gas_parameters_of_last_tx: None,
+            nonce_of_last_submission: None,
+        }
+    }
+
     async fn get_submission_nonce(&self) -> Result<U256> {
         // this command returns the tx count ever mined at the latest block
         // Mempool tx are not considered.
@@ -68,13 +110,14 @@ impl Submitter {
         ethflow_contract: H160,
     ) -> Result<()> {
         let confirm_params = ConfirmParams {
-            block_timeout: Some(5),
+            block_timeout: Some(self.config.confirmation_block_timeout),
             ..Default::default()
         };
         let resolve_conditions = ResolveCondition::Confirmed(confirm_params);
         let gas_price_estimation = self.gas_estimator.estimate().await?;
         let nonce = self.get_submission_nonce().await?;
         let gas_price = calculate_submission_gas_price(
+            &self.config,
             self.gas_parameters_of_last_tx,
             gas_price_estimation,
             nonce,
@@ -83,8 +126,8 @@ impl Submitter {
 
         self.gas_parameters_of_last_tx = Some(gas_price);
         self.nonce_of_last_submission = Some(nonce);
-        let ethflow_contract = CoWSwapEthFlow::at(&self.web3, ethflow_contract);
-        let tx_result = ethflow_contract
+        let contract = CoWSwapEthFlow::at(&self.submission_web3, ethflow_contract);
+        let tx_result = contract
             .invalidate_orders_ignoring_not_allowed(encoded_ethflow_orders)
             .gas_price(into_gas_price(&gas_price))
             .from(self.account.clone())
@@ -93,6 +136,8 @@ impl Submitter {
             .resolve(resolve_conditions)
             .send()
             .await;
+        self.record_attempt(&uids, ethflow_contract, nonce, &gas_price, &tx_result)
+            .await;
         match tx_result {
             Ok(handle) => {
                 tracing::debug!(
@@ -109,22 +154,127 @@ impl Submitter {
                     tracing::debug!(?err, "transaction already mined");
                 } else if TX_ALREADY_KNOWN.iter().any(|msg| err.contains(msg)) {
                     // This case means that the node is already aware of the tx
-                    // This can only happen after restarts, or close to the MAX_GAS_PRICE
+                    // This can only happen after restarts, or close to the max gas price
                     // as usually we would always increase the gas tip compared to previous tx.
                     // Hence, we irgnore the warning and just retry.
                     tracing::debug!(?err, "transaction already known");
                 } else {
                     // Todo: Handle the error "replacement transaction underpriced"
-                    // This could happen after restarts or close to the MAX_GAS_PRICE
+                    // This could happen after restarts or close to the max gas price
                     tracing::warn!(?err, "submission failed");
                 }
             }
         }
         Ok(())
     }
+
+    /// Stores the outcome of a submission in the database for auditing and
+    /// for keeping track of the spent fees.
+    async fn record_attempt(
+        &self,
+        uids: &[OrderUid],
+        ethflow_contract: H160,
+        nonce: U256,
+        gas_price: &GasPrice1559,
+        tx_result: &Result<TransactionResult, ExecutionError>,
+    ) {
+        let (status, receipt, error) = match tx_result {
+            Ok(TransactionResult::Receipt(receipt)) => (Status::Success, Some(receipt), None),
+            Ok(TransactionResult::Hash(_)) => (Status::Pending, None, None),
+            Err(ExecutionError::Failed(receipt)) => {
+                (Status::Reverted, Some(receipt.as_ref()), None)
+            }
+            // The transaction might still get mined after the timeout, so its
+            // outcome gets reconciled later.
+            Err(err @ ExecutionError::ConfirmTimeout(_)) => {
+                (Status::Pending, None, Some(err.to_string()))
+            }
+            Err(err) => (Status::Failed, None, Some(err.to_string())),
+        };
+        let tx_hash = match tx_result {
+            Ok(result) => Some(result.hash()),
+            Err(ExecutionError::Failed(receipt)) => Some(receipt.transaction_hash),
+            Err(ExecutionError::ConfirmTimeout(result)) => Some(result.hash()),
+            Err(_) => None,
+        };
+        let attempt = RefundAttempt {
+            timestamp: Utc::now(),
+            ethflow_contract: ByteArray(ethflow_contract.0),
+            order_uids: uids.to_vec(),
+            nonce: i64::try_from(nonce.as_u64()).unwrap_or(i64::MAX),
+            max_fee_per_gas: u256_to_big_decimal(&U256::from_f64_lossy(gas_price.max_fee_per_gas)),
+            max_priority_fee_per_gas: u256_to_big_decimal(&U256::from_f64_lossy(
+                gas_price.max_priority_fee_per_gas,
+            )),
+            status,
+            tx_hash: tx_hash.map(|hash| ByteArray(hash.0)),
+            gas_used: receipt
+                .and_then(|receipt| receipt.gas_used)
+                .map(|gas| u256_to_big_decimal(&gas)),
+            fee: receipt.and_then(fee).map(|fee| u256_to_big_decimal(&fee)),
+            error,
+        };
+        let result = async {
+            let mut ex = self.db.acquire().await?;
+            ethflow_refund_attempts::insert(&mut ex, &attempt).await
+        }
+        .await;
+        if let Err(err) = result {
+            tracing::warn!(?err, ?attempt, "failed to record refund attempt");
+        }
+    }
+
+    /// Looks up the outcome of the attempts since the given timestamp that
+    /// were still pending when they got recorded, so the fees of transactions
+    /// that got mined late count towards the spent fees.
+    pub async fn reconcile_pending_attempts(&self, since: DateTime<Utc>) -> Result<()> {
+        let mut ex = self.db.acquire().await?;
+        let attempts = ethflow_refund_attempts::pending_since(&mut ex, since).await?;
+        if attempts.is_empty() {
+            return Ok(());
+        }
+        // Fetched before the receipts so a used up nonce means that a missing
+        // receipt belongs to a transaction that got replaced.
+        let nonce = self.get_submission_nonce().await?;
+        for attempt in attempts {
+            let Some(tx_hash) = attempt.tx_hash else {
+                continue;
+            };
+            let receipt = self.web3.eth().transaction_receipt(H256(tx_hash.0)).await?;
+            let status = match &receipt {
+                Some(receipt) if receipt.status == Some(1u64.into()) => Status::Success,
+                Some(_) => Status::Reverted,
+                None if U256::from(attempt.nonce) < nonce => Status::Failed,
+                None => continue,
+            };
+            ethflow_refund_attempts::update_pending(
+                &mut ex,
+                &tx_hash,
+                status,
+                receipt
+                    .as_ref()
+                    .and_then(|receipt| receipt.gas_used)
+                    .map(|gas| u256_to_big_decimal(&gas))
+                    .as_ref(),
+                receipt
+                    .as_ref()
+                    .and_then(fee)
+                    .map(|fee| u256_to_big_decimal(&fee))
+                    .as_ref(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Wei paid for a mined transaction.
+fn fee(receipt: &TransactionReceipt) -> Option<U256> {
+    receipt.gas_used?.checked_mul(receipt.effective_gas_price?)
 }
 
 fn calculate_submission_gas_price(
+    config: &Config,
     gas_price_of_last_submission: Option<GasPrice1559>,
     web3_gas_estimation: GasPrice1559,
     newest_nonce: U256,
@@ -132,10 +282,11 @@ fn calculate_submission_gas_price(
 ) -> Result<GasPrice1559> {
     // The gas price of the refund tx is the current prevailing gas price
     // of the web3 gas estimation plus a buffer.
-    let mut new_gas_price = web3_gas_estimation.bump(GAS_PRICE_BUFFER_FACTOR);
+    let mut new_gas_price = web3_gas_estimation.bump(config.gas_price_buffer_factor);
     // limit the prio_fee to max_fee_per_gas as otherwise tx is invalid
-    new_gas_price.max_priority_fee_per_gas =
-        (START_PRIORITY_FEE_TIP as f64).min(new_gas_price.max_fee_per_gas);
+    new_gas_price.max_priority_fee_per_gas = config
+        .start_priority_fee_tip
+        .min(new_gas_price.max_fee_per_gas);
 
     // If tx from the previous submission was not mined,
     // we incease the tip and max_gas_fee for miners
@@ -152,15 +303,15 @@ fn calculate_submission_gas_price(
         }
     }
 
-    if new_gas_price.max_fee_per_gas > MAX_GAS_PRICE as f64 {
+    if new_gas_price.max_fee_per_gas > config.max_gas_price {
         tracing::warn!(
             "Refunding txs are likely not mined in time, as the current gas price {:?} is higher \
-             than MAX_GAS_PRICE specified {:?}",
+             than the max gas price specified {:?}",
             new_gas_price.max_fee_per_gas,
-            MAX_GAS_PRICE
+            config.max_gas_price
         );
         new_gas_price.max_fee_per_gas =
-            f64::min(MAX_GAS_PRICE as f64, new_gas_price.max_fee_per_gas);
+            f64::min(config.max_gas_price, new_gas_price.max_fee_per_gas);
     }
     new_gas_price.max_priority_fee_per_gas = f64::min(
         new_gas_price.max_priority_fee_per_gas,
@@ -173,8 +324,18 @@ fn calculate_submission_gas_price(
 mod tests {
     use super::*;
 
+    const MAX_GAS_PRICE: f64 = 800_000_000_000f64;
+    const GAS_PRICE_BUFFER_FACTOR: f64 = 1.3;
+    const START_PRIORITY_FEE_TIP: f64 = 2_000_000_000f64;
+
     #[test]
     fn test_calculate_submission_gas_price() {
+        let config = Config {
+            max_gas_price: MAX_GAS_PRICE,
+            gas_price_buffer_factor: GAS_PRICE_BUFFER_FACTOR,
+            start_priority_fee_tip: START_PRIORITY_FEE_TIP,
+            confirmation_block_timeout: 5,
+        };
         // First case: previous tx was successful
         let max_fee_per_gas = 4_000_000_000f64;
         let web3_gas_estimation = GasPrice1559 {
@@ -186,6 +347,7 @@ mod tests {
         let nonce_of_last_submission = None;
         let gas_price_of_last_submission = None;
         let result = calculate_submission_gas_price(
+            &config,
             gas_price_of_last_submission,
             web3_gas_estimation,
             newest_nonce,
@@ -194,7 +356,7 @@ mod tests {
         .unwrap();
         let expected_result = GasPrice1559 {
             max_fee_per_gas: max_fee_per_gas * GAS_PRICE_BUFFER_FACTOR,
-            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP as f64,
+            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP,
             base_fee_per_gas: 2_000_000_000f64,
         };
         assert_eq!(result, expected_result);
@@ -203,10 +365,11 @@ mod tests {
         let max_fee_per_gas_of_last_tx = max_fee_per_gas * 2f64;
         let gas_price_of_last_submission = GasPrice1559 {
             max_fee_per_gas: max_fee_per_gas_of_last_tx,
-            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP as f64,
+            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP,
             base_fee_per_gas: 2_000_000_000f64,
         };
         let result = calculate_submission_gas_price(
+            &config,
             Some(gas_price_of_last_submission),
             web3_gas_estimation,
             newest_nonce,
@@ -215,12 +378,12 @@ mod tests {
         .unwrap();
         let expected_result = GasPrice1559 {
             max_fee_per_gas: max_fee_per_gas_of_last_tx * GAS_PRICE_BUMP,
-            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP as f64 * GAS_PRICE_BUMP,
+            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP * GAS_PRICE_BUMP,
             base_fee_per_gas: 2_000_000_000f64,
         };
         assert_eq!(result, expected_result);
         // Thrid case: MAX_GAS_PRICE is not exceeded
-        let max_fee_per_gas = MAX_GAS_PRICE as f64 + 1000f64;
+        let max_fee_per_gas = MAX_GAS_PRICE + 1000f64;
         let web3_gas_estimation = GasPrice1559 {
             base_fee_per_gas: 2_000_000_000f64,
             max_fee_per_gas,
@@ -229,6 +392,7 @@ mod tests {
         let nonce_of_last_submission = None;
         let gas_price_of_last_submission = None;
         let result = calculate_submission_gas_price(
+            &config,
             gas_price_of_last_submission,
             web3_gas_estimation,
             newest_nonce,
@@ -237,8 +401,8 @@ mod tests {
         .unwrap();
         let expected_result = GasPrice1559 {
             base_fee_per_gas: 2_000_000_000f64,
-            max_fee_per_gas: MAX_GAS_PRICE as f64,
-            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP as f64,
+            max_fee_per_gas: MAX_GAS_PRICE,
+            max_priority_fee_per_gas: START_PRIORITY_FEE_TIP,
         };
         assert_eq!(result, expected_result);
     }
@@ -0,0 +1,75 @@
+use {
+    primitive_types::H160,
+    shared::{arguments::display_option, logging_args_with_default_filter},
+    std::{path::PathBuf, time::Duration},
+    tracing::level_filters::LevelFilter,
+    url::Url,
+};
+
+logging_args_with_default_filter!(LoggingArguments, "warn,replay=debug,autopilot=info");
+
+#[derive(clap::Parser)]
+pub struct Arguments {
+    #[clap(flatten)]
+    pub logging: LoggingArguments,
+
+    /// Url of the Postgres database the autopilot stored the auction and
+    /// the solver competition in.
+    #[clap(long, env, default_value = "postgresql://")]
+    pub db_url: Url,
+
+    /// Url of the locally running driver the auction gets replayed against.
+    #[clap(long, env, default_value = "http://localhost:11088/solver")]
+    pub driver_url: Url,
+
+    /// The id of the auction to replay.
+    #[clap(long, env)]
+    pub auction_id: i64,
+
+    /// Path to a JSON dump of the auction as archived by the autopilot. If
+    /// not specified the auction gets reconstructed from the database.
+    #[clap(long, env)]
+    pub auction_file: Option<PathBuf>,
+
+    /// Submission address of the solver whose stored solutions the replayed
+    /// solutions get compared to.
+    #[clap(long, env)]
+    pub solver: H160,
+
+    /// Tokens that get marked as trusted in the replayed auction.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub trusted_tokens: Vec<H160>,
+
+    /// How much time the driver gets to solve the replayed auction.
+    #[clap(long, env, default_value = "15s", value_parser = humantime::parse_duration)]
+    pub solve_time_limit: Duration,
+}
+
+impl std::fmt::Display for Arguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Arguments {
+            logging,
+            db_url: _,
+            driver_url,
+            auction_id,
+            auction_file,
+            solver,
+            trusted_tokens,
+            solve_time_limit,
+        } = self;
+
+        write!(f, "{logging}")?;
+        writeln!(f, "db_url: SECRET")?;
+        writeln!(f, "driver_url: {driver_url}")?;
+        writeln!(f, "auction_id: {auction_id}")?;
+        display_option(
+            f,
+            "auction_file",
+            &auction_file.as_ref().map(|path| path.display()),
+        )?;
+        writeln!(f, "solver: {solver:?}")?;
+        writeln!(f, "trusted_tokens: {trusted_tokens:?}")?;
+        writeln!(f, "solve_time_limit: {solve_time_limit:?}")?;
+        Ok(())
+    }
+}
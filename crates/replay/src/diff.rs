@@ -0,0 +1,517 @@
+//! Comparison of the solutions a driver proposed when replaying an auction
+//! with the solutions that were stored for the original auction.
+//!
+//! Simulation outcomes can only be compared partially: the solver competition
+//! doesn't store simulation results of proposed solutions and the driver
+//! drops solutions that fail to simulate instead of returning them. Execution
+//! outcomes are therefore compared for the winning solutions the autopilot
+//! asked to settle, using the recorded settlement outcome and on-chain gas,
+//! against the replayed solutions, which by definition simulated successfully.
+//! Gas is only compared if the driver reports its simulated gas.
+
+use {
+    anyhow::{Context, Result},
+    autopilot::infra::solvers::dto::solve,
+    database::solver_competition_v2::SolverCompetition,
+    model::order::OrderUid,
+    number::conversions::big_decimal_to_u256,
+    primitive_types::{H160, U256},
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt,
+    },
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Solution {
+    /// Solution uid assigned by the autopilot for stored solutions and the
+    /// solution id reported by the driver for replayed ones.
+    pub id: u64,
+    pub score: U256,
+    pub prices: BTreeMap<H160, U256>,
+    pub trades: BTreeMap<OrderUid, Executed>,
+    /// Whether the autopilot filtered out the solution (e.g. because it was
+    /// deemed unfair). Always `false` for replayed solutions.
+    pub filtered_out: bool,
+    /// How executing the solution went, if known.
+    pub execution: Option<Execution>,
+}
+
+/// Outcome of executing a solution. For stored solutions this is the recorded
+/// settlement execution, for replayed ones the driver's simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Execution {
+    Success {
+        gas: Option<u64>,
+    },
+    /// Executing the solution failed, e.g. because the settlement reverted or
+    /// did not get mined in time.
+    Failure(String),
+}
+
+impl Execution {
+    /// Creates the execution from the outcome stored for a settlement
+    /// execution and the gas the settlement used on-chain.
+    pub fn from_outcome(outcome: String, gas: Option<u64>) -> Self {
+        match outcome.as_str() {
+            "success" => Self::Success { gas },
+            _ => Self::Failure(outcome),
+        }
+    }
+
+    /// Whether both executions differ in their outcome. Gas only gets
+    /// compared if it is known for both.
+    fn differs(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Success { gas: Some(a) }, Self::Success { gas: Some(b) }) => a != b,
+            (Self::Success { .. }, Self::Success { .. }) => false,
+            (Self::Failure(a), Self::Failure(b)) => a != b,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Executed {
+    pub sell: U256,
+    pub buy: U256,
+}
+
+/// Extracts the solutions the given solver proposed in the stored
+/// competition. `executions` holds the known executions by solution uid.
+pub fn stored(
+    competition: &SolverCompetition,
+    solver: H160,
+    executions: &HashMap<i64, Execution>,
+) -> Result<Vec<Solution>> {
+    let amount = |value| big_decimal_to_u256(value).context("invalid amount");
+    competition
+        .solutions
+        .iter()
+        .filter(|solution| solution.solver.0 == solver.0)
+        .map(|solution| {
+            Ok(Solution {
+                id: u64::try_from(solution.uid).context("negative solution uid")?,
+                score: amount(&solution.score)?,
+                prices: solution
+                    .price_tokens
+                    .iter()
+                    .zip(&solution.price_values)
+                    .map(|(token, price)| Ok((H160(token.0), amount(price)?)))
+                    .collect::<Result<_>>()?,
+                trades: competition
+                    .trades
+                    .iter()
+                    .filter(|trade| trade.solution_uid == solution.uid)
+                    .map(|trade| {
+                        let executed = Executed {
+                            sell: amount(&trade.executed_sell)?,
+                            buy: amount(&trade.executed_buy)?,
+                        };
+                        Ok((OrderUid(trade.order_uid.0), executed))
+                    })
+                    .collect::<Result<_>>()?,
+                filtered_out: solution.filtered_out,
+                execution: executions.get(&solution.uid).cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Converts the solutions returned by the driver. The driver only returns
+/// solutions that simulated successfully.
+pub fn replayed(response: solve::Response) -> Vec<Solution> {
+    response
+        .solutions
+        .into_iter()
+        .map(|solution| Solution {
+            id: solution.solution_id,
+            score: solution.score,
+            prices: solution.clearing_prices.into_iter().collect(),
+            trades: solution
+                .orders
+                .into_iter()
+                .map(|(uid, order)| {
+                    let order = order.into_domain();
+                    let executed = Executed {
+                        sell: order.executed_sell.0,
+                        buy: order.executed_buy.0,
+                    };
+                    (uid, executed)
+                })
+                .collect(),
+            filtered_out: false,
+            execution: Some(Execution::Success { gas: solution.gas }),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Difference {
+    /// A stored solution has no replayed counterpart. This happens when the
+    /// solver did not find it again or the driver discarded it, e.g. because
+    /// it failed to simulate.
+    Missing { stored: Solution },
+    /// The driver proposed more solutions than were stored.
+    Unexpected { replayed: Solution },
+    Score {
+        stored: u64,
+        replayed: u64,
+        before: U256,
+        after: U256,
+    },
+    Price {
+        stored: u64,
+        replayed: u64,
+        token: H160,
+        before: Option<U256>,
+        after: Option<U256>,
+    },
+    Trade {
+        stored: u64,
+        replayed: u64,
+        order: OrderUid,
+        before: Option<Executed>,
+        after: Option<Executed>,
+    },
+    Execution {
+        stored: u64,
+        replayed: u64,
+        before: Execution,
+        after: Execution,
+    },
+}
+
+/// Computes the differences between the stored and the replayed solutions.
+///
+/// Both lists are paired up by descending score since the solution ids of
+/// the original and the replayed run are unrelated.
+pub fn diff(mut stored: Vec<Solution>, mut replayed: Vec<Solution>) -> Vec<Difference> {
+    stored.sort_by(|a, b| b.score.cmp(&a.score));
+    replayed.sort_by(|a, b| b.score.cmp(&a.score));
+
+    let mut differences = Vec::new();
+    let mut replayed = replayed.into_iter();
+    for before in stored {
+        let Some(after) = replayed.next() else {
+            differences.push(Difference::Missing { stored: before });
+            continue;
+        };
+        let (stored, replayed) = (before.id, after.id);
+        if before.score != after.score {
+            differences.push(Difference::Score {
+                stored,
+                replayed,
+                before: before.score,
+                after: after.score,
+            });
+        }
+        differences.extend(
+            changes(&before.prices, &after.prices).map(|(token, before, after)| {
+                Difference::Price {
+                    stored,
+                    replayed,
+                    token,
+                    before,
+                    after,
+                }
+            }),
+        );
+        differences.extend(
+            changes(&before.trades, &after.trades).map(|(order, before, after)| {
+                Difference::Trade {
+                    stored,
+                    replayed,
+                    order,
+                    before,
+                    after,
+                }
+            }),
+        );
+        if let (Some(before), Some(after)) = (before.execution, after.execution) {
+            if before.differs(&after) {
+                differences.push(Difference::Execution {
+                    stored,
+                    replayed,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+    differences.extend(replayed.map(|replayed| Difference::Unexpected { replayed }));
+    differences
+}
+
+/// Returns all keys whose values differ between both maps.
+fn changes<'a, K: Ord + Copy, V: PartialEq + Copy>(
+    before: &'a BTreeMap<K, V>,
+    after: &'a BTreeMap<K, V>,
+) -> impl Iterator<Item = (K, Option<V>, Option<V>)> + 'a {
+    let removed_or_changed = before
+        .iter()
+        .map(|(key, value)| (*key, Some(*value), after.get(key).copied()));
+    let added = after
+        .iter()
+        .filter(|(key, _)| !before.contains_key(key))
+        .map(|(key, value)| (*key, None, Some(*value)));
+    removed_or_changed
+        .chain(added)
+        .filter(|(_, before, after)| before != after)
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { stored } => write!(
+                f,
+                "stored solution {} (score {}, filtered out: {}) was not reproduced or failed to \
+                 simulate",
+                stored.id, stored.score, stored.filtered_out
+            ),
+            Self::Unexpected { replayed } => write!(
+                f,
+                "replayed solution {} (score {}) has no stored counterpart",
+                replayed.id, replayed.score
+            ),
+            Self::Score {
+                stored,
+                replayed,
+                before,
+                after,
+            } => write!(
+                f,
+                "solution {stored} -> {replayed}: score changed from {before} to {after}"
+            ),
+            Self::Price {
+                stored,
+                replayed,
+                token,
+                before,
+                after,
+            } => write!(
+                f,
+                "solution {stored} -> {replayed}: clearing price of {token:?} changed from \
+                 {before:?} to {after:?}"
+            ),
+            Self::Trade {
+                stored,
+                replayed,
+                order,
+                before,
+                after,
+            } => write!(
+                f,
+                "solution {stored} -> {replayed}: execution of order {order} changed from \
+                 {before:?} to {after:?}"
+            ),
+            Self::Execution {
+                stored,
+                replayed,
+                before,
+                after,
+            } => write!(
+                f,
+                "solution {stored} -> {replayed}: execution changed from {before:?} to {after:?}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, maplit::btreemap};
+
+    fn token(byte: u8) -> H160 {
+        H160([byte; 20])
+    }
+
+    #[test]
+    fn identical_solutions_have_no_differences() {
+        let solution = Solution {
+            id: 1,
+            score: 100.into(),
+            prices: btreemap! { token(1) => 2.into() },
+            trades: btreemap! {
+                OrderUid([1; 56]) => Executed { sell: 10.into(), buy: 20.into() },
+            },
+            filtered_out: false,
+            execution: None,
+        };
+        let replayed = Solution {
+            id: 7,
+            ..solution.clone()
+        };
+        assert_eq!(diff(vec![solution], vec![replayed]), vec![]);
+    }
+
+    #[test]
+    fn pairs_solutions_by_score() {
+        let stored = vec![
+            Solution {
+                id: 1,
+                score: 50.into(),
+                prices: btreemap! { token(1) => 2.into(), token(2) => 3.into() },
+                trades: btreemap! {
+                    OrderUid([1; 56]) => Executed { sell: 10.into(), buy: 20.into() },
+                },
+                ..Default::default()
+            },
+            Solution {
+                id: 2,
+                score: 100.into(),
+                ..Default::default()
+            },
+        ];
+        let replayed = vec![Solution {
+            id: 0,
+            score: 100.into(),
+            prices: btreemap! { token(1) => 4.into() },
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            diff(stored.clone(), replayed),
+            vec![
+                Difference::Price {
+                    stored: 2,
+                    replayed: 0,
+                    token: token(1),
+                    before: None,
+                    after: Some(4.into()),
+                },
+                Difference::Missing {
+                    stored: stored[0].clone()
+                },
+            ]
+        );
+
+        let replayed = vec![
+            Solution {
+                id: 0,
+                score: 40.into(),
+                prices: btreemap! { token(1) => 2.into() },
+                trades: btreemap! {
+                    OrderUid([1; 56]) => Executed { sell: 10.into(), buy: 19.into() },
+                    OrderUid([2; 56]) => Executed { sell: 1.into(), buy: 1.into() },
+                },
+                ..Default::default()
+            },
+            Solution {
+                id: 1,
+                score: 100.into(),
+                ..Default::default()
+            },
+            Solution {
+                id: 2,
+                score: 10.into(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            diff(stored, replayed.clone()),
+            vec![
+                Difference::Score {
+                    stored: 1,
+                    replayed: 0,
+                    before: 50.into(),
+                    after: 40.into(),
+                },
+                Difference::Price {
+                    stored: 1,
+                    replayed: 0,
+                    token: token(2),
+                    before: Some(3.into()),
+                    after: None,
+                },
+                Difference::Trade {
+                    stored: 1,
+                    replayed: 0,
+                    order: OrderUid([1; 56]),
+                    before: Some(Executed {
+                        sell: 10.into(),
+                        buy: 20.into()
+                    }),
+                    after: Some(Executed {
+                        sell: 10.into(),
+                        buy: 19.into()
+                    }),
+                },
+                Difference::Trade {
+                    stored: 1,
+                    replayed: 0,
+                    order: OrderUid([2; 56]),
+                    before: None,
+                    after: Some(Executed {
+                        sell: 1.into(),
+                        buy: 1.into()
+                    }),
+                },
+                Difference::Unexpected {
+                    replayed: replayed[2].clone()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_executions() {
+        let stored = vec![
+            Solution {
+                id: 1,
+                score: 100.into(),
+                execution: Some(Execution::from_outcome("timeout".to_string(), None)),
+                ..Default::default()
+            },
+            Solution {
+                id: 2,
+                score: 50.into(),
+                execution: Some(Execution::from_outcome("success".to_string(), Some(100))),
+                ..Default::default()
+            },
+            Solution {
+                id: 3,
+                score: 10.into(),
+                execution: Some(Execution::Success { gas: Some(100) }),
+                ..Default::default()
+            },
+        ];
+        let replayed = vec![
+            Solution {
+                id: 4,
+                score: 100.into(),
+                execution: Some(Execution::Success { gas: None }),
+                ..Default::default()
+            },
+            Solution {
+                id: 5,
+                score: 50.into(),
+                execution: Some(Execution::Success { gas: None }),
+                ..Default::default()
+            },
+            Solution {
+                id: 6,
+                score: 10.into(),
+                execution: Some(Execution::Success { gas: Some(120) }),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            diff(stored, replayed),
+            vec![
+                Difference::Execution {
+                    stored: 1,
+                    replayed: 4,
+                    before: Execution::Failure("timeout".to_string()),
+                    after: Execution::Success { gas: None },
+                },
+                Difference::Execution {
+                    stored: 3,
+                    replayed: 6,
+                    before: Execution::Success { gas: Some(100) },
+                    after: Execution::Success { gas: Some(120) },
+                },
+            ]
+        );
+    }
+}
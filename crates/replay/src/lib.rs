@@ -0,0 +1,161 @@
+//! Replays a recorded auction against a locally running driver and compares
+//! the proposed solutions with what was stored for the original auction.
+
+pub mod arguments;
+pub mod diff;
+
+use {
+    crate::arguments::Arguments,
+    anyhow::{Context, Result},
+    autopilot::{
+        arguments::Account,
+        database::Postgres,
+        domain,
+        infra::{self, persistence::dto, solvers::dto::solve},
+    },
+    clap::Parser,
+    database::{byte_array::ByteArray, solver_competition_v2::SolverCompetition},
+    number::conversions::big_decimal_to_u256,
+    primitive_types::H160,
+    std::{
+        collections::{HashMap, HashSet},
+        num::NonZeroUsize,
+        sync::Arc,
+    },
+};
+
+pub async fn start(args: impl Iterator<Item = String>) {
+    let args = Arguments::parse_from(args);
+    let obs_config = observe::Config::new(
+        args.logging.log_filter.as_str(),
+        args.logging.log_stderr_threshold,
+        args.logging.use_json_logs,
+        None,
+    );
+    observe::tracing::initialize(&obs_config);
+    observe::panic_hook::install();
+    tracing::info!("running replay with validated arguments:\n{}", args);
+    if let Err(err) = run(args).await {
+        tracing::error!(?err, "replay failed");
+        std::process::exit(1);
+    }
+}
+
+pub async fn run(args: Arguments) -> Result<()> {
+    let postgres = Postgres::new(args.db_url.as_str(), NonZeroUsize::MIN)
+        .await
+        .context("failed to connect to database")?;
+    let pool = postgres.pool.clone();
+    let persistence = infra::Persistence::new(None, Arc::new(postgres)).await;
+
+    let auction = load_auction(&args, &persistence).await?;
+    tracing::info!(
+        id = auction.id,
+        orders = auction.orders.len(),
+        "replaying auction"
+    );
+
+    let driver = infra::Driver::try_new(
+        args.driver_url.clone(),
+        "replay".to_string(),
+        None,
+        Account::Address(args.solver),
+        false,
+    )
+    .await?;
+    let request = solve::Request::new(
+        &auction,
+        &args.trusted_tokens.iter().copied().collect::<HashSet<_>>(),
+        args.solve_time_limit,
+    );
+    let replayed = diff::replayed(
+        driver
+            .solve(request)
+            .await
+            .context("driver failed to solve")?,
+    );
+
+    let mut ex = pool.acquire().await?;
+    let competition = database::solver_competition_v2::load_by_id(&mut ex, args.auction_id)
+        .await?
+        .context("no solver competition stored for auction")?;
+    let executions = stored_executions(&mut ex, &competition, args.solver).await?;
+    let stored = diff::stored(&competition, args.solver, &executions)?;
+
+    println!(
+        "auction {}: {} stored and {} replayed solutions for solver {:?}",
+        args.auction_id,
+        stored.len(),
+        replayed.len(),
+        args.solver
+    );
+    let differences = diff::diff(stored, replayed);
+    if differences.is_empty() {
+        println!("replayed solutions match the stored ones");
+    }
+    for difference in differences {
+        println!("{difference}");
+    }
+    Ok(())
+}
+
+/// Loads how executing the solver's winning solutions went in the original
+/// auction, keyed by solution uid.
+async fn stored_executions(
+    ex: &mut sqlx::PgConnection,
+    competition: &SolverCompetition,
+    solver: H160,
+) -> Result<HashMap<i64, diff::Execution>> {
+    let Some(outcome) =
+        database::settlement_executions::outcome(ex, competition.auction.id, ByteArray(solver.0))
+            .await?
+    else {
+        return Ok(Default::default());
+    };
+
+    let mut executions = HashMap::new();
+    let winners = competition
+        .solutions
+        .iter()
+        .filter(|solution| solution.solver.0 == solver.0 && solution.is_winner);
+    for winner in winners {
+        let settlement = competition
+            .settlements
+            .iter()
+            .find(|settlement| settlement.solution_uid == winner.uid);
+        let gas = match settlement {
+            Some(settlement) => database::settlement_observations::fetch(ex, &[settlement.tx_hash])
+                .await?
+                .first()
+                .and_then(|observation| big_decimal_to_u256(&observation.gas_used))
+                .and_then(|gas| u64::try_from(gas).ok()),
+            None => None,
+        };
+        executions.insert(
+            winner.uid,
+            diff::Execution::from_outcome(outcome.clone(), gas),
+        );
+    }
+    Ok(executions)
+}
+
+/// Loads the auction either from the JSON dump archived by the autopilot or
+/// reconstructs it from the database.
+async fn load_auction(
+    args: &Arguments,
+    persistence: &infra::Persistence,
+) -> Result<domain::Auction> {
+    match &args.auction_file {
+        Some(path) => {
+            let file = std::fs::read(path).context("failed to read auction file")?;
+            let auction: dto::RawAuctionData =
+                serde_json::from_slice(&file).context("invalid auction file")?;
+            dto::Auction {
+                id: args.auction_id,
+                auction,
+            }
+            .try_into_domain()
+        }
+        None => persistence.reconstruct_auction(args.auction_id).await,
+    }
+}
@@ -4,13 +4,23 @@ pub mod list_based;
 pub mod token_owner_finder;
 pub mod trace_call;
 
-use {anyhow::Result, primitive_types::H160};
+use {
+    anyhow::Result,
+    primitive_types::{H160, U256, U512},
+};
 
 /// How well behaved a token is.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TokenQuality {
     Good,
-    Bad { reason: String },
+    /// The token can be traded, but transfers of it arrive reduced by the
+    /// measured fee.
+    FeeOnTransfer {
+        fee: TransferFee,
+    },
+    Bad {
+        reason: String,
+    },
 }
 
 impl TokenQuality {
@@ -18,6 +28,19 @@ impl TokenQuality {
         matches!(self, Self::Good { .. })
     }
 
+    /// Whether orders trading the token are supported. Unlike
+    /// [`Self::is_good`] this includes tokens that take a fee on transfer.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Self::Bad { .. })
+    }
+
+    pub fn transfer_fee(&self) -> Option<TransferFee> {
+        match self {
+            Self::FeeOnTransfer { fee } => Some(*fee),
+            _ => None,
+        }
+    }
+
     pub fn bad(reason: impl ToString) -> Self {
         Self::Bad {
             reason: reason.to_string(),
@@ -25,9 +48,87 @@ impl TokenQuality {
     }
 }
 
+/// A fee a token takes on every transfer, in basis points of the transferred
+/// amount.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub struct TransferFee {
+    bps: u32,
+}
+
+impl TransferFee {
+    const BPS_BASE: u32 = 10_000;
+
+    /// Creates a transfer fee of `bps` basis points. Returns `None` if no fee
+    /// is taken at all or if it takes the entire amount.
+    pub fn from_bps(bps: u32) -> Option<Self> {
+        (bps > 0 && bps < Self::BPS_BASE).then_some(Self { bps })
+    }
+
+    /// Measures the fee of a transfer of `sent` tokens that resulted in the
+    /// recipient receiving only `received` tokens. The fee is rounded up.
+    pub fn measure(sent: U256, received: U256) -> Option<Self> {
+        let lost = sent.checked_sub(received)?;
+        if lost.is_zero() {
+            return None;
+        }
+        let sent = U512::from(sent);
+        let bps = (lost.full_mul(Self::BPS_BASE.into()) + sent - U512::one()) / sent;
+        Self::from_bps(bps.low_u32())
+    }
+
+    pub fn bps(&self) -> u32 {
+        self.bps
+    }
+
+    /// The amount the recipient receives when `amount` gets transferred.
+    pub fn received(&self, amount: U256) -> U256 {
+        let received = amount.full_mul((Self::BPS_BASE - self.bps).into()) / Self::BPS_BASE;
+        U256::try_from(received).expect("received amount is at most the transferred amount")
+    }
+
+    /// The amount that needs to be transferred for the recipient to receive
+    /// at least `amount`. Saturates at `U256::MAX`.
+    pub fn required(&self, amount: U256) -> U256 {
+        let remainder = U512::from(Self::BPS_BASE - self.bps);
+        let required =
+            (amount.full_mul(Self::BPS_BASE.into()) + remainder - U512::one()) / remainder;
+        U256::try_from(required).unwrap_or(U256::MAX)
+    }
+
+    /// The part of `amount` that gets lost when transferring it.
+    pub fn fee(&self, amount: U256) -> U256 {
+        amount - self.received(amount)
+    }
+}
+
 /// Detect how well behaved a token is.
 #[mockall::automock]
 #[async_trait::async_trait]
 pub trait BadTokenDetecting: Send + Sync {
     async fn detect(&self, token: H160) -> Result<TokenQuality>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_fee_amounts() {
+        let fee = TransferFee::measure(1_000.into(), 990.into()).unwrap();
+        assert_eq!(fee.bps(), 100);
+        assert_eq!(fee.received(1_000.into()), 990.into());
+        assert_eq!(fee.fee(1_000.into()), 10.into());
+        assert_eq!(fee.required(990.into()), 1_000.into());
+
+        // Measured fees round up and required amounts always cover the fee.
+        let fee = TransferFee::measure(3_000.into(), 2_999.into()).unwrap();
+        assert_eq!(fee.bps(), 4);
+        for amount in [1_u64, 7, 2_499, 1_000_000_007] {
+            assert!(fee.received(fee.required(amount.into())) >= amount.into());
+        }
+        assert_eq!(fee.required(U256::MAX), U256::MAX);
+
+        assert_eq!(TransferFee::measure(1_000.into(), 1_000.into()), None);
+        assert_eq!(TransferFee::measure(1_000.into(), 0.into()), None);
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    super::{BadTokenDetecting, TokenQuality, token_owner_finder::TokenOwnerFinding},
+    super::{BadTokenDetecting, TokenQuality, TransferFee, token_owner_finder::TokenOwnerFinding},
     crate::{ethrpc::Web3, trace_many},
     anyhow::{Context, Result, bail, ensure},
     contracts::ERC20,
@@ -26,6 +26,9 @@ use {
 /// - we cannot find an amm pool of the token to one of the base tokens
 /// - transfer into the settlement contract or back out fails
 /// - a transfer loses total balance
+///
+/// Tokens that take a fee on transfer are not considered bad, but are reported
+/// along with the measured fee.
 pub struct TraceCallDetector {
     inner: TraceCallDetectorRaw,
     finder: Arc<dyn TokenOwnerFinding>,
@@ -118,7 +121,7 @@ impl TraceCallDetectorRaw {
             })
             .collect();
         // We transfer the full available amount of the token from the amm pool into the
-        // settlement contract and then to an arbitrary address.
+        // settlement contract and then half of it to an arbitrary address.
         // Note that gas use can depend on the recipient because for the standard
         // implementation sending to an address that does not have any balance
         // yet (implicitly 0) causes an allocation.
@@ -161,6 +164,13 @@ impl TraceCallDetectorRaw {
             .public_address()
     }
 
+    // Only half of the amount gets transferred out of the settlement contract
+    // again, so that the transfer can succeed even if the token took a fee on
+    // the way in.
+    fn out_amount(amount: U256) -> U256 {
+        amount - amount / 2
+    }
+
     fn create_trace_request(&self, token: H160, amount: U256, take_from: H160) -> Vec<CallRequest> {
         let instance = ERC20::at(&self.web3, token);
 
@@ -180,7 +190,7 @@ impl TraceCallDetectorRaw {
         let tx = instance.balance_of(recipient).m.tx;
         requests.push(call_request(None, token, tx));
         // 4
-        let tx = instance.transfer(recipient, amount).tx;
+        let tx = instance.transfer(recipient, Self::out_amount(amount)).tx;
         requests.push(call_request(Some(self.settlement_contract), token, tx));
         // 5
         let tx = instance.balance_of(self.settlement_contract).m.tx;
@@ -256,52 +266,55 @@ impl TraceCallDetectorRaw {
 
         tracing::debug!(%amount, %balance_before_in, %balance_after_in, %balance_after_out);
 
-        let computed_balance_after_in = match balance_before_in.checked_add(amount) {
-            Some(amount) => amount,
-            None => {
-                return Ok(TokenQuality::bad(format!(
-                    "Transferring {amount} into settlement contract would overflow its balance."
-                )));
-            }
-        };
-        // Allow for a small discrepancy (1 wei) in the balance after the transfer which
-        // may come from rounding discrepancies in tokens that track balances
-        // with "shares" (e.g. eUSD).
-        if balance_after_in < computed_balance_after_in.saturating_sub(U256::one()) {
+        if balance_before_in.checked_add(amount).is_none() {
             return Ok(TokenQuality::bad(format!(
-                "Transferring {amount} into settlement contract was expected to result in a \
-                 balance of {computed_balance_after_in} but actually resulted in \
-                 {balance_after_in}. A common cause for this is that the token takes a fee on \
-                 transfer."
+                "Transferring {amount} into settlement contract would overflow its balance."
             )));
         }
-        if balance_after_out != balance_before_in {
+        let received_in = balance_after_in.saturating_sub(balance_before_in);
+        let Ok(fee_in) = measure_transfer_fee(amount, received_in) else {
             return Ok(TokenQuality::bad(format!(
-                "Transferring {amount} out of settlement contract was expected to result in the \
-                 original balance of {balance_before_in} but actually resulted in \
-                 {balance_after_out}."
+                "Transferring {amount} into settlement contract increased its balance by only \
+                 {received_in}."
             )));
-        }
-        let computed_balance_recipient_after = match balance_recipient_before.checked_add(amount) {
-            Some(amount) => amount,
-            None => {
-                return Ok(TokenQuality::bad(format!(
-                    "Transferring {amount} into arbitrary recipient {arbitrary:?} would overflow \
-                     its balance."
-                )));
-            }
         };
+
+        let out_amount = Self::out_amount(amount);
+        let computed_balance_after_out = balance_after_in.checked_sub(out_amount);
+        if computed_balance_after_out != Some(balance_after_out) {
+            return Ok(TokenQuality::bad(format!(
+                "Transferring {out_amount} out of settlement contract with a balance of \
+                 {balance_after_in} resulted in a balance of {balance_after_out}."
+            )));
+        }
+
+        let computed_balance_recipient_after =
+            match balance_recipient_before.checked_add(out_amount) {
+                Some(amount) => amount,
+                None => {
+                    return Ok(TokenQuality::bad(format!(
+                        "Transferring {out_amount} into arbitrary recipient {arbitrary:?} would \
+                         overflow its balance."
+                    )));
+                }
+            };
         // Allow for a small discrepancy (1 wei) in the balance after the transfer
         // which may come from rounding discrepancies in tokens that track
         // balances with "shares" (e.g. eUSD).
         if computed_balance_recipient_after < balance_recipient_after.saturating_sub(U256::one()) {
             return Ok(TokenQuality::bad(format!(
-                "Transferring {amount} into arbitrary recipient {arbitrary:?} was expected to \
+                "Transferring {out_amount} into arbitrary recipient {arbitrary:?} was expected to \
                  result in a balance of {computed_balance_recipient_after} but actually resulted \
-                 in {balance_recipient_after}. A common cause for this is that the token takes a \
-                 fee on transfer."
+                 in {balance_recipient_after}."
             )));
         }
+        let received_out = balance_recipient_after.saturating_sub(balance_recipient_before);
+        let Ok(fee_out) = measure_transfer_fee(out_amount, received_out) else {
+            return Ok(TokenQuality::bad(format!(
+                "Transferring {out_amount} into arbitrary recipient {arbitrary:?} increased its \
+                 balance by only {received_out}."
+            )));
+        };
 
         if let Err(err) = ensure_transaction_ok_and_get_gas(&traces[7])? {
             return Ok(TokenQuality::bad(format!(
@@ -310,8 +323,27 @@ impl TraceCallDetectorRaw {
         }
 
         let _gas_per_transfer = (gas_in + gas_out) / 2;
-        Ok(TokenQuality::Good)
+        match [fee_in, fee_out]
+            .into_iter()
+            .flatten()
+            .max_by_key(TransferFee::bps)
+        {
+            Some(fee) => Ok(TokenQuality::FeeOnTransfer { fee }),
+            None => Ok(TokenQuality::Good),
+        }
+    }
+}
+
+/// Measures the fee taken by a transfer of `sent` tokens which resulted in the
+/// recipient receiving `received` tokens. Allows for a small discrepancy (1
+/// wei) which may come from rounding discrepancies in tokens that track
+/// balances with "shares" (e.g. eUSD). Returns an error if the transfer took
+/// the entire amount.
+fn measure_transfer_fee(sent: U256, received: U256) -> Result<Option<TransferFee>, ()> {
+    if received.saturating_add(U256::one()) >= sent {
+        return Ok(None);
     }
+    TransferFee::measure(sent, received).map(Some).ok_or(())
 }
 
 fn call_request(
@@ -515,6 +547,77 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn handle_response_fee_on_transfer() {
+        let balance = |balance: u64| BlockTrace {
+            output: encode_u256(balance.into()),
+            trace: None,
+            vm_trace: None,
+            state_diff: None,
+            transaction_hash: None,
+        };
+        let call = || BlockTrace {
+            output: Default::default(),
+            trace: Some(vec![TransactionTrace {
+                trace_address: Vec::new(),
+                subtraces: 0,
+                action: Action::Call(Call {
+                    from: H160::zero(),
+                    to: H160::zero(),
+                    value: 0.into(),
+                    gas: 0.into(),
+                    input: Bytes(Vec::new()),
+                    call_type: CallType::None,
+                }),
+                action_type: ActionType::Call,
+                result: Some(Res::Call(CallResult {
+                    gas_used: 1.into(),
+                    output: Bytes(Vec::new()),
+                })),
+                error: None,
+            }]),
+            vm_trace: None,
+            state_diff: None,
+            transaction_hash: None,
+        };
+
+        // The token takes 1% on the way into the settlement contract and
+        // a bit less on the way out.
+        let traces = &[
+            balance(0),
+            call(),
+            balance(990),
+            balance(0),
+            call(),
+            balance(490),
+            balance(497),
+            call(),
+        ];
+        let result =
+            TraceCallDetectorRaw::handle_response(traces, 1_000.into(), H160::zero()).unwrap();
+        assert_eq!(
+            result,
+            TokenQuality::FeeOnTransfer {
+                fee: TransferFee::from_bps(100).unwrap(),
+            }
+        );
+
+        // Tokens that take everything are bad.
+        let traces = &[
+            balance(0),
+            call(),
+            balance(0),
+            balance(0),
+            call(),
+            balance(0),
+            balance(0),
+            call(),
+        ];
+        let result =
+            TraceCallDetectorRaw::handle_response(traces, 1_000.into(), H160::zero()).unwrap();
+        assert!(!result.is_supported());
+    }
+
     #[test]
     fn arbitrary_recipient_() {
         println!("{:?}", TraceCallDetectorRaw::arbitrary_recipient());
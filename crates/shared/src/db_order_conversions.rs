@@ -99,6 +99,11 @@ pub fn full_order_into_model_order(order: database::orders::FullOrder) -> Result
             .transpose()
             .context("full app data isn't utf-8")?,
         quote: None,
+        condition: order
+            .condition
+            .map(serde_json::from_value)
+            .transpose()
+            .context("invalid order condition")?,
     };
     let data = OrderData {
         sell_token: H160(order.sell_token.0),
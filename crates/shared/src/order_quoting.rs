@@ -7,17 +7,18 @@ use {
     },
     crate::{
         account_balances::{BalanceFetching, Query},
+        bad_token::{BadTokenDetecting, TransferFee},
         db_order_conversions::order_kind_from,
         fee::FeeParameters,
         order_validation::PreOrderData,
         price_estimation::{Estimate, QuoteVerificationMode, Verification},
         trade_finding::external::dto,
     },
-    anyhow::{Context, Result},
+    anyhow::{Context, Result, anyhow},
     chrono::{DateTime, Duration, Utc},
     database::quotes::{Quote as QuoteRow, QuoteKind},
     ethcontract::{H160, U256},
-    futures::TryFutureExt,
+    futures::{StreamExt, TryFutureExt, stream::BoxStream},
     gas_estimation::GasPriceEstimating,
     model::{
         interaction::InteractionData,
@@ -25,7 +26,7 @@ use {
         quote::{OrderQuoteRequest, OrderQuoteSide, QuoteId, QuoteSigningScheme, SellAmount},
     },
     num::FromPrimitive,
-    number::conversions::big_decimal_to_u256,
+    number::{conversions::big_decimal_to_u256, nonzero::U256 as NonZeroU256},
     std::sync::Arc,
     thiserror::Error,
 };
@@ -43,27 +44,44 @@ pub struct QuoteParameters {
 }
 
 impl QuoteParameters {
+    /// Builds the price estimation query for the amounts that actually arrive
+    /// in the settlement contract, i.e. the sell amount after the sell token
+    /// transfer fee for sell orders and the buy amount that needs to be bought
+    /// to pay out the order after the buy token transfer fee for buy orders.
     fn to_price_query(
         &self,
         default_quote_timeout: std::time::Duration,
-    ) -> price_estimation::Query {
+        transfer_fees: &TransferFees,
+    ) -> Result<price_estimation::Query, CalculateQuoteError> {
         let (kind, in_amount) = match self.side {
             OrderQuoteSide::Sell {
                 sell_amount:
                     SellAmount::BeforeFee { value: sell_amount }
                     | SellAmount::AfterFee { value: sell_amount },
-            } => (OrderKind::Sell, sell_amount),
+            } => (
+                OrderKind::Sell,
+                transfer_fees
+                    .sell
+                    .map_or(sell_amount.get(), |fee| fee.received(sell_amount.get())),
+            ),
             OrderQuoteSide::Buy {
                 buy_amount_after_fee,
-            } => (OrderKind::Buy, buy_amount_after_fee),
+            } => (
+                OrderKind::Buy,
+                transfer_fees.buy.map_or(buy_amount_after_fee.get(), |fee| {
+                    fee.required(buy_amount_after_fee.get())
+                }),
+            ),
         };
+        let in_amount = NonZeroU256::try_from(in_amount)
+            .map_err(|_| anyhow!("the entire amount is lost to the transfer fee"))?;
 
         let timeout = self
             .timeout
             .unwrap_or(default_quote_timeout)
             .min(default_quote_timeout);
 
-        price_estimation::Query {
+        Ok(price_estimation::Query {
             verification: self.verification.clone(),
             sell_token: self.sell_token,
             buy_token: self.buy_token,
@@ -71,7 +89,7 @@ impl QuoteParameters {
             kind,
             block_dependent: true,
             timeout,
-        }
+        })
     }
 
     pub fn additional_cost(&self) -> u64 {
@@ -81,6 +99,20 @@ impl QuoteParameters {
     }
 }
 
+/// Prices needed to compute the fee of a quote.
+#[derive(Clone, Copy, Debug)]
+struct FeePrices {
+    gas_price: f64,
+    sell_token_price: f64,
+}
+
+/// The fees the traded tokens take on transfers.
+#[derive(Clone, Copy, Debug, Default)]
+struct TransferFees {
+    sell: Option<TransferFee>,
+    buy: Option<TransferFee>,
+}
+
 /// A calculated order quote.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Quote {
@@ -241,6 +273,10 @@ pub trait OrderQuoting: Send + Sync {
         parameters: QuoteParameters,
     ) -> Result<Quote, CalculateQuoteError>;
 
+    /// Computes quotes for the specified order parameters and reports them as
+    /// soon as the individual price sources return. Doesn't store the quotes.
+    fn calculate_quote_stream(&self, parameters: QuoteParameters) -> BoxStream<'_, QuoteUpdate>;
+
     /// Stores a quote.
     async fn store_quote(&self, quote: Quote) -> Result<Quote>;
 
@@ -252,6 +288,16 @@ pub trait OrderQuoting: Send + Sync {
     ) -> Result<Quote, FindQuoteError>;
 }
 
+/// Quote computed from the result of a single price source while other
+/// sources are still being queried.
+#[derive(Debug)]
+pub struct QuoteUpdate {
+    pub result: Result<Quote, CalculateQuoteError>,
+    /// Whether this is the best quote reported so far. Only ever set for
+    /// successful quotes.
+    pub best: bool,
+}
+
 #[derive(Error, Debug)]
 pub enum CalculateQuoteError {
     #[error("sell amount does not cover fee")]
@@ -413,6 +459,7 @@ pub struct OrderQuoter {
     balance_fetcher: Arc<dyn BalanceFetching>,
     quote_verification: QuoteVerificationMode,
     default_quote_timeout: std::time::Duration,
+    bad_token_detector: Arc<dyn BadTokenDetecting>,
 }
 
 impl OrderQuoter {
@@ -426,6 +473,7 @@ impl OrderQuoter {
         balance_fetcher: Arc<dyn BalanceFetching>,
         quote_verification: QuoteVerificationMode,
         default_quote_timeout: std::time::Duration,
+        bad_token_detector: Arc<dyn BadTokenDetecting>,
     ) -> Self {
         Self {
             price_estimator,
@@ -437,6 +485,7 @@ impl OrderQuoter {
             balance_fetcher,
             quote_verification,
             default_quote_timeout,
+            bad_token_detector,
         }
     }
 
@@ -444,38 +493,74 @@ impl OrderQuoter {
         &self,
         parameters: &QuoteParameters,
     ) -> Result<QuoteData, CalculateQuoteError> {
-        let expiration = match parameters.signing_scheme {
-            QuoteSigningScheme::Eip1271 {
-                onchain_order: true,
-                ..
-            } => self.now.now() + self.validity.eip1271_onchain_quote,
-            QuoteSigningScheme::PreSign {
-                onchain_order: true,
-            } => self.now.now() + self.validity.presign_onchain_quote,
-            _ => self.now.now() + self.validity.standard_quote,
-        };
+        let transfer_fees = self.transfer_fees(parameters).await?;
+        let trade_query =
+            Arc::new(parameters.to_price_query(self.default_quote_timeout, &transfer_fees)?);
+        let (prices, trade_estimate) = futures::try_join!(
+            self.fee_prices(parameters, trade_query.timeout),
+            self.price_estimator
+                .estimate(trade_query.clone())
+                .map_err(|err| (EstimatorKind::Regular, err).into()),
+        )?;
+
+        self.quote_data(
+            parameters,
+            &trade_query,
+            &transfer_fees,
+            &prices,
+            trade_estimate,
+        )
+        .await
+    }
 
-        let trade_query = Arc::new(parameters.to_price_query(self.default_quote_timeout));
-        let (gas_estimate, trade_estimate, sell_token_price, _) = futures::try_join!(
+    /// Fetches the prices needed to compute the fee of a quote.
+    async fn fee_prices(
+        &self,
+        parameters: &QuoteParameters,
+        timeout: std::time::Duration,
+    ) -> Result<FeePrices, CalculateQuoteError> {
+        let (gas_estimate, sell_token_price, _) = futures::try_join!(
             self.gas_estimator
                 .estimate()
                 .map_err(|err| CalculateQuoteError::from((
                     EstimatorKind::Gas,
                     PriceEstimationError::ProtocolInternal(err)
                 ))),
-            self.price_estimator
-                .estimate(trade_query.clone())
-                .map_err(|err| (EstimatorKind::Regular, err).into()),
             self.native_price_estimator
-                .estimate_native_price(parameters.sell_token, trade_query.timeout)
+                .estimate_native_price(parameters.sell_token, timeout)
                 .map_err(|err| (EstimatorKind::NativeSell, err).into()),
             // We don't care about the native price of the buy_token for the quote but we need it
             // when we build the auction. To prevent creating orders which we can't settle later on
             // we make the native buy_token price a requirement here as well.
             self.native_price_estimator
-                .estimate_native_price(parameters.buy_token, trade_query.timeout)
+                .estimate_native_price(parameters.buy_token, timeout)
                 .map_err(|err| (EstimatorKind::NativeBuy, err).into()),
         )?;
+        Ok(FeePrices {
+            gas_price: gas_estimate.effective_gas_price(),
+            sell_token_price,
+        })
+    }
+
+    /// Computes the quote data for a trade estimate.
+    async fn quote_data(
+        &self,
+        parameters: &QuoteParameters,
+        trade_query: &price_estimation::Query,
+        transfer_fees: &TransferFees,
+        prices: &FeePrices,
+        trade_estimate: Estimate,
+    ) -> Result<QuoteData, CalculateQuoteError> {
+        let expiration = match parameters.signing_scheme {
+            QuoteSigningScheme::Eip1271 {
+                onchain_order: true,
+                ..
+            } => self.now.now() + self.validity.eip1271_onchain_quote,
+            QuoteSigningScheme::PreSign {
+                onchain_order: true,
+            } => self.now.now() + self.validity.presign_onchain_quote,
+            _ => self.now.now() + self.validity.standard_quote,
+        };
 
         let (quoted_sell_amount, quoted_buy_amount) = match &parameters.side {
             OrderQuoteSide::Sell {
@@ -483,15 +568,25 @@ impl OrderQuoter {
             }
             | OrderQuoteSide::Sell {
                 sell_amount: SellAmount::AfterFee { value: sell_amount },
-            } => (sell_amount.get(), trade_estimate.out_amount),
+            } => (
+                sell_amount.get(),
+                transfer_fees.buy.map_or(trade_estimate.out_amount, |fee| {
+                    fee.received(trade_estimate.out_amount)
+                }),
+            ),
             OrderQuoteSide::Buy {
                 buy_amount_after_fee: buy_amount,
-            } => (trade_estimate.out_amount, buy_amount.get()),
+            } => (
+                transfer_fees.sell.map_or(trade_estimate.out_amount, |fee| {
+                    fee.required(trade_estimate.out_amount)
+                }),
+                buy_amount.get(),
+            ),
         };
         let fee_parameters = FeeParameters {
             gas_amount: trade_estimate.gas as _,
-            gas_price: gas_estimate.effective_gas_price(),
-            sell_token_price,
+            gas_price: prices.gas_price,
+            sell_token_price: prices.sell_token_price,
         };
 
         self.verify_quote(&trade_estimate, parameters, quoted_sell_amount)
@@ -520,6 +615,49 @@ impl OrderQuoter {
         Ok(quote)
     }
 
+    /// Turns computed quote data into a quote for the specified parameters.
+    fn quote(parameters: &QuoteParameters, data: QuoteData) -> Result<Quote, CalculateQuoteError> {
+        let mut quote =
+            Quote::new(Default::default(), data).with_additional_cost(parameters.additional_cost());
+
+        // Make sure to scale the sell and buy amounts for quotes for sell
+        // amounts before fees.
+        if let OrderQuoteSide::Sell {
+            sell_amount:
+                SellAmount::BeforeFee {
+                    value: sell_amount_before_fee,
+                },
+        } = &parameters.side
+        {
+            let sell_amount =
+                Into::<U256>::into(*sell_amount_before_fee).saturating_sub(quote.fee_amount);
+            if sell_amount == U256::zero() {
+                // We want a sell_amount of at least 1!
+                return Err(CalculateQuoteError::SellAmountDoesNotCoverFee {
+                    fee_amount: quote.fee_amount,
+                });
+            }
+
+            quote = quote.with_scaled_sell_amount(sell_amount);
+        }
+
+        Ok(quote)
+    }
+
+    /// Looks up the transfer fees of the traded tokens. Quotes for tokens that
+    /// take a fee on transfer are computed for the amounts that actually
+    /// arrive in the settlement contract.
+    async fn transfer_fees(&self, parameters: &QuoteParameters) -> Result<TransferFees> {
+        let (sell, buy) = futures::try_join!(
+            self.bad_token_detector.detect(parameters.sell_token),
+            self.bad_token_detector.detect(parameters.buy_token),
+        )?;
+        Ok(TransferFees {
+            sell: sell.transfer_fee(),
+            buy: buy.transfer_fee(),
+        })
+    }
+
     /// Makes sure a quote was verified according to the configured rule.
     async fn verify_quote(
         &self,
@@ -584,32 +722,60 @@ impl OrderQuoting for OrderQuoter {
         parameters: QuoteParameters,
     ) -> Result<Quote, CalculateQuoteError> {
         let data = self.compute_quote_data(&parameters).await?;
-        let mut quote =
-            Quote::new(Default::default(), data).with_additional_cost(parameters.additional_cost());
+        let quote = Self::quote(&parameters, data)?;
+        tracing::debug!(?quote, "computed quote");
+        Ok(quote)
+    }
 
-        // Make sure to scale the sell and buy amounts for quotes for sell
-        // amounts before fees.
-        if let OrderQuoteSide::Sell {
-            sell_amount:
-                SellAmount::BeforeFee {
-                    value: sell_amount_before_fee,
-                },
-        } = &parameters.side
-        {
-            let sell_amount =
-                Into::<U256>::into(*sell_amount_before_fee).saturating_sub(quote.fee_amount);
-            if sell_amount == U256::zero() {
-                // We want a sell_amount of at least 1!
-                return Err(CalculateQuoteError::SellAmountDoesNotCoverFee {
-                    fee_amount: quote.fee_amount,
-                });
-            }
+    fn calculate_quote_stream(&self, parameters: QuoteParameters) -> BoxStream<'_, QuoteUpdate> {
+        async_stream::stream! {
+            let transfer_fees = match self.transfer_fees(&parameters).await {
+                Ok(transfer_fees) => transfer_fees,
+                Err(err) => {
+                    yield QuoteUpdate { result: Err(err.into()), best: false };
+                    return;
+                }
+            };
+            let trade_query =
+                match parameters.to_price_query(self.default_quote_timeout, &transfer_fees) {
+                    Ok(query) => Arc::new(query),
+                    Err(err) => {
+                        yield QuoteUpdate { result: Err(err), best: false };
+                        return;
+                    }
+                };
+
+            // Start estimating the trade while the fee prices get fetched.
+            let mut estimates = self.price_estimator.estimate_streaming(trade_query.clone());
+            let (prices, mut next) = futures::join!(
+                self.fee_prices(&parameters, trade_query.timeout),
+                estimates.next(),
+            );
+            let prices = match prices {
+                Ok(prices) => prices,
+                Err(err) => {
+                    yield QuoteUpdate { result: Err(err), best: false };
+                    return;
+                }
+            };
 
-            quote = quote.with_scaled_sell_amount(sell_amount);
+            while let Some(update) = next {
+                let result = match update.result {
+                    Ok(estimate) => self
+                        .quote_data(&parameters, &trade_query, &transfer_fees, &prices, estimate)
+                        .await
+                        .and_then(|data| Self::quote(&parameters, data)),
+                    Err(err) => Err((EstimatorKind::Regular, err).into()),
+                };
+                tracing::debug!(?result, best = update.best, "computed streamed quote");
+                yield QuoteUpdate {
+                    best: update.best && result.is_ok(),
+                    result,
+                };
+                next = estimates.next().await;
+            }
         }
-
-        tracing::debug!(?quote, "computed quote");
-        Ok(quote)
+        .boxed()
     }
 
     async fn store_quote(&self, quote: Quote) -> Result<Quote> {
@@ -770,8 +936,10 @@ mod tests {
         super::*,
         crate::{
             account_balances::MockBalanceFetching,
+            bad_token::{MockBadTokenDetecting, TokenQuality, list_based::ListBasedDetector},
             gas_price_estimation::FakeGasPriceEstimator,
             price_estimation::{
+                EstimateUpdate,
                 HEALTHY_PRICE_ESTIMATION_TIME,
                 MockPriceEstimating,
                 native::MockNativePriceEstimating,
@@ -794,6 +962,10 @@ mod tests {
         Arc::new(mock)
     }
 
+    fn no_bad_tokens() -> Arc<dyn BadTokenDetecting> {
+        Arc::new(ListBasedDetector::deny_list(Vec::new()))
+    }
+
     #[test]
     fn pre_order_data_from_quote_request() {
         let quote_request = OrderQuoteRequest {
@@ -924,6 +1096,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -1065,6 +1238,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -1098,6 +1272,101 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn streams_quotes_of_each_price_source() {
+        let now = Utc::now();
+        let parameters = QuoteParameters {
+            sell_token: H160([1; 20]),
+            buy_token: H160([2; 20]),
+            side: OrderQuoteSide::Sell {
+                sell_amount: SellAmount::AfterFee {
+                    value: NonZeroU256::try_from(100).unwrap(),
+                },
+            },
+            ..Default::default()
+        };
+        let estimate = |out_amount: u64, verified: bool| price_estimation::Estimate {
+            out_amount: out_amount.into(),
+            gas: 3,
+            solver: H160([1; 20]),
+            verified,
+            execution: Default::default(),
+        };
+
+        let mut price_estimator = MockPriceEstimating::new();
+        price_estimator
+            .expect_estimate_streaming()
+            .return_once(move |_| {
+                futures::stream::iter([
+                    EstimateUpdate {
+                        result: Ok(estimate(42, false)),
+                        best: true,
+                    },
+                    EstimateUpdate {
+                        result: Err(PriceEstimationError::NoLiquidity),
+                        best: false,
+                    },
+                    EstimateUpdate {
+                        result: Ok(estimate(50, true)),
+                        best: true,
+                    },
+                    EstimateUpdate {
+                        result: Ok(estimate(45, false)),
+                        best: false,
+                    },
+                ])
+                .boxed()
+            });
+
+        let mut native_price_estimator = MockNativePriceEstimating::new();
+        native_price_estimator
+            .expect_estimate_native_price()
+            .returning(|_, _| async { Ok(0.2) }.boxed());
+
+        let gas_estimator = FakeGasPriceEstimator(Arc::new(Mutex::new(GasPrice1559 {
+            base_fee_per_gas: 1.5,
+            max_fee_per_gas: 3.0,
+            max_priority_fee_per_gas: 0.5,
+        })));
+
+        let quoter = OrderQuoter {
+            price_estimator: Arc::new(price_estimator),
+            native_price_estimator: Arc::new(native_price_estimator),
+            gas_estimator: Arc::new(gas_estimator),
+            storage: Arc::new(MockQuoteStoring::new()),
+            now: Arc::new(now),
+            validity: Validity::default(),
+            quote_verification: QuoteVerificationMode::Unverified,
+            balance_fetcher: mock_balance_fetcher(),
+            default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
+        };
+
+        let updates = quoter
+            .calculate_quote_stream(parameters)
+            .collect::<Vec<_>>()
+            .await;
+        let updates = updates
+            .iter()
+            .map(|update| {
+                let quote = update.result.as_ref().ok();
+                (
+                    quote.map(|quote| (quote.buy_amount, quote.data.verified)),
+                    update.best,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            updates,
+            vec![
+                (Some((42.into(), false)), true),
+                (None, false),
+                (Some((50.into(), true)), true),
+                (Some((45.into(), false)), false),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn compute_buy_quote() {
         let now = Utc::now();
@@ -1201,6 +1470,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         let quote = quoter.calculate_quote(parameters).await.unwrap();
@@ -1234,6 +1504,77 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn compute_quote_with_transfer_fees() {
+        let (sell_token, buy_token) = (H160([1; 20]), H160([2; 20]));
+        let parameters = QuoteParameters {
+            sell_token,
+            buy_token,
+            side: OrderQuoteSide::Sell {
+                sell_amount: SellAmount::AfterFee {
+                    value: NonZeroU256::try_from(1_000).unwrap(),
+                },
+            },
+            verification: Default::default(),
+            signing_scheme: QuoteSigningScheme::Eip712,
+            additional_gas: 0,
+            timeout: None,
+        };
+
+        // Only the sell amount after the sell token transfer fee arrives in
+        // the settlement contract and the estimated buy amount loses the buy
+        // token transfer fee when it gets bought.
+        let mut price_estimator = MockPriceEstimating::new();
+        price_estimator
+            .expect_estimate()
+            .withf(|q| q.in_amount == NonZeroU256::try_from(990).unwrap())
+            .returning(|_| {
+                async {
+                    Ok(price_estimation::Estimate {
+                        out_amount: 500.into(),
+                        gas: 3,
+                        solver: H160([1; 20]),
+                        verified: false,
+                        execution: Default::default(),
+                    })
+                }
+                .boxed()
+            });
+        let mut native_price_estimator = MockNativePriceEstimating::new();
+        native_price_estimator
+            .expect_estimate_native_price()
+            .returning(|_, _| async { Ok(0.2) }.boxed());
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        bad_token_detector.expect_detect().returning(move |token| {
+            let bps = if token == sell_token { 100 } else { 200 };
+            Ok(TokenQuality::FeeOnTransfer {
+                fee: TransferFee::from_bps(bps).unwrap(),
+            })
+        });
+        let gas_estimator = FakeGasPriceEstimator(Arc::new(Mutex::new(GasPrice1559 {
+            base_fee_per_gas: 1.5,
+            max_fee_per_gas: 3.0,
+            max_priority_fee_per_gas: 0.5,
+        })));
+
+        let quoter = OrderQuoter {
+            price_estimator: Arc::new(price_estimator),
+            native_price_estimator: Arc::new(native_price_estimator),
+            gas_estimator: Arc::new(gas_estimator),
+            storage: Arc::new(MockQuoteStoring::new()),
+            now: Arc::new(Utc::now),
+            validity: super::Validity::default(),
+            quote_verification: QuoteVerificationMode::Unverified,
+            balance_fetcher: mock_balance_fetcher(),
+            default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: Arc::new(bad_token_detector),
+        };
+
+        let quote = quoter.calculate_quote(parameters).await.unwrap();
+        assert_eq!(quote.data.quoted_sell_amount, 1_000.into());
+        assert_eq!(quote.data.quoted_buy_amount, 490.into());
+    }
+
     #[tokio::test]
     async fn compute_sell_before_fee_quote_insufficient_amount_error() {
         let parameters = QuoteParameters {
@@ -1300,6 +1641,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert!(matches!(
@@ -1374,6 +1716,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert!(matches!(
@@ -1435,6 +1778,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert_eq!(
@@ -1518,6 +1862,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert_eq!(
@@ -1603,6 +1948,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert_eq!(
@@ -1676,6 +2022,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert!(matches!(
@@ -1707,6 +2054,7 @@ mod tests {
             quote_verification: QuoteVerificationMode::Unverified,
             balance_fetcher: mock_balance_fetcher(),
             default_quote_timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+            bad_token_detector: no_bad_tokens(),
         };
 
         assert!(matches!(
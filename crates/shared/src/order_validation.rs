@@ -29,6 +29,7 @@ use {
             Interactions,
             Order,
             OrderClass,
+            OrderCondition,
             OrderCreation,
             OrderCreationAppData,
             OrderData,
@@ -88,13 +89,24 @@ pub trait OrderValidating: Send + Sync {
     /// `full_app_data_override` is used as the full app data and the contract
     /// app data hash is not validated against it (the hash doesn't have to
     /// match). The full app data is still otherwise validated.
+    ///
+    /// Also returns whether the order counts towards the owner's maximum
+    /// number of limit orders.
     async fn validate_and_construct_order(
         &self,
         order: OrderCreation,
         domain_separator: &DomainSeparator,
         settlement_contract: H160,
         full_app_data_override: Option<String>,
-    ) -> Result<(Order, Option<Quote>), ValidationError>;
+    ) -> Result<(Order, Option<Quote>, bool), ValidationError>;
+
+    /// Verifies that `owner` can place `new_orders` additional limit orders
+    /// without exceeding the maximum number of open limit orders per user.
+    async fn check_limit_order_capacity(
+        &self,
+        owner: H160,
+        new_orders: u64,
+    ) -> Result<(), ValidationError>;
 }
 
 #[derive(Debug)]
@@ -154,6 +166,9 @@ pub enum ValidationError {
     TooManyLimitOrders,
     TooMuchGas,
     QuoteNotVerified,
+    /// The order condition is malformed or not supported for this kind of
+    /// order.
+    InvalidCondition(&'static str),
     Other(anyhow::Error),
 }
 
@@ -316,18 +331,6 @@ impl OrderValidator {
         }
     }
 
-    async fn check_max_limit_orders(&self, owner: H160) -> Result<(), ValidationError> {
-        let num_limit_orders = self
-            .limit_order_counter
-            .count(owner)
-            .await
-            .map_err(ValidationError::Other)?;
-        if num_limit_orders >= self.max_limit_orders_per_user {
-            return Err(ValidationError::TooManyLimitOrders);
-        }
-        Ok(())
-    }
-
     fn custom_interactions(&self, hooks: &Hooks) -> Interactions {
         let to_interactions = |hooks: &[Hook]| -> Vec<InteractionData> {
             if hooks.is_empty() {
@@ -548,7 +551,7 @@ impl OrderValidating for OrderValidator {
         domain_separator: &DomainSeparator,
         settlement_contract: H160,
         full_app_data_override: Option<String>,
-    ) -> Result<(Order, Option<Quote>), ValidationError> {
+    ) -> Result<(Order, Option<Quote>, bool), ValidationError> {
         // Happens before signature verification because a miscalculated app data hash
         // by the API user would lead to being unable to validate the signature below.
         let app_data = self.validate_app_data(&order.app_data, &full_app_data_override)?;
@@ -593,6 +596,12 @@ impl OrderValidating for OrderValidator {
         if data.buy_amount.is_zero() || data.sell_amount.is_zero() {
             return Err(ValidationError::ZeroAmount);
         }
+        // Conditions are only taken from the signed app data so they can't be
+        // attached to or removed from an order without the owner's consent.
+        let condition = app_data.inner.protocol.condition;
+        if let Some(condition) = &condition {
+            validate_condition(condition, &data)?;
+        }
 
         let pre_order = PreOrderData::from_order_creation(owner, &data, signing_scheme);
         let class = pre_order.class;
@@ -632,7 +641,7 @@ impl OrderValidating for OrderValidator {
         // Check if we need to re-classify the market order if it is outside the market
         // price. We consider out-of-price orders as liquidity orders. See
         // <https://github.com/cowprotocol/services/pull/301>.
-        let (class, quote) = match class {
+        let (class, quote, is_limit_order) = match class {
             // This has to be here in order to keep the previous behaviour
             OrderClass::Market => {
                 let quote = get_quote_and_check_fee(
@@ -662,9 +671,9 @@ impl OrderValidating for OrderValidator {
                     data.kind,
                 ) {
                     tracing::debug!(%uid, ?owner, ?class, "order being flagged as outside market price");
-                    (OrderClass::Limit, Some(quote))
+                    (OrderClass::Limit, Some(quote), false)
                 } else {
-                    (class, Some(quote))
+                    (class, Some(quote), false)
                 }
             }
             OrderClass::Limit => {
@@ -678,7 +687,7 @@ impl OrderValidating for OrderValidator {
                 {
                     Ok(quote) => {
                         // If the order is not "In-Market", check for the limit orders
-                        if is_order_outside_market_price(
+                        let is_limit_order = is_order_outside_market_price(
                             &Amounts {
                                 sell: data.sell_amount,
                                 buy: data.buy_amount,
@@ -690,16 +699,14 @@ impl OrderValidating for OrderValidator {
                                 fee: quote.fee_amount,
                             },
                             data.kind,
-                        ) {
-                            self.check_max_limit_orders(owner).await?;
-                        }
-                        (class, Some(quote))
+                        );
+                        (class, Some(quote), is_limit_order)
                     }
                     // If there is not enough liquidity, it's still possible to place this order (as
                     // an implicit out of market order)
                     Err(ValidationError::PriceForQuote(PriceEstimationError::NoLiquidity)) => {
                         tracing::debug!("placing order without quote");
-                        (class, None)
+                        (class, None, false)
                     }
                     Err(other) => return Err(other),
                 }
@@ -709,7 +716,7 @@ impl OrderValidating for OrderValidator {
                     get_quote_and_check_fee(&*self.quoter, &quote_parameters, order.quote_id, None)
                         .await?;
                 // If the order is not "In-Market", check for the limit orders
-                if is_order_outside_market_price(
+                let is_limit_order = is_order_outside_market_price(
                     &Amounts {
                         sell: data.sell_amount,
                         buy: data.buy_amount,
@@ -721,12 +728,13 @@ impl OrderValidating for OrderValidator {
                         fee: quote.fee_amount,
                     },
                     data.kind,
-                ) {
-                    self.check_max_limit_orders(owner).await?;
-                }
-                (OrderClass::Limit, None)
+                );
+                (OrderClass::Limit, None, is_limit_order)
             }
         };
+        if is_limit_order {
+            self.check_limit_order_capacity(owner, 1).await?;
+        }
 
         if quote.as_ref().is_some_and(|quote| {
             // Quoted gas does not include additional gas for hooks nor ERC1271 signatures
@@ -753,6 +761,7 @@ impl OrderValidating for OrderValidator {
                     .map(|q| q.try_to_model_order_quote())
                     .transpose()
                     .map_err(ValidationError::Other)?,
+                condition,
                 ..Default::default()
             },
             signature: order.signature.clone(),
@@ -760,8 +769,61 @@ impl OrderValidating for OrderValidator {
             interactions: app_data.interactions,
         };
 
-        Ok((order, quote))
+        Ok((order, quote, is_limit_order))
+    }
+
+    async fn check_limit_order_capacity(
+        &self,
+        owner: H160,
+        new_orders: u64,
+    ) -> Result<(), ValidationError> {
+        let num_limit_orders = self
+            .limit_order_counter
+            .count(owner)
+            .await
+            .map_err(ValidationError::Other)?;
+        if num_limit_orders.saturating_add(new_orders) > self.max_limit_orders_per_user {
+            return Err(ValidationError::TooManyLimitOrders);
+        }
+        Ok(())
+    }
+}
+
+fn validate_condition(
+    condition: &OrderCondition,
+    order: &OrderData,
+) -> Result<(), ValidationError> {
+    match *condition {
+        OrderCondition::Twap {
+            parts,
+            start_time,
+            part_duration,
+        } => {
+            if order.kind != OrderKind::Sell || !order.partially_fillable {
+                return Err(ValidationError::InvalidCondition(
+                    "TWAP orders must be partially fillable sell orders",
+                ));
+            }
+            if parts == 0 || part_duration == 0 {
+                return Err(ValidationError::InvalidCondition(
+                    "TWAP orders need at least one part with a non-zero duration",
+                ));
+            }
+            if start_time >= order.valid_to {
+                return Err(ValidationError::InvalidCondition(
+                    "TWAP orders must start before they expire",
+                ));
+            }
+        }
+        OrderCondition::StopLoss { strike_price } => {
+            if strike_price.is_zero() {
+                return Err(ValidationError::InvalidCondition(
+                    "stop-loss orders need a non-zero strike price",
+                ));
+            }
+        }
     }
+    Ok(())
 }
 
 /// Order validity period configuration.
@@ -1439,7 +1501,7 @@ mod tests {
             fee_amount: U256::zero(),
             ..creation.clone()
         };
-        let (order, _) = validator
+        let (order, ..) = validator
             .validate_and_construct_order(creation_, &domain_separator, Default::default(), None)
             .await
             .unwrap();
@@ -1454,7 +1516,7 @@ mod tests {
             },
             ..creation
         };
-        let (order, _) = validator
+        let (order, ..) = validator
             .validate_and_construct_order(creation_, &domain_separator, Default::default(), None)
             .await
             .unwrap();
@@ -1600,17 +1662,16 @@ mod tests {
             },
             ..Default::default()
         };
-        assert!(
-            validator
-                .validate_and_construct_order(
-                    creation.clone(),
-                    &Default::default(),
-                    Default::default(),
-                    None,
-                )
-                .await
-                .is_ok()
-        );
+        let (_, _, is_limit_order) = validator
+            .validate_and_construct_order(
+                creation.clone(),
+                &Default::default(),
+                Default::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!is_limit_order);
     }
 
     #[tokio::test]
@@ -1664,6 +1725,138 @@ mod tests {
         assert!(matches!(result, Err(ValidationError::ZeroAmount)));
     }
 
+    #[tokio::test]
+    async fn post_validate_condition_from_app_data() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            dummy_contract!(HooksTrampoline, [0xcf; 20]),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            0,
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+        let order = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(1),
+            fee_amount: U256::from(1),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            // TWAP conditions are only valid for partially fillable sell orders.
+            app_data: OrderCreationAppData::Full {
+                full: json!({
+                    "metadata": {
+                        "condition": {
+                            "kind": "twap",
+                            "parts": 4,
+                            "startTime": 0,
+                            "partDuration": 60,
+                        }
+                    }
+                })
+                .to_string(),
+            },
+            ..Default::default()
+        };
+        let result = validator
+            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .await;
+        assert!(matches!(result, Err(ValidationError::InvalidCondition(_))));
+    }
+
+    #[test]
+    fn validates_order_conditions() {
+        let twap = OrderCondition::Twap {
+            parts: 4,
+            start_time: 100,
+            part_duration: 60,
+        };
+        let order = OrderData {
+            kind: OrderKind::Sell,
+            partially_fillable: true,
+            valid_to: 1000,
+            ..Default::default()
+        };
+        assert!(validate_condition(&twap, &order).is_ok());
+
+        for invalid in [
+            OrderData {
+                kind: OrderKind::Buy,
+                ..order
+            },
+            OrderData {
+                partially_fillable: false,
+                ..order
+            },
+            OrderData {
+                valid_to: 100,
+                ..order
+            },
+        ] {
+            assert!(matches!(
+                validate_condition(&twap, &invalid),
+                Err(ValidationError::InvalidCondition(_))
+            ));
+        }
+        for invalid in [
+            OrderCondition::Twap {
+                parts: 0,
+                start_time: 100,
+                part_duration: 60,
+            },
+            OrderCondition::Twap {
+                parts: 4,
+                start_time: 100,
+                part_duration: 0,
+            },
+            OrderCondition::StopLoss {
+                strike_price: U256::zero(),
+            },
+        ] {
+            assert!(matches!(
+                validate_condition(&invalid, &order),
+                Err(ValidationError::InvalidCondition(_))
+            ));
+        }
+
+        let stop_loss = OrderCondition::StopLoss {
+            strike_price: U256::exp10(18),
+        };
+        assert!(
+            validate_condition(
+                &stop_loss,
+                &OrderData {
+                    kind: OrderKind::Buy,
+                    ..Default::default()
+                }
+            )
+            .is_ok()
+        );
+    }
+
     #[tokio::test]
     async fn post_validate_err_wrong_owner() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -2292,7 +2485,7 @@ mod tests {
             quote_id,
             ..Default::default()
         };
-        let (_, returned_quote_id) = validator
+        let (_, returned_quote_id, _) = validator
             .validate_and_construct_order(
                 creation.clone(),
                 &Default::default(),
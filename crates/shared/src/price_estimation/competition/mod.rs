@@ -3,7 +3,7 @@ use {
     crate::price_estimation::PriceEstimationError,
     futures::{
         future::{BoxFuture, FutureExt},
-        stream::{FuturesUnordered, StreamExt},
+        stream::{FuturesUnordered, Stream, StreamExt},
     },
     gas_estimation::GasPriceEstimating,
     model::order::OrderKind,
@@ -68,68 +68,73 @@ impl<T: Send + Sync + 'static> CompetitionEstimator<T> {
     }
 
     /// Produce results for the given `input` until the caller does not expect
-    /// any more results or we produced all the results we can.
-    async fn produce_results<Q, R>(
-        &self,
+    /// any more results or we produced all the results we can. Results are
+    /// yielded as soon as they arrive.
+    fn produce_results<'a, Q, R>(
+        &'a self,
         query: Q,
-        result_is_usable: impl Fn(&Result<R, PriceEstimationError>) -> bool,
+        result_is_usable: impl Fn(&Result<R, PriceEstimationError>) -> bool + Send + 'a,
         get_single_result: impl Fn(&T, Q) -> BoxFuture<'_, Result<R, PriceEstimationError>>
         + Send
         + 'static,
-    ) -> Vec<ResultWithIndex<R>>
+    ) -> impl Stream<Item = ResultWithIndex<R>> + Send + 'a
     where
         Q: Clone + Debug + Send + 'static,
-        R: Clone + Debug + Send,
+        R: Clone + Debug + Send + 'a,
     {
-        let start = Instant::now();
-        let mut results = vec![];
-        let mut stage_index = 0;
-
-        let missing_results = |results: &[ResultWithIndex<R>]| {
-            let usable = results.iter().filter(|(_, r)| result_is_usable(r)).count();
-            self.usable_results_for_early_return
-                .get()
-                .saturating_sub(usable)
-        };
-
-        'outer: while stage_index < self.stages.len() {
-            let mut requests = FuturesUnordered::new();
-
-            // Collect requests until it's at least theoretically possible to produce enough
-            // results to return early.
-            let requests_for_batch = missing_results(&results);
-            while stage_index < self.stages.len() && requests.len() < requests_for_batch {
-                let stage = &self.stages.get(stage_index).expect("index checked by loop");
-                let futures = stage.iter().enumerate().map(|(index, (_name, estimator))| {
-                    get_single_result(estimator, query.clone())
-                        .map(move |result| (EstimatorIndex(stage_index, index), result))
-                        .boxed()
-                });
-
-                requests.extend(futures);
-                stage_index += 1;
-            }
+        async_stream::stream! {
+            let start = Instant::now();
+            let mut results = 0;
+            let mut usable_results = 0;
+            let mut stage_index = 0;
+
+            let missing_results = |usable_results: usize| {
+                self.usable_results_for_early_return
+                    .get()
+                    .saturating_sub(usable_results)
+            };
+
+            'outer: while stage_index < self.stages.len() {
+                let mut requests = FuturesUnordered::new();
+
+                // Collect requests until it's at least theoretically possible to produce
+                // enough results to return early.
+                let requests_for_batch = missing_results(usable_results);
+                while stage_index < self.stages.len() && requests.len() < requests_for_batch {
+                    let stage = &self.stages.get(stage_index).expect("index checked by loop");
+                    let futures = stage.iter().enumerate().map(|(index, (_name, estimator))| {
+                        get_single_result(estimator, query.clone())
+                            .map(move |result| (EstimatorIndex(stage_index, index), result))
+                            .boxed()
+                    });
+
+                    requests.extend(futures);
+                    stage_index += 1;
+                }
 
-            while let Some((estimator_index, result)) = requests.next().await {
-                let (name, _estimator) = &self.stages[estimator_index.0][estimator_index.1];
-                tracing::debug!(
-                    ?query,
-                    ?result,
-                    estimator = name,
-                    requests = requests.len(),
-                    results = results.len(),
-                    elapsed = ?start.elapsed(),
-                    "new price estimate"
-                );
-                results.push((estimator_index, result));
-
-                if missing_results(&results) == 0 {
-                    break 'outer;
+                while let Some((estimator_index, result)) = requests.next().await {
+                    let (name, _estimator) = &self.stages[estimator_index.0][estimator_index.1];
+                    tracing::debug!(
+                        ?query,
+                        ?result,
+                        estimator = name,
+                        requests = requests.len(),
+                        results,
+                        elapsed = ?start.elapsed(),
+                        "new price estimate"
+                    );
+                    results += 1;
+                    if result_is_usable(&result) {
+                        usable_results += 1;
+                    }
+                    yield (estimator_index, result);
+
+                    if missing_results(usable_results) == 0 {
+                        break 'outer;
+                    }
                 }
             }
         }
-
-        results
     }
 
     fn report_winner<Q: Debug, R: Debug>(
@@ -151,7 +156,7 @@ impl<T: Send + Sync + 'static> CompetitionEstimator<T> {
     }
 }
 
-fn compare_error(a: &PriceEstimationError, b: &PriceEstimationError) -> Ordering {
+pub(crate) fn compare_error(a: &PriceEstimationError, b: &PriceEstimationError) -> Ordering {
     // Errors are sorted by recoverability. E.g. a rate-limited estimation may
     // succeed if tried again, whereas unsupported order types can never recover
     // unless code changes. This can be used to decide which errors we want to
@@ -5,7 +5,7 @@ use {
         native::{NativePriceEstimateResult, NativePriceEstimating, is_price_malformed},
     },
     anyhow::Context,
-    futures::{FutureExt, future::BoxFuture},
+    futures::{FutureExt, StreamExt, future::BoxFuture},
     model::order::OrderKind,
     primitive_types::H160,
     std::{cmp::Ordering, sync::Arc, time::Duration},
@@ -31,6 +31,7 @@ impl NativePriceEstimating for CompetitionEstimator<Arc<dyn NativePriceEstimatin
                     }
                     .boxed()
                 })
+                .collect::<Vec<_>>()
                 .await;
             let winner = results
                 .into_iter()
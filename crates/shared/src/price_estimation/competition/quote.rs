@@ -1,7 +1,8 @@
 use {
-    super::{CompetitionEstimator, PriceRanking, compare_error},
+    super::{CompetitionEstimator, PriceRanking, ResultWithIndex, compare_error},
     crate::price_estimation::{
         Estimate,
+        EstimateUpdate,
         PriceEstimateResult,
         PriceEstimating,
         PriceEstimationError,
@@ -9,7 +10,10 @@ use {
         QuoteVerificationMode,
     },
     anyhow::Context,
-    futures::future::{BoxFuture, FutureExt, TryFutureExt},
+    futures::{
+        future::{BoxFuture, FutureExt, TryFutureExt},
+        stream::{BoxStream, StreamExt},
+    },
     model::order::OrderKind,
     primitive_types::{H160, U256},
     std::{cmp::Ordering, sync::Arc, time::Duration},
@@ -32,6 +36,7 @@ impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
             let gas_is_reasonable = |r: &PriceEstimateResult| r.as_ref().is_ok_and(|r| r.gas > 0);
             let get_results = self
                 .produce_results(query.clone(), gas_is_reasonable, |e, q| e.estimate(q))
+                .collect::<Vec<_>>()
                 .map(Result::Ok);
 
             let (context, results) = futures::try_join!(get_context, get_results)?;
@@ -54,6 +59,55 @@ impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
         }
         .boxed()
     }
+
+    fn estimate_streaming(&self, mut query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        Arc::make_mut(&mut query).timeout /= self.stages.len() as u32;
+
+        async_stream::stream! {
+            let out_token = match query.kind {
+                OrderKind::Buy => query.sell_token,
+                OrderKind::Sell => query.buy_token,
+            };
+            let context = match self.ranking.provide_context(out_token, query.timeout).await {
+                Ok(context) => context,
+                Err(err) => {
+                    yield EstimateUpdate { result: Err(err), best: false };
+                    return;
+                }
+            };
+
+            let gas_is_reasonable = |r: &PriceEstimateResult| r.as_ref().is_ok_and(|r| r.gas > 0);
+            let results = self.produce_results(query.clone(), gas_is_reasonable, |e, q| e.estimate(q));
+            let mut results = std::pin::pin!(results);
+            let mut best: Option<ResultWithIndex<Estimate>> = None;
+            while let Some((index, result)) = results.next().await {
+                if result.is_ok() && !gas_is_reasonable(&result) {
+                    continue;
+                }
+                let is_best = result.is_ok()
+                    && best.as_ref().is_none_or(|(_, best)| {
+                        compare_quote_result(
+                            &query,
+                            &result,
+                            best,
+                            &context,
+                            !matches!(self.verification_mode, QuoteVerificationMode::Unverified),
+                        )
+                        .is_gt()
+                    });
+                if is_best {
+                    best = Some((index, result.clone()));
+                }
+                yield EstimateUpdate { result, best: is_best };
+            }
+
+            if let Some(winner) = best {
+                // Reported for the metrics only since the result was already streamed.
+                let _ = self.report_winner(&query, query.kind, winner);
+            }
+        }
+        .boxed()
+    }
 }
 
 fn compare_quote_result(
@@ -391,4 +445,50 @@ mod tests {
         .await;
         assert_eq!(best, better_unverified_quote);
     }
+
+    #[tokio::test]
+    async fn streams_results_as_they_arrive() {
+        fn estimator(estimate: PriceEstimateResult, delay_ms: u64) -> Arc<dyn PriceEstimating> {
+            let mut estimator = MockPriceEstimating::new();
+            estimator.expect_estimate().times(1).return_once(move |_| {
+                async move {
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    estimate
+                }
+                .boxed()
+            });
+            Arc::new(estimator)
+        }
+
+        let estimator: CompetitionEstimator<Arc<dyn PriceEstimating>> = CompetitionEstimator::new(
+            vec![vec![
+                ("a".to_owned(), estimator(price(150, 1), 30)),
+                ("b".to_owned(), estimator(price(200, 1), 20)),
+                (
+                    "c".to_owned(),
+                    estimator(error(PriceEstimationError::NoLiquidity), 10),
+                ),
+                ("d".to_owned(), estimator(price(100, 1), 0)),
+            ]],
+            PriceRanking::MaxOutAmount,
+        );
+
+        let updates = estimator
+            .estimate_streaming(Arc::new(Query {
+                kind: OrderKind::Sell,
+                ..Default::default()
+            }))
+            .map(|update| (update.result, update.best))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            updates,
+            vec![
+                (price(100, 1), true),
+                (error(PriceEstimationError::NoLiquidity), false),
+                (price(200, 1), true),
+                (price(150, 1), false),
+            ]
+        );
+    }
 }
@@ -125,6 +125,7 @@ impl<'a> PriceEstimatorFactory<'a> {
             network.settlement,
             network.native_token,
             args.quote_inaccuracy_limit.clone(),
+            components.bad_token_detector.clone(),
         )
         .await?;
         Ok(Some(Arc::new(verifier)))
@@ -227,6 +228,25 @@ impl<'a> PriceEstimatorFactory<'a> {
                     )),
                 ))
             }
+            NativePriceEstimatorSource::OnchainOracle => {
+                let name = "OnchainOracle".to_string();
+                let oracle = &self.args.onchain_oracle;
+                Ok((
+                    name.clone(),
+                    Arc::new(InstrumentedPriceEstimator::new(
+                        native::OnchainOracle::new(
+                            self.network.web3.clone(),
+                            self.network.native_token,
+                            self.components.tokens.clone(),
+                            &oracle.chainlink_native_price_feeds,
+                            &oracle.uniswap_v3_native_price_pools,
+                            oracle.chainlink_max_staleness,
+                            oracle.uniswap_v3_twap_period,
+                        )?,
+                        name,
+                    )),
+                ))
+            }
             NativePriceEstimatorSource::CoinGecko => {
                 let name = "CoinGecko".to_string();
                 let coin_gecko = native::CoinGecko::new(
@@ -363,12 +383,33 @@ impl<'a> PriceEstimatorFactory<'a> {
             estimators.push(stages);
         }
 
-        let competition_estimator =
-            CompetitionEstimator::new(estimators, PriceRanking::MaxOutAmount)
-                .with_verification(self.args.quote_verification)
-                .with_early_return(results_required);
+        let estimator: Box<dyn NativePriceEstimating> =
+            match self.args.native_price_consensus_max_deviation {
+                Some(max_deviation) => {
+                    let sources: Vec<_> = estimators.into_iter().flatten().collect();
+                    anyhow::ensure!(
+                        max_deviation > 0.,
+                        "native price consensus max deviation must be positive"
+                    );
+                    anyhow::ensure!(
+                        (1..=sources.len()).contains(&self.args.native_price_consensus_min_sources),
+                        "native price consensus requires between 1 and {} sources",
+                        sources.len()
+                    );
+                    Box::new(native::Consensus::new(
+                        sources,
+                        max_deviation,
+                        self.args.native_price_consensus_min_sources,
+                    ))
+                }
+                None => Box::new(
+                    CompetitionEstimator::new(estimators, PriceRanking::MaxOutAmount)
+                        .with_verification(self.args.quote_verification)
+                        .with_early_return(results_required),
+                ),
+            };
         let native_estimator = Arc::new(CachingNativePriceEstimator::new(
-            Box::new(competition_estimator),
+            estimator,
             self.args.native_price_cache_max_age,
             self.args.native_price_cache_refresh,
             Some(self.args.native_price_cache_max_update_size),
@@ -7,7 +7,10 @@ use {
     anyhow::{Result, ensure},
     bigdecimal::BigDecimal,
     ethcontract::{H160, U256},
-    futures::future::BoxFuture,
+    futures::{
+        future::{BoxFuture, FutureExt},
+        stream::{BoxStream, StreamExt},
+    },
     itertools::Itertools,
     model::order::{BuyTokenDestination, OrderKind, SellTokenSource},
     number::nonzero::U256 as NonZeroU256,
@@ -77,6 +80,7 @@ pub enum NativePriceEstimator {
     Driver(ExternalSolver),
     OneInchSpotPriceApi,
     CoinGecko,
+    OnchainOracle,
 }
 
 impl Display for NativePriceEstimator {
@@ -85,6 +89,7 @@ impl Display for NativePriceEstimator {
             NativePriceEstimator::Driver(s) => format!("{}|{}", &s.name, s.url),
             NativePriceEstimator::OneInchSpotPriceApi => "OneInchSpotPriceApi".into(),
             NativePriceEstimator::CoinGecko => "CoinGecko".into(),
+            NativePriceEstimator::OnchainOracle => "OnchainOracle".into(),
         };
         write!(f, "{formatter}")
     }
@@ -135,6 +140,7 @@ impl FromStr for NativePriceEstimator {
         match s {
             "OneInchSpotPriceApi" => Ok(NativePriceEstimator::OneInchSpotPriceApi),
             "CoinGecko" => Ok(NativePriceEstimator::CoinGecko),
+            "OnchainOracle" => Ok(NativePriceEstimator::OnchainOracle),
             estimator => Ok(NativePriceEstimator::Driver(ExternalSolver::from_str(
                 estimator,
             )?)),
@@ -196,6 +202,20 @@ pub struct Arguments {
     #[clap(long, env, default_value = "1")]
     pub native_price_cache_concurrent_requests: usize,
 
+    /// When set, all configured native price estimators get queried in
+    /// parallel instead of in stages. Prices deviating from the median of all
+    /// sources by more than this factor get discarded as outliers. E.g. a value
+    /// of `0.05` discards prices more than 5 percent away from the median.
+    /// With fewer than three prices all of them have to be within this factor
+    /// of each other instead. Must be positive.
+    #[clap(long, env)]
+    pub native_price_consensus_max_deviation: Option<f64>,
+
+    /// How many native price sources need to agree on a price when
+    /// `--native-price-consensus-max-deviation` is set.
+    #[clap(long, env, default_value = "2")]
+    pub native_price_consensus_min_sources: usize,
+
     /// The amount in native tokens atoms to use for price estimation. Should be
     /// reasonably large so that small pools do not influence the prices. If
     /// not set a reasonable default is used based on network id.
@@ -218,6 +238,10 @@ pub struct Arguments {
     #[clap(flatten)]
     pub coin_gecko: CoinGecko,
 
+    /// The on-chain oracle native price configuration
+    #[clap(flatten)]
+    pub onchain_oracle: OnchainOracle,
+
     /// How inaccurate a quote must be before it gets discarded provided as a
     /// factor.
     /// E.g. a value of `0.01` means at most 1 percent of the sell or buy tokens
@@ -280,6 +304,52 @@ where
     Ok((input[..pos].parse()?, input[pos + 1..].parse()?))
 }
 
+#[derive(clap::Parser)]
+pub struct OnchainOracle {
+    /// Chainlink aggregators quoting tokens in the native token used by the
+    /// `OnchainOracle` native price estimator:
+    /// "<token1>|<aggregator1>,<token2>|<aggregator2>"
+    #[clap(
+        long,
+        env,
+        value_delimiter = ',',
+        value_parser = parse_tuple::<H160, H160>
+    )]
+    pub chainlink_native_price_feeds: Vec<(H160, H160)>,
+
+    /// How old the latest answer of a Chainlink aggregator may be before it is
+    /// no longer used.
+    #[clap(
+        long,
+        env,
+        default_value = "25h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub chainlink_max_staleness: Duration,
+
+    /// Uniswap V3 pools trading tokens against the native token whose time
+    /// weighted average price is used by the `OnchainOracle` native price
+    /// estimator when no Chainlink price is available:
+    /// "<token1>|<pool1>,<token2>|<pool2>"
+    #[clap(
+        long,
+        env,
+        value_delimiter = ',',
+        value_parser = parse_tuple::<H160, H160>
+    )]
+    pub uniswap_v3_native_price_pools: Vec<(H160, H160)>,
+
+    /// The period over which the time weighted average price of Uniswap V3
+    /// pools is computed.
+    #[clap(
+        long,
+        env,
+        default_value = "30m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub uniswap_v3_twap_period: Duration,
+}
+
 #[derive(clap::Parser)]
 pub struct CoinGecko {
     /// The API key for the CoinGecko API.
@@ -344,11 +414,14 @@ impl Display for Arguments {
             native_price_prefetch_time,
             native_price_cache_max_update_size,
             native_price_cache_concurrent_requests,
+            native_price_consensus_max_deviation,
+            native_price_consensus_min_sources,
             amount_to_estimate_prices_with,
             balancer_sor_url,
             one_inch_api_key,
             one_inch_url,
             coin_gecko,
+            onchain_oracle,
             quote_inaccuracy_limit,
             quote_verification,
             quote_timeout,
@@ -381,6 +454,15 @@ impl Display for Arguments {
             f,
             "native_price_cache_concurrent_requests: {native_price_cache_concurrent_requests}"
         )?;
+        display_option(
+            f,
+            "native_price_consensus_max_deviation",
+            native_price_consensus_max_deviation,
+        )?;
+        writeln!(
+            f,
+            "native_price_consensus_min_sources: {native_price_consensus_min_sources}"
+        )?;
         display_option(
             f,
             "amount_to_estimate_prices_with: {}",
@@ -415,6 +497,26 @@ impl Display for Arguments {
                 |coin_gecko_buffered| coin_gecko_buffered.coin_gecko_broadcast_channel_capacity
             ),
         )?;
+        writeln!(
+            f,
+            "chainlink_native_price_feeds: {:?}",
+            onchain_oracle.chainlink_native_price_feeds
+        )?;
+        writeln!(
+            f,
+            "chainlink_max_staleness: {:?}",
+            onchain_oracle.chainlink_max_staleness
+        )?;
+        writeln!(
+            f,
+            "uniswap_v3_native_price_pools: {:?}",
+            onchain_oracle.uniswap_v3_native_price_pools
+        )?;
+        writeln!(
+            f,
+            "uniswap_v3_twap_period: {:?}",
+            onchain_oracle.uniswap_v3_twap_period
+        )?;
         writeln!(f, "quote_inaccuracy_limit: {quote_inaccuracy_limit}")?;
         writeln!(f, "quote_verification: {quote_verification:?}")?;
         writeln!(f, "quote_timeout: {quote_timeout:?}")?;
@@ -546,9 +648,33 @@ impl Estimate {
 
 pub type PriceEstimateResult = Result<Estimate, PriceEstimationError>;
 
+/// Result of a single price source reported while a price estimation is still
+/// in progress.
+#[derive(Clone, Debug)]
+pub struct EstimateUpdate {
+    pub result: PriceEstimateResult,
+    /// Whether this is the best estimate reported so far. Only ever set for
+    /// successful estimates.
+    pub best: bool,
+}
+
 #[mockall::automock]
 pub trait PriceEstimating: Send + Sync + 'static {
     fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult>;
+
+    /// Reports the results of the individual price sources as soon as they
+    /// arrive instead of waiting for the final result. Estimators that don't
+    /// compete multiple sources against each other report a single update with
+    /// the result of [`PriceEstimating::estimate`].
+    fn estimate_streaming(&self, query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        self.estimate(query)
+            .map(|result| EstimateUpdate {
+                best: result.is_ok(),
+                result,
+            })
+            .into_stream()
+            .boxed()
+    }
 }
 
 pub const HEALTHY_PRICE_ESTIMATION_TIME: Duration = Duration::from_millis(5_000);
@@ -624,6 +750,7 @@ mod tests {
             )
             .to_string(),
             &NativePriceEstimator::OneInchSpotPriceApi.to_string(),
+            &NativePriceEstimator::OnchainOracle.to_string(),
             "one|http://localhost:1111/,two|http://localhost:2222/;three|http://localhost:3333/,four|http://localhost:4444/",
             &format!(
                 "one|http://localhost:1111/,two|http://localhost:2222/;{},four|http://localhost:4444/",
@@ -0,0 +1,298 @@
+use {
+    super::{NativePrice, NativePriceEstimateResult, NativePriceEstimating, is_price_malformed},
+    crate::price_estimation::{PriceEstimationError, competition::compare_error},
+    anyhow::anyhow,
+    futures::{FutureExt, future::BoxFuture},
+    primitive_types::H160,
+    std::{sync::Arc, time::Duration},
+};
+
+/// Native price estimator which queries all of its sources in parallel and
+/// only returns a price if enough of them agree on it.
+///
+/// The reference price is the median of all reported prices. Prices deviating
+/// from it by more than the configured factor are considered outliers and get
+/// discarded. The final price is the median of the remaining prices.
+///
+/// With fewer than [`MIN_SOURCES_FOR_OUTLIERS`] prices the median doesn't
+/// tell which of them is off, so instead all prices have to agree with each
+/// other within the configured factor.
+pub struct Consensus {
+    sources: Vec<(String, Arc<dyn NativePriceEstimating>)>,
+    max_deviation: f64,
+    min_agreeing_sources: usize,
+}
+
+/// Minimum number of prices needed to identify individual outliers.
+const MIN_SOURCES_FOR_OUTLIERS: usize = 3;
+
+impl Consensus {
+    pub fn new(
+        sources: Vec<(String, Arc<dyn NativePriceEstimating>)>,
+        max_deviation: f64,
+        min_agreeing_sources: usize,
+    ) -> Self {
+        Self {
+            sources,
+            max_deviation,
+            min_agreeing_sources,
+        }
+    }
+
+    /// Returns the median of the agreeing prices if there are enough of them.
+    fn agreed_price(&self, agreeing: Vec<NativePrice>) -> NativePriceEstimateResult {
+        if agreeing.len() < self.min_agreeing_sources {
+            return Err(PriceEstimationError::EstimatorInternal(anyhow!(
+                "only {} of the required {} native price sources agree",
+                agreeing.len(),
+                self.min_agreeing_sources
+            )));
+        }
+        median(agreeing).ok_or_else(|| {
+            PriceEstimationError::EstimatorInternal(anyhow!("no valid native price"))
+        })
+    }
+}
+
+impl NativePriceEstimating for Consensus {
+    fn estimate_native_price(
+        &self,
+        token: H160,
+        timeout: Duration,
+    ) -> BoxFuture<'_, NativePriceEstimateResult> {
+        async move {
+            let results =
+                futures::future::join_all(self.sources.iter().map(|(name, estimator)| {
+                    estimator
+                        .estimate_native_price(token, timeout)
+                        .map(move |result| (name, result))
+                }))
+                .await;
+
+            let mut prices = Vec::with_capacity(results.len());
+            let mut best_error = None;
+            for (name, result) in results {
+                match result {
+                    Ok(price) if is_price_malformed(price) => {
+                        tracing::debug!(?token, estimator = name, price, "malformed price");
+                    }
+                    Ok(price) => prices.push((name, price)),
+                    Err(err) => {
+                        best_error = match best_error {
+                            Some(best) if compare_error(&best, &err).is_ge() => Some(best),
+                            _ => Some(err),
+                        };
+                    }
+                }
+            }
+
+            let Some(reference) = median(prices.iter().map(|(_, price)| *price).collect()) else {
+                return Err(best_error.unwrap_or_else(|| {
+                    PriceEstimationError::EstimatorInternal(anyhow!("no valid native price"))
+                }));
+            };
+
+            if prices.len() < MIN_SOURCES_FOR_OUTLIERS {
+                let (min, max) = prices
+                    .iter()
+                    .fold((f64::MAX, f64::MIN), |(min, max), (_, price)| {
+                        (min.min(*price), max.max(*price))
+                    });
+                let deviation = (max - min) / min;
+                if deviation > self.max_deviation {
+                    tracing::warn!(?token, ?prices, deviation, "native price sources disagree");
+                    return Err(PriceEstimationError::EstimatorInternal(anyhow!(
+                        "native price sources disagree by {deviation}"
+                    )));
+                }
+                for (name, _) in &prices {
+                    metrics::deviation(name, deviation);
+                }
+                return self.agreed_price(prices.into_iter().map(|(_, price)| price).collect());
+            }
+
+            let mut agreeing = Vec::with_capacity(prices.len());
+            for (name, price) in prices {
+                let deviation = (price - reference).abs() / reference;
+                metrics::deviation(name, deviation);
+                if deviation > self.max_deviation {
+                    tracing::warn!(
+                        ?token,
+                        estimator = name,
+                        price,
+                        reference,
+                        deviation,
+                        "discarding outlier native price"
+                    );
+                    metrics::outlier(name);
+                    continue;
+                }
+                agreeing.push(price);
+            }
+            self.agreed_price(agreeing)
+        }
+        .boxed()
+    }
+}
+
+/// Computes the median of the given prices. For an even number of prices the
+/// mean of the two middle values is used.
+fn median(mut prices: Vec<NativePrice>) -> Option<NativePrice> {
+    if prices.is_empty() {
+        return None;
+    }
+    prices.sort_by(f64::total_cmp);
+    let middle = prices.len() / 2;
+    Some(if prices.len() % 2 == 0 {
+        (prices[middle - 1] + prices[middle]) / 2.
+    } else {
+        prices[middle]
+    })
+}
+
+mod metrics {
+    use {
+        observe::metrics,
+        prometheus::{HistogramVec, IntCounterVec},
+    };
+
+    #[derive(prometheus_metric_storage::MetricStorage)]
+    #[metric(subsystem = "consensus_native_price")]
+    struct Metrics {
+        /// Relative deviation of a source's native price from the median of
+        /// all sources.
+        #[metric(
+            labels("estimator_type"),
+            buckets(0.001, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0)
+        )]
+        deviation: HistogramVec,
+
+        /// Number of native prices discarded for deviating too much from the
+        /// median of all sources.
+        #[metric(labels("estimator_type"))]
+        outliers: IntCounterVec,
+    }
+
+    impl Metrics {
+        fn get() -> &'static Self {
+            Metrics::instance(metrics::get_storage_registry()).unwrap()
+        }
+    }
+
+    pub(super) fn deviation(estimator: &str, deviation: f64) {
+        Metrics::get()
+            .deviation
+            .with_label_values(&[estimator])
+            .observe(deviation);
+    }
+
+    pub(super) fn outlier(estimator: &str) {
+        Metrics::get()
+            .outliers
+            .with_label_values(&[estimator])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::price_estimation::{
+            HEALTHY_PRICE_ESTIMATION_TIME,
+            native::MockNativePriceEstimating,
+        },
+    };
+
+    fn source(
+        name: &str,
+        result: NativePriceEstimateResult,
+    ) -> (String, Arc<dyn NativePriceEstimating>) {
+        let mut estimator = MockNativePriceEstimating::new();
+        estimator
+            .expect_estimate_native_price()
+            .return_once(move |_, _| async move { result }.boxed());
+        (name.to_string(), Arc::new(estimator))
+    }
+
+    #[tokio::test]
+    async fn discards_outliers() {
+        let consensus = Consensus::new(
+            vec![
+                source("a", Ok(1.0)),
+                source("b", Ok(1.02)),
+                source("c", Ok(0.99)),
+                source("d", Ok(5.0)),
+            ],
+            0.05,
+            2,
+        );
+        let price = consensus
+            .estimate_native_price(H160([1; 20]), HEALTHY_PRICE_ESTIMATION_TIME)
+            .await
+            .unwrap();
+        assert_eq!(price, 1.0);
+    }
+
+    #[tokio::test]
+    async fn requires_enough_agreeing_sources() {
+        let consensus = Consensus::new(
+            vec![
+                source("a", Ok(1.0)),
+                source("b", Ok(2.0)),
+                source("c", Err(PriceEstimationError::NoLiquidity)),
+            ],
+            0.1,
+            2,
+        );
+        let result = consensus
+            .estimate_native_price(H160([1; 20]), HEALTHY_PRICE_ESTIMATION_TIME)
+            .await;
+        assert!(matches!(
+            result,
+            Err(PriceEstimationError::EstimatorInternal(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn requires_two_sources_to_agree_with_each_other() {
+        let consensus = Consensus::new(vec![source("a", Ok(1.0)), source("b", Ok(1.08))], 0.1, 2);
+        let price = consensus
+            .estimate_native_price(H160([1; 20]), HEALTHY_PRICE_ESTIMATION_TIME)
+            .await
+            .unwrap();
+        assert!((price - 1.04).abs() < 1e-9);
+
+        let consensus = Consensus::new(vec![source("a", Ok(1.0)), source("b", Ok(5.0))], 0.1, 1);
+        let result = consensus
+            .estimate_native_price(H160([1; 20]), HEALTHY_PRICE_ESTIMATION_TIME)
+            .await;
+        assert!(matches!(
+            result,
+            Err(PriceEstimationError::EstimatorInternal(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn propagates_most_recoverable_error() {
+        let consensus = Consensus::new(
+            vec![
+                source("a", Err(PriceEstimationError::NoLiquidity)),
+                source("b", Err(PriceEstimationError::RateLimited)),
+            ],
+            0.1,
+            1,
+        );
+        let result = consensus
+            .estimate_native_price(H160([1; 20]), HEALTHY_PRICE_ESTIMATION_TIME)
+            .await;
+        assert!(matches!(result, Err(PriceEstimationError::RateLimited)));
+    }
+
+    #[test]
+    fn computes_median() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3., 1., 2.]), Some(2.));
+        assert_eq!(median(vec![4., 1., 3., 2.]), Some(2.5));
+    }
+}
@@ -12,9 +12,16 @@ use {
 };
 
 mod coingecko;
+mod consensus;
+mod onchain_oracle;
 mod oneinch;
 
-pub use self::{coingecko::CoinGecko, oneinch::OneInch};
+pub use self::{
+    coingecko::CoinGecko,
+    consensus::Consensus,
+    onchain_oracle::OnchainOracle,
+    oneinch::OneInch,
+};
 
 pub type NativePrice = f64;
 pub type NativePriceEstimateResult = Result<NativePrice, PriceEstimationError>;
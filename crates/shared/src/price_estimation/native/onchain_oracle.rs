@@ -0,0 +1,297 @@
+//! Native price estimator reading on-chain oracles. Prices are read from
+//! Chainlink aggregators where one is configured for a token and fall back to
+//! the time weighted average price of a Uniswap V3 pool pairing the token with
+//! the native token.
+
+use {
+    super::{NativePrice, NativePriceEstimateResult, NativePriceEstimating, is_price_malformed},
+    crate::{price_estimation::PriceEstimationError, token_info::TokenInfoFetching},
+    anyhow::{Context, Result, anyhow, ensure},
+    contracts::{ChainlinkAggregator, UniswapV3Pool, dummy_contract},
+    ethcontract::{
+        I256,
+        U256,
+        errors::ExecutionError,
+        tokens::Tokenize,
+        web3::{
+            ethabi::{self, Token},
+            types::Bytes,
+        },
+    },
+    ethrpc::{
+        Web3,
+        multicall::{Call, MulticallExt},
+    },
+    futures::{FutureExt, future::BoxFuture},
+    primitive_types::H160,
+    std::{
+        collections::HashMap,
+        sync::Arc,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Prices of Uniswap V3 pools are quoted in powers of this base.
+const TICK_BASE: f64 = 1.0001;
+
+/// The oracles configured for a token.
+#[derive(Clone, Copy, Debug, Default)]
+struct Feeds {
+    /// Chainlink aggregator quoting the token in the native token.
+    chainlink: Option<H160>,
+    /// Uniswap V3 pool trading the token against the native token.
+    uniswap_v3: Option<H160>,
+}
+
+pub struct OnchainOracle {
+    web3: Web3,
+    native_token: H160,
+    token_infos: Arc<dyn TokenInfoFetching>,
+    feeds: HashMap<H160, Feeds>,
+    chainlink_max_staleness: Duration,
+    twap_period: u32,
+}
+
+impl OnchainOracle {
+    pub fn new(
+        web3: Web3,
+        native_token: H160,
+        token_infos: Arc<dyn TokenInfoFetching>,
+        chainlink_feeds: &[(H160, H160)],
+        uniswap_v3_pools: &[(H160, H160)],
+        chainlink_max_staleness: Duration,
+        twap_period: Duration,
+    ) -> Result<Self> {
+        let twap_period = u32::try_from(twap_period.as_secs())
+            .ok()
+            .filter(|period| *period > 0)
+            .context("invalid Uniswap V3 TWAP period")?;
+
+        let mut feeds = HashMap::<H160, Feeds>::new();
+        for (token, aggregator) in chainlink_feeds {
+            feeds.entry(*token).or_default().chainlink = Some(*aggregator);
+        }
+        for (token, pool) in uniswap_v3_pools {
+            feeds.entry(*token).or_default().uniswap_v3 = Some(*pool);
+        }
+
+        Ok(Self {
+            web3,
+            native_token,
+            token_infos,
+            feeds,
+            chainlink_max_staleness,
+            twap_period,
+        })
+    }
+
+    async fn estimate(&self, token: H160) -> NativePriceEstimateResult {
+        let Some(feeds) = self.feeds.get(&token).copied() else {
+            return Err(PriceEstimationError::NoLiquidity);
+        };
+
+        let mut calls = Vec::new();
+        if let Some(aggregator) = feeds.chainlink {
+            let aggregator = dummy_contract!(ChainlinkAggregator, aggregator);
+            calls.push(call(aggregator.address(), aggregator.decimals().m.tx.data));
+            calls.push(call(
+                aggregator.address(),
+                aggregator.latest_round_data().m.tx.data,
+            ));
+        }
+        if let Some(pool) = feeds.uniswap_v3 {
+            let pool = dummy_contract!(UniswapV3Pool, pool);
+            calls.push(call(pool.address(), pool.token0().m.tx.data));
+            calls.push(call(pool.address(), pool.token1().m.tx.data));
+            calls.push(call(
+                pool.address(),
+                pool.observe(vec![self.twap_period, 0]).m.tx.data,
+            ));
+        }
+
+        let mut results = self
+            .web3
+            .eth()
+            .multicall(calls, Default::default(), None)
+            .await
+            .into_iter();
+
+        let mut errors = Vec::new();
+        if feeds.chainlink.is_some() {
+            let (decimals, round) = (results.next(), results.next());
+            match self.chainlink_price(token, decimals, round).await {
+                Ok(price) => return Ok(price),
+                Err(err) => {
+                    tracing::debug!(?token, ?err, "failed to read Chainlink price");
+                    errors.push(err);
+                }
+            }
+        }
+        if feeds.uniswap_v3.is_some() {
+            let (token0, token1, observations) = (results.next(), results.next(), results.next());
+            match self.uniswap_v3_price(token, token0, token1, observations) {
+                Ok(price) => return Ok(price),
+                Err(err) => {
+                    tracing::debug!(?token, ?err, "failed to read Uniswap V3 TWAP");
+                    errors.push(err);
+                }
+            }
+        }
+
+        Err(PriceEstimationError::EstimatorInternal(anyhow!(
+            "no oracle returned a price: {errors:?}"
+        )))
+    }
+
+    async fn chainlink_price(
+        &self,
+        token: H160,
+        decimals: Option<CallResult>,
+        round: Option<CallResult>,
+    ) -> Result<NativePrice> {
+        let chainlink_abi = &ChainlinkAggregator::raw_contract().interface.abi;
+        let (feed_decimals,): (u8,) = decode(chainlink_abi, "decimals", decimals)?;
+        let (_, answer, _, updated_at, _): (U256, I256, U256, U256, U256) =
+            decode(chainlink_abi, "latestRoundData", round)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let age = now.saturating_sub(updated_at.try_into().unwrap_or(u64::MAX));
+        ensure!(
+            age <= self.chainlink_max_staleness.as_secs(),
+            "stale answer updated {age}s ago"
+        );
+
+        let token_decimals = self
+            .token_infos
+            .get_token_info(token)
+            .await?
+            .decimals
+            .context("unknown token decimals")?;
+        chainlink_price(answer, feed_decimals, token_decimals)
+    }
+
+    fn uniswap_v3_price(
+        &self,
+        token: H160,
+        token0: Option<CallResult>,
+        token1: Option<CallResult>,
+        observations: Option<CallResult>,
+    ) -> Result<NativePrice> {
+        let pool_abi = &UniswapV3Pool::raw_contract().interface.abi;
+        let (token0,): (H160,) = decode(pool_abi, "token0", token0)?;
+        let (token1,): (H160,) = decode(pool_abi, "token1", token1)?;
+        let (tick_cumulatives, _): (Vec<I256>, Vec<U256>) =
+            decode(pool_abi, "observe", observations)?;
+
+        let token_is_token0 = match (token0, token1) {
+            (t0, t1) if t0 == token && t1 == self.native_token => true,
+            (t0, t1) if t0 == self.native_token && t1 == token => false,
+            _ => anyhow::bail!("pool does not trade token against native token"),
+        };
+        let [past, now] = tick_cumulatives[..] else {
+            anyhow::bail!("unexpected number of observations");
+        };
+        let tick = average_tick(past, now, self.twap_period)?;
+        let price = tick_price(tick, token_is_token0);
+        ensure!(!is_price_malformed(price), "malformed price {price}");
+        Ok(price)
+    }
+}
+
+impl NativePriceEstimating for OnchainOracle {
+    fn estimate_native_price(
+        &self,
+        token: H160,
+        _timeout: Duration,
+    ) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token).boxed()
+    }
+}
+
+type CallResult = Result<Vec<u8>, ExecutionError>;
+
+fn call(to: H160, data: Option<Bytes>) -> Call {
+    Call {
+        to,
+        data: data.map(|data| data.0).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Decodes the return data of a multicall entry for the given contract
+/// function.
+fn decode<T: Tokenize>(
+    abi: &ethabi::Contract,
+    function: &str,
+    result: Option<CallResult>,
+) -> Result<T> {
+    let data = result.context("missing multicall result")??;
+    let tokens = abi
+        .function(function)?
+        .decode_output(&data)
+        .context("decode")?;
+    Ok(T::from_token(Token::Tuple(tokens))?)
+}
+
+/// Converts a Chainlink answer denominated in whole native tokens per whole
+/// token into the price of one token atom in native token atoms.
+fn chainlink_price(answer: I256, feed_decimals: u8, token_decimals: u8) -> Result<NativePrice> {
+    ensure!(answer > I256::zero(), "non-positive answer {answer}");
+    let answer = answer.into_raw().to_f64_lossy();
+    let price =
+        answer / 10f64.powi(feed_decimals.into()) * 10f64.powi(18 - i32::from(token_decimals));
+    ensure!(!is_price_malformed(price), "malformed price {price}");
+    Ok(price)
+}
+
+/// Computes the time weighted average tick from two tick accumulator
+/// observations `period` seconds apart. Like the Uniswap `OracleLibrary`, the
+/// result is rounded towards negative infinity.
+fn average_tick(past: I256, now: I256, period: u32) -> Result<i64> {
+    let delta = now.checked_sub(past).context("tick accumulator overflow")?;
+    // Tick accumulators are `int56` values, so their difference always fits.
+    let delta = i64::try_from(delta).context("tick accumulator delta out of range")?;
+    Ok(delta.div_euclid(period.into()))
+}
+
+/// Returns the price of one token atom in native token atoms for the given
+/// pool tick.
+fn tick_price(tick: i64, token_is_token0: bool) -> NativePrice {
+    let price = TICK_BASE.powf(tick as f64);
+    if token_is_token0 { price } else { 1. / price }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_chainlink_answers() {
+        // 0.0005 ETH per USDC with 18 feed decimals and 6 token decimals.
+        let price = chainlink_price(I256::from(500_000_000_000_000_i128), 18, 6).unwrap();
+        assert!((price - 5e8).abs() < 1e-3);
+
+        assert!(chainlink_price(I256::zero(), 18, 18).is_err());
+        assert!(chainlink_price(I256::from(-1_i128), 18, 18).is_err());
+    }
+
+    #[test]
+    fn rounds_average_tick_down() {
+        assert_eq!(
+            average_tick(I256::from(0_i128), I256::from(3_600_i128), 1_800).unwrap(),
+            2
+        );
+        assert_eq!(
+            average_tick(I256::from(100_i128), I256::from(-1_i128), 10).unwrap(),
+            -11
+        );
+    }
+
+    #[test]
+    fn tick_price_depends_on_token_order() {
+        assert_eq!(tick_price(0, true), 1.);
+        let price = tick_price(-23_028, true);
+        assert!((price - 0.1).abs() < 1e-4);
+        assert!((tick_price(-23_028, false) - 1. / price).abs() < 1e-9);
+    }
+}
@@ -3,6 +3,8 @@ use {
         bad_token::{BadTokenDetecting, TokenQuality},
         price_estimation::{
             Estimate,
+            EstimateUpdate,
+            PriceEstimateResult,
             PriceEstimating,
             PriceEstimationError,
             Query,
@@ -10,7 +12,7 @@ use {
         },
     },
     anyhow::anyhow,
-    futures::FutureExt,
+    futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream},
     model::order::BUY_ETH_ADDRESS,
     primitive_types::H160,
     std::sync::Arc,
@@ -50,63 +52,54 @@ impl SanitizedPriceEstimator {
         }
         Ok(())
     }
-}
-
-impl PriceEstimating for SanitizedPriceEstimator {
-    fn estimate(
-        &self,
-        query: Arc<Query>,
-    ) -> futures::future::BoxFuture<'_, super::PriceEstimateResult> {
-        async move {
-            self.handle_bad_tokens(&query).await?;
 
-            // buy_token == sell_token => 1 to 1 conversion
-            if query.buy_token == query.sell_token {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: 0,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial price estimation");
-                return Ok(estimation);
-            }
+    /// Either estimates the query on its own or adjusts it to be estimated by
+    /// the inner estimator.
+    async fn sanitize(&self, query: &Query) -> Result<Sanitized, PriceEstimationError> {
+        self.handle_bad_tokens(query).await?;
 
-            // sell WETH for ETH => 1 to 1 conversion with cost for unwrapping
-            if query.sell_token == self.native_token && query.buy_token == BUY_ETH_ADDRESS {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: GAS_PER_WETH_UNWRAP,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial unwrap estimation");
-                return Ok(estimation);
-            }
+        // buy_token == sell_token => 1 to 1 conversion
+        if query.buy_token == query.sell_token {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: 0,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
+            };
+            tracing::debug!(?query, ?estimation, "generate trivial price estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            // sell ETH for WETH => 1 to 1 conversion with cost for wrapping
-            if query.sell_token == BUY_ETH_ADDRESS && query.buy_token == self.native_token {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: GAS_PER_WETH_WRAP,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial wrap estimation");
-                return Ok(estimation);
-            }
+        // sell WETH for ETH => 1 to 1 conversion with cost for unwrapping
+        if query.sell_token == self.native_token && query.buy_token == BUY_ETH_ADDRESS {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: GAS_PER_WETH_UNWRAP,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
+            };
+            tracing::debug!(?query, ?estimation, "generate trivial unwrap estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            enum Modification {
-                AddGas(u64),
-            }
+        // sell ETH for WETH => 1 to 1 conversion with cost for wrapping
+        if query.sell_token == BUY_ETH_ADDRESS && query.buy_token == self.native_token {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: GAS_PER_WETH_WRAP,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
+            };
+            tracing::debug!(?query, ?estimation, "generate trivial wrap estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            let mut adjusted_query = Query::clone(&*query);
-            let modification = if query.sell_token != self.native_token
-                && query.buy_token == BUY_ETH_ADDRESS
-            {
+        let mut adjusted_query = Query::clone(query);
+        let modification =
+            if query.sell_token != self.native_token && query.buy_token == BUY_ETH_ADDRESS {
                 tracing::debug!(?query, "estimate price for buying native asset");
                 adjusted_query.buy_token = self.native_token;
                 Some(Modification::AddGas(GAS_PER_WETH_UNWRAP))
@@ -118,23 +111,81 @@ impl PriceEstimating for SanitizedPriceEstimator {
                 None
             };
 
-            let mut estimate = self.inner.estimate(Arc::new(adjusted_query)).await?;
+        Ok(Sanitized::Adjusted(Arc::new(adjusted_query), modification))
+    }
+}
+
+enum Sanitized {
+    /// The query can be answered without asking the inner estimator.
+    Trivial(Estimate),
+    /// The query needs to be estimated by the inner estimator and its result
+    /// modified afterwards.
+    Adjusted(Arc<Query>, Option<Modification>),
+}
+
+#[derive(Clone, Copy)]
+enum Modification {
+    AddGas(u64),
+}
 
-            match modification {
-                Some(Modification::AddGas(gas)) => {
-                    estimate.gas = estimate.gas.checked_add(gas).ok_or_else(|| {
-                        PriceEstimationError::ProtocolInternal(anyhow!(
-                            "cost of converting native asset would overflow gas price"
-                        ))
-                    })?;
-                    tracing::debug!(
-                        ?query,
-                        ?estimate,
-                        "added cost of converting native asset to price estimation"
-                    );
-                    Ok(estimate)
+/// Applies the modification needed to turn the adjusted query's estimate into
+/// an estimate of the original query.
+fn apply(
+    modification: Option<Modification>,
+    query: &Query,
+    mut estimate: Estimate,
+) -> PriceEstimateResult {
+    match modification {
+        Some(Modification::AddGas(gas)) => {
+            estimate.gas = estimate.gas.checked_add(gas).ok_or_else(|| {
+                PriceEstimationError::ProtocolInternal(anyhow!(
+                    "cost of converting native asset would overflow gas price"
+                ))
+            })?;
+            tracing::debug!(
+                ?query,
+                ?estimate,
+                "added cost of converting native asset to price estimation"
+            );
+            Ok(estimate)
+        }
+        None => Ok(estimate),
+    }
+}
+
+impl PriceEstimating for SanitizedPriceEstimator {
+    fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult> {
+        async move {
+            match self.sanitize(&query).await? {
+                Sanitized::Trivial(estimate) => Ok(estimate),
+                Sanitized::Adjusted(adjusted_query, modification) => {
+                    let estimate = self.inner.estimate(adjusted_query).await?;
+                    apply(modification, &query, estimate)
+                }
+            }
+        }
+        .boxed()
+    }
+
+    fn estimate_streaming(&self, query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        async_stream::stream! {
+            match self.sanitize(&query).await {
+                Err(err) => yield EstimateUpdate { result: Err(err), best: false },
+                Ok(Sanitized::Trivial(estimate)) => {
+                    yield EstimateUpdate { result: Ok(estimate), best: true }
+                }
+                Ok(Sanitized::Adjusted(adjusted_query, modification)) => {
+                    let mut updates = self.inner.estimate_streaming(adjusted_query);
+                    while let Some(update) = updates.next().await {
+                        let result = update
+                            .result
+                            .and_then(|estimate| apply(modification, &query, estimate));
+                        yield EstimateUpdate {
+                            best: update.best && result.is_ok(),
+                            result,
+                        };
+                    }
                 }
-                None => Ok(estimate),
             }
         }
         .boxed()
@@ -4,6 +4,7 @@ use {
     self::balance_overrides::{BalanceOverrideRequest, BalanceOverriding},
     super::{Estimate, Verification},
     crate::{
+        bad_token::{BadTokenDetecting, TransferFee},
         code_fetching::CodeFetching,
         code_simulation::CodeSimulating,
         encoded_settlement::{EncodedSettlement, EncodedTrade, encode_trade},
@@ -66,6 +67,7 @@ pub struct TradeVerifier {
     native_token: H160,
     quote_inaccuracy_limit: BigRational,
     domain_separator: DomainSeparator,
+    bad_token_detector: Arc<dyn BadTokenDetecting>,
 }
 
 impl TradeVerifier {
@@ -83,6 +85,7 @@ impl TradeVerifier {
         settlement: H160,
         native_token: H160,
         quote_inaccuracy_limit: BigDecimal,
+        bad_token_detector: Arc<dyn BadTokenDetecting>,
     ) -> Result<Self> {
         let settlement_contract = GPv2Settlement::at(&web3, settlement);
         let domain_separator =
@@ -97,6 +100,7 @@ impl TradeVerifier {
             quote_inaccuracy_limit: big_decimal_to_big_rational(&quote_inaccuracy_limit),
             web3,
             domain_separator,
+            bad_token_detector,
         })
     }
 
@@ -223,6 +227,17 @@ impl TradeVerifier {
             .context("could not decode simulation output")
             .map_err(Error::SimulationFailed)?;
 
+        let (sell_token_fee, buy_token_fee) = self
+            .transfer_fees(query)
+            .await
+            .map_err(Error::SimulationFailed)?;
+        // The trader of a sell order only receives the bought tokens after the
+        // buy token transfer fee. Report the amount the settlement contract
+        // paid out instead, like unverified estimates do.
+        if let (OrderKind::Sell, Some(fee)) = (query.kind, buy_token_fee) {
+            summary.out_amount = fee.required(summary.out_amount);
+        }
+
         {
             // Quote accuracy gets determined by how many tokens had to be paid out of the
             // settlement buffers to make the quote happen. When the settlement contract
@@ -249,6 +264,23 @@ impl TradeVerifier {
                     .entry(query.buy_token)
                     .and_modify(|balance| *balance += u256_to_big_rational(&buy_amount));
             }
+
+            // Tokens that take a fee on transfer arrive reduced in the settlement
+            // contract, both when pulled from the trader and when bought by the
+            // solver's interactions. These losses are expected and don't affect
+            // the quote's accuracy.
+            if let Some(fee) = sell_token_fee {
+                summary
+                    .tokens_lost
+                    .entry(query.sell_token)
+                    .and_modify(|balance| *balance -= u256_to_big_rational(&fee.fee(sell_amount)));
+            }
+            if let Some(fee) = buy_token_fee {
+                summary
+                    .tokens_lost
+                    .entry(query.buy_token)
+                    .and_modify(|balance| *balance -= u256_to_big_rational(&fee.fee(buy_amount)));
+            }
         }
 
         tracing::debug!(
@@ -268,6 +300,18 @@ impl TradeVerifier {
         ensure_quote_accuracy(&self.quote_inaccuracy_limit, query, trade, &summary)
     }
 
+    /// Looks up the transfer fees of the sell and buy token.
+    async fn transfer_fees(
+        &self,
+        query: &PriceQuery,
+    ) -> Result<(Option<TransferFee>, Option<TransferFee>)> {
+        let (sell, buy) = futures::try_join!(
+            self.bad_token_detector.detect(query.sell_token),
+            self.bad_token_detector.detect(query.buy_token),
+        )?;
+        Ok((sell.transfer_fee(), buy.transfer_fee()))
+    }
+
     /// Configures all the state overrides that are needed to mock the given
     /// trade.
     async fn prepare_state_overrides(
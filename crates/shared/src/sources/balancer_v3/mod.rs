@@ -0,0 +1,24 @@
+//! Balancer V3 liquidity from weighted and stable pools.
+//!
+//! Unlike Balancer V2, all V3 pools get registered with the singleton `Vault`
+//! contract, so pools are indexed from its `PoolRegistered` events (see
+//! `registry`) and their dynamic state is fetched from the `Vault` on demand
+//! for the requested token pairs (see `pool_fetching`). The `swap` module
+//! emulates the V3 `Vault` swap logic on top of the Balancer V2 pool math.
+//!
+//! Boosted pools are supported as pools of their (ERC-4626) wrapped tokens,
+//! since the token rates get applied to the pool balances. Swapping through
+//! the `Vault`'s wrapping buffers is not supported.
+
+pub mod pool_fetching;
+mod registry;
+pub mod swap;
+
+pub use self::pool_fetching::{
+    BalancerV3FactoryKind,
+    BalancerV3PoolFetcher,
+    BalancerV3PoolFetching,
+    Pool,
+    PoolKind,
+    TokenState,
+};
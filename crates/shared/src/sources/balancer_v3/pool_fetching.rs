@@ -0,0 +1,339 @@
+//! Pool fetching for Balancer V3 pools. Pools trading the requested token
+//! pairs are looked up in the registry and their dynamic state (balances, token
+//! rates, fees and pool specific parameters) is fetched from the `Vault` and
+//! the pool contracts at the requested block.
+
+use {
+    super::registry::{PoolInfo, PoolStorage, VaultContract},
+    crate::{
+        event_handling::EventHandler,
+        maintenance::Maintaining,
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+        sources::balancer_v2::{pool_fetching::AmplificationParameter, swap::fixed_point::Bfp},
+    },
+    anyhow::{Context, Result, ensure},
+    contracts::{
+        BalancerV3StablePool,
+        BalancerV3Vault,
+        BalancerV3WeightedPool,
+        errors::EthcontractErrorType,
+    },
+    ethcontract::{BlockId, H160, U256, errors::MethodError},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, CurrentBlockWatcher},
+    },
+    futures::{TryFutureExt as _, future},
+    model::TokenPair,
+    std::{collections::HashSet, sync::Arc},
+    tokio::sync::Mutex,
+};
+
+/// The supported Balancer V3 pool factories.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BalancerV3FactoryKind {
+    Weighted,
+    Stable,
+}
+
+/// A Balancer V3 pool along with its state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    /// The tokens of the pool in the order the `Vault` indexes them.
+    pub tokens: Vec<TokenState>,
+    /// The static swap fee percentage.
+    pub swap_fee: Bfp,
+    pub kind: PoolKind,
+}
+
+/// The state of a single token of a Balancer V3 pool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TokenState {
+    pub token: H160,
+    /// The raw token balance of the pool.
+    pub balance: U256,
+    /// The factor converting raw token amounts into the 18 decimal "live"
+    /// amounts the pool math operates on. This is the product of the decimal
+    /// scaling factor and the token rate, so for tokens with rate providers
+    /// (like the wrapped tokens of boosted pools) it changes over time.
+    pub scaling_factor: Bfp,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolKind {
+    /// The normalized weights of the tokens in the same order as `tokens`.
+    Weighted(Vec<Bfp>),
+    Stable(AmplificationParameter),
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait BalancerV3PoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// Type alias for the event handler indexing the pools registered with the
+/// `Vault`.
+type PoolUpdater = Mutex<EventHandler<VaultContract, PoolStorage>>;
+
+struct Registry {
+    vault: BalancerV3Vault,
+    updater: PoolUpdater,
+}
+
+impl Registry {
+    async fn pools_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        self.updater
+            .lock()
+            .await
+            .store()
+            .pools_for_token_pairs(token_pairs)
+    }
+
+    async fn pool_infos(&self, addresses: &HashSet<H160>) -> Vec<PoolInfo> {
+        let updater = self.updater.lock().await;
+        addresses
+            .iter()
+            .filter_map(|address| updater.store().pool(*address).cloned())
+            .collect()
+    }
+}
+
+pub struct BalancerV3PoolFetcher {
+    registry: Arc<Registry>,
+    cache: RecentBlockCache<H160, Pool, PoolStateFetcher>,
+}
+
+impl BalancerV3PoolFetcher {
+    /// Creates a new pool fetcher by indexing all pools of the specified
+    /// factories that got registered with the `Vault` since it was deployed at
+    /// `deployment_block`. This can take a while, so the fetcher should be
+    /// created in the background.
+    pub async fn new(
+        web3: Web3,
+        vault: H160,
+        deployment_block: u64,
+        factories: Vec<(BalancerV3FactoryKind, H160)>,
+        block_retriever: Arc<dyn BlockRetrieving>,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "balancerV3".into());
+        let vault = BalancerV3Vault::at(&web3, vault);
+        let mut updater = EventHandler::new(
+            block_retriever,
+            VaultContract(vault.clone()),
+            PoolStorage::new(factories, deployment_block),
+            None,
+        );
+        updater
+            .update_events()
+            .await
+            .context("failed to index Balancer V3 pools")?;
+
+        let registry = Arc::new(Registry {
+            vault,
+            updater: Mutex::new(updater),
+        });
+        let cache = RecentBlockCache::new(
+            config,
+            PoolStateFetcher(registry.clone()),
+            block_stream,
+            "balancerv3",
+        )?;
+        Ok(Self { registry, cache })
+    }
+}
+
+#[async_trait::async_trait]
+impl BalancerV3PoolFetching for BalancerV3PoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses = self.registry.pools_for_token_pairs(&token_pairs).await;
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for BalancerV3PoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.registry.updater.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "BalancerV3PoolFetcher"
+    }
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+struct PoolStateFetcher(Arc<Registry>);
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for PoolStateFetcher {
+    async fn fetch_values(&self, addresses: HashSet<H160>, at_block: Block) -> Result<Vec<Pool>> {
+        let block = BlockId::Number(at_block.into());
+        let pools = self.0.pool_infos(&addresses).await;
+        let pools = future::join_all(
+            pools
+                .into_iter()
+                .map(|info| fetch_pool_state(&self.0.vault, info, block)),
+        )
+        .await;
+        collect_pool_results(pools)
+    }
+}
+
+/// Fetches the state of the specified pool. Returns `None` for paused pools.
+async fn fetch_pool_state(
+    vault: &BalancerV3Vault,
+    info: PoolInfo,
+    block: BlockId,
+) -> Result<Option<Pool>> {
+    let (pool_data, swap_fee, paused, kind) = futures::try_join!(
+        vault
+            .get_pool_data(info.address)
+            .block(block)
+            .call()
+            .err_into(),
+        vault
+            .get_static_swap_fee_percentage(info.address)
+            .block(block)
+            .call()
+            .err_into(),
+        vault
+            .is_pool_paused(info.address)
+            .block(block)
+            .call()
+            .err_into(),
+        fetch_pool_kind(vault, &info, block),
+    )?;
+    if paused {
+        return Ok(None);
+    }
+
+    let (_, tokens, _, balances, _, rates, decimal_scaling_factors) = pool_data;
+    ensure!(
+        tokens == info.tokens
+            && balances.len() == tokens.len()
+            && rates.len() == tokens.len()
+            && decimal_scaling_factors.len() == tokens.len(),
+        "inconsistent Balancer V3 pool data for {:?}",
+        info.address,
+    );
+    if let PoolKind::Weighted(weights) = &kind {
+        ensure!(
+            weights.len() == tokens.len(),
+            "inconsistent Balancer V3 pool weights for {:?}",
+            info.address,
+        );
+    }
+
+    let tokens = tokens
+        .into_iter()
+        .zip(balances)
+        .zip(rates.into_iter().zip(decimal_scaling_factors))
+        .map(|((token, balance), (rate, decimal_scaling_factor))| {
+            Ok(TokenState {
+                token,
+                balance,
+                scaling_factor: Bfp::from_wei(
+                    decimal_scaling_factor
+                        .checked_mul(rate)
+                        .context("scaling factor overflow")?,
+                ),
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Some(Pool {
+        address: info.address,
+        tokens,
+        swap_fee: Bfp::from_wei(swap_fee),
+        kind,
+    }))
+}
+
+/// Fetches the pool specific parameters of the specified pool.
+async fn fetch_pool_kind(
+    vault: &BalancerV3Vault,
+    info: &PoolInfo,
+    block: BlockId,
+) -> Result<PoolKind> {
+    let web3 = vault.raw_instance().web3();
+    match info.kind {
+        BalancerV3FactoryKind::Weighted => {
+            let pool = BalancerV3WeightedPool::at(&web3, info.address);
+            let weights = pool.get_normalized_weights().block(block).call().await?;
+            Ok(PoolKind::Weighted(
+                weights.into_iter().map(Bfp::from_wei).collect(),
+            ))
+        }
+        BalancerV3FactoryKind::Stable => {
+            let pool = BalancerV3StablePool::at(&web3, info.address);
+            let (factor, _, precision) = pool
+                .get_amplification_parameter()
+                .block(block)
+                .call()
+                .await?;
+            Ok(PoolKind::Stable(AmplificationParameter::try_new(
+                factor, precision,
+            )?))
+        }
+    }
+}
+
+/// Drops paused pools and pools whose state can't be fetched because the
+/// contract reverted and forwards all other errors.
+fn collect_pool_results(pools: Vec<Result<Option<Pool>>>) -> Result<Vec<Pool>> {
+    pools
+        .into_iter()
+        .filter_map(|pool| match pool {
+            Ok(pool) => pool.map(Ok),
+            Err(err) if is_contract_error(&err) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+fn is_contract_error(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<MethodError>()
+            .map(EthcontractErrorType::classify),
+        Some(EthcontractErrorType::Contract),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::errors::{testing_contract_error, testing_node_error},
+    };
+
+    #[test]
+    fn collecting_results_filters_paused_pools_and_contract_errors() {
+        let pool = Pool {
+            address: H160([1; 20]),
+            tokens: vec![],
+            swap_fee: Bfp::zero(),
+            kind: PoolKind::Weighted(vec![]),
+        };
+        let results = vec![
+            Ok(Some(pool.clone())),
+            Ok(None),
+            Err(testing_contract_error().into()),
+        ];
+        assert_eq!(collect_pool_results(results).unwrap(), vec![pool]);
+
+        let results = vec![Err(testing_node_error().into())];
+        assert!(collect_pool_results(results).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! A registry of the Balancer V3 pools deployed by the supported factories.
+//!
+//! Every Balancer V3 pool needs to be registered with the `Vault` before it
+//! can be used, so pools are indexed from the `Vault`'s `PoolRegistered`
+//! events, which already contain the pool's factory and tokens.
+
+use {
+    super::pool_fetching::BalancerV3FactoryKind,
+    crate::event_handling::{EventRetrieving, EventStoring},
+    anyhow::{Context, Result},
+    contracts::{
+        BalancerV3Vault,
+        balancer_v3_vault::{Event as VaultEvent, event_data::PoolRegistered},
+    },
+    ethcontract::{Event, H160, H256, dyns::DynAllEventsBuilder},
+    ethrpc::block_stream::RangeInclusive,
+    hex_literal::hex,
+    model::TokenPair,
+    std::collections::{HashMap, HashSet},
+};
+
+/// `PoolRegistered(address,address,(address,uint8,address,bool)[],uint256,
+/// uint32,(address,address,address),(bool,bool,bool,bool,bool,bool,bool,bool,
+/// bool,bool,address),(bool,bool,bool,bool))`
+const POOL_REGISTERED_TOPIC: H256 = H256(hex!(
+    "bc1561eeab9f40962e2fb827a7ff9c7cdb47a9d7c84caeefa4ed90e043842dad"
+));
+
+pub struct VaultContract(pub BalancerV3Vault);
+
+impl EventRetrieving for VaultContract {
+    type Event = VaultEvent;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = self.0.all_events();
+        events.filter = events.filter.topic0(POOL_REGISTERED_TOPIC.into());
+        events
+    }
+}
+
+/// The static information of a Balancer V3 pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolInfo {
+    pub address: H160,
+    pub kind: BalancerV3FactoryKind,
+    /// The tokens of the pool in the order the `Vault` indexes them.
+    pub tokens: Vec<H160>,
+    pub block_created: u64,
+}
+
+/// In-memory storage of all registered pools of the supported factories.
+pub struct PoolStorage {
+    factories: HashMap<H160, BalancerV3FactoryKind>,
+    pools: HashMap<H160, PoolInfo>,
+    /// The block from which on pool registrations get indexed.
+    start_block: u64,
+}
+
+impl PoolStorage {
+    /// Creates an empty storage for the specified factories which starts
+    /// indexing at the specified block.
+    pub fn new(factories: Vec<(BalancerV3FactoryKind, H160)>, start_block: u64) -> Self {
+        Self {
+            factories: factories
+                .into_iter()
+                .map(|(kind, address)| (address, kind))
+                .collect(),
+            pools: Default::default(),
+            start_block,
+        }
+    }
+
+    /// Returns all pools that trade any of the specified token pairs.
+    pub fn pools_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        self.pools
+            .values()
+            .filter(|pool| {
+                token_pairs.iter().any(|pair| {
+                    let (a, b) = pair.get();
+                    pool.tokens.contains(&a) && pool.tokens.contains(&b)
+                })
+            })
+            .map(|pool| pool.address)
+            .collect()
+    }
+
+    /// Returns the static information of the pool at the specified address.
+    pub fn pool(&self, address: H160) -> Option<&PoolInfo> {
+        self.pools.get(&address)
+    }
+
+    /// Indexes a pool registration. Pools of unsupported factories and pools
+    /// with hooks, which can adjust swap amounts and fees in ways that can't
+    /// be emulated, are ignored.
+    fn index_pool_registration(&mut self, registration: PoolRegistered, block_created: u64) {
+        let Some(&kind) = self.factories.get(&registration.factory) else {
+            return;
+        };
+        let (.., hooks_contract) = registration.hooks_config;
+        if !hooks_contract.is_zero() {
+            return;
+        }
+
+        self.pools.insert(
+            registration.pool,
+            PoolInfo {
+                address: registration.pool,
+                kind,
+                tokens: registration
+                    .token_config
+                    .iter()
+                    .map(|(token, ..)| *token)
+                    .collect(),
+                block_created,
+            },
+        );
+    }
+
+    fn remove_pools_newer_than_block(&mut self, block: u64) {
+        self.pools.retain(|_, pool| pool.block_created < block);
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<VaultEvent> for PoolStorage {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<VaultEvent>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.remove_pools_newer_than_block(*range.start());
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<VaultEvent>>) -> Result<()> {
+        for event in events {
+            let block_created = event.meta.context("event missing metadata")?.block_number;
+            let VaultEvent::PoolRegistered(registration) = event.data;
+            self.index_pool_registration(registration, block_created);
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        Ok(self
+            .pools
+            .values()
+            .map(|pool| pool.block_created)
+            .max()
+            .unwrap_or_default()
+            .max(self.start_block))
+    }
+
+    async fn persist_last_indexed_block(&mut self, _block: u64) -> Result<()> {
+        // storage is only in-memory so we don't need to persist anything here
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::EventMetadata};
+
+    const WEIGHTED_FACTORY: H160 = H160([0xf1; 20]);
+    const STABLE_FACTORY: H160 = H160([0xf2; 20]);
+
+    fn storage() -> PoolStorage {
+        PoolStorage::new(
+            vec![
+                (BalancerV3FactoryKind::Weighted, WEIGHTED_FACTORY),
+                (BalancerV3FactoryKind::Stable, STABLE_FACTORY),
+            ],
+            10,
+        )
+    }
+
+    fn registration(
+        pool: u8,
+        factory: H160,
+        tokens: &[u8],
+        block_number: u64,
+    ) -> Event<VaultEvent> {
+        Event {
+            data: VaultEvent::PoolRegistered(PoolRegistered {
+                pool: H160([pool; 20]),
+                factory,
+                token_config: tokens
+                    .iter()
+                    .map(|token| (H160([*token; 20]), 0, H160::zero(), false))
+                    .collect(),
+                ..Default::default()
+            }),
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn indexes_pools_of_supported_factories() {
+        let mut storage = storage();
+        let mut with_hooks = registration(4, WEIGHTED_FACTORY, &[1, 2], 14);
+        let VaultEvent::PoolRegistered(registration_data) = &mut with_hooks.data;
+        registration_data.hooks_config.10 = H160([0xaa; 20]);
+        storage
+            .append_events(vec![
+                registration(1, WEIGHTED_FACTORY, &[1, 2, 3], 11),
+                registration(2, STABLE_FACTORY, &[2, 3], 12),
+                registration(3, H160([0xf3; 20]), &[1, 2], 13),
+                with_hooks,
+            ])
+            .await
+            .unwrap();
+
+        let pair = |a: u8, b: u8| TokenPair::new(H160([a; 20]), H160([b; 20])).unwrap();
+        assert_eq!(
+            storage.pools_for_token_pairs(&[pair(1, 2)].into()),
+            [H160([1; 20])].into(),
+        );
+        assert_eq!(
+            storage.pools_for_token_pairs(&[pair(2, 3)].into()),
+            [H160([1; 20]), H160([2; 20])].into(),
+        );
+        assert_eq!(
+            storage.pool(H160([2; 20])),
+            Some(&PoolInfo {
+                address: H160([2; 20]),
+                kind: BalancerV3FactoryKind::Stable,
+                tokens: vec![H160([2; 20]), H160([3; 20])],
+                block_created: 12,
+            }),
+        );
+        assert_eq!(storage.last_event_block().await.unwrap(), 12);
+    }
+
+    #[tokio::test]
+    async fn replacing_events_removes_newer_pools() {
+        let mut storage = storage();
+        assert_eq!(storage.last_event_block().await.unwrap(), 10);
+
+        storage
+            .append_events(vec![
+                registration(1, WEIGHTED_FACTORY, &[1, 2], 11),
+                registration(2, WEIGHTED_FACTORY, &[1, 2], 12),
+                registration(3, WEIGHTED_FACTORY, &[1, 2], 13),
+            ])
+            .await
+            .unwrap();
+        storage
+            .replace_events(
+                vec![registration(4, STABLE_FACTORY, &[1, 2], 12)],
+                RangeInclusive::try_new(12, 13).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(storage.pool(H160([2; 20])).is_none());
+        assert!(storage.pool(H160([3; 20])).is_none());
+        assert_eq!(
+            storage.pool(H160([4; 20])).map(|pool| pool.kind),
+            Some(BalancerV3FactoryKind::Stable),
+        );
+        assert_eq!(storage.last_event_block().await.unwrap(), 12);
+    }
+}
@@ -0,0 +1,359 @@
+//! Module emulating swaps over Balancer V3 pools. The `Vault` scales all
+//! amounts to 18 decimals (applying the token rates), charges the swap fee on
+//! the scaled input amount and then calls into the pool math, which is the
+//! same as for Balancer V2 weighted (version 3+) and stable pools. The original
+//! contract code can be found at:
+//! <https://github.com/balancer/balancer-v3-monorepo/blob/main/pkg/vault/contracts/Vault.sol>
+
+use {
+    super::{Pool, PoolKind, TokenState},
+    crate::{
+        baseline_solver::BaselineSolvable,
+        sources::balancer_v2::swap::{
+            converge_in_amount,
+            fixed_point::Bfp,
+            stable_math,
+            weighted_math,
+        },
+    },
+    ethcontract::{H160, U256},
+};
+
+/// Rough estimate of a single hop swap through the batch router, including the
+/// `Permit2` token transfer.
+pub const SWAP_GAS_COST: usize = 150_000;
+
+/// The `Vault` rejects swaps where the scaled input or output amount is below
+/// this amount.
+const MINIMUM_TRADE_AMOUNT: u64 = 1_000_000;
+
+impl TokenState {
+    /// Scales a raw token amount to its live representation, rounding down.
+    fn to_scaled_18_round_down(&self, amount: U256) -> Option<Bfp> {
+        Bfp::from_wei(amount).mul_down(self.scaling_factor).ok()
+    }
+
+    /// Scales a raw token amount to its live representation, rounding up.
+    fn to_scaled_18_round_up(&self, amount: U256) -> Option<Bfp> {
+        Bfp::from_wei(amount).mul_up(self.scaling_factor).ok()
+    }
+
+    /// Converts a live amount back into a raw token amount, rounding down.
+    fn to_raw_round_down(&self, amount: Bfp) -> Option<U256> {
+        Some(amount.div_down(self.scaling_factor).ok()?.as_uint256())
+    }
+
+    /// Converts a live amount back into a raw token amount, rounding up.
+    fn to_raw_round_up(&self, amount: Bfp) -> Option<U256> {
+        Some(amount.div_up(self.scaling_factor).ok()?.as_uint256())
+    }
+
+    fn live_balance(&self) -> Option<Bfp> {
+        self.to_scaled_18_round_down(self.balance)
+    }
+}
+
+fn ensure_valid_trade_amount(amount: Bfp) -> Option<Bfp> {
+    (amount.is_zero() || amount.as_uint256() >= MINIMUM_TRADE_AMOUNT.into()).then_some(amount)
+}
+
+impl Pool {
+    fn token_index(&self, token: H160) -> Option<usize> {
+        self.tokens.iter().position(|state| state.token == token)
+    }
+
+    fn live_balances(&self) -> Option<Vec<Bfp>> {
+        self.tokens.iter().map(TokenState::live_balance).collect()
+    }
+
+    /// Computes the scaled output amount for the specified scaled input amount
+    /// (after fees) with the pool math.
+    fn out_given_exact_in(&self, index_in: usize, index_out: usize, amount_in: Bfp) -> Option<Bfp> {
+        let mut balances = self.live_balances()?;
+        match &self.kind {
+            PoolKind::Weighted(weights) => weighted_math::calc_out_given_in_v3(
+                balances[index_in],
+                *weights.get(index_in)?,
+                balances[index_out],
+                *weights.get(index_out)?,
+                amount_in,
+            )
+            .ok(),
+            PoolKind::Stable(amplification_parameter) => stable_math::calc_out_given_in(
+                amplification_parameter.with_base(*stable_math::AMP_PRECISION)?,
+                &mut balances,
+                index_in,
+                index_out,
+                amount_in,
+            )
+            .ok(),
+        }
+    }
+
+    /// Computes the scaled input amount (before fees) for the specified scaled
+    /// output amount with the pool math.
+    fn in_given_exact_out(
+        &self,
+        index_in: usize,
+        index_out: usize,
+        amount_out: Bfp,
+    ) -> Option<Bfp> {
+        let mut balances = self.live_balances()?;
+        match &self.kind {
+            PoolKind::Weighted(weights) => weighted_math::calc_in_given_out_v3(
+                balances[index_in],
+                *weights.get(index_in)?,
+                balances[index_out],
+                *weights.get(index_out)?,
+                amount_out,
+            )
+            .ok(),
+            PoolKind::Stable(amplification_parameter) => stable_math::calc_in_given_out(
+                amplification_parameter.with_base(*stable_math::AMP_PRECISION)?,
+                &mut balances,
+                index_in,
+                index_out,
+                amount_out,
+            )
+            .ok(),
+        }
+    }
+
+    fn get_amount_out_inner(
+        &self,
+        out_token: H160,
+        in_amount: U256,
+        in_token: H160,
+    ) -> Option<U256> {
+        let (index_in, index_out) = (self.token_index(in_token)?, self.token_index(out_token)?);
+        if index_in == index_out {
+            return None;
+        }
+
+        let amount_in = self.tokens[index_in].to_scaled_18_round_down(in_amount)?;
+        let amount_in = ensure_valid_trade_amount(amount_in)?;
+        let fee = amount_in.mul_up(self.swap_fee).ok()?;
+        let amount_out = self.out_given_exact_in(index_in, index_out, amount_in.sub(fee).ok()?)?;
+        let amount_out = ensure_valid_trade_amount(amount_out)?;
+        self.tokens[index_out].to_raw_round_down(amount_out)
+    }
+}
+
+impl BaselineSolvable for Pool {
+    async fn get_amount_out(
+        &self,
+        out_token: H160,
+        (in_amount, in_token): (U256, H160),
+    ) -> Option<U256> {
+        self.get_amount_out_inner(out_token, in_amount, in_token)
+    }
+
+    async fn get_amount_in(
+        &self,
+        in_token: H160,
+        (out_amount, out_token): (U256, H160),
+    ) -> Option<U256> {
+        let (index_in, index_out) = (self.token_index(in_token)?, self.token_index(out_token)?);
+        if index_in == index_out {
+            return None;
+        }
+
+        let amount_out = self.tokens[index_out].to_scaled_18_round_up(out_amount)?;
+        let amount_out = ensure_valid_trade_amount(amount_out)?;
+        let amount_in = self.in_given_exact_out(index_in, index_out, amount_out)?;
+        // For exact output swaps, the fee is charged on top of the computed
+        // input amount, such that it amounts to the fee percentage of the
+        // total input amount.
+        let fee = amount_in
+            .mul_up(self.swap_fee)
+            .ok()?
+            .div_up(self.swap_fee.complement())
+            .ok()?;
+        let amount_in = ensure_valid_trade_amount(amount_in.add(fee).ok()?)?;
+        let in_amount = self.tokens[index_in].to_raw_round_up(amount_in)?;
+
+        converge_in_amount(in_amount, out_amount, |x| {
+            self.get_amount_out_inner(out_token, x, in_token)
+        })
+    }
+
+    async fn gas_cost(&self) -> usize {
+        SWAP_GAS_COST
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::sources::balancer_v2::pool_fetching::{
+            AmplificationParameter,
+            CommonPoolState,
+            TokenState as V2TokenState,
+            WeightedPool,
+            WeightedPoolVersion,
+            WeightedTokenState,
+        },
+    };
+
+    fn token(token: u8, balance: u128, scaling_factor: Bfp) -> TokenState {
+        TokenState {
+            token: H160([token; 20]),
+            balance: balance.into(),
+            scaling_factor,
+        }
+    }
+
+    fn weighted_pool(swap_fee: Bfp) -> Pool {
+        Pool {
+            address: H160([0xa; 20]),
+            tokens: vec![
+                token(1, 60_000_000_000_000_000_000, Bfp::exp10(0)),
+                token(2, 250_000_000_000, Bfp::exp10(12)),
+            ],
+            swap_fee,
+            kind: PoolKind::Weighted(vec![bfp!("0.8"), bfp!("0.2")]),
+        }
+    }
+
+    fn stable_pool(tokens: Vec<TokenState>) -> Pool {
+        Pool {
+            address: H160([0xa; 20]),
+            tokens,
+            swap_fee: bfp!("0.0004"),
+            kind: PoolKind::Stable(AmplificationParameter::try_new(200.into(), 1.into()).unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn weighted_pools_use_balancer_v2_math() {
+        // Without fees, the V3 weighted pool math is the same as the one of
+        // Balancer V2 weighted pools of version 3 and higher.
+        let pool = weighted_pool(Bfp::zero());
+        let v2_pool = WeightedPool {
+            common: CommonPoolState {
+                id: Default::default(),
+                address: pool.address,
+                swap_fee: Bfp::zero(),
+                paused: false,
+            },
+            reserves: pool
+                .tokens
+                .iter()
+                .zip([bfp!("0.8"), bfp!("0.2")])
+                .map(|(state, weight)| {
+                    (
+                        state.token,
+                        WeightedTokenState {
+                            common: V2TokenState {
+                                balance: state.balance,
+                                scaling_factor: state.scaling_factor,
+                            },
+                            weight,
+                        },
+                    )
+                })
+                .collect(),
+            version: WeightedPoolVersion::V3Plus,
+        };
+
+        let (weth, usdc) = (H160([1; 20]), H160([2; 20]));
+        for amount in [1_000_000_000_000_000_u128, 5_000_000_000_000_000_000] {
+            assert_eq!(
+                pool.get_amount_out(usdc, (amount.into(), weth)).await,
+                v2_pool.get_amount_out(usdc, (amount.into(), weth)).await,
+            );
+        }
+        for amount in [1_000_000_u128, 5_000_000_000] {
+            assert_eq!(
+                pool.get_amount_in(weth, (amount.into(), usdc)).await,
+                v2_pool.get_amount_in(weth, (amount.into(), usdc)).await,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn charges_swap_fee_on_scaled_input_amount() {
+        let (weth, usdc) = (H160([1; 20]), H160([2; 20]));
+        let pool = weighted_pool(bfp!("0.01"));
+        let pool_without_fee = weighted_pool(Bfp::zero());
+
+        assert_eq!(
+            pool.get_amount_out(usdc, (1_000_000_000_000_000_000_u128.into(), weth))
+                .await,
+            pool_without_fee
+                .get_amount_out(usdc, (990_000_000_000_000_000_u128.into(), weth))
+                .await,
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_token_rates() {
+        // A token with a rate of 2 behaves like a token with twice the balance
+        // for which every input amount is doubled.
+        let (wrapped, token_b) = (H160([1; 20]), H160([2; 20]));
+        let pool = stable_pool(vec![
+            token(1, 500_000_000_000_000_000_000, bfp!("2")),
+            token(2, 1_000_000_000_000_000_000_000, Bfp::exp10(0)),
+        ]);
+        let unwrapped_pool = stable_pool(vec![
+            token(1, 1_000_000_000_000_000_000_000, Bfp::exp10(0)),
+            token(2, 1_000_000_000_000_000_000_000, Bfp::exp10(0)),
+        ]);
+
+        assert_eq!(
+            pool.get_amount_out(token_b, (5_000_000_000_000_000_000_u128.into(), wrapped))
+                .await,
+            unwrapped_pool
+                .get_amount_out(token_b, (10_000_000_000_000_000_000_u128.into(), wrapped))
+                .await,
+        );
+    }
+
+    #[tokio::test]
+    async fn computed_input_amounts_buy_requested_amount() {
+        let (token_a, token_b) = (H160([1; 20]), H160([2; 20]));
+        let pools = [
+            weighted_pool(bfp!("0.003")),
+            stable_pool(vec![
+                token(1, 1_000_000_000_000_000_000_000, Bfp::exp10(0)),
+                token(2, 1_000_000_000_000_000_000_000, bfp!("1.05")),
+            ]),
+        ];
+        for pool in pools {
+            let out_amount = U256::from(1_000_000_000_u128);
+            let in_amount = pool
+                .get_amount_in(token_a, (out_amount, token_b))
+                .await
+                .unwrap();
+            assert!(
+                pool.get_amount_out(token_b, (in_amount, token_a))
+                    .await
+                    .unwrap()
+                    >= out_amount
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_tokens_and_tiny_amounts() {
+        let pool = weighted_pool(Bfp::zero());
+        let (weth, usdc) = (H160([1; 20]), H160([2; 20]));
+
+        assert!(
+            pool.get_amount_out(H160([3; 20]), (1_000_000_000_u128.into(), weth))
+                .await
+                .is_none()
+        );
+        assert!(
+            pool.get_amount_out(weth, (1_000_000_000_u128.into(), weth))
+                .await
+                .is_none()
+        );
+        assert!(
+            pool.get_amount_out(usdc, (999_999_u64.into(), weth))
+                .await
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Curve liquidity from StableSwap-NG and Twocrypto-NG pools.
+//!
+//! Pools are indexed from the deployment events of their factories (see
+//! `registry`) and their dynamic state is fetched on demand for the requested
+//! token pairs (see `pool_fetching`). The `swap` module emulates the pools'
+//! invariant math so they can be used for baseline routing.
+
+pub mod pool_fetching;
+mod registry;
+pub mod swap;
+
+pub use self::pool_fetching::{
+    CurveFactoryKind,
+    CurvePoolFetcher,
+    CurvePoolFetching,
+    Pool,
+    PoolKind,
+};
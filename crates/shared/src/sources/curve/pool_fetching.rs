@@ -0,0 +1,356 @@
+//! Pool fetching for Curve pools. The registry of each configured factory is
+//! queried for pools trading the requested token pairs and their dynamic state
+//! (balances, fees and invariant parameters) is fetched at the requested block.
+
+use {
+    super::registry::{Factory, PoolInfo, PoolStorage},
+    crate::{
+        event_handling::EventHandler,
+        maintenance::Maintaining,
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+    },
+    anyhow::Result,
+    contracts::{CurveStableSwapNgPool, CurveTwocryptoPool, errors::EthcontractErrorType},
+    ethcontract::{BlockId, H160, U256, errors::MethodError},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, CurrentBlockWatcher},
+    },
+    futures::future,
+    model::TokenPair,
+    std::{collections::HashSet, sync::Arc},
+    tokio::sync::Mutex,
+};
+
+/// The supported Curve factories.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CurveFactoryKind {
+    /// Factory for StableSwap-NG plain pools. Meta pools are not supported.
+    StableSwapNg,
+    /// Factory for two coin CryptoSwap-NG pools.
+    TwocryptoNg,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    /// The coins of the pool in the order the pool indexes them.
+    pub tokens: Vec<H160>,
+    /// The balances of the coins in the same order as `tokens`.
+    pub balances: Vec<U256>,
+    pub kind: PoolKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolKind {
+    StableSwap(StableSwapState),
+    CryptoSwap(CryptoSwapState),
+}
+
+/// The state of a StableSwap-NG pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StableSwapState {
+    /// The amplification coefficient multiplied by `A_PRECISION`.
+    pub amplification: U256,
+    /// The base swap fee in units of `FEE_DENOMINATOR` (1e10).
+    pub fee: U256,
+    /// Multiplier by which the fee grows for imbalanced pools in units of
+    /// `FEE_DENOMINATOR`.
+    pub offpeg_fee_multiplier: U256,
+    /// Rates by which the balances are scaled to 18 decimals (with a precision
+    /// of 1e18). These include the token decimals as well as oracle rates for
+    /// rebasing or yield bearing tokens.
+    pub rates: Vec<U256>,
+}
+
+/// The state of a two coin CryptoSwap-NG pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CryptoSwapState {
+    /// The amplification coefficient multiplied by `N_COINS**N_COINS` and
+    /// `A_MULTIPLIER`.
+    pub a: U256,
+    pub gamma: U256,
+    /// The invariant.
+    pub d: U256,
+    /// The price of the second coin in units of the first one (with a
+    /// precision of 1e18).
+    pub price_scale: U256,
+    /// Factors by which the balances are scaled to 18 decimals.
+    pub precisions: [U256; 2],
+    /// Fee charged for balanced pools in units of `FEE_DENOMINATOR` (1e10).
+    pub mid_fee: U256,
+    /// Fee charged for imbalanced pools in units of `FEE_DENOMINATOR`.
+    pub out_fee: U256,
+    /// Determines how fast the fee moves from `mid_fee` to `out_fee`.
+    pub fee_gamma: U256,
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait CurvePoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// Type alias for the event handler indexing the pools of a single factory.
+type PoolUpdater = Mutex<EventHandler<Factory, PoolStorage>>;
+
+struct Registry {
+    web3: Web3,
+    updaters: Vec<PoolUpdater>,
+}
+
+impl Registry {
+    async fn pools_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        let mut pools = HashSet::new();
+        for updater in &self.updaters {
+            pools.extend(
+                updater
+                    .lock()
+                    .await
+                    .store()
+                    .pools_for_token_pairs(token_pairs),
+            );
+        }
+        pools
+    }
+
+    async fn pool_infos(&self, addresses: &HashSet<H160>) -> Vec<(CurveFactoryKind, PoolInfo)> {
+        let mut pools = Vec::new();
+        for updater in &self.updaters {
+            let updater = updater.lock().await;
+            let store = updater.store();
+            pools.extend(
+                addresses
+                    .iter()
+                    .filter_map(|address| Some((store.kind(), store.pool(*address)?.clone()))),
+            );
+        }
+        pools
+    }
+}
+
+pub struct CurvePoolFetcher {
+    registry: Arc<Registry>,
+    cache: RecentBlockCache<H160, Pool, PoolStateFetcher>,
+}
+
+impl CurvePoolFetcher {
+    /// Creates a new pool fetcher for the specified factories. All pools the
+    /// factories deployed so far get loaded from the chain on start up, newly
+    /// deployed pools get indexed when the fetcher is maintained.
+    pub async fn new(
+        web3: Web3,
+        factories: Vec<(CurveFactoryKind, H160)>,
+        block_retriever: Arc<dyn BlockRetrieving>,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "curve".into());
+        let block = *block_stream.borrow();
+        let updaters = future::try_join_all(factories.into_iter().map(|(kind, address)| {
+            let factory = Factory {
+                kind,
+                address,
+                web3: web3.clone(),
+            };
+            let block_retriever = block_retriever.clone();
+            async move {
+                let storage = PoolStorage::initialize(factory.clone(), block.number).await?;
+                Ok::<_, anyhow::Error>(Mutex::new(EventHandler::new(
+                    block_retriever,
+                    factory,
+                    storage,
+                    Some((block.number, block.hash)),
+                )))
+            }
+        }))
+        .await?;
+
+        let registry = Arc::new(Registry {
+            web3: web3.clone(),
+            updaters,
+        });
+        let cache = RecentBlockCache::new(
+            config,
+            PoolStateFetcher(registry.clone()),
+            block_stream,
+            "curve",
+        )?;
+        Ok(Self { registry, cache })
+    }
+}
+
+#[async_trait::async_trait]
+impl CurvePoolFetching for CurvePoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses = self.registry.pools_for_token_pairs(&token_pairs).await;
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for CurvePoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        future::try_join_all(
+            self.registry
+                .updaters
+                .iter()
+                .map(|updater| updater.run_maintenance()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "CurvePoolFetcher"
+    }
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+struct PoolStateFetcher(Arc<Registry>);
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for PoolStateFetcher {
+    async fn fetch_values(&self, addresses: HashSet<H160>, at_block: Block) -> Result<Vec<Pool>> {
+        let block = BlockId::Number(at_block.into());
+        let pools = self.0.pool_infos(&addresses).await;
+        let pools = future::join_all(
+            pools
+                .into_iter()
+                .map(|(kind, info)| fetch_pool_state(&self.0.web3, kind, info, block)),
+        )
+        .await;
+        collect_pool_results(pools)
+    }
+}
+
+async fn fetch_pool_state(
+    web3: &Web3,
+    kind: CurveFactoryKind,
+    info: PoolInfo,
+    block: BlockId,
+) -> Result<Pool> {
+    match kind {
+        CurveFactoryKind::StableSwapNg => {
+            let pool = CurveStableSwapNgPool::at(web3, info.address);
+            let (balances, rates, amplification, fee, offpeg_fee_multiplier) = futures::try_join!(
+                pool.get_balances().block(block).call(),
+                pool.stored_rates().block(block).call(),
+                pool.a_precise().block(block).call(),
+                pool.fee().block(block).call(),
+                pool.offpeg_fee_multiplier().block(block).call(),
+            )?;
+            Ok(Pool {
+                address: info.address,
+                tokens: info.tokens,
+                balances,
+                kind: PoolKind::StableSwap(StableSwapState {
+                    amplification,
+                    fee,
+                    offpeg_fee_multiplier,
+                    rates,
+                }),
+            })
+        }
+        CurveFactoryKind::TwocryptoNg => {
+            let pool = CurveTwocryptoPool::at(web3, info.address);
+            let (
+                balance0,
+                balance1,
+                precisions,
+                a,
+                gamma,
+                d,
+                price_scale,
+                mid_fee,
+                out_fee,
+                fee_gamma,
+            ) = futures::try_join!(
+                pool.balances(0.into()).block(block).call(),
+                pool.balances(1.into()).block(block).call(),
+                pool.precisions().block(block).call(),
+                pool.a().block(block).call(),
+                pool.gamma().block(block).call(),
+                pool.d().block(block).call(),
+                pool.price_scale().block(block).call(),
+                pool.mid_fee().block(block).call(),
+                pool.out_fee().block(block).call(),
+                pool.fee_gamma().block(block).call(),
+            )?;
+            Ok(Pool {
+                address: info.address,
+                tokens: info.tokens,
+                balances: vec![balance0, balance1],
+                kind: PoolKind::CryptoSwap(CryptoSwapState {
+                    a,
+                    gamma,
+                    d,
+                    price_scale,
+                    precisions,
+                    mid_fee,
+                    out_fee,
+                    fee_gamma,
+                }),
+            })
+        }
+    }
+}
+
+/// Drops pools whose state can't be fetched because the contract reverted
+/// (e.g. killed pools or pools with broken rate oracles) and forwards all other
+/// errors.
+fn collect_pool_results(pools: Vec<Result<Pool>>) -> Result<Vec<Pool>> {
+    pools
+        .into_iter()
+        .filter_map(|pool| match pool {
+            Ok(pool) => Some(Ok(pool)),
+            Err(err) if is_contract_error(&err) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+fn is_contract_error(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<MethodError>()
+            .map(EthcontractErrorType::classify),
+        Some(EthcontractErrorType::Contract),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::errors::{testing_contract_error, testing_node_error},
+    };
+
+    #[test]
+    fn collecting_results_filters_contract_errors() {
+        let pool = Pool {
+            address: H160([1; 20]),
+            tokens: vec![H160([2; 20]), H160([3; 20])],
+            balances: vec![1.into(), 1.into()],
+            kind: PoolKind::StableSwap(StableSwapState {
+                amplification: 100.into(),
+                fee: 0.into(),
+                offpeg_fee_multiplier: 0.into(),
+                rates: vec![1.into(), 1.into()],
+            }),
+        };
+        let results = vec![Ok(pool.clone()), Err(testing_contract_error().into())];
+        assert_eq!(collect_pool_results(results).unwrap(), vec![pool]);
+
+        let results = vec![Err(testing_node_error().into())];
+        assert!(collect_pool_results(results).is_err());
+    }
+}
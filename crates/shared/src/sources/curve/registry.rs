@@ -0,0 +1,363 @@
+//! A pool registry for a single Curve factory. Pools are indexed from the
+//! factory's deployment events.
+//!
+//! Curve factories keep a list of all pools they deployed, so instead of
+//! decoding the (factory specific) deployment events we treat each of them as a
+//! signal that the next entry of that list was populated and look it up with
+//! `pool_list(index)`.
+
+use {
+    super::pool_fetching::CurveFactoryKind,
+    crate::event_handling::{EventRetrieving, EventStoring},
+    anyhow::{Context, Result},
+    contracts::{CurveStableSwapNgFactory, CurveTwocryptoFactory},
+    ethcontract::{
+        BlockId,
+        BlockNumber,
+        Event,
+        H160,
+        H256,
+        RawLog,
+        common::abi::Error,
+        contract::ParseLog,
+        dyns::DynAllEventsBuilder,
+        errors::ExecutionError,
+    },
+    ethrpc::{Web3, block_stream::RangeInclusive},
+    futures::future,
+    hex_literal::hex,
+    model::TokenPair,
+    std::collections::HashSet,
+};
+
+/// `PlainPoolDeployed(address[],uint256,uint256,address)`
+const PLAIN_POOL_DEPLOYED_TOPIC: [u8; 32] =
+    hex!("d1d60d4611e4091bb2e5f699eeb79136c21ac2305ad609f3de569afc3471eecc");
+/// `MetaPoolDeployed(address,address,uint256,uint256,address)`
+const META_POOL_DEPLOYED_TOPIC: [u8; 32] =
+    hex!("01f31cd2abdeb4e5e10ba500f2db0f937d9e8c735ab04681925441b4ea37eda5");
+/// `TwocryptoPoolDeployed(address,string,string,address[2],address,bytes32,
+/// uint256[2],uint256,uint256,uint256,uint256,address)`
+const TWOCRYPTO_POOL_DEPLOYED_TOPIC: [u8; 32] =
+    hex!("8152a3037e3dc54154ad0d2cadb1cf7e1d1b9e2b625faa3dfb4fe03d609102ca");
+
+/// Signals that the factory appended a new pool to its pool list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolDeployed;
+
+impl ParseLog for PoolDeployed {
+    fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+        match log.topics.first() {
+            Some(H256(PLAIN_POOL_DEPLOYED_TOPIC))
+            | Some(H256(META_POOL_DEPLOYED_TOPIC))
+            | Some(H256(TWOCRYPTO_POOL_DEPLOYED_TOPIC)) => Ok(PoolDeployed),
+            _ => Err(ExecutionError::from(Error::InvalidData)),
+        }
+    }
+}
+
+/// A Curve factory contract along with its kind.
+#[derive(Clone)]
+pub struct Factory {
+    pub kind: CurveFactoryKind,
+    pub address: H160,
+    pub web3: Web3,
+}
+
+impl Factory {
+    fn stable_swap(&self) -> CurveStableSwapNgFactory {
+        CurveStableSwapNgFactory::at(&self.web3, self.address)
+    }
+
+    fn twocrypto(&self) -> CurveTwocryptoFactory {
+        CurveTwocryptoFactory::at(&self.web3, self.address)
+    }
+
+    /// Returns the number of pools the factory deployed.
+    pub async fn pool_count(&self, block: BlockId) -> Result<usize> {
+        let count = match self.kind {
+            CurveFactoryKind::StableSwapNg => {
+                self.stable_swap().pool_count().block(block).call().await?
+            }
+            CurveFactoryKind::TwocryptoNg => {
+                self.twocrypto().pool_count().block(block).call().await?
+            }
+        };
+        usize::try_from(count).context("pool count overflow")
+    }
+
+    /// Returns the pool at the specified index of the factory's pool list.
+    /// Pools that can't be traded with the supported math (i.e. StableSwap
+    /// meta pools) are reported as `None`.
+    pub async fn pool(&self, index: usize, block: BlockId) -> Result<Option<PoolInfo>> {
+        let index = index.into();
+        match self.kind {
+            CurveFactoryKind::StableSwapNg => {
+                let factory = self.stable_swap();
+                let address = factory.pool_list(index).block(block).call().await?;
+                if factory.is_meta(address).block(block).call().await? {
+                    return Ok(None);
+                }
+                let tokens = factory.get_coins(address).block(block).call().await?;
+                Ok(Some(PoolInfo { address, tokens }))
+            }
+            CurveFactoryKind::TwocryptoNg => {
+                let factory = self.twocrypto();
+                let address = factory.pool_list(index).block(block).call().await?;
+                let tokens = factory.get_coins(address).block(block).call().await?;
+                Ok(Some(PoolInfo {
+                    address,
+                    tokens: tokens.to_vec(),
+                }))
+            }
+        }
+    }
+}
+
+impl EventRetrieving for Factory {
+    type Event = PoolDeployed;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = DynAllEventsBuilder::new(self.web3.clone(), self.address, None);
+        let topics = match self.kind {
+            CurveFactoryKind::StableSwapNg => {
+                vec![
+                    H256(PLAIN_POOL_DEPLOYED_TOPIC),
+                    H256(META_POOL_DEPLOYED_TOPIC),
+                ]
+            }
+            CurveFactoryKind::TwocryptoNg => vec![H256(TWOCRYPTO_POOL_DEPLOYED_TOPIC)],
+        };
+        events.filter = events.filter.topic0(topics.into());
+        events
+    }
+}
+
+/// The static information of a Curve pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolInfo {
+    pub address: H160,
+    /// The coins of the pool in the order the pool indexes them.
+    pub tokens: Vec<H160>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Deployment {
+    block: u64,
+    pool: Option<PoolInfo>,
+}
+
+/// In-memory storage of all pools of a factory in the order of the factory's
+/// pool list.
+pub struct PoolStorage {
+    factory: Factory,
+    deployments: Vec<Deployment>,
+}
+
+impl PoolStorage {
+    /// Creates a new storage by loading all pools the factory deployed up to
+    /// and including the specified block.
+    pub async fn initialize(factory: Factory, block: u64) -> Result<Self> {
+        let block_id = BlockId::Number(BlockNumber::Number(block.into()));
+        let count = factory.pool_count(block_id).await?;
+        let pools = future::try_join_all((0..count).map(|index| factory.pool(index, block_id)))
+            .await
+            .context("failed to load initial Curve pools")?;
+        Ok(Self {
+            factory,
+            deployments: pools
+                .into_iter()
+                .map(|pool| Deployment { block, pool })
+                .collect(),
+        })
+    }
+
+    /// Returns all pools that trade any of the specified token pairs.
+    pub fn pools_for_token_pairs(&self, token_pairs: &HashSet<TokenPair>) -> Vec<H160> {
+        self.deployments
+            .iter()
+            .filter_map(|deployment| deployment.pool.as_ref())
+            .filter(|pool| {
+                token_pairs.iter().any(|pair| {
+                    let (a, b) = pair.get();
+                    pool.tokens.contains(&a) && pool.tokens.contains(&b)
+                })
+            })
+            .map(|pool| pool.address)
+            .collect()
+    }
+
+    /// Returns the static information of the pool at the specified address.
+    pub fn pool(&self, address: H160) -> Option<&PoolInfo> {
+        self.deployments
+            .iter()
+            .filter_map(|deployment| deployment.pool.as_ref())
+            .find(|pool| pool.address == address)
+    }
+
+    pub fn kind(&self) -> CurveFactoryKind {
+        self.factory.kind
+    }
+
+    fn remove_pools_newer_than_block(&mut self, block: u64) {
+        self.deployments
+            .retain(|deployment| deployment.block < block);
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<PoolDeployed> for PoolStorage {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<PoolDeployed>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.remove_pools_newer_than_block(*range.start());
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<PoolDeployed>>) -> Result<()> {
+        for event in events {
+            let block = event.meta.context("event missing metadata")?.block_number;
+            let block_id = BlockId::Number(BlockNumber::Number(block.into()));
+            let pool = self
+                .factory
+                .pool(self.deployments.len(), block_id)
+                .await
+                .context("failed to load deployed Curve pool")?;
+            self.deployments.push(Deployment { block, pool });
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        Ok(self
+            .deployments
+            .last()
+            .map(|deployment| deployment.block)
+            .unwrap_or_default())
+    }
+
+    async fn persist_last_indexed_block(&mut self, _block: u64) -> Result<()> {
+        // storage is only in-memory so we don't need to persist anything here
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ethrpc::create_env_test_transport};
+
+    fn pool(address: u8, tokens: &[u8]) -> PoolInfo {
+        PoolInfo {
+            address: H160([address; 20]),
+            tokens: tokens.iter().map(|token| H160([*token; 20])).collect(),
+        }
+    }
+
+    fn storage(deployments: Vec<Deployment>) -> PoolStorage {
+        PoolStorage {
+            factory: Factory {
+                kind: CurveFactoryKind::StableSwapNg,
+                address: H160::zero(),
+                web3: ethrpc::dummy::web3(),
+            },
+            deployments,
+        }
+    }
+
+    #[test]
+    fn parses_deployment_events() {
+        for topic in [
+            PLAIN_POOL_DEPLOYED_TOPIC,
+            META_POOL_DEPLOYED_TOPIC,
+            TWOCRYPTO_POOL_DEPLOYED_TOPIC,
+        ] {
+            let log = RawLog {
+                topics: vec![H256(topic)],
+                data: vec![],
+            };
+            assert_eq!(PoolDeployed::parse_log(log).unwrap(), PoolDeployed);
+        }
+        let log = RawLog {
+            topics: vec![H256::zero()],
+            data: vec![],
+        };
+        assert!(PoolDeployed::parse_log(log).is_err());
+    }
+
+    #[test]
+    fn finds_pools_for_token_pairs() {
+        let storage = storage(vec![
+            Deployment {
+                block: 1,
+                pool: Some(pool(1, &[1, 2, 3])),
+            },
+            Deployment {
+                block: 2,
+                pool: None,
+            },
+            Deployment {
+                block: 3,
+                pool: Some(pool(2, &[2, 3])),
+            },
+        ]);
+
+        let pair = |a: u8, b: u8| TokenPair::new(H160([a; 20]), H160([b; 20])).unwrap();
+        assert_eq!(
+            storage.pools_for_token_pairs(&[pair(1, 3)].into()),
+            vec![H160([1; 20])]
+        );
+        assert_eq!(
+            storage.pools_for_token_pairs(&[pair(2, 3)].into()),
+            vec![H160([1; 20]), H160([2; 20])]
+        );
+        assert!(
+            storage
+                .pools_for_token_pairs(&[pair(1, 4)].into())
+                .is_empty()
+        );
+        assert_eq!(storage.pool(H160([2; 20])), Some(&pool(2, &[2, 3])));
+    }
+
+    #[tokio::test]
+    async fn replacing_events_removes_newer_pools() {
+        let mut storage = storage(vec![
+            Deployment {
+                block: 1,
+                pool: Some(pool(1, &[1, 2])),
+            },
+            Deployment {
+                block: 2,
+                pool: Some(pool(2, &[1, 2])),
+            },
+            Deployment {
+                block: 3,
+                pool: Some(pool(3, &[1, 2])),
+            },
+        ]);
+
+        storage
+            .replace_events(vec![], RangeInclusive::try_new(2, 3).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(storage.last_event_block().await.unwrap(), 1);
+        assert_eq!(storage.deployments.len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn mainnet_stable_swap_pools() {
+        let web3 = Web3::new(create_env_test_transport());
+        let factory = Factory {
+            kind: CurveFactoryKind::StableSwapNg,
+            address: CurveStableSwapNgFactory::deployed(&web3)
+                .await
+                .unwrap()
+                .address(),
+            web3,
+        };
+        let storage = PoolStorage::initialize(factory, 20_000_000).await.unwrap();
+        println!("{:#?}", storage.deployments);
+    }
+}
@@ -0,0 +1,171 @@
+use {
+    crate::{
+        baseline_solver::BaselineSolvable,
+        sources::{
+            balancer_v2::swap::converge_in_amount,
+            curve::pool_fetching::{Pool, PoolKind},
+        },
+    },
+    ethcontract::{H160, U256},
+};
+
+mod crypto_math;
+mod stable_math;
+
+// Rough estimates of the cost of `exchange` calls on StableSwap-NG and
+// Twocrypto-NG pools.
+pub const STABLE_SWAP_GAS_COST: usize = 130_000;
+pub const CRYPTO_SWAP_GAS_COST: usize = 200_000;
+
+impl Pool {
+    /// Returns the index of the token in the pool's coins.
+    pub fn index_of(&self, token: H160) -> Option<usize> {
+        self.tokens.iter().position(|t| *t == token)
+    }
+
+    fn get_amount_out_inner(
+        &self,
+        out_token: H160,
+        in_amount: U256,
+        in_token: H160,
+    ) -> Option<U256> {
+        let i = self.index_of(in_token)?;
+        let j = self.index_of(out_token)?;
+        match &self.kind {
+            PoolKind::StableSwap(state) => {
+                stable_math::get_dy(state, &self.balances, i, j, in_amount)
+            }
+            PoolKind::CryptoSwap(state) => {
+                crypto_math::get_dy(state, &self.balances, i, j, in_amount)
+            }
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    async fn get_amount_out(
+        &self,
+        out_token: H160,
+        (in_amount, in_token): (U256, H160),
+    ) -> Option<U256> {
+        self.get_amount_out_inner(out_token, in_amount, in_token)
+    }
+
+    async fn get_amount_in(
+        &self,
+        in_token: H160,
+        (out_amount, out_token): (U256, H160),
+    ) -> Option<U256> {
+        let i = self.index_of(in_token)?;
+        let j = self.index_of(out_token)?;
+        let in_amount = match &self.kind {
+            PoolKind::StableSwap(state) => {
+                stable_math::get_dx(state, &self.balances, i, j, out_amount)
+            }
+            PoolKind::CryptoSwap(state) => {
+                crypto_math::get_dx(state, &self.balances, i, j, out_amount)
+            }
+        }?;
+        // The input amounts are computed with the fee of the current pool
+        // state, which might be lower than the fee that is actually charged.
+        converge_in_amount(in_amount, out_amount, |x| {
+            self.get_amount_out_inner(out_token, x, in_token)
+        })
+    }
+
+    async fn gas_cost(&self) -> usize {
+        match self.kind {
+            PoolKind::StableSwap(_) => STABLE_SWAP_GAS_COST,
+            PoolKind::CryptoSwap(_) => CRYPTO_SWAP_GAS_COST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::sources::curve::pool_fetching::{CryptoSwapState, StableSwapState},
+    };
+
+    fn token(byte: u8) -> H160 {
+        H160([byte; 20])
+    }
+
+    #[tokio::test]
+    async fn stable_swap_amounts() {
+        // 1M USDC and 1M DAI.
+        let pool = Pool {
+            address: H160([0xff; 20]),
+            tokens: vec![token(1), token(2)],
+            balances: vec![U256::exp10(12), U256::exp10(24)],
+            kind: PoolKind::StableSwap(StableSwapState {
+                amplification: U256::from(150_000),
+                fee: U256::from(1_000_000),
+                offpeg_fee_multiplier: U256::from(20_000_000_000_u64),
+                rates: vec![U256::exp10(30), U256::exp10(18)],
+            }),
+        };
+
+        let out_amount = pool
+            .get_amount_out(token(2), (U256::exp10(9), token(1)))
+            .await
+            .unwrap();
+        assert_eq!(
+            out_amount,
+            U256::from_dec_str("999899333843882470565").unwrap()
+        );
+        let in_amount = pool
+            .get_amount_in(token(1), (out_amount, token(2)))
+            .await
+            .unwrap();
+        assert_eq!(in_amount, U256::exp10(9));
+
+        assert_eq!(
+            pool.get_amount_out(token(3), (U256::exp10(9), token(1)))
+                .await,
+            None
+        );
+        assert_eq!(pool.gas_cost().await, STABLE_SWAP_GAS_COST);
+    }
+
+    #[tokio::test]
+    async fn crypto_swap_amounts() {
+        // 1M USDC and 500 WETH at a price of 2000 USDC per WETH.
+        let pool = Pool {
+            address: H160([0xff; 20]),
+            tokens: vec![token(1), token(2)],
+            balances: vec![U256::exp10(12), U256::from(500) * U256::exp10(18)],
+            kind: PoolKind::CryptoSwap(CryptoSwapState {
+                a: U256::from(400_000),
+                gamma: U256::from(145_000_000_000_000_u64),
+                d: U256::from(2) * U256::exp10(24),
+                price_scale: U256::from(2000) * U256::exp10(18),
+                precisions: [U256::exp10(12), U256::one()],
+                mid_fee: U256::from(26_000_000),
+                out_fee: U256::from(45_000_000),
+                fee_gamma: U256::from(230_000_000_000_000_u64),
+            }),
+        };
+
+        let out_amount = pool
+            .get_amount_out(token(2), (U256::from(2000) * U256::exp10(6), token(1)))
+            .await
+            .unwrap();
+        assert_eq!(out_amount, U256::from(997_267_755_843_717_152_u64));
+
+        // Computing the input amount underestimates the fee, so it gets bumped
+        // until the requested output amount is reached.
+        let in_amount = pool
+            .get_amount_in(token(1), (out_amount, token(2)))
+            .await
+            .unwrap();
+        assert!(in_amount > U256::from(1_999_934_872));
+        let bumped_out_amount = pool
+            .get_amount_out(token(2), (in_amount, token(1)))
+            .await
+            .unwrap();
+        assert!(bumped_out_amount >= out_amount);
+        assert_eq!(pool.gas_cost().await, CRYPTO_SWAP_GAS_COST);
+    }
+}
@@ -0,0 +1,278 @@
+//! Module emulating the StableSwap invariant math of the Curve StableSwap-NG
+//! pools. The original contract code can be found at:
+//! https://github.com/curvefi/stableswap-ng/blob/main/contracts/main/CurveStableSwapNG.vy
+
+use {crate::sources::curve::pool_fetching::StableSwapState, ethcontract::U256};
+
+pub const A_PRECISION: u64 = 100;
+pub const FEE_DENOMINATOR: u64 = 10_000_000_000;
+const PRECISION: u64 = 1_000_000_000_000_000_000;
+const MAX_ITERATIONS: usize = 255;
+
+/// Computes the invariant `D` for the given normalized balances.
+pub fn get_d(xp: &[U256], amp: U256) -> Option<U256> {
+    let n_coins = U256::from(xp.len());
+    let s = xp
+        .iter()
+        .try_fold(U256::zero(), |sum, x| sum.checked_add(*x))?;
+    if s.is_zero() {
+        return Some(U256::zero());
+    }
+
+    let a_precision = U256::from(A_PRECISION);
+    let n_pow_n = U256::from(xp.len().pow(u32::try_from(xp.len()).ok()?));
+    let ann = amp.checked_mul(n_coins)?;
+    let mut d = s;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = d_p.checked_mul(d)?.checked_div(*x)?;
+        }
+        d_p /= n_pow_n;
+        let d_prev = d;
+
+        // (Ann * S / A_PRECISION + D_P * N_COINS) * D
+        let numerator = (ann.checked_mul(s)? / a_precision)
+            .checked_add(d_p.checked_mul(n_coins)?)?
+            .checked_mul(d)?;
+        // (Ann - A_PRECISION) * D / A_PRECISION + (N_COINS + 1) * D_P
+        let denominator = (ann.checked_sub(a_precision)?.checked_mul(d)? / a_precision)
+            .checked_add((n_coins + 1).checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if abs_diff(d, d_prev) <= U256::one() {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Computes the new balance of coin `j` after the balance of coin `i` changed
+/// to `x` while keeping the invariant `d` constant.
+pub fn get_y(i: usize, j: usize, x: U256, xp: &[U256], amp: U256, d: U256) -> Option<U256> {
+    if i == j || i >= xp.len() || j >= xp.len() {
+        return None;
+    }
+
+    let n_coins = U256::from(xp.len());
+    let ann = amp.checked_mul(n_coins)?;
+    let mut c = d;
+    let mut s = U256::zero();
+    for (k, xp_k) in xp.iter().enumerate() {
+        let x_k = if k == i {
+            x
+        } else if k != j {
+            *xp_k
+        } else {
+            continue;
+        };
+        s = s.checked_add(x_k)?;
+        c = c.checked_mul(d)?.checked_div(x_k.checked_mul(n_coins)?)?;
+    }
+    c = c
+        .checked_mul(d)?
+        .checked_mul(A_PRECISION.into())?
+        .checked_div(ann.checked_mul(n_coins)?)?;
+    let b = s.checked_add(d.checked_mul(A_PRECISION.into())?.checked_div(ann)?)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        // (y * y + c) / (2 * y + b - D)
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2.into())?.checked_add(b)?.checked_sub(d)?)?;
+        if abs_diff(y, y_prev) <= U256::one() {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// Computes the fee charged for a swap between two coins with the given
+/// (averaged) normalized balances. Pools with an off-peg fee multiplier charge
+/// higher fees the more imbalanced they are.
+pub fn dynamic_fee(xpi: U256, xpj: U256, fee: U256, fee_multiplier: U256) -> Option<U256> {
+    let fee_denominator = U256::from(FEE_DENOMINATOR);
+    if fee_multiplier <= fee_denominator {
+        return Some(fee);
+    }
+
+    let xps2 = xpi.checked_add(xpj)?.checked_pow(2.into())?;
+    let imbalance = (fee_multiplier - fee_denominator)
+        .checked_mul(4.into())?
+        .checked_mul(xpi)?
+        .checked_mul(xpj)?
+        .checked_div(xps2)?;
+    fee_multiplier
+        .checked_mul(fee)?
+        .checked_div(imbalance.checked_add(fee_denominator)?)
+}
+
+/// Normalizes the pool balances to 18 decimals using the stored rates.
+fn xp(state: &StableSwapState, balances: &[U256]) -> Option<Vec<U256>> {
+    if state.rates.len() != balances.len() {
+        return None;
+    }
+    state
+        .rates
+        .iter()
+        .zip(balances)
+        .map(|(rate, balance)| Some(rate.checked_mul(*balance)? / PRECISION))
+        .collect()
+}
+
+/// Computes the amount of coin `j` received for selling `dx` of coin `i`.
+///
+/// https://github.com/curvefi/stableswap-ng/blob/main/contracts/main/CurveStableSwapNGViews.vy
+pub fn get_dy(
+    state: &StableSwapState,
+    balances: &[U256],
+    i: usize,
+    j: usize,
+    dx: U256,
+) -> Option<U256> {
+    let xp = xp(state, balances)?;
+    let rate_i = *state.rates.get(i)?;
+    let rate_j = *state.rates.get(j)?;
+    let x = xp[i].checked_add(dx.checked_mul(rate_i)? / PRECISION)?;
+    let d = get_d(&xp, state.amplification)?;
+    let y = get_y(i, j, x, &xp, state.amplification, d)?;
+    let dy = xp[j].checked_sub(y)?.checked_sub(1.into())?;
+
+    let fee = dynamic_fee(
+        xp[i].checked_add(x)? / 2,
+        xp[j].checked_add(y)? / 2,
+        state.fee,
+        state.offpeg_fee_multiplier,
+    )?
+    .checked_mul(dy)?
+        / FEE_DENOMINATOR;
+    dy.checked_sub(fee)?
+        .checked_mul(PRECISION.into())?
+        .checked_div(rate_j)
+}
+
+/// Computes the amount of coin `i` that needs to be sold in order to receive
+/// `dy` of coin `j`. Note that the fee is approximated based on the current
+/// balances, so the result might not be exact.
+///
+/// https://github.com/curvefi/stableswap-ng/blob/main/contracts/main/CurveStableSwapNGViews.vy
+pub fn get_dx(
+    state: &StableSwapState,
+    balances: &[U256],
+    i: usize,
+    j: usize,
+    dy: U256,
+) -> Option<U256> {
+    let xp = xp(state, balances)?;
+    let rate_i = *state.rates.get(i)?;
+    let rate_j = *state.rates.get(j)?;
+    let d = get_d(&xp, state.amplification)?;
+
+    let fee_denominator = U256::from(FEE_DENOMINATOR);
+    let fee = dynamic_fee(xp[i], xp[j], state.fee, state.offpeg_fee_multiplier)?;
+    let dy_with_fee = (dy.checked_mul(rate_j)? / PRECISION)
+        .checked_add(1.into())?
+        .checked_mul(fee_denominator)?
+        .checked_div(fee_denominator.checked_sub(fee)?)?;
+    let y = xp[j].checked_sub(dy_with_fee)?;
+    let x = get_y(j, i, y, &xp, state.amplification, d)?;
+    x.checked_sub(xp[i])?
+        .checked_mul(PRECISION.into())?
+        .checked_div(rate_i)
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b { a - b } else { b - a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(rates: Vec<U256>) -> StableSwapState {
+        StableSwapState {
+            // A = 1500
+            amplification: U256::from(150_000),
+            // 0.01%
+            fee: U256::from(1_000_000),
+            // 2x
+            offpeg_fee_multiplier: U256::from(20_000_000_000_u64),
+            rates,
+        }
+    }
+
+    #[test]
+    fn balanced_invariant_is_sum_of_balances() {
+        let xp = vec![U256::exp10(24), U256::exp10(24)];
+        assert_eq!(
+            get_d(&xp, U256::from(150_000)).unwrap(),
+            U256::from(2) * U256::exp10(24)
+        );
+        assert_eq!(
+            get_d(&[U256::zero(), U256::zero()], 100.into()),
+            Some(0.into())
+        );
+    }
+
+    #[test]
+    fn get_y_keeps_invariant() {
+        let amp = U256::from(150_000);
+        let xp = vec![U256::exp10(24), U256::from(2) * U256::exp10(24)];
+        let d = get_d(&xp, amp).unwrap();
+
+        let x = xp[0] + U256::exp10(21);
+        let y = get_y(0, 1, x, &xp, amp, d).unwrap();
+        assert!(y < xp[1]);
+        let new_d = get_d(&[x, y], amp).unwrap();
+        assert!(abs_diff(d, new_d) <= 2.into());
+    }
+
+    #[test]
+    fn swaps_close_to_peg_for_balanced_pools() {
+        // USDC (6 decimals) and DAI (18 decimals) pool with 1M of each.
+        let state = state(vec![U256::exp10(30), U256::exp10(18)]);
+        let balances = [U256::exp10(12), U256::exp10(24)];
+
+        let dy = get_dy(&state, &balances, 0, 1, U256::exp10(9)).unwrap();
+        // Selling 1000 USDC yields slightly less than 1000 DAI because of the
+        // fee and slippage.
+        assert!(dy < U256::exp10(21));
+        assert!(dy > U256::exp10(21) * 9_998 / 10_000);
+
+        let dx = get_dx(&state, &balances, 0, 1, dy).unwrap();
+        assert!(abs_diff(dx, U256::exp10(9)) <= 1.into());
+    }
+
+    #[test]
+    fn dynamic_fee_increases_when_off_peg() {
+        let fee = U256::from(1_000_000);
+        let multiplier = U256::from(20_000_000_000_u64);
+        let balanced = dynamic_fee(U256::exp10(24), U256::exp10(24), fee, multiplier).unwrap();
+        let imbalanced = dynamic_fee(U256::exp10(24), U256::exp10(23), fee, multiplier).unwrap();
+        assert_eq!(balanced, fee);
+        assert!(imbalanced > fee);
+        assert!(imbalanced <= fee * 2);
+
+        // No multiplier means a constant fee.
+        assert_eq!(
+            dynamic_fee(
+                U256::exp10(24),
+                U256::exp10(23),
+                fee,
+                FEE_DENOMINATOR.into()
+            )
+            .unwrap(),
+            fee
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_indices() {
+        let state = state(vec![U256::exp10(18), U256::exp10(18)]);
+        let balances = [U256::exp10(24), U256::exp10(24)];
+        assert_eq!(get_dy(&state, &balances, 0, 0, U256::exp10(18)), None);
+        assert_eq!(get_dy(&state, &balances, 0, 2, U256::exp10(18)), None);
+    }
+}
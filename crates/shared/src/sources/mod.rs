@@ -1,10 +1,13 @@
 //! Top-level module organizing all baseline liquidity sources.
 
 pub mod balancer_v2;
+pub mod balancer_v3;
+pub mod curve;
 pub mod swapr;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
 pub mod uniswap_v3_pair_provider;
+pub mod uniswap_v4;
 
 use {
     self::uniswap_v2::pool_fetching::{Pool, PoolFetching},
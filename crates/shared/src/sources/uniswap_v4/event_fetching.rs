@@ -0,0 +1,123 @@
+use {
+    crate::event_handling::EventRetrieving,
+    contracts::{
+        UniswapV4PoolManager,
+        uniswap_v4_pool_manager::event_data::{Initialize, ModifyLiquidity, Swap},
+    },
+    ethcontract::{
+        H160,
+        H256,
+        RawLog,
+        common::abi::Error,
+        contract::ParseLog,
+        dyns::DynAllEventsBuilder,
+        errors::ExecutionError,
+    },
+    ethrpc::Web3,
+    hex_literal::hex,
+};
+
+/// `Initialize(bytes32,address,address,uint24,int24,address,uint160,int24)`
+const INITIALIZE_TOPIC: [u8; 32] =
+    hex!("dd466e674ea557f56295e2d0218a125ea4b4f0f6f3307b95f85e6110838d6438");
+/// `ModifyLiquidity(bytes32,address,int24,int24,int256,bytes32)`
+const MODIFY_LIQUIDITY_TOPIC: [u8; 32] =
+    hex!("f208f4912782fd25c7f114ca3723a2d5dd6f3bcc3ac8db5af63baa85f711d5ec");
+/// `Swap(bytes32,address,int128,int128,uint160,uint128,int24,uint24)`
+const SWAP_TOPIC: [u8; 32] =
+    hex!("40e9cecb9f5f1f1c5b9c97dec2917b7ee92e57ba5563708daca94dd84ad7112f");
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UniswapV4Event {
+    Initialize(Initialize),
+    ModifyLiquidity(ModifyLiquidity),
+    Swap(Swap),
+}
+
+impl ParseLog for UniswapV4Event {
+    fn parse_log(log: RawLog) -> Result<Self, ExecutionError> {
+        let decode = |name| {
+            UniswapV4PoolManager::raw_contract()
+                .interface
+                .abi
+                .event(name)
+                .expect("generated event decode")
+        };
+        match log.topics.first() {
+            Some(H256(INITIALIZE_TOPIC)) => Ok(UniswapV4Event::Initialize(
+                log.decode(decode("Initialize"))?,
+            )),
+            Some(H256(MODIFY_LIQUIDITY_TOPIC)) => Ok(UniswapV4Event::ModifyLiquidity(
+                log.decode(decode("ModifyLiquidity"))?,
+            )),
+            Some(H256(SWAP_TOPIC)) => Ok(UniswapV4Event::Swap(log.decode(decode("Swap"))?)),
+            _ => Err(ExecutionError::from(Error::InvalidData)),
+        }
+    }
+}
+
+/// Retrieves the pool events emitted by the Uniswap V4 `PoolManager`.
+pub struct PoolManagerEventFetcher {
+    pub web3: Web3,
+    pub address: H160,
+}
+
+impl EventRetrieving for PoolManagerEventFetcher {
+    type Event = UniswapV4Event;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = DynAllEventsBuilder::new(self.web3.clone(), self.address, None);
+        let topics = vec![
+            H256(INITIALIZE_TOPIC),
+            H256(MODIFY_LIQUIDITY_TOPIC),
+            H256(SWAP_TOPIC),
+        ];
+        events.filter = events.filter.topic0(topics.into());
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethcontract::{U256, common::abi::Token},
+    };
+
+    #[test]
+    fn parses_swap_events() {
+        let id = H256([1; 32]);
+        let log = RawLog {
+            topics: vec![H256(SWAP_TOPIC), id, H256::from(H160([2; 20]))],
+            data: ethcontract::common::abi::encode(&[
+                Token::Int(100.into()),
+                // -99 in two's complement
+                Token::Int(U256::MAX - 98),
+                Token::Uint(1_000.into()),
+                Token::Uint(2_000.into()),
+                // -5 in two's complement
+                Token::Int(U256::MAX - 4),
+                Token::Uint(3_000.into()),
+            ]),
+        };
+
+        let UniswapV4Event::Swap(swap) = UniswapV4Event::parse_log(log).unwrap() else {
+            panic!("unexpected event");
+        };
+        assert_eq!(swap.id.0, id.0);
+        assert_eq!((swap.amount0, swap.amount1), (100, -99));
+        assert_eq!(swap.sqrt_price_x96, 1_000.into());
+        assert_eq!(swap.liquidity, 2_000);
+        assert_eq!(swap.tick, -5);
+        assert_eq!(swap.fee, 3_000);
+    }
+
+    #[test]
+    fn rejects_unknown_events() {
+        let log = RawLog {
+            topics: vec![H256::zero()],
+            data: vec![],
+        };
+        assert!(UniswapV4Event::parse_log(log).is_err());
+    }
+}
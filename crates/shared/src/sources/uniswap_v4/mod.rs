@@ -0,0 +1,18 @@
+//! Uniswap V4 liquidity from the singleton `PoolManager` contract.
+//!
+//! All V4 pools live in the `PoolManager`, so their state is indexed from its
+//! `Initialize`, `ModifyLiquidity` and `Swap` events (see `event_fetching`)
+//! and kept in memory (see `pool_fetching`). Only pools without hooks and with
+//! a static fee are exposed, since the behaviour of all other pools can't be
+//! derived from the indexed events alone.
+
+pub mod event_fetching;
+pub mod pool_fetching;
+
+pub use self::pool_fetching::{
+    Pool,
+    PoolKey,
+    PoolState,
+    UniswapV4PoolFetcher,
+    UniswapV4PoolFetching,
+};
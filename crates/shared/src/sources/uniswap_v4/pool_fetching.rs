@@ -0,0 +1,484 @@
+//! In-memory index of the Uniswap V4 pools. The state of every pool is derived
+//! from the `PoolManager` events: events older than `MAX_REORG_BLOCK_COUNT`
+//! blocks are folded into a checkpoint while more recent ones are kept around
+//! so they can be reverted on reorgs and to serve pool states for specific
+//! blocks.
+
+use {
+    super::event_fetching::{PoolManagerEventFetcher, UniswapV4Event},
+    crate::{
+        event_handling::{EventHandler, EventStoring, MAX_REORG_BLOCK_COUNT},
+        maintenance::Maintaining,
+        recent_block_cache::Block,
+    },
+    anyhow::{Context, Result},
+    ethcontract::{Event, H160, H256, U256},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, RangeInclusive},
+    },
+    model::TokenPair,
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        sync::Arc,
+    },
+    tokio::sync::Mutex,
+};
+
+/// Fee value marking pools whose fee is set by their hooks.
+const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
+/// A Uniswap V4 pool along with its current state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pool {
+    /// The pool ID, i.e. the hash of the ABI encoded `key`.
+    pub id: H256,
+    pub key: PoolKey,
+    pub state: PoolState,
+}
+
+/// The static parameters identifying a Uniswap V4 pool.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolKey {
+    /// The lower currency of the pool. The zero address denotes native ETH.
+    pub currency0: H160,
+    pub currency1: H160,
+    /// The swap fee in pips (i.e. units of 1e-6).
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: H160,
+}
+
+impl PoolKey {
+    /// Returns whether the pool can be traded based on the indexed state
+    /// alone. This excludes pools with hooks (which can run arbitrary logic on
+    /// every swap), pools with dynamic fees and pools trading native ETH
+    /// (which the settlement contract can't send without unwrapping first).
+    pub fn is_supported(&self) -> bool {
+        self.hooks.is_zero()
+            && self.fee & DYNAMIC_FEE_FLAG == 0
+            && !self.currency0.is_zero()
+            && !self.currency1.is_zero()
+    }
+
+    pub fn token_pair(&self) -> Option<TokenPair> {
+        TokenPair::new(self.currency0, self.currency1)
+    }
+}
+
+/// The concentrated liquidity state of a Uniswap V4 pool.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolState {
+    pub sqrt_price: U256,
+    pub liquidity: u128,
+    pub tick: i32,
+    /// The net liquidity change when crossing each initialized tick.
+    pub liquidity_net: BTreeMap<i32, i128>,
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait UniswapV4PoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// Applies a single `PoolManager` event to the pools. Events of pools that are
+/// not part of `pools` are ignored.
+fn apply_event(pools: &mut HashMap<H256, Pool>, event: &UniswapV4Event) -> Result<()> {
+    match event {
+        UniswapV4Event::Initialize(initialize) => {
+            let id = H256(initialize.id.0);
+            pools.insert(
+                id,
+                Pool {
+                    id,
+                    key: PoolKey {
+                        currency0: initialize.currency0,
+                        currency1: initialize.currency1,
+                        fee: initialize.fee,
+                        tick_spacing: initialize.tick_spacing,
+                        hooks: initialize.hooks,
+                    },
+                    state: PoolState {
+                        sqrt_price: initialize.sqrt_price_x96,
+                        liquidity: 0,
+                        tick: initialize.tick,
+                        liquidity_net: Default::default(),
+                    },
+                },
+            );
+        }
+        UniswapV4Event::ModifyLiquidity(modify) => {
+            let Some(pool) = pools
+                .get_mut(&H256(modify.id.0))
+                .map(|pool| &mut pool.state)
+            else {
+                return Ok(());
+            };
+            let delta =
+                i128::try_from(modify.liquidity_delta).context("liquidity delta overflow")?;
+
+            // liquidity tracks the liquidity on recent tick,
+            // only need to update it if the position includes the recent tick.
+            if modify.tick_lower <= pool.tick && pool.tick < modify.tick_upper {
+                pool.liquidity = pool
+                    .liquidity
+                    .checked_add_signed(delta)
+                    .context("liquidity overflow")?;
+            }
+
+            for (tick, delta) in [(modify.tick_lower, delta), (modify.tick_upper, -delta)] {
+                let net = pool.liquidity_net.entry(tick).or_default();
+                *net = net.checked_add(delta).context("liquidity net overflow")?;
+                // remove 0 entries to save bandwidth
+                if *net == 0 {
+                    pool.liquidity_net.remove(&tick);
+                }
+            }
+        }
+        UniswapV4Event::Swap(swap) => {
+            if let Some(pool) = pools.get_mut(&H256(swap.id.0)).map(|pool| &mut pool.state) {
+                pool.sqrt_price = swap.sqrt_price_x96;
+                pool.liquidity = swap.liquidity;
+                pool.tick = swap.tick;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// In-memory storage of the state of all Uniswap V4 pools.
+pub struct PoolStorage {
+    /// The pool states including all events up to `checkpoint_block`.
+    checkpoint: HashMap<H256, Pool>,
+    checkpoint_block: u64,
+    /// Events that are not part of the checkpoint yet, keyed by block number
+    /// and log index.
+    recent_events: BTreeMap<(u64, usize), Event<UniswapV4Event>>,
+}
+
+impl PoolStorage {
+    /// Creates an empty storage which starts indexing at the specified block.
+    pub fn new(start_block: u64) -> Self {
+        Self {
+            checkpoint: Default::default(),
+            checkpoint_block: start_block,
+            recent_events: Default::default(),
+        }
+    }
+
+    /// Returns all supported pools with liquidity trading any of the
+    /// specified token pairs at the specified block. `None` returns the pools
+    /// at the most recently indexed block.
+    pub fn pools(&self, token_pairs: &HashSet<TokenPair>, block: Option<u64>) -> Result<Vec<Pool>> {
+        let is_relevant = |pool: &Pool| {
+            pool.key.is_supported()
+                && pool
+                    .key
+                    .token_pair()
+                    .is_some_and(|pair| token_pairs.contains(&pair))
+        };
+
+        let mut pools: HashMap<_, _> = self
+            .checkpoint
+            .values()
+            .filter(|pool| is_relevant(pool))
+            .map(|pool| (pool.id, pool.clone()))
+            .collect();
+        for event in self
+            .recent_events
+            .range(..=(block.unwrap_or(u64::MAX), usize::MAX))
+            .map(|(_, event)| &event.data)
+        {
+            apply_event(&mut pools, event)?;
+        }
+
+        Ok(pools
+            .into_values()
+            .filter(|pool| is_relevant(pool) && pool.state.liquidity > 0)
+            .collect())
+    }
+
+    /// Folds all events older than `MAX_REORG_BLOCK_COUNT` blocks before the
+    /// most recent event into the checkpoint.
+    fn update_checkpoint(&mut self) -> Result<()> {
+        let Some(&(last_block, _)) = self.recent_events.keys().last() else {
+            return Ok(());
+        };
+        let recent = self
+            .recent_events
+            .split_off(&(last_block.saturating_sub(MAX_REORG_BLOCK_COUNT), 0));
+        let finalized = std::mem::replace(&mut self.recent_events, recent);
+        for ((block, _), event) in finalized {
+            apply_event(&mut self.checkpoint, &event.data)?;
+            self.checkpoint_block = block;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<UniswapV4Event> for PoolStorage {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<UniswapV4Event>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.recent_events.split_off(&(*range.start(), 0));
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<UniswapV4Event>>) -> Result<()> {
+        for event in events {
+            let meta = event.meta.as_ref().context("event meta is empty")?;
+            self.recent_events
+                .insert((meta.block_number, meta.log_index), event);
+        }
+        self.update_checkpoint()
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        Ok(self
+            .recent_events
+            .keys()
+            .last()
+            .map(|(block, _)| *block)
+            .unwrap_or(self.checkpoint_block))
+    }
+
+    async fn persist_last_indexed_block(&mut self, _block: u64) -> Result<()> {
+        // storage is only in-memory so we don't need to persist anything here
+        Ok(())
+    }
+}
+
+/// Type alias for the event handler indexing the `PoolManager` events.
+type PoolUpdater = Mutex<EventHandler<PoolManagerEventFetcher, PoolStorage>>;
+
+pub struct UniswapV4PoolFetcher {
+    updater: PoolUpdater,
+}
+
+impl UniswapV4PoolFetcher {
+    /// Creates a new pool fetcher by indexing all events the `PoolManager`
+    /// emitted since it was deployed at `deployment_block`. This can take a
+    /// while, so the fetcher should be created in the background.
+    pub async fn new(
+        web3: Web3,
+        pool_manager: H160,
+        deployment_block: u64,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "uniswapV4".into());
+        let mut updater = EventHandler::new(
+            block_retriever,
+            PoolManagerEventFetcher {
+                web3,
+                address: pool_manager,
+            },
+            PoolStorage::new(deployment_block),
+            None,
+        );
+        updater
+            .update_events()
+            .await
+            .context("failed to index Uniswap V4 pools")?;
+        Ok(Self {
+            updater: Mutex::new(updater),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl UniswapV4PoolFetching for UniswapV4PoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let block = match at_block {
+            Block::Recent => None,
+            Block::Number(number) => Some(number),
+            // The checkpoint trails the most recent event by the maximum reorg
+            // depth, so it is a good approximation of the finalized state.
+            Block::Finalized => Some(0),
+        };
+        self.updater.lock().await.store().pools(&token_pairs, block)
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for UniswapV4PoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.updater.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "UniswapV4PoolFetcher"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::uniswap_v4_pool_manager::event_data::{Initialize, ModifyLiquidity, Swap},
+        ethcontract::{Bytes, EventMetadata, I256},
+    };
+
+    fn key(currency0: u8, currency1: u8) -> PoolKey {
+        PoolKey {
+            currency0: H160([currency0; 20]),
+            currency1: H160([currency1; 20]),
+            fee: 3_000,
+            tick_spacing: 60,
+            hooks: H160::zero(),
+        }
+    }
+
+    fn initialize(id: u8, key: &PoolKey) -> UniswapV4Event {
+        UniswapV4Event::Initialize(Initialize {
+            id: Bytes([id; 32]),
+            currency0: key.currency0,
+            currency1: key.currency1,
+            fee: key.fee,
+            tick_spacing: key.tick_spacing,
+            hooks: key.hooks,
+            sqrt_price_x96: U256::from(1) << 96,
+            tick: 0,
+        })
+    }
+
+    fn modify(id: u8, tick_lower: i32, tick_upper: i32, delta: i64) -> UniswapV4Event {
+        UniswapV4Event::ModifyLiquidity(ModifyLiquidity {
+            id: Bytes([id; 32]),
+            tick_lower,
+            tick_upper,
+            liquidity_delta: I256::from(delta),
+            ..Default::default()
+        })
+    }
+
+    fn event(data: UniswapV4Event, block_number: u64, log_index: usize) -> Event<UniswapV4Event> {
+        Event {
+            data,
+            meta: Some(EventMetadata {
+                block_number,
+                log_index,
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn applies_liquidity_changes() {
+        let mut pools = HashMap::new();
+        apply_event(&mut pools, &initialize(1, &key(1, 2))).unwrap();
+        apply_event(&mut pools, &modify(1, -60, 60, 1_000)).unwrap();
+        apply_event(&mut pools, &modify(1, 60, 120, 500)).unwrap();
+        apply_event(&mut pools, &modify(1, -60, 60, -400)).unwrap();
+
+        let state = &pools[&H256([1; 32])].state;
+        assert_eq!(state.liquidity, 600);
+        assert_eq!(
+            state.liquidity_net,
+            BTreeMap::from([(-60, 600), (60, -100), (120, -500)])
+        );
+
+        apply_event(&mut pools, &modify(1, 60, 120, -500)).unwrap();
+        let state = &pools[&H256([1; 32])].state;
+        assert_eq!(state.liquidity, 600);
+        assert_eq!(
+            state.liquidity_net,
+            BTreeMap::from([(-60, 600), (60, -600)])
+        );
+    }
+
+    #[test]
+    fn applies_swaps() {
+        let mut pools = HashMap::new();
+        apply_event(&mut pools, &initialize(1, &key(1, 2))).unwrap();
+        apply_event(
+            &mut pools,
+            &UniswapV4Event::Swap(Swap {
+                id: Bytes([1; 32]),
+                sqrt_price_x96: 1.into(),
+                liquidity: 2,
+                tick: 3,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        // events of unknown pools are ignored
+        apply_event(&mut pools, &modify(2, -60, 60, 1_000)).unwrap();
+
+        assert_eq!(pools.len(), 1);
+        let state = &pools[&H256([1; 32])].state;
+        assert_eq!(state.sqrt_price, U256::from(1));
+        assert_eq!(state.liquidity, 2);
+        assert_eq!(state.tick, 3);
+    }
+
+    #[test]
+    fn only_supports_static_fee_pools_without_hooks() {
+        assert!(key(1, 2).is_supported());
+        assert!(
+            !PoolKey {
+                hooks: H160([3; 20]),
+                ..key(1, 2)
+            }
+            .is_supported()
+        );
+        assert!(
+            !PoolKey {
+                fee: DYNAMIC_FEE_FLAG,
+                ..key(1, 2)
+            }
+            .is_supported()
+        );
+        assert!(!key(0, 2).is_supported());
+    }
+
+    #[tokio::test]
+    async fn folds_old_events_into_checkpoint() {
+        let mut storage = PoolStorage::new(0);
+        storage
+            .append_events(vec![
+                event(initialize(1, &key(1, 2)), 1, 0),
+                event(modify(1, -60, 60, 1_000), 1, 1),
+                event(initialize(2, &key(1, 2)), 100, 0),
+                event(modify(2, -60, 60, 1_000), 100, 1),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(storage.checkpoint.len(), 1);
+        assert_eq!(storage.checkpoint_block, 1);
+        assert_eq!(storage.recent_events.len(), 2);
+        assert_eq!(storage.last_event_block().await.unwrap(), 100);
+
+        let pair = TokenPair::new(H160([1; 20]), H160([2; 20])).unwrap();
+        assert_eq!(storage.pools(&[pair].into(), None).unwrap().len(), 2);
+        assert_eq!(storage.pools(&[pair].into(), Some(99)).unwrap().len(), 1);
+
+        // reorgs only revert recent events
+        storage
+            .replace_events(vec![], RangeInclusive::try_new(50, 100).unwrap())
+            .await
+            .unwrap();
+        assert!(storage.recent_events.is_empty());
+        assert_eq!(storage.last_event_block().await.unwrap(), 1);
+        assert_eq!(storage.pools(&[pair].into(), None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn filters_pools_without_liquidity() {
+        let mut storage = PoolStorage::new(0);
+        apply_event(&mut storage.checkpoint, &initialize(1, &key(1, 2))).unwrap();
+        apply_event(&mut storage.checkpoint, &initialize(2, &key(1, 2))).unwrap();
+        apply_event(&mut storage.checkpoint, &initialize(3, &key(2, 3))).unwrap();
+        for id in [1, 3] {
+            apply_event(&mut storage.checkpoint, &modify(id, -60, 60, 1_000)).unwrap();
+        }
+
+        let pair = TokenPair::new(H160([1; 20]), H160([2; 20])).unwrap();
+        let pools = storage.pools(&[pair].into(), None).unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].id, H256([1; 32]));
+    }
+}
@@ -0,0 +1,83 @@
+use {
+    contracts::{BalancerV3BatchRouter, dummy_contract},
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::{EncodedInteraction, Interaction},
+    },
+};
+
+/// Buys exactly `asset_out` on a single Balancer V3 pool through the
+/// `BatchRouter`, paying at most `asset_in_max`. The router pulls the input
+/// tokens from the caller with `Permit2` and sends the output tokens to the
+/// caller.
+#[derive(Clone, Debug)]
+pub struct BalancerV3SwapGivenOutInteraction {
+    pub batch_router: H160,
+    pub pool: H160,
+    pub asset_in_max: TokenAmount,
+    pub asset_out: TokenAmount,
+}
+
+impl Interaction for BalancerV3SwapGivenOutInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let router = dummy_contract!(BalancerV3BatchRouter, self.batch_router);
+        let method = router.swap_exact_out(
+            vec![(
+                self.asset_in_max.token,
+                vec![(
+                    self.pool,
+                    self.asset_out.token,
+                    false, // isBuffer
+                )],
+                self.asset_in_max.amount,
+                self.asset_out.amount,
+            )],
+            U256::MAX, // deadline
+            false,     // wethIsEth
+            Bytes::default(),
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.batch_router, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn encode_swap_given_out() {
+        let interaction = BalancerV3SwapGivenOutInteraction {
+            batch_router: H160([0x01; 20]),
+            pool: H160([0x03; 20]),
+            asset_in_max: TokenAmount::new(H160([0x02; 20]), 5),
+            asset_out: TokenAmount::new(H160([0x04; 20]), 6),
+        };
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x01; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "8eb1b65e
+                 0000000000000000000000000000000000000000000000000000000000000080
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 00000000000000000000000000000000000000000000000000000000000001c0
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000020
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000000000000000000000000000000000000000080
+                 0000000000000000000000000000000000000000000000000000000000000005
+                 0000000000000000000000000000000000000000000000000000000000000006
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000303030303030303030303030303030303030303
+                 0000000000000000000000000404040404040404040404040404040404040404
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+}
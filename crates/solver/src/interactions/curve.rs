@@ -0,0 +1,84 @@
+use {
+    contracts::{CurveStableSwapNgPool, CurveTwocryptoPool, dummy_contract},
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// The `exchange` flavour of a Curve pool. StableSwap pools index their coins
+/// with `int128` while CryptoSwap pools use `uint256`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurveExchange {
+    StableSwap,
+    CryptoSwap,
+}
+
+/// Sells exactly `dx` of coin `i` for at least `min_dy` of coin `j`. The pool
+/// pulls the input tokens from and sends the output tokens to the caller.
+#[derive(Clone, Debug)]
+pub struct CurveExchangeInteraction {
+    pub pool: H160,
+    pub exchange: CurveExchange,
+    pub i: usize,
+    pub j: usize,
+    pub dx: U256,
+    pub min_dy: U256,
+}
+
+impl Interaction for CurveExchangeInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let calldata = match self.exchange {
+            CurveExchange::StableSwap => {
+                let pool = dummy_contract!(CurveStableSwapNgPool, self.pool);
+                let method = pool.exchange(self.i as i128, self.j as i128, self.dx, self.min_dy);
+                method.tx.data.expect("no calldata").0
+            }
+            CurveExchange::CryptoSwap => {
+                let pool = dummy_contract!(CurveTwocryptoPool, self.pool);
+                let method = pool.exchange(self.i.into(), self.j.into(), self.dx, self.min_dy);
+                method.tx.data.expect("no calldata").0
+            }
+        };
+        (self.pool, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn encode_exchange() {
+        let interaction = CurveExchangeInteraction {
+            pool: H160([0x01; 20]),
+            exchange: CurveExchange::StableSwap,
+            i: 0,
+            j: 1,
+            dx: 2.into(),
+            min_dy: 3.into(),
+        };
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x01; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            [
+                // exchange(int128,int128,uint256,uint256)
+                &hex!("3df02124")[..],
+                &hex!("0000000000000000000000000000000000000000000000000000000000000000"),
+                &hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+                &hex!("0000000000000000000000000000000000000000000000000000000000000002"),
+                &hex!("0000000000000000000000000000000000000000000000000000000000000003"),
+            ]
+            .concat()
+        );
+
+        let interaction = CurveExchangeInteraction {
+            exchange: CurveExchange::CryptoSwap,
+            ..interaction
+        };
+        let (_, _, calldata) = interaction.encode();
+        // exchange(uint256,uint256,uint256,uint256)
+        assert_eq!(calldata.0[..4], hex!("5b41b908"));
+    }
+}
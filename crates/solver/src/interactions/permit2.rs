@@ -0,0 +1,57 @@
+use {
+    contracts::{Permit2, dummy_contract},
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// The largest `uint48` value, used as an expiration that never elapses.
+const NEVER_EXPIRES: u64 = (1 << 48) - 1;
+
+/// Allows `spender` to transfer up to `amount` of `token` from the caller
+/// through `Permit2`. This requires the caller to have approved `Permit2` for
+/// the token itself.
+#[derive(Clone, Debug)]
+pub struct Permit2ApproveInteraction {
+    pub permit2: H160,
+    pub token: H160,
+    pub spender: H160,
+    pub amount: U256,
+}
+
+impl Interaction for Permit2ApproveInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let permit2 = dummy_contract!(Permit2, self.permit2);
+        let method = permit2.approve(self.token, self.spender, self.amount, NEVER_EXPIRES);
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.permit2, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn encode_approve() {
+        let interaction = Permit2ApproveInteraction {
+            permit2: H160([0x01; 20]),
+            token: H160([0x02; 20]),
+            spender: H160([0x03; 20]),
+            amount: 4.into(),
+        };
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x01; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "87517c45
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000303030303030303030303030303030303030303
+                 0000000000000000000000000000000000000000000000000000000000000004
+                 0000000000000000000000000000000000000000000000000000ffffffffffff"
+            )
+        );
+    }
+}
@@ -33,6 +33,25 @@ impl UniswapInteraction {
         let calldata = method.tx.data.expect("no calldata").0;
         (self.router.address(), 0.into(), Bytes(calldata))
     }
+
+    /// Encodes a swap of exactly `amount_in_max` for at least `amount_out`
+    /// tokens. Unlike [`Self::encode_swap`] the router computes the output
+    /// amount from the tokens the pair actually received and checks the
+    /// tokens the settlement contract actually received, so it works with
+    /// tokens that take a fee on transfer.
+    pub fn encode_swap_supporting_fee_on_transfer(&self) -> EncodedInteraction {
+        let method = self
+            .router
+            .swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(
+                self.amount_in_max,
+                self.amount_out,
+                vec![self.token_in, self.token_out],
+                self.settlement.address(),
+                U256::MAX,
+            );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.router.address(), 0.into(), Bytes(calldata))
+    }
 }
 
 #[cfg(test)]
@@ -87,4 +106,32 @@ mod tests {
         assert_eq!(&call[208..228], token_in.as_fixed_bytes());
         assert_eq!(call[228..260], u8_as_32_bytes_be(token_out));
     }
+
+    #[test]
+    fn encode_uniswap_call_supporting_fee_on_transfer() {
+        let router = dummy_contract!(IUniswapLikeRouter, H160::from_low_u64_be(4));
+        let settlement = GPv2Settlement::at(&dummy::web3(), H160::from_low_u64_be(9));
+        let interaction = UniswapInteraction {
+            router: router.clone(),
+            settlement,
+            amount_out: 5.into(),
+            amount_in_max: 6.into(),
+            token_in: H160::from_low_u64_be(7),
+            token_out: H160::from_low_u64_be(8),
+        };
+        let swap_call = interaction.encode_swap_supporting_fee_on_transfer();
+
+        assert_eq!(swap_call.0, router.address());
+        let call = &swap_call.2.0;
+        assert_eq!(call[0..4], hex!("5c11d795"));
+        // The maximum input amount gets swapped exactly.
+        assert_eq!(call[4..36], u8_as_32_bytes_be(6));
+        assert_eq!(call[36..68], u8_as_32_bytes_be(5));
+        assert_eq!(call[68..100], u8_as_32_bytes_be(160));
+        assert_eq!(call[100..132], u8_as_32_bytes_be(9));
+        assert_eq!(call[132..164], [0xffu8; 32]);
+        assert_eq!(call[164..196], u8_as_32_bytes_be(2));
+        assert_eq!(call[196..228], u8_as_32_bytes_be(7));
+        assert_eq!(call[228..260], u8_as_32_bytes_be(8));
+    }
 }
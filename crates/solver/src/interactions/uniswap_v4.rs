@@ -0,0 +1,190 @@
+use {
+    contracts::{UniswapV4UniversalRouter, dummy_contract},
+    ethcontract::{
+        Bytes,
+        I256,
+        common::abi::{self, Token},
+    },
+    primitive_types::{H160, U256},
+    shared::{
+        interaction::{EncodedInteraction, Interaction},
+        sources::uniswap_v4::PoolKey,
+    },
+};
+
+/// `UniversalRouter` command executing a sequence of V4 router actions.
+const V4_SWAP: u8 = 0x10;
+/// V4 router actions, see <https://github.com/Uniswap/v4-periphery/blob/main/src/libraries/Actions.sol>.
+const SWAP_EXACT_IN_SINGLE: u8 = 0x06;
+const SETTLE: u8 = 0x0b;
+const TAKE: u8 = 0x0e;
+/// Amount telling `SETTLE` and `TAKE` to use the full outstanding delta.
+const OPEN_DELTA: u64 = 0;
+
+/// Sells exactly `amount_in` on a single Uniswap V4 pool through the
+/// `UniversalRouter` for at least `amount_out_minimum`. The router pays for
+/// the swap with its own balance, so the input tokens need to be transferred
+/// to it beforehand, and sends the output tokens to `recipient`.
+#[derive(Clone, Debug)]
+pub struct UniswapV4SwapInteraction {
+    pub router: H160,
+    pub pool_key: PoolKey,
+    /// Whether `currency0` is sold for `currency1`.
+    pub zero_for_one: bool,
+    pub amount_in: U256,
+    pub amount_out_minimum: U256,
+    pub recipient: H160,
+}
+
+impl UniswapV4SwapInteraction {
+    fn currencies(&self) -> (H160, H160) {
+        if self.zero_for_one {
+            (self.pool_key.currency0, self.pool_key.currency1)
+        } else {
+            (self.pool_key.currency1, self.pool_key.currency0)
+        }
+    }
+
+    fn swap_params(&self) -> Vec<u8> {
+        abi::encode(&[Token::Tuple(vec![
+            Token::Tuple(vec![
+                Token::Address(self.pool_key.currency0),
+                Token::Address(self.pool_key.currency1),
+                Token::Uint(self.pool_key.fee.into()),
+                Token::Int(I256::from(self.pool_key.tick_spacing).into_raw()),
+                Token::Address(self.pool_key.hooks),
+            ]),
+            Token::Bool(self.zero_for_one),
+            Token::Uint(self.amount_in),
+            Token::Uint(self.amount_out_minimum),
+            // hook data
+            Token::Bytes(vec![]),
+        ])])
+    }
+}
+
+impl Interaction for UniswapV4SwapInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let (currency_in, currency_out) = self.currencies();
+        let params = vec![
+            self.swap_params(),
+            abi::encode(&[
+                Token::Address(currency_in),
+                Token::Uint(OPEN_DELTA.into()),
+                // pay with the router's balance
+                Token::Bool(false),
+            ]),
+            abi::encode(&[
+                Token::Address(currency_out),
+                Token::Address(self.recipient),
+                Token::Uint(OPEN_DELTA.into()),
+            ]),
+        ];
+        let input = abi::encode(&[
+            Token::Bytes(vec![SWAP_EXACT_IN_SINGLE, SETTLE, TAKE]),
+            Token::Array(params.into_iter().map(Token::Bytes).collect()),
+        ]);
+
+        let router = dummy_contract!(UniswapV4UniversalRouter, self.router);
+        let method = router.execute(Bytes(vec![V4_SWAP]), vec![Bytes(input)], U256::MAX);
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.router, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::common::abi::ParamType, hex_literal::hex};
+
+    #[test]
+    fn encode_exact_input_swap() {
+        let interaction = UniswapV4SwapInteraction {
+            router: H160([0x01; 20]),
+            pool_key: PoolKey {
+                currency0: H160([0x02; 20]),
+                currency1: H160([0x03; 20]),
+                fee: 500,
+                tick_spacing: -10,
+                hooks: H160::zero(),
+            },
+            zero_for_one: false,
+            amount_in: 4.into(),
+            amount_out_minimum: 5.into(),
+            recipient: H160([0x06; 20]),
+        };
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x01; 20]));
+        assert_eq!(value, 0.into());
+        // execute(bytes,bytes[],uint256)
+        assert_eq!(calldata.0[..4], hex!("3593564c"));
+
+        let execute = abi::decode(
+            &[
+                ParamType::Bytes,
+                ParamType::Array(Box::new(ParamType::Bytes)),
+                ParamType::Uint(256),
+            ],
+            &calldata.0[4..],
+        )
+        .unwrap();
+        assert_eq!(execute[0], Token::Bytes(vec![0x10]));
+        assert_eq!(execute[2], Token::Uint(U256::MAX));
+        let Token::Array(inputs) = &execute[1] else {
+            panic!("unexpected inputs");
+        };
+        let [Token::Bytes(input)] = inputs.as_slice() else {
+            panic!("unexpected inputs");
+        };
+
+        let actions = abi::decode(
+            &[
+                ParamType::Bytes,
+                ParamType::Array(Box::new(ParamType::Bytes)),
+            ],
+            input,
+        )
+        .unwrap();
+        assert_eq!(actions[0], Token::Bytes(vec![0x06, 0x0b, 0x0e]));
+        assert_eq!(
+            actions[1],
+            Token::Array(vec![
+                // offset of the dynamic params struct, the pool key, the swap
+                // direction, the amounts and the empty hook data
+                Token::Bytes(
+                    hex!(
+                        "0000000000000000000000000000000000000000000000000000000000000020
+                         0000000000000000000000000202020202020202020202020202020202020202
+                         0000000000000000000000000303030303030303030303030303030303030303
+                         00000000000000000000000000000000000000000000000000000000000001f4
+                         fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6
+                         0000000000000000000000000000000000000000000000000000000000000000
+                         0000000000000000000000000000000000000000000000000000000000000000
+                         0000000000000000000000000000000000000000000000000000000000000004
+                         0000000000000000000000000000000000000000000000000000000000000005
+                         0000000000000000000000000000000000000000000000000000000000000120
+                         0000000000000000000000000000000000000000000000000000000000000000"
+                    )
+                    .to_vec()
+                ),
+                // settle the input currency with the router's balance
+                Token::Bytes(
+                    hex!(
+                        "0000000000000000000000000303030303030303030303030303030303030303
+                         0000000000000000000000000000000000000000000000000000000000000000
+                         0000000000000000000000000000000000000000000000000000000000000000"
+                    )
+                    .to_vec()
+                ),
+                // take the output currency to the recipient
+                Token::Bytes(
+                    hex!(
+                        "0000000000000000000000000202020202020202020202020202020202020202
+                         0000000000000000000000000606060606060606060606060606060606060606
+                         0000000000000000000000000000000000000000000000000000000000000000"
+                    )
+                    .to_vec()
+                ),
+            ])
+        );
+    }
+}
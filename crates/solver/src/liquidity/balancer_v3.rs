@@ -0,0 +1,329 @@
+//! Module for providing Balancer V3 pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            BalancerV3SwapGivenOutInteraction,
+            Permit2ApproveInteraction,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, BalancerV3PoolOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::Result,
+    contracts::GPv2Settlement,
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::balancer_v3::BalancerV3PoolFetching,
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Balancer V3 pools.
+pub struct BalancerV3Liquidity {
+    pool_fetcher: Arc<dyn BalancerV3PoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+    batch_router: H160,
+    permit2: H160,
+}
+
+impl BalancerV3Liquidity {
+    pub fn new(
+        web3: Web3,
+        pool_fetcher: Arc<dyn BalancerV3PoolFetching>,
+        settlement: GPv2Settlement,
+        batch_router: H160,
+        permit2: H160,
+    ) -> Self {
+        let allowance_manager = AllowanceManager::new(web3, settlement.address());
+        Self {
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+            batch_router,
+            permit2,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for BalancerV3Liquidity {
+    /// Returns relevant Balancer V3 pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+
+        // The batch router pulls the tokens of all pools through `Permit2`, so
+        // the settlement contract needs to approve `Permit2` for them.
+        let tokens = pools
+            .iter()
+            .flat_map(|pool| pool.tokens.iter().map(|state| state.token))
+            .collect();
+        let allowances = self
+            .allowance_manager
+            .get_allowances(tokens, self.permit2)
+            .await?;
+
+        let inner = Arc::new(Inner {
+            batch_router: self.batch_router,
+            permit2: self.permit2,
+            allowances,
+        });
+        Ok(pools
+            .into_iter()
+            .map(|pool| {
+                Liquidity::BalancerV3(BalancerV3PoolOrder {
+                    settlement_handling: Arc::new(SettlementHandler {
+                        pool: pool.address,
+                        inner: inner.clone(),
+                    }),
+                    pool,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    pool: H160,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    batch_router: H160,
+    permit2: H160,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(pool: H160, batch_router: H160, permit2: H160, allowances: Allowances) -> Self {
+        Self {
+            pool,
+            inner: Arc::new(Inner {
+                batch_router,
+                permit2,
+                allowances,
+            }),
+        }
+    }
+
+    pub fn pool(&self) -> H160 {
+        self.pool
+    }
+
+    pub fn batch_router(&self) -> H160 {
+        self.inner.batch_router
+    }
+
+    pub fn permit2(&self) -> H160 {
+        self.inner.permit2
+    }
+
+    /// Encodes a swap buying exactly `output` for at most `input_max`. Since
+    /// the batch router pulls the input tokens through `Permit2`, it first
+    /// needs to be allowed to transfer up to `input_max` through it.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> (Permit2ApproveInteraction, BalancerV3SwapGivenOutInteraction) {
+        (
+            Permit2ApproveInteraction {
+                permit2: self.inner.permit2,
+                token: input_max.token,
+                spender: self.inner.batch_router,
+                amount: input_max.amount,
+            },
+            BalancerV3SwapGivenOutInteraction {
+                batch_router: self.inner.batch_router,
+                pool: self.pool,
+                asset_in_max: input_max,
+                asset_out: output,
+            },
+        )
+    }
+}
+
+impl SettlementHandling<BalancerV3PoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        if let Some(approval) = self
+            .inner
+            .allowances
+            .approve_token(execution.input_max.clone())?
+        {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        let (permit, swap) = self.swap(execution.input_max, execution.output);
+        encoder.append_to_execution_plan_internalizable(Arc::new(permit), execution.internalizable);
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::interactions::allowances::{Approval, MockAllowanceManaging},
+        maplit::{hashmap, hashset},
+        mockall::predicate::*,
+        shared::{
+            http_solver::model::InternalizationStrategy,
+            interaction::Interaction,
+            sources::{
+                balancer_v2::swap::fixed_point::Bfp,
+                balancer_v3::{
+                    Pool,
+                    PoolKind,
+                    TokenState,
+                    pool_fetching::MockBalancerV3PoolFetching,
+                },
+            },
+        },
+    };
+
+    const BATCH_ROUTER: H160 = H160([0xb1; 20]);
+    const PERMIT2: H160 = H160([0xb2; 20]);
+
+    fn pool(address: H160, tokens: &[H160]) -> Pool {
+        Pool {
+            address,
+            tokens: tokens
+                .iter()
+                .map(|token| TokenState {
+                    token: *token,
+                    balance: 1.into(),
+                    scaling_factor: Bfp::exp10(0),
+                })
+                .collect(),
+            swap_fee: Bfp::zero(),
+            kind: PoolKind::Weighted(vec![Bfp::exp10(0); tokens.len()]),
+        }
+    }
+
+    #[tokio::test]
+    async fn approves_permit2_and_batch_router() {
+        let tokens = [H160([1; 20]), H160([2; 20]), H160([3; 20])];
+        let pools = [
+            pool(H160([0xa; 20]), &[tokens[0], tokens[1]]),
+            pool(H160([0xb; 20]), &[tokens[1], tokens[2]]),
+        ];
+
+        let mut pool_fetcher = MockBalancerV3PoolFetching::new();
+        let fetched = pools.to_vec();
+        pool_fetcher
+            .expect_fetch()
+            .return_once(move |_, _| Ok(fetched));
+        let mut allowance_manager = MockAllowanceManaging::new();
+        allowance_manager
+            .expect_get_allowances()
+            .with(eq(hashset![tokens[0], tokens[1], tokens[2]]), eq(PERMIT2))
+            .returning(|_, spender| {
+                Ok(Allowances::new(
+                    spender,
+                    hashmap! {
+                        H160([1; 20]) => 1_000.into(),
+                        H160([2; 20]) => 0.into(),
+                        H160([3; 20]) => 0.into(),
+                    },
+                ))
+            });
+
+        let liquidity = BalancerV3Liquidity {
+            pool_fetcher: Arc::new(pool_fetcher),
+            allowance_manager: Box::new(allowance_manager),
+            batch_router: BATCH_ROUTER,
+            permit2: PERMIT2,
+        };
+        let orders = liquidity
+            .get_liquidity(
+                hashset![TokenPair::new(tokens[0], tokens[1]).unwrap()],
+                Block::Recent,
+            )
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 2);
+
+        let encode = |order: &Liquidity, input_max: TokenAmount, output: TokenAmount| {
+            let Liquidity::BalancerV3(order) = order else {
+                panic!("unexpected liquidity");
+            };
+            let mut encoder = SettlementEncoder::new(Default::default());
+            order
+                .settlement_handling
+                .encode(
+                    AmmOrderExecution {
+                        input_max,
+                        output,
+                        internalizable: false,
+                    },
+                    &mut encoder,
+                )
+                .unwrap();
+            let [_, interactions, _] = encoder
+                .finish(InternalizationStrategy::SkipInternalizableInteraction)
+                .interactions;
+            interactions
+        };
+        let swap = |pool: H160, input_max: TokenAmount, output: TokenAmount| {
+            [
+                Permit2ApproveInteraction {
+                    permit2: PERMIT2,
+                    token: input_max.token,
+                    spender: BATCH_ROUTER,
+                    amount: input_max.amount,
+                }
+                .encode(),
+                BalancerV3SwapGivenOutInteraction {
+                    batch_router: BATCH_ROUTER,
+                    pool,
+                    asset_in_max: input_max,
+                    asset_out: output,
+                }
+                .encode(),
+            ]
+        };
+
+        let (input_max, output) = (
+            TokenAmount::new(tokens[0], 100),
+            TokenAmount::new(tokens[1], 99),
+        );
+        assert_eq!(
+            encode(&orders[0], input_max.clone(), output.clone()),
+            swap(pools[0].address, input_max, output),
+        );
+
+        let (input_max, output) = (
+            TokenAmount::new(tokens[1], 100),
+            TokenAmount::new(tokens[2], 99),
+        );
+        assert_eq!(
+            encode(&orders[1], input_max.clone(), output.clone()),
+            [
+                vec![
+                    Approval {
+                        token: tokens[1],
+                        spender: PERMIT2,
+                    }
+                    .encode()
+                ],
+                swap(pools[1].address, input_max, output).to_vec(),
+            ]
+            .concat(),
+        );
+    }
+}
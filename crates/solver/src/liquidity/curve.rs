@@ -0,0 +1,302 @@
+//! Module for providing Curve pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            CurveExchange,
+            CurveExchangeInteraction,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, CurvePoolOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Context, Result},
+    contracts::GPv2Settlement,
+    futures::future,
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::curve::{CurvePoolFetching, Pool, PoolKind},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Curve pools.
+pub struct CurveLiquidity {
+    pool_fetcher: Arc<dyn CurvePoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl CurveLiquidity {
+    pub fn new(
+        web3: Web3,
+        pool_fetcher: Arc<dyn CurvePoolFetching>,
+        settlement: GPv2Settlement,
+    ) -> Self {
+        let allowance_manager = AllowanceManager::new(web3, settlement.address());
+        Self {
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+
+    async fn order(&self, pool: Pool) -> Result<CurvePoolOrder> {
+        // Unlike for the other AMMs, every Curve pool pulls the tokens it is
+        // trading itself, so allowances are tracked per pool.
+        let allowances = self
+            .allowance_manager
+            .get_allowances(pool.tokens.iter().copied().collect(), pool.address)
+            .await?;
+        Ok(CurvePoolOrder {
+            settlement_handling: Arc::new(SettlementHandler::new(&pool, allowances)),
+            pool,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for CurveLiquidity {
+    /// Returns relevant Curve pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+        let orders = future::try_join_all(pools.into_iter().map(|pool| self.order(pool))).await?;
+        Ok(orders.into_iter().map(Liquidity::Curve).collect())
+    }
+}
+
+pub struct SettlementHandler {
+    pool: H160,
+    tokens: Vec<H160>,
+    exchange: CurveExchange,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(pool: &Pool, allowances: Allowances) -> Self {
+        Self {
+            pool: pool.address,
+            tokens: pool.tokens.clone(),
+            exchange: match pool.kind {
+                PoolKind::StableSwap(_) => CurveExchange::StableSwap,
+                PoolKind::CryptoSwap(_) => CurveExchange::CryptoSwap,
+            },
+            allowances,
+        }
+    }
+
+    pub fn pool(&self) -> H160 {
+        self.pool
+    }
+
+    /// Encodes a swap of `input_max` for `output`. Curve pools only support
+    /// exact input swaps, so the whole `input_max` amount gets sold and
+    /// `output` is used as the minimum amount to receive.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> Result<CurveExchangeInteraction> {
+        let index = |token| {
+            self.tokens
+                .iter()
+                .position(|t| *t == token)
+                .with_context(|| format!("token {token:?} not traded by Curve pool"))
+        };
+        Ok(CurveExchangeInteraction {
+            pool: self.pool,
+            exchange: self.exchange,
+            i: index(input_max.token)?,
+            j: index(output.token)?,
+            dx: input_max.amount,
+            min_dy: output.amount,
+        })
+    }
+}
+
+impl SettlementHandling<CurvePoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let swap = self.swap(execution.input_max.clone(), execution.output)?;
+        if let Some(approval) = self
+            .allowances
+            .approve_token_or_default(execution.input_max)
+        {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::interactions::allowances::{Approval, MockAllowanceManaging},
+        maplit::{hashmap, hashset},
+        mockall::predicate::*,
+        shared::{
+            http_solver::model::InternalizationStrategy,
+            interaction::Interaction,
+            sources::curve::pool_fetching::{MockCurvePoolFetching, StableSwapState},
+        },
+    };
+
+    fn pool(address: H160, tokens: Vec<H160>) -> Pool {
+        Pool {
+            address,
+            balances: vec![1.into(); tokens.len()],
+            tokens,
+            kind: PoolKind::StableSwap(StableSwapState {
+                amplification: 100.into(),
+                fee: 0.into(),
+                offpeg_fee_multiplier: 0.into(),
+                rates: vec![],
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_allowances_per_pool() {
+        let tokens = [H160([1; 20]), H160([2; 20]), H160([3; 20])];
+        let pools = [
+            pool(H160([0xa; 20]), vec![tokens[0], tokens[1], tokens[2]]),
+            pool(H160([0xb; 20]), vec![tokens[1], tokens[2]]),
+        ];
+
+        let mut pool_fetcher = MockCurvePoolFetching::new();
+        let fetched = pools.to_vec();
+        pool_fetcher
+            .expect_fetch()
+            .return_once(move |_, _| Ok(fetched));
+        let mut allowance_manager = MockAllowanceManaging::new();
+        allowance_manager
+            .expect_get_allowances()
+            .with(
+                eq(hashset![tokens[0], tokens[1], tokens[2]]),
+                eq(pools[0].address),
+            )
+            .returning(|_, spender| {
+                Ok(Allowances::new(
+                    spender,
+                    hashmap! { H160([1; 20]) => 1_000.into() },
+                ))
+            });
+        allowance_manager
+            .expect_get_allowances()
+            .with(eq(hashset![tokens[1], tokens[2]]), eq(pools[1].address))
+            .returning(|_, spender| Ok(Allowances::empty(spender)));
+
+        let liquidity = CurveLiquidity {
+            pool_fetcher: Arc::new(pool_fetcher),
+            allowance_manager: Box::new(allowance_manager),
+        };
+        let orders = liquidity
+            .get_liquidity(
+                hashset![TokenPair::new(tokens[1], tokens[2]).unwrap()],
+                Block::Recent,
+            )
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 2);
+
+        let Liquidity::Curve(order) = &orders[0] else {
+            panic!("unexpected liquidity");
+        };
+        let mut encoder = SettlementEncoder::new(Default::default());
+        order
+            .settlement_handling
+            .encode(
+                AmmOrderExecution {
+                    input_max: TokenAmount::new(tokens[0], 100),
+                    output: TokenAmount::new(tokens[2], 99),
+                    internalizable: false,
+                },
+                &mut encoder,
+            )
+            .unwrap();
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+        assert_eq!(
+            interactions,
+            [CurveExchangeInteraction {
+                pool: pools[0].address,
+                exchange: CurveExchange::StableSwap,
+                i: 0,
+                j: 2,
+                dx: 100.into(),
+                min_dy: 99.into(),
+            }
+            .encode()],
+        );
+
+        let Liquidity::Curve(order) = &orders[1] else {
+            panic!("unexpected liquidity");
+        };
+        let mut encoder = SettlementEncoder::new(Default::default());
+        order
+            .settlement_handling
+            .encode(
+                AmmOrderExecution {
+                    input_max: TokenAmount::new(tokens[1], 100),
+                    output: TokenAmount::new(tokens[2], 99),
+                    internalizable: false,
+                },
+                &mut encoder,
+            )
+            .unwrap();
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+        assert_eq!(
+            interactions,
+            [
+                Approval {
+                    token: tokens[1],
+                    spender: pools[1].address,
+                }
+                .encode(),
+                CurveExchangeInteraction {
+                    pool: pools[1].address,
+                    exchange: CurveExchange::StableSwap,
+                    i: 0,
+                    j: 1,
+                    dx: 100.into(),
+                    min_dy: 99.into(),
+                }
+                .encode(),
+            ],
+        );
+    }
+
+    #[test]
+    fn rejects_tokens_not_traded_by_pool() {
+        let handler = SettlementHandler::new(
+            &pool(H160([0xa; 20]), vec![H160([1; 20]), H160([2; 20])]),
+            Allowances::empty(H160([0xa; 20])),
+        );
+        assert!(
+            handler
+                .swap(
+                    TokenAmount::new(H160([3; 20]), 1),
+                    TokenAmount::new(H160([2; 20]), 1),
+                )
+                .is_err()
+        );
+    }
+}
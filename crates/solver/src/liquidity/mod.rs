@@ -1,8 +1,11 @@
 pub mod balancer_v2;
+pub mod balancer_v3;
+pub mod curve;
 pub mod order_converter;
 pub mod slippage;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
+pub mod uniswap_v4;
 pub mod zeroex;
 
 #[cfg(test)]
@@ -28,8 +31,11 @@ use {
                 },
                 swap::fixed_point::Bfp,
             },
+            balancer_v3,
+            curve,
             uniswap_v2::pool_fetching::Pool,
             uniswap_v3::pool_fetching::PoolInfo,
+            uniswap_v4,
         },
     },
     std::{collections::BTreeMap, sync::Arc},
@@ -45,6 +51,9 @@ pub enum Liquidity {
     BalancerStable(StablePoolOrder),
     LimitOrder(LimitOrder),
     Concentrated(ConcentratedLiquidity),
+    Curve(CurvePoolOrder),
+    UniswapV4(UniswapV4PoolOrder),
+    BalancerV3(BalancerV3PoolOrder),
 }
 
 /// A trait associating some liquidity model to how it is executed and encoded
@@ -393,6 +402,78 @@ impl Settleable for ConcentratedLiquidity {
     }
 }
 
+/// Curve StableSwap or CryptoSwap pool trading two or more coins.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct CurvePoolOrder {
+    pub pool: curve::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for CurvePoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Curve Pool {:?}", self.pool.tokens)
+    }
+}
+
+impl Settleable for CurvePoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+/// Uniswap V4 concentrated liquidity pool without hooks.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct UniswapV4PoolOrder {
+    pub pool: uniswap_v4::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for UniswapV4PoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Uniswap V4 Pool {:?}", self.pool.id)
+    }
+}
+
+impl Settleable for UniswapV4PoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+/// Balancer V3 weighted or stable pool.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct BalancerV3PoolOrder {
+    pub pool: balancer_v3::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for BalancerV3PoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Balancer V3 Pool {:?}", self.pool.address)
+    }
+}
+
+impl Settleable for BalancerV3PoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use {super::*, maplit::btreemap, std::sync::Mutex};
@@ -0,0 +1,220 @@
+//! Module for providing Uniswap V4 pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{Erc20TransferInteraction, UniswapV4SwapInteraction},
+        liquidity::{AmmOrderExecution, Liquidity, SettlementHandling, UniswapV4PoolOrder},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Result, ensure},
+    contracts::{ERC20, dummy_contract},
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::uniswap_v4::{Pool, PoolKey, UniswapV4PoolFetching},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Uniswap V4 pools.
+pub struct UniswapV4Liquidity {
+    pool_fetcher: Arc<dyn UniswapV4PoolFetching>,
+    router: H160,
+    pool_manager: H160,
+    settlement: H160,
+}
+
+impl UniswapV4Liquidity {
+    pub fn new(
+        pool_fetcher: Arc<dyn UniswapV4PoolFetching>,
+        router: H160,
+        pool_manager: H160,
+        settlement: H160,
+    ) -> Self {
+        Self {
+            pool_fetcher,
+            router,
+            pool_manager,
+            settlement,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for UniswapV4Liquidity {
+    /// Returns relevant Uniswap V4 pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+        Ok(pools
+            .into_iter()
+            .map(|pool| {
+                Liquidity::UniswapV4(UniswapV4PoolOrder {
+                    settlement_handling: Arc::new(SettlementHandler::new(
+                        self.router,
+                        self.pool_manager,
+                        self.settlement,
+                        &pool,
+                    )),
+                    pool,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    router: H160,
+    pool_manager: H160,
+    settlement: H160,
+    pool_key: PoolKey,
+}
+
+impl SettlementHandler {
+    pub fn new(router: H160, pool_manager: H160, settlement: H160, pool: &Pool) -> Self {
+        Self {
+            router,
+            pool_manager,
+            settlement,
+            pool_key: pool.key.clone(),
+        }
+    }
+
+    pub fn router(&self) -> H160 {
+        self.router
+    }
+
+    pub fn pool_manager(&self) -> H160 {
+        self.pool_manager
+    }
+
+    /// Encodes a swap of `input_max` for `output`. Like with Curve, only exact
+    /// input swaps are supported, so the whole `input_max` amount gets sold
+    /// and `output` is used as the minimum amount to receive.
+    ///
+    /// The `UniversalRouter` pays for the swap with its own balance, so the
+    /// input tokens get transferred to it first instead of approving it.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> Result<(Erc20TransferInteraction, UniswapV4SwapInteraction)> {
+        let zero_for_one = input_max.token == self.pool_key.currency0;
+        let currencies = if zero_for_one {
+            (self.pool_key.currency0, self.pool_key.currency1)
+        } else {
+            (self.pool_key.currency1, self.pool_key.currency0)
+        };
+        ensure!(
+            currencies == (input_max.token, output.token),
+            "tokens not traded by Uniswap V4 pool",
+        );
+
+        Ok((
+            Erc20TransferInteraction {
+                token: dummy_contract!(ERC20, input_max.token),
+                recipient: self.router,
+                amount: input_max.amount,
+            },
+            UniswapV4SwapInteraction {
+                router: self.router,
+                pool_key: self.pool_key.clone(),
+                zero_for_one,
+                amount_in: input_max.amount,
+                amount_out_minimum: output.amount,
+                recipient: self.settlement,
+            },
+        ))
+    }
+}
+
+impl SettlementHandling<UniswapV4PoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let (transfer, swap) = self.swap(execution.input_max, execution.output)?;
+        encoder
+            .append_to_execution_plan_internalizable(Arc::new(transfer), execution.internalizable);
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        shared::{http_solver::model::InternalizationStrategy, interaction::Interaction},
+    };
+
+    fn handler() -> SettlementHandler {
+        SettlementHandler::new(
+            H160([0xa; 20]),
+            H160([0xc; 20]),
+            H160([0xb; 20]),
+            &Pool {
+                key: PoolKey {
+                    currency0: H160([1; 20]),
+                    currency1: H160([2; 20]),
+                    fee: 3_000,
+                    tick_spacing: 60,
+                    hooks: H160::zero(),
+                },
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn encodes_transfer_and_swap() {
+        let handler = handler();
+        let mut encoder = SettlementEncoder::new(Default::default());
+        handler
+            .encode(
+                AmmOrderExecution {
+                    input_max: TokenAmount::new(H160([2; 20]), 100),
+                    output: TokenAmount::new(H160([1; 20]), 99),
+                    internalizable: false,
+                },
+                &mut encoder,
+            )
+            .unwrap();
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+
+        let (transfer, swap) = handler
+            .swap(
+                TokenAmount::new(H160([2; 20]), 100),
+                TokenAmount::new(H160([1; 20]), 99),
+            )
+            .unwrap();
+        assert!(!swap.zero_for_one);
+        assert_eq!(swap.recipient, H160([0xb; 20]));
+        assert_eq!(transfer.recipient, H160([0xa; 20]));
+        assert_eq!(interactions, [transfer.encode(), swap.encode()]);
+    }
+
+    #[test]
+    fn rejects_tokens_not_traded_by_pool() {
+        let handler = handler();
+        for (sell, buy) in [(1, 1), (1, 3), (3, 2)] {
+            assert!(
+                handler
+                    .swap(
+                        TokenAmount::new(H160([sell; 20]), 1),
+                        TokenAmount::new(H160([buy; 20]), 1),
+                    )
+                    .is_err()
+            );
+        }
+    }
+}
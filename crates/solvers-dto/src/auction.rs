@@ -166,6 +166,7 @@ pub enum Liquidity {
     WeightedProduct(WeightedProductPool),
     Stable(StablePool),
     ConcentratedLiquidity(ConcentratedLiquidityPool),
+    Curve(CurvePool),
     LimitOrder(ForeignLimitOrder),
 }
 
@@ -196,6 +197,8 @@ pub struct ConstantProductReserve {
 pub struct WeightedProductPool {
     pub id: String,
     pub address: H160,
+    /// The Balancer V2 pool ID. Balancer V3 pools don't have IDs, so for them
+    /// this is the pool address followed by 12 zero bytes.
     pub balancer_pool_id: H256,
     #[serde_as(as = "HexOrDecimalU256")]
     pub gas_estimate: U256,
@@ -227,6 +230,8 @@ pub enum WeightedProductVersion {
 pub struct StablePool {
     pub id: String,
     pub address: H160,
+    /// The Balancer V2 pool ID. Balancer V3 pools don't have IDs, so for them
+    /// this is the pool address followed by 12 zero bytes.
     pub balancer_pool_id: H256,
     #[serde_as(as = "HexOrDecimalU256")]
     pub gas_estimate: U256,
@@ -262,6 +267,68 @@ pub struct ConcentratedLiquidityPool {
     #[serde_as(as = "HashMap<DisplayFromStr, DisplayFromStr>")]
     pub liquidity_net: HashMap<i32, i128>,
     pub fee: BigDecimal,
+    /// The ID of Uniswap V4 pools. These are all managed by the same
+    /// `PoolManager` contract (the pool's `address`) and swapped through the
+    /// `UniversalRouter` (the pool's `router`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_id: Option<H256>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurvePool {
+    pub id: String,
+    pub address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    pub tokens: Vec<H160>,
+    #[serde_as(as = "Vec<HexOrDecimalU256>")]
+    pub balances: Vec<U256>,
+    pub parameters: CurveParameters,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "variant", rename_all = "camelCase")]
+pub enum CurveParameters {
+    StableSwap(CurveStableSwapParameters),
+    CryptoSwap(CurveCryptoSwapParameters),
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveStableSwapParameters {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub amplification: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub fee: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub offpeg_fee_multiplier: U256,
+    #[serde_as(as = "Vec<HexOrDecimalU256>")]
+    pub rates: Vec<U256>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveCryptoSwapParameters {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub a: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gamma: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub d: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub price_scale: U256,
+    #[serde_as(as = "[HexOrDecimalU256; 2]")]
+    pub precisions: [U256; 2],
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub mid_fee: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub out_fee: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub fee_gamma: U256,
 }
 
 #[serde_as]
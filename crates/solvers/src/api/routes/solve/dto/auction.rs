@@ -84,6 +84,7 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
                 Liquidity::ConcentratedLiquidity(liquidity) => {
                     concentrated_liquidity_pool::to_domain(liquidity)
                 }
+                Liquidity::Curve(liquidity) => curve_pool::to_domain(liquidity),
                 Liquidity::LimitOrder(liquidity) => Ok(foreign_limit_order::to_domain(liquidity)),
             })
             .try_collect()?,
@@ -247,6 +248,60 @@ mod concentrated_liquidity_pool {
                         .to_u32()
                         .ok_or("invalid concentrated liquidity pool fee")?,
                 ),
+                v4_pool_id: pool.pool_id,
+            }),
+        })
+    }
+}
+
+mod curve_pool {
+    use super::*;
+
+    pub fn to_domain(pool: &CurvePool) -> Result<liquidity::Liquidity, Error> {
+        if pool.tokens.len() < 2 || pool.tokens.len() != pool.balances.len() {
+            return Err("invalid number of curve pool tokens or balances".into());
+        }
+        if !pool.tokens.iter().all_unique() {
+            return Err("duplicate curve pool token addresses".into());
+        }
+
+        let parameters = match &pool.parameters {
+            CurveParameters::StableSwap(parameters) => {
+                if parameters.rates.len() != pool.tokens.len() {
+                    return Err("invalid number of curve pool rates".into());
+                }
+                liquidity::curve::Parameters::StableSwap(liquidity::curve::StableSwap {
+                    amplification: parameters.amplification,
+                    fee: parameters.fee,
+                    offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                    rates: parameters.rates.clone(),
+                })
+            }
+            CurveParameters::CryptoSwap(parameters) => {
+                if pool.tokens.len() != 2 {
+                    return Err("invalid number of curve crypto pool tokens".into());
+                }
+                liquidity::curve::Parameters::CryptoSwap(liquidity::curve::CryptoSwap {
+                    a: parameters.a,
+                    gamma: parameters.gamma,
+                    d: parameters.d,
+                    price_scale: parameters.price_scale,
+                    precisions: parameters.precisions,
+                    mid_fee: parameters.mid_fee,
+                    out_fee: parameters.out_fee,
+                    fee_gamma: parameters.fee_gamma,
+                })
+            }
+        };
+
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(pool.id.clone()),
+            address: pool.address,
+            gas: eth::Gas(pool.gas_estimate),
+            state: liquidity::State::Curve(liquidity::curve::Pool {
+                tokens: pool.tokens.iter().copied().map(eth::TokenAddress).collect(),
+                balances: pool.balances.clone(),
+                parameters,
             }),
         })
     }
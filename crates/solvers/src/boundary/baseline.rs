@@ -212,6 +212,21 @@ fn to_boundary_liquidity(
                         }
                     }
                 }
+                liquidity::State::Curve(pool) => {
+                    let boundary_pool =
+                        boundary::liquidity::curve::to_boundary_pool(liquidity.address, pool);
+                    for pair in pool.token_pairs() {
+                        let token_pair = to_boundary_token_pair(&pair);
+                        onchain_liquidity
+                            .entry(token_pair)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair,
+                                source: LiquiditySource::Curve(boundary_pool.clone()),
+                            });
+                    }
+                }
                 liquidity::State::LimitOrder(limit_order) => {
                     if let Some(token_pair) =
                         TokenPair::new(limit_order.maker.token.0, limit_order.taker.token.0)
@@ -231,6 +246,10 @@ fn to_boundary_liquidity(
                         // liquidity sources that rely on concentrated pools are disabled
                         return onchain_liquidity;
                     };
+                    if pool.v4_pool_id.is_some() {
+                        // Uniswap V4 pools can't be quoted with the Uniswap V3 quoter
+                        return onchain_liquidity;
+                    }
 
                     let token_pair = to_boundary_token_pair(&pool.tokens);
                     onchain_liquidity
@@ -266,6 +285,7 @@ enum LiquiditySource {
     ConstantProduct(boundary::liquidity::constant_product::Pool),
     WeightedProduct(boundary::liquidity::weighted_product::Pool),
     Stable(boundary::liquidity::stable::Pool),
+    Curve(boundary::liquidity::curve::Pool),
     LimitOrder(liquidity::limit_order::LimitOrder),
     Concentrated(boundary::liquidity::concentrated::Pool),
 }
@@ -276,6 +296,7 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_out(out_token, input).await,
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_out(out_token, input).await,
             LiquiditySource::Stable(pool) => pool.get_amount_out(out_token, input).await,
+            LiquiditySource::Curve(pool) => pool.get_amount_out(out_token, input).await,
             LiquiditySource::LimitOrder(limit_order) => {
                 limit_order.get_amount_out(out_token, input).await
             }
@@ -288,6 +309,7 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_in(in_token, out).await,
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_in(in_token, out).await,
             LiquiditySource::Stable(pool) => pool.get_amount_in(in_token, out).await,
+            LiquiditySource::Curve(pool) => pool.get_amount_in(in_token, out).await,
             LiquiditySource::LimitOrder(limit_order) => {
                 limit_order.get_amount_in(in_token, out).await
             }
@@ -300,6 +322,7 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.gas_cost().await,
             LiquiditySource::WeightedProduct(pool) => pool.gas_cost().await,
             LiquiditySource::Stable(pool) => pool.gas_cost().await,
+            LiquiditySource::Curve(pool) => pool.gas_cost().await,
             LiquiditySource::LimitOrder(limit_order) => limit_order.gas_cost().await,
             LiquiditySource::Concentrated(pool) => pool.gas_cost().await,
         }
@@ -0,0 +1,40 @@
+pub use shared::sources::curve::Pool;
+use {
+    crate::domain::liquidity,
+    ethereum_types::H160,
+    shared::sources::curve::{
+        PoolKind,
+        pool_fetching::{CryptoSwapState, StableSwapState},
+    },
+};
+
+/// Converts a domain pool into a [`shared`] Curve pool.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::curve::Pool) -> Pool {
+    Pool {
+        address,
+        tokens: pool.tokens.iter().map(|token| token.0).collect(),
+        balances: pool.balances.clone(),
+        kind: match &pool.parameters {
+            liquidity::curve::Parameters::StableSwap(parameters) => {
+                PoolKind::StableSwap(StableSwapState {
+                    amplification: parameters.amplification,
+                    fee: parameters.fee,
+                    offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                    rates: parameters.rates.clone(),
+                })
+            }
+            liquidity::curve::Parameters::CryptoSwap(parameters) => {
+                PoolKind::CryptoSwap(CryptoSwapState {
+                    a: parameters.a,
+                    gamma: parameters.gamma,
+                    d: parameters.d,
+                    price_scale: parameters.price_scale,
+                    precisions: parameters.precisions,
+                    mid_fee: parameters.mid_fee,
+                    out_fee: parameters.out_fee,
+                    fee_gamma: parameters.fee_gamma,
+                })
+            }
+        },
+    }
+}
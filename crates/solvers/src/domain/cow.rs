@@ -0,0 +1,160 @@
+//! Matching of opposite orders in an auction against each other, also known as
+//! "coincidences of wants" (CoWs).
+//!
+//! Two orders trading the same token pair in opposite directions can be
+//! settled against each other at a uniform clearing price without touching any
+//! on-chain liquidity. Usually the orders are not of the same size though, so
+//! the excess of the larger order (the residual) gets swapped over on-chain
+//! liquidity and the clearing price is chosen such that the settlement
+//! contract never pays out more than it receives.
+//!
+//! Only fill-or-kill sell orders with protocol computed fees are matched, all
+//! other orders are left to the baseline path finder.
+
+use {
+    crate::domain::{
+        auction,
+        eth,
+        order::{self, Order},
+        solution,
+    },
+    ethereum_types::{U256, U512},
+    std::collections::HashSet,
+};
+
+/// A pair of opposite orders to settle against each other.
+#[derive(Debug)]
+pub struct Match {
+    /// The order whose sell amount is worth more at reference prices. Part of
+    /// its sell amount may need to be swapped over on-chain liquidity.
+    excess: Order,
+    /// The order that gets fully matched against the excess order.
+    other: Order,
+    /// Amount of the excess order's sell token that can not be matched
+    /// against the other order at reference prices.
+    residual: U256,
+}
+
+/// Pairs up opposite orders of the auction. Every order is part of at most one
+/// match and orders are paired in the order they appear in the auction.
+pub fn matches(orders: &[Order], tokens: &auction::Tokens) -> Vec<Match> {
+    let eligible = orders
+        .iter()
+        .filter(|order| {
+            order.side == order::Side::Sell
+                && !order.partially_fillable
+                && !order.solver_determines_fee()
+        })
+        .collect::<Vec<_>>();
+
+    let mut matched = HashSet::new();
+    let mut matches = Vec::new();
+    for (i, order) in eligible.iter().enumerate() {
+        if matched.contains(&order.uid) {
+            continue;
+        }
+        let Some(opposite) = eligible[i + 1..].iter().find(|opposite| {
+            !matched.contains(&opposite.uid)
+                && opposite.sell.token == order.buy.token
+                && opposite.buy.token == order.sell.token
+        }) else {
+            continue;
+        };
+        if let Some(cow) = Match::new(order, opposite, tokens) {
+            matched.extend([order.uid, opposite.uid]);
+            matches.push(cow);
+        }
+    }
+    matches
+}
+
+impl Match {
+    fn new(a: &Order, b: &Order, tokens: &auction::Tokens) -> Option<Self> {
+        let price_a = tokens.reference_price(&a.sell.token)?.0.0;
+        let price_b = tokens.reference_price(&b.sell.token)?.0.0;
+        let value_a = a.sell.amount.full_mul(price_a);
+        let value_b = b.sell.amount.full_mul(price_b);
+        let (excess, other, price_excess, price_other) = if value_a >= value_b {
+            (a, b, price_a, price_b)
+        } else {
+            (b, a, price_b, price_a)
+        };
+
+        let matched: U256 = (other.sell.amount.full_mul(price_other) / U512::from(price_excess))
+            .try_into()
+            .ok()?;
+        Some(Self {
+            excess: excess.clone(),
+            other: other.clone(),
+            residual: excess.sell.amount.saturating_sub(matched),
+        })
+    }
+
+    /// The orders of the match.
+    pub fn orders(&self) -> [&Order; 2] {
+        [&self.excess, &self.other]
+    }
+
+    /// The largest amount of the excess order's sell token that should be
+    /// swapped over on-chain liquidity. Swapping less than that is fine, the
+    /// clearing price just gets worse for the other order.
+    pub fn residual(&self) -> eth::Asset {
+        eth::Asset {
+            token: self.excess.sell.token,
+            amount: self.residual,
+        }
+    }
+
+    /// Returns whether swapping `input` of the excess order's sell token for
+    /// `output` of its buy token leaves the settlement contract with enough
+    /// tokens to pay out both orders.
+    pub fn is_balanced(&self, input: U256, output: U256) -> bool {
+        let Some(remaining) = self.excess.sell.amount.checked_sub(input) else {
+            return false;
+        };
+        let Some(buy) = self.other.sell.amount.checked_add(output) else {
+            return false;
+        };
+        // The other order receives `excess.sell * other.sell / buy` of the
+        // excess order's sell token, which has to be covered by what is left
+        // after the swap.
+        remaining.full_mul(buy) >= self.excess.sell.amount.full_mul(self.other.sell.amount)
+    }
+
+    /// Settles both orders against each other after swapping `input` of the
+    /// excess order's sell token for `output` of its buy token. Returns `None`
+    /// if the resulting clearing price violates one of the orders' limit
+    /// prices or the swap leaves the settlement contract short of tokens.
+    ///
+    /// The returned solution does not contain any interactions or gas yet.
+    pub fn settle(&self, input: U256, output: U256) -> Option<solution::Solution> {
+        if !self.is_balanced(input, output) {
+            return None;
+        }
+
+        // The excess order receives the other order's sell amount plus
+        // everything the swap yields, the other order gets the excess order's
+        // sell token at the same exchange rate.
+        let buy = self.other.sell.amount.checked_add(output)?;
+        if buy < self.excess.buy.amount {
+            return None;
+        }
+        if self.other.sell.amount.full_mul(self.excess.sell.amount)
+            < self.other.buy.amount.full_mul(buy)
+        {
+            return None;
+        }
+
+        Some(solution::Solution {
+            prices: solution::ClearingPrices::new([
+                (self.excess.sell.token, buy),
+                (self.other.sell.token, self.excess.sell.amount),
+            ]),
+            trades: vec![
+                solution::Trade::Fulfillment(solution::Fulfillment::fill(self.excess.clone())?),
+                solution::Trade::Fulfillment(solution::Fulfillment::fill(self.other.clone())?),
+            ],
+            ..Default::default()
+        })
+    }
+}
@@ -1,4 +1,8 @@
-use {crate::domain::liquidity, ethereum_types::U256, std::collections::BTreeMap};
+use {
+    crate::domain::liquidity,
+    ethereum_types::{H256, U256},
+    std::collections::BTreeMap,
+};
 
 /// State for a UniswapV3-like concentrated liquidity pool.
 #[derive(Clone, Debug)]
@@ -9,6 +13,9 @@ pub struct Pool {
     pub tick: Tick,
     pub liquidity_net: BTreeMap<Tick, LiquidityNet>,
     pub fee: Fee,
+    /// The pool ID for Uniswap V4 pools, which share the same `PoolManager`
+    /// contract address.
+    pub v4_pool_id: Option<H256>,
 }
 
 /// A compressed representation of the current exchange rate between the tokens
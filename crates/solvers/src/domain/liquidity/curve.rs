@@ -0,0 +1,61 @@
+use {
+    crate::domain::{eth, liquidity},
+    ethereum_types::U256,
+    itertools::Itertools as _,
+};
+
+/// State for a Curve StableSwap-NG or Twocrypto-NG pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    /// The coins of the pool in the order the pool indexes them.
+    pub tokens: Vec<eth::TokenAddress>,
+    /// The balances of the coins in the same order as `tokens`.
+    pub balances: Vec<U256>,
+    pub parameters: Parameters,
+}
+
+/// The invariant specific parameters of a pool.
+#[derive(Clone, Debug)]
+pub enum Parameters {
+    StableSwap(StableSwap),
+    CryptoSwap(CryptoSwap),
+}
+
+/// Parameters of a StableSwap-NG pool. All fees are in units of 1e10.
+#[derive(Clone, Debug)]
+pub struct StableSwap {
+    /// The amplification coefficient multiplied by `A_PRECISION` (100).
+    pub amplification: U256,
+    pub fee: U256,
+    pub offpeg_fee_multiplier: U256,
+    /// Rates (with 18 decimals) by which the balances are scaled to 18
+    /// decimals.
+    pub rates: Vec<U256>,
+}
+
+/// Parameters of a Twocrypto-NG pool. All fees are in units of 1e10.
+#[derive(Clone, Debug)]
+pub struct CryptoSwap {
+    /// The amplification coefficient multiplied by `N_COINS**N_COINS` and
+    /// `A_MULTIPLIER` (10000).
+    pub a: U256,
+    pub gamma: U256,
+    pub d: U256,
+    /// The price of the second coin in units of the first one (with 18
+    /// decimals).
+    pub price_scale: U256,
+    pub precisions: [U256; 2],
+    pub mid_fee: U256,
+    pub out_fee: U256,
+    pub fee_gamma: U256,
+}
+
+impl Pool {
+    /// Returns an iterator over the tokens pairs traded by the pool.
+    pub fn token_pairs(&self) -> impl Iterator<Item = liquidity::TokenPair> + '_ {
+        self.tokens
+            .iter()
+            .tuple_combinations()
+            .filter_map(|(a, b)| liquidity::TokenPair::new(*a, *b))
+    }
+}
@@ -1,6 +1,7 @@
 //! Core solver engine logic.
 
 pub mod auction;
+pub mod cow;
 pub mod eth;
 pub mod liquidity;
 pub mod notification;
@@ -4,6 +4,10 @@
 //! path of at most length `max_hops + 1` over a set of on-chain liquidity. It
 //! **does not** try to split large orders into multiple parts and route them
 //! over separate paths.
+//!
+//! When configured with the [`Engine::CowMatching`] engine, opposite orders
+//! are first settled against each other (see [`cow`]) and only the residual
+//! amounts are routed over on-chain liquidity.
 
 use {
     super::solution::Solution,
@@ -11,6 +15,7 @@ use {
         boundary,
         domain::{
             auction,
+            cow,
             eth,
             liquidity,
             order::{self, Order},
@@ -37,8 +42,24 @@ pub struct Config {
     pub solution_gas_offset: eth::SignedGas,
     pub native_token_price_estimation_amount: eth::U256,
     pub node_url: Option<Url>,
+    pub engine: Engine,
+}
+
+/// The solving strategy of the solver engine.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Engine {
+    /// Route every order individually over on-chain liquidity.
+    #[default]
+    Baseline,
+    /// Match opposite orders against each other at uniform clearing prices
+    /// before routing any remaining orders individually.
+    CowMatching,
 }
 
+/// The number of steps used to search for the amount of a CoW's residual to
+/// swap over on-chain liquidity.
+const RESIDUAL_SEARCH_STEPS: usize = 16;
+
 struct Inner {
     weth: eth::WethAddress,
 
@@ -71,6 +92,9 @@ struct Inner {
 
     /// If provided, the solver can rely on Uniswap V3 LPs
     uni_v3_quoter_v2: Option<contracts::UniswapV3QuoterV2>,
+
+    /// The solving strategy to use.
+    engine: Engine,
 }
 
 impl Solver {
@@ -97,6 +121,7 @@ impl Solver {
             solution_gas_offset: config.solution_gas_offset,
             native_token_price_estimation_amount: config.native_token_price_estimation_amount,
             uni_v3_quoter_v2,
+            engine: config.engine,
         }))
     }
 
@@ -153,7 +178,27 @@ impl Inner {
             self.uni_v3_quoter_v2.as_ref(),
         );
 
+        let mut matched = HashSet::new();
+        if self.engine == Engine::CowMatching {
+            let cows = cow::matches(&auction.orders, &auction.tokens);
+            for (i, cow) in cows.iter().enumerate() {
+                let Some(solution) = self.settle_cow(cow, &boundary_solver).await else {
+                    continue;
+                };
+                matched.extend(cow.orders().map(|order| order.uid));
+                let solution = solution
+                    .with_id(solution::Id((auction.orders.len() + i) as u64))
+                    .with_buffers_internalizations(&auction.tokens);
+                if sender.send(solution).is_err() {
+                    tracing::debug!("deadline hit, receiver dropped");
+                }
+            }
+        }
+
         for (i, order) in auction.orders.into_iter().enumerate() {
+            if matched.contains(&order.uid) {
+                continue;
+            }
             let sell_token = order.sell.token;
             let sell_token_price = match auction.tokens.reference_price(&sell_token) {
                 Some(price) => price,
@@ -246,6 +291,94 @@ impl Inner {
         }
     }
 
+    /// Settles a CoW, swapping as much of its residual over on-chain
+    /// liquidity as possible without the swap paying out less than the
+    /// uniform clearing price.
+    async fn settle_cow(
+        &self,
+        cow: &cow::Match,
+        boundary_solver: &boundary::baseline::Solver<'_>,
+    ) -> Option<Solution> {
+        let residual = cow.residual();
+        let route_for = async |amount: U256| {
+            if amount.is_zero() {
+                return None;
+            }
+            let request = Request {
+                sell: eth::Asset {
+                    token: residual.token,
+                    amount,
+                },
+                buy: eth::Asset {
+                    token: cow.orders()[0].buy.token,
+                    amount: U256::zero(),
+                },
+                side: order::Side::Sell,
+            };
+            let route = boundary_solver.route(request, self.max_hops).await?;
+            cow.is_balanced(route.input().amount, route.output().amount)
+                .then_some(route)
+        };
+
+        // Swapping more of the residual yields a better price for the larger
+        // order, until the swap's exchange rate drops below the clearing
+        // price. Binary search for the largest amount that can be swapped.
+        let mut route = route_for(residual.amount).await;
+        if route.is_none() && !residual.amount.is_zero() {
+            let (mut low, mut high) = (U256::zero(), residual.amount);
+            for _ in 0..RESIDUAL_SEARCH_STEPS {
+                let mid = (low + high) / 2;
+                match route_for(mid).await {
+                    Some(found) => {
+                        low = mid;
+                        route = Some(found);
+                    }
+                    None => high = mid,
+                }
+            }
+        }
+
+        // The solution gas offset only accounts for a single trade, so add
+        // the cost of settling the second order of the CoW.
+        let cow_overhead = eth::U256::from(
+            shared::price_estimation::gas::TRADE
+                + 2 * shared::price_estimation::gas::ERC20_TRANSFER,
+        );
+        let swapped = route.and_then(|route| {
+            let solution = cow.settle(route.input().amount, route.output().amount)?;
+            Some((solution, route))
+        });
+        let solution = match swapped {
+            Some((solution, route)) => {
+                let gas = route.gas() + self.solution_gas_offset;
+                solution::Solution {
+                    interactions: route
+                        .segments
+                        .iter()
+                        .map(|segment| {
+                            solution::Interaction::Liquidity(Box::new(
+                                solution::LiquidityInteraction {
+                                    liquidity: segment.liquidity.clone(),
+                                    input: segment.input,
+                                    output: segment.output,
+                                    internalize: false,
+                                },
+                            ))
+                        })
+                        .collect(),
+                    ..solution
+                }
+                .with_gas(eth::Gas(gas.0.saturating_add(cow_overhead)))
+            }
+            None => {
+                let gas = eth::Gas(U256::zero()) + self.solution_gas_offset;
+                cow.settle(U256::zero(), U256::zero())?
+                    .with_gas(eth::Gas(gas.0.saturating_add(cow_overhead)))
+            }
+        };
+        Some(solution)
+    }
+
     fn requests_for_order(&self, order: &Order) -> impl Iterator<Item = Request> + use<> {
         let order::Order {
             sell, buy, side, ..
@@ -53,6 +53,30 @@ struct Config {
     /// If this is configured the solver will also use liquidity sources
     /// that rely on RPC request.
     node_url: Option<Url>,
+
+    /// The solving strategy of the engine.
+    #[serde(default)]
+    engine: Engine,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Engine {
+    /// Route every order individually over on-chain liquidity.
+    #[default]
+    Baseline,
+    /// Settle opposite orders against each other and only route the
+    /// remaining amounts over on-chain liquidity.
+    CowMatching,
+}
+
+impl From<Engine> for solver::Engine {
+    fn from(value: Engine) -> Self {
+        match value {
+            Engine::Baseline => Self::Baseline,
+            Engine::CowMatching => Self::CowMatching,
+        }
+    }
 }
 
 /// Load the driver configuration from a TOML file.
@@ -90,6 +114,7 @@ pub async fn load(path: &Path) -> solver::Config {
         solution_gas_offset: config.solution_gas_offset.into(),
         native_token_price_estimation_amount: config.native_token_price_estimation_amount,
         node_url: config.node_url,
+        engine: config.engine.into(),
     }
 }
 
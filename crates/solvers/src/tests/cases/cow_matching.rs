@@ -0,0 +1,420 @@
+//! Test cases for the CoW matching engine, settling opposite orders against
+//! each other and routing the residual over on-chain liquidity.
+
+use {crate::tests, serde_json::json};
+
+fn config() -> tests::Config {
+    tests::Config::String(
+        r#"
+chain-id = "1"
+base-tokens = []
+max-hops = 0
+max-partial-attempts = 5
+native-token-price-estimation-amount = "100000000000000000"
+engine = "cow-matching"
+"#
+        .to_owned(),
+    )
+}
+
+/// Two orders of equal value at reference prices are settled against each
+/// other without any on-chain liquidity.
+#[tokio::test]
+async fn perfect_match() {
+    let engine = tests::SolverEngine::new("baseline", config()).await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                },
+                "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "500000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                              1111111111111111111111111111111111111111\
+                              11111111",
+                    "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "buyToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "sellAmount": "1000000000000000000",
+                    "fullSellAmount": "1000000000000000000",
+                    "buyAmount": "1900000000000000000000",
+                    "fullBuyAmount": "1900000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                },
+                {
+                    "uid": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                              2222222222222222222222222222222222222222\
+                              22222222",
+                    "sellToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "buyToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "sellAmount": "2000000000000000000000",
+                    "fullSellAmount": "2000000000000000000000",
+                    "buyAmount": "950000000000000000",
+                    "fullBuyAmount": "950000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 2,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "2000000000000000000000",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "1000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                                    1111111111111111111111111111111111111111\
+                                    11111111",
+                        "executedAmount": "1000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                                    2222222222222222222222222222222222222222\
+                                    22222222",
+                        "executedAmount": "2000000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [],
+                "postInteractions": [],
+                "gas": 205417,
+            }]
+        }),
+    );
+}
+
+/// The residual of the larger order is swapped over a pool offering a better
+/// rate than the reference prices, improving the clearing price.
+#[tokio::test]
+async fn residual_swap() {
+    let engine = tests::SolverEngine::new("baseline", config()).await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                },
+                "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "500000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                              1111111111111111111111111111111111111111\
+                              11111111",
+                    "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "buyToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "sellAmount": "2000000000000000000",
+                    "fullSellAmount": "2000000000000000000",
+                    "buyAmount": "3900000000000000000000",
+                    "fullBuyAmount": "3900000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                },
+                {
+                    "uid": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                              2222222222222222222222222222222222222222\
+                              22222222",
+                    "sellToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "buyToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "sellAmount": "2000000000000000000000",
+                    "fullSellAmount": "2000000000000000000000",
+                    "buyAmount": "900000000000000000",
+                    "fullBuyAmount": "900000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                            "balance": "100000000000000000000"
+                        },
+                        "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                            "balance": "210000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x97b744df0b59d93A866304f97431D8EfAd29a08d",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 2,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "4073031872233828727585",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "2000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                                    1111111111111111111111111111111111111111\
+                                    11111111",
+                        "executedAmount": "2000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                                    2222222222222222222222222222222222222222\
+                                    22222222",
+                        "executedAmount": "2000000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "1000000000000000000",
+                        "outputAmount": "2073031872233828727585"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 265417,
+            }]
+        }),
+    );
+}
+
+/// Swapping the whole residual would pay out less than the clearing price, so
+/// only part of it is swapped over the pool.
+#[tokio::test]
+async fn partial_residual_swap() {
+    let engine = tests::SolverEngine::new("baseline", config()).await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                },
+                "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "500000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                              1111111111111111111111111111111111111111\
+                              11111111",
+                    "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "buyToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "sellAmount": "2000000000000000000",
+                    "fullSellAmount": "2000000000000000000",
+                    "buyAmount": "3800000000000000000000",
+                    "fullBuyAmount": "3800000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                },
+                {
+                    "uid": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                              2222222222222222222222222222222222222222\
+                              22222222",
+                    "sellToken": "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB",
+                    "buyToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "sellAmount": "2000000000000000000000",
+                    "fullSellAmount": "2000000000000000000000",
+                    "buyAmount": "900000000000000000",
+                    "fullBuyAmount": "900000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                            "balance": "10000000000000000000"
+                        },
+                        "0xDEf1CA1fb7FBcDC777520aa7f396b4E015F497aB": {
+                            "balance": "21000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x97b744df0b59d93A866304f97431D8EfAd29a08d",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 2,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "3823741721944965583939",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "2000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x1111111111111111111111111111111111111111111111111111111111111111\
+                                    1111111111111111111111111111111111111111\
+                                    11111111",
+                        "executedAmount": "2000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2222222222222222222222222222222222222222222222222222222222222222\
+                                    2222222222222222222222222222222222222222\
+                                    22222222",
+                        "executedAmount": "2000000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "953903198242187500",
+                        "outputAmount": "1823741721944965583939"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 265417,
+            }]
+        }),
+    );
+}
@@ -2,6 +2,7 @@
 
 mod bal_liquidity;
 mod buy_order_rounding;
+mod cow_matching;
 mod direct_swap;
 mod internalization;
 mod limit_order_quoting;
@@ -0,0 +1,14 @@
+-- Announce every newly inserted order event on the `order_events` channel so
+-- that the orderbook can push status updates to API subscribers instead of
+-- having them poll for changes.
+-- The payload has the form `<hex encoded order uid>:<label>`.
+CREATE FUNCTION notify_order_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('order_events', encode(NEW.order_uid, 'hex') || ':' || NEW.label);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER order_events_notify
+    AFTER INSERT ON order_events
+    FOR EACH ROW EXECUTE FUNCTION notify_order_event();
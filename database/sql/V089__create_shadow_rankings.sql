@@ -0,0 +1,24 @@
+-- Winner selection mechanisms can run in shadow mode next to the one deciding
+-- the actual winners of an auction. To be able to compare the mechanisms we
+-- store how each of them ranked the proposed solutions and which reference
+-- scores it computed.
+CREATE TABLE shadow_proposed_solutions
+(
+    auction_id   BIGINT      NOT NULL,
+    arbitrator   TEXT        NOT NULL,
+    solution_uid BIGINT      NOT NULL,
+    ranking      BIGINT      NOT NULL,
+    is_winner    BOOLEAN     NOT NULL,
+    filtered_out BOOLEAN     NOT NULL,
+    score        NUMERIC(78) NOT NULL,
+    PRIMARY KEY (auction_id, arbitrator, solution_uid)
+);
+
+CREATE TABLE shadow_reference_scores
+(
+    auction_id      BIGINT      NOT NULL,
+    arbitrator      TEXT        NOT NULL,
+    solver          BYTEA       NOT NULL,
+    reference_score NUMERIC(78) NOT NULL,
+    PRIMARY KEY (auction_id, arbitrator, solver)
+);
@@ -0,0 +1,4 @@
+-- Fee policies can be restricted to certain token pairs, order sizes and owner
+-- volumes. The name of the configured tier that created a fee policy is stored
+-- so it can be shown to users.
+ALTER TABLE fee_policies ADD COLUMN tier text;
@@ -0,0 +1,7 @@
+-- Orders can be placed with a condition (e.g. a TWAP schedule or a stop-loss
+-- trigger) that determines when the autopilot includes them in auctions.
+CREATE TABLE order_conditions
+(
+    order_uid BYTEA PRIMARY KEY,
+    condition JSONB NOT NULL
+);
@@ -0,0 +1,20 @@
+-- Drivers can share the results of their token quality and settlement
+-- simulations so that the same work isn't repeated by every driver.
+CREATE TABLE driver_token_qualities
+(
+    token BYTEA PRIMARY KEY,
+    supported BOOLEAN NOT NULL,
+    -- Fee in basis points taken on every transfer of a supported token.
+    transfer_fee_bps INTEGER,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Simulation results are only valid for the block they were computed on.
+CREATE TABLE driver_simulations
+(
+    block_number BIGINT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    gas NUMERIC(78,0),
+    access_list JSONB,
+    PRIMARY KEY (block_number, tx_hash)
+);
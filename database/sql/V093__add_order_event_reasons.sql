@@ -0,0 +1,3 @@
+-- Details about why an order event happened, e.g. why an order got filtered
+-- from the auction.
+ALTER TABLE order_events ADD COLUMN reason JSONB;
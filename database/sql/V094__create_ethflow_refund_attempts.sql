@@ -0,0 +1,24 @@
+CREATE TYPE EthflowRefundAttemptStatus AS ENUM (
+  'success',
+  'reverted',
+  'failed'
+);
+
+-- Records every transaction the refunder submitted to refund expired ethflow
+-- orders so the spent fees can be audited and limited.
+CREATE TABLE ethflow_refund_attempts (
+    timestamp timestamptz NOT NULL,
+    ethflow_contract bytea NOT NULL,
+    order_uids bytea[] NOT NULL,
+    nonce bigint NOT NULL,
+    max_fee_per_gas numeric(78,0) NOT NULL,
+    max_priority_fee_per_gas numeric(78,0) NOT NULL,
+    status EthflowRefundAttemptStatus NOT NULL,
+    tx_hash bytea,
+    gas_used numeric(78,0),
+    fee numeric(78,0),
+    error text
+);
+
+-- Used to sum up the fees spent in a given time frame.
+CREATE INDEX ethflow_refund_attempts_by_timestamp ON ethflow_refund_attempts USING BTREE (timestamp);
@@ -0,0 +1,6 @@
+-- Users can request their expired ethflow orders to be refunded with priority.
+-- The refunder refunds these orders regardless of their price deviation.
+CREATE TABLE ethflow_refund_requests (
+    order_uid bytea PRIMARY KEY,
+    timestamp timestamptz NOT NULL
+);
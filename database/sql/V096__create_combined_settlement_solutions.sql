@@ -0,0 +1,10 @@
+-- Winning solutions of different solvers which got merged into a single
+-- settlement transaction submitted by another solver.
+CREATE TABLE combined_settlement_solutions (
+    auction_id bigint NOT NULL,
+    solution_uid bigint NOT NULL,
+    submitter bytea NOT NULL,
+    PRIMARY KEY (auction_id, solution_uid)
+);
+
+CREATE INDEX combined_settlement_solutions_submitter ON combined_settlement_solutions (auction_id, submitter);
@@ -0,0 +1 @@
+ALTER TYPE EthflowRefundAttemptStatus ADD VALUE 'pending';